@@ -0,0 +1,123 @@
+//! Prometheus metrics of the price oracle's offchain worker.
+//!
+//! The offchain worker counts its fetches, their failures and latencies and its submissions in
+//! the node's persistent offchain storage, see [`pallet_price_oracle::telemetry`]. After every
+//! imported block, which is when the offchain worker runs, the counters are read back and exported
+//! through the node's Prometheus endpoint.
+
+use codec::Decode;
+use futures::StreamExt;
+use pallet_price_oracle::telemetry::{OcwTelemetry, TELEMETRY_KEY};
+use sc_client_api::BlockchainEvents;
+use solochain_template_runtime::opaque::Block;
+use sp_core::offchain::{OffchainStorage, STORAGE_PREFIX};
+use std::sync::Arc;
+use substrate_prometheus_endpoint::{
+	exponential_buckets, register, Counter, CounterVec, HistogramOpts, HistogramVec, Opts,
+	PrometheusError, Registry, U64,
+};
+
+/// The metrics of the offchain worker, labelled by exchange id where they are per exchange.
+pub struct OracleMetrics {
+	fetches: CounterVec<U64>,
+	fetch_failures: CounterVec<U64>,
+	submissions: Counter<U64>,
+	failed_submissions: Counter<U64>,
+	fetch_latency: HistogramVec,
+	/// The telemetry exported so far, which the next update adds to.
+	exported: OcwTelemetry,
+}
+
+impl OracleMetrics {
+	/// Register the metrics with `registry`.
+	pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			fetches: register(
+				CounterVec::new(
+					Opts::new(
+						"substrate_price_oracle_fetches_total",
+						"Price fetches started by the offchain worker",
+					),
+					&["exchange"],
+				)?,
+				registry,
+			)?,
+			fetch_failures: register(
+				CounterVec::new(
+					Opts::new(
+						"substrate_price_oracle_fetch_failures_total",
+						"Price fetches of the offchain worker that returned no usable price",
+					),
+					&["exchange"],
+				)?,
+				registry,
+			)?,
+			submissions: register(
+				Counter::new(
+					"substrate_price_oracle_submissions_total",
+					"Transactions the offchain worker handed to the transaction pool",
+				)?,
+				registry,
+			)?,
+			failed_submissions: register(
+				Counter::new(
+					"substrate_price_oracle_failed_submissions_total",
+					"Transactions of the offchain worker the transaction pool refused",
+				)?,
+				registry,
+			)?,
+			fetch_latency: register(
+				HistogramVec::new(
+					HistogramOpts::new(
+						"substrate_price_oracle_fetch_latency_seconds",
+						"Time the offchain worker took to fetch a price",
+					)
+					.buckets(exponential_buckets(0.05, 2.0, 9)?),
+					&["exchange"],
+				)?,
+				registry,
+			)?,
+			exported: OcwTelemetry::default(),
+		})
+	}
+
+	/// Export what `telemetry` recorded since the last update.
+	pub fn update(&mut self, telemetry: OcwTelemetry) {
+		for (exchange_id, venue) in &telemetry.venues {
+			let exported = self.exported.venues.get(exchange_id).copied().unwrap_or_default();
+			let label = exchange_id.to_string();
+			self.fetches
+				.with_label_values(&[label.as_str()])
+				.inc_by(venue.fetches.saturating_sub(exported.fetches));
+			self.fetch_failures
+				.with_label_values(&[label.as_str()])
+				.inc_by(venue.failures.saturating_sub(exported.failures));
+		}
+		self.submissions
+			.inc_by(telemetry.submissions.saturating_sub(self.exported.submissions));
+		self.failed_submissions
+			.inc_by(telemetry.failed_submissions.saturating_sub(self.exported.failed_submissions));
+		for (exchange_id, millis) in telemetry.latencies_since(self.exported.latency_samples) {
+			self.fetch_latency
+				.with_label_values(&[exchange_id.to_string().as_str()])
+				.observe(*millis as f64 / 1_000.0);
+		}
+		self.exported = telemetry;
+	}
+}
+
+/// Export the telemetry the offchain worker keeps in `storage` after every block `client`
+/// imports.
+pub async fn run<C, S>(client: Arc<C>, storage: S, mut metrics: OracleMetrics)
+where
+	C: BlockchainEvents<Block>,
+	S: OffchainStorage,
+{
+	let mut imported = client.import_notification_stream();
+	while imported.next().await.is_some() {
+		let Some(encoded) = storage.get(STORAGE_PREFIX, TELEMETRY_KEY) else { continue };
+		if let Ok(telemetry) = OcwTelemetry::decode(&mut &encoded[..]) {
+			metrics.update(telemetry);
+		}
+	}
+}
@@ -9,6 +9,7 @@ use sc_telemetry::{Telemetry, TelemetryWorker};
 use sc_transaction_pool_api::OffchainTransactionPoolFactory;
 use solochain_template_runtime::{self, apis::RuntimeApi, opaque::Block};
 use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
+use sp_keystore::Keystore;
 use std::{sync::Arc, time::Duration};
 
 pub(crate) type FullClient = sc_service::TFullClient<
@@ -184,6 +185,14 @@ pub fn new_full<
 		})?;
 
 	if config.offchain_worker.enabled {
+		// Development nodes sign prices with the operator key of their dev account, which the
+		// development genesis registers.
+		if let Some(seed) = &config.dev_key_seed {
+			keystore_container
+				.keystore()
+				.sr25519_generate_new(pallet_price_oracle::KEY_TYPE, Some(seed))
+				.map_err(|e| ServiceError::Other(format!("Failed to add the oracle key: {e}")))?;
+		}
 		let offchain_workers =
 			sc_offchain::OffchainWorkers::new(sc_offchain::OffchainWorkerOptions {
 				runtime_api_provider: client.clone(),
@@ -202,6 +211,17 @@ pub fn new_full<
 			"offchain-worker",
 			offchain_workers.run(client.clone(), task_manager.spawn_handle()).boxed(),
 		);
+
+		if let (Some(registry), Some(storage)) =
+			(config.prometheus_registry(), backend.offchain_storage())
+		{
+			let metrics = crate::oracle_metrics::OracleMetrics::register(registry)?;
+			task_manager.spawn_handle().spawn(
+				"price-oracle-metrics",
+				"offchain-worker",
+				crate::oracle_metrics::run(client.clone(), storage, metrics),
+			);
+		}
 	}
 
 	let role = config.role;
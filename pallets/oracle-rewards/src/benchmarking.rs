@@ -0,0 +1,67 @@
+//! Benchmarks of the oracle rewards pallet.
+
+use super::*;
+use crate::ContributionReporter;
+use frame_benchmarking::v2::*;
+use frame_support::traits::{Currency, Get, Hooks};
+use sp_runtime::traits::{Bounded, Saturating};
+
+/// Fill the reward pot well beyond anything the benchmarks pay out.
+fn fund_pot<T: Config>() {
+	T::Currency::make_free_balance_be(
+		&Pallet::<T>::account_id(),
+		BalanceOf::<T>::max_value() / 2u32.into(),
+	);
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn note_contribution() {
+		let who: T::AccountId = account("operator", 0, 0);
+
+		#[block]
+		{
+			Pallet::<T>::note_contribution(&who, 1);
+		}
+
+		assert_eq!(EraPoints::<T>::get(&who), 1);
+	}
+
+	#[benchmark]
+	fn note_submission() {
+		fund_pot::<T>();
+		let who: T::AccountId = account("operator", 0, 0);
+
+		#[block]
+		{
+			Pallet::<T>::note_submission(&who);
+		}
+
+		assert_eq!(RewardedSubmissions::<T>::get(&who), 1);
+	}
+
+	#[benchmark]
+	fn end_era(o: Linear<0, { T::MaxOperators::get() }>, s: Linear<0, { T::MaxOperators::get() }>) {
+		fund_pot::<T>();
+		for i in 0..o {
+			Pallet::<T>::note_contribution(&account("operator", i, 0), 1);
+		}
+		for i in 0..s {
+			RewardedSubmissions::<T>::insert(account::<T::AccountId>("submitter", i, 0), 1);
+		}
+		let now = EraStart::<T>::get().saturating_add(T::EraLength::get());
+
+		#[block]
+		{
+			Pallet::<T>::on_initialize(now);
+		}
+
+		assert_eq!(CurrentEra::<T>::get(), 1);
+		assert_eq!(EraPoints::<T>::count(), 0);
+	}
+
+	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(1), crate::mock::Test);
+}
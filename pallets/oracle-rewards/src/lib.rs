@@ -0,0 +1,286 @@
+//! # Oracle Rewards Pallet
+//!
+//! Pays price oracle operators for the observations they contribute.
+//!
+//! The price oracle reports contribution points for an operator through [`ContributionReporter`]
+//! whenever one of its observations is accepted. Points accumulate over an era of
+//! [`Config::EraLength`] blocks; when the era ends, [`Config::EraPayout`] is split between the
+//! operators proportionally to their points and the tally is reset.
+//!
+//! Operators submitting signed prices are additionally paid [`Config::RewardPerSubmission`] for
+//! every accepted submission, up to [`Config::MaxRewardedSubmissions`] per era.
+//!
+//! Rewards are transferred from the pallet pot account ([`Pallet::account_id`]), which is funded
+//! by treasury spends or by routing inflation to it. If the pot holds less than the era payout,
+//! whatever it can spare without being reaped is distributed instead.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+use frame_support::weights::Weight;
+
+const LOG_TARGET: &str = "runtime::oracle-rewards";
+
+/// Sink for operator contributions, called by the price oracle.
+pub trait ContributionReporter<AccountId> {
+	/// Credit `who` with `points` in the current era.
+	fn note_contribution(who: &AccountId, points: u32);
+
+	/// Record an accepted signed submission by `who`.
+	fn note_submission(who: &AccountId);
+
+	/// Worst case weight of [`Self::note_contribution`].
+	fn note_contribution_weight() -> Weight;
+
+	/// Worst case weight of [`Self::note_submission`].
+	fn note_submission_weight() -> Weight;
+}
+
+impl<AccountId> ContributionReporter<AccountId> for () {
+	fn note_contribution(_who: &AccountId, _points: u32) {}
+
+	fn note_submission(_who: &AccountId) {}
+
+	fn note_contribution_weight() -> Weight {
+		Weight::zero()
+	}
+
+	fn note_submission_weight() -> Weight {
+		Weight::zero()
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::{WeightInfo, LOG_TARGET};
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{Currency, ExistenceRequirement},
+		PalletId,
+	};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::{
+		traits::{AccountIdConversion, Saturating, Zero},
+		Perbill,
+	};
+
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	/// Sequential index of a reward era.
+	pub type EraIndex = u32;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Overarching event type. Declared rather than bounded on `frame_system::Config` for the
+		/// release trains before stable2506, which require it.
+		#[allow(deprecated)]
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// Currency rewards are paid in.
+		type Currency: Currency<Self::AccountId>;
+
+		/// Identifier the reward pot account is derived from.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Number of blocks in a reward era.
+		#[pallet::constant]
+		type EraLength: Get<BlockNumberFor<Self>>;
+
+		/// Amount split between operators at the end of every era.
+		#[pallet::constant]
+		type EraPayout: Get<BalanceOf<Self>>;
+
+		/// Maximum number of operators that can accrue points or submission rewards within one
+		/// era. Bounds the weight of the era payout.
+		#[pallet::constant]
+		type MaxOperators: Get<u32>;
+
+		/// Amount paid to an operator for each accepted signed submission.
+		#[pallet::constant]
+		type RewardPerSubmission: Get<BalanceOf<Self>>;
+
+		/// Maximum number of submissions rewarded per operator within one era.
+		#[pallet::constant]
+		type MaxRewardedSubmissions: Get<u32>;
+
+		/// Weight information for the hooks and contribution reports of this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// Index of the era currently accruing points.
+	#[pallet::storage]
+	pub type CurrentEra<T> = StorageValue<_, EraIndex, ValueQuery>;
+
+	/// Block at which the current era started.
+	#[pallet::storage]
+	pub type EraStart<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
+	/// Points accrued by each operator in the current era.
+	#[pallet::storage]
+	pub type EraPoints<T: Config> =
+		CountedStorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	/// Sum of [`EraPoints`] for the current era.
+	#[pallet::storage]
+	pub type TotalEraPoints<T> = StorageValue<_, u32, ValueQuery>;
+
+	/// Number of submissions rewarded for each operator in the current era.
+	#[pallet::storage]
+	pub type RewardedSubmissions<T: Config> =
+		CountedStorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An operator was paid its share of an era payout.
+		RewardPaid { era: EraIndex, who: T::AccountId, amount: BalanceOf<T> },
+		/// An era ended and `total` was distributed between `operators`.
+		EraPaid { era: EraIndex, total: BalanceOf<T>, operators: u32 },
+		/// An operator was paid for an accepted submission.
+		SubmissionRewarded { era: EraIndex, who: T::AccountId, amount: BalanceOf<T> },
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+			if n.saturating_sub(EraStart::<T>::get()) < T::EraLength::get() {
+				return T::DbWeight::get().reads(1)
+			}
+			Self::end_era(n)
+		}
+
+		fn integrity_test() {
+			assert!(!T::EraLength::get().is_zero(), "EraLength must be non-zero");
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Account the era payouts are transferred from.
+		pub fn account_id() -> T::AccountId {
+			T::PalletId::get().into_account_truncating()
+		}
+
+		/// Distribute the payout of the current era and start the next one at `now`.
+		pub(crate) fn end_era(now: BlockNumberFor<T>) -> Weight {
+			let era = CurrentEra::<T>::get();
+			let operators = EraPoints::<T>::count();
+			let submitters = RewardedSubmissions::<T>::count();
+			let total_points = TotalEraPoints::<T>::get();
+
+			let pot = Self::account_id();
+			let spare =
+				T::Currency::free_balance(&pot).saturating_sub(T::Currency::minimum_balance());
+			let payout = T::EraPayout::get().min(spare);
+
+			let mut paid = BalanceOf::<T>::zero();
+			if !total_points.is_zero() && !payout.is_zero() {
+				for (who, points) in EraPoints::<T>::iter() {
+					let amount = Perbill::from_rational(points, total_points).mul_floor(payout);
+					if amount.is_zero() {
+						continue
+					}
+					match T::Currency::transfer(&pot, &who, amount, ExistenceRequirement::KeepAlive)
+					{
+						Ok(()) => {
+							paid = paid.saturating_add(amount);
+							Self::deposit_event(Event::RewardPaid { era, who, amount });
+						},
+						Err(e) => log::warn!(
+							target: LOG_TARGET,
+							"Failed to pay era {} reward of {:?}: {:?}",
+							era,
+							amount,
+							e,
+						),
+					}
+				}
+			}
+
+			let _ = EraPoints::<T>::clear(u32::MAX, None);
+			let _ = RewardedSubmissions::<T>::clear(u32::MAX, None);
+			TotalEraPoints::<T>::kill();
+			CurrentEra::<T>::put(era.saturating_add(1));
+			EraStart::<T>::put(now);
+			Self::deposit_event(Event::EraPaid { era, total: paid, operators });
+
+			T::WeightInfo::end_era(operators, submitters)
+		}
+	}
+
+	impl<T: Config> crate::ContributionReporter<T::AccountId> for Pallet<T> {
+		fn note_contribution(who: &T::AccountId, points: u32) {
+			if points.is_zero() {
+				return
+			}
+			if !EraPoints::<T>::contains_key(who) &&
+				EraPoints::<T>::count() >= T::MaxOperators::get()
+			{
+				log::debug!(target: LOG_TARGET, "Operator limit reached, dropping contribution");
+				return
+			}
+			EraPoints::<T>::mutate(who, |p| *p = p.saturating_add(points));
+			TotalEraPoints::<T>::mutate(|t| *t = t.saturating_add(points));
+		}
+
+		fn note_submission(who: &T::AccountId) {
+			let rewarded = RewardedSubmissions::<T>::get(who);
+			if rewarded >= T::MaxRewardedSubmissions::get() {
+				return
+			}
+			if rewarded.is_zero() && RewardedSubmissions::<T>::count() >= T::MaxOperators::get() {
+				log::debug!(target: LOG_TARGET, "Operator limit reached, dropping submission");
+				return
+			}
+
+			let era = CurrentEra::<T>::get();
+			let amount = T::RewardPerSubmission::get();
+			match T::Currency::transfer(
+				&Self::account_id(),
+				who,
+				amount,
+				ExistenceRequirement::KeepAlive,
+			) {
+				Ok(()) => {
+					RewardedSubmissions::<T>::insert(who, rewarded.saturating_add(1));
+					Self::deposit_event(Event::SubmissionRewarded {
+						era,
+						who: who.clone(),
+						amount,
+					});
+				},
+				Err(e) => log::warn!(
+					target: LOG_TARGET,
+					"Failed to pay submission reward of {:?}: {:?}",
+					amount,
+					e,
+				),
+			}
+		}
+
+		fn note_contribution_weight() -> Weight {
+			T::WeightInfo::note_contribution()
+		}
+
+		fn note_submission_weight() -> Weight {
+			T::WeightInfo::note_submission()
+		}
+	}
+}
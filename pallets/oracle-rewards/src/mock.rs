@@ -0,0 +1,82 @@
+use crate as pallet_oracle_rewards;
+use frame_support::{
+	derive_impl, parameter_types,
+	traits::{ConstU32, ConstU64},
+	PalletId,
+};
+use sp_runtime::BuildStorage;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+#[frame_support::runtime]
+mod runtime {
+	#[runtime::runtime]
+	#[runtime::derive(
+		RuntimeCall,
+		RuntimeEvent,
+		RuntimeError,
+		RuntimeOrigin,
+		RuntimeFreezeReason,
+		RuntimeHoldReason,
+		RuntimeSlashReason,
+		RuntimeLockId,
+		RuntimeTask,
+		RuntimeViewFunction
+	)]
+	pub struct Test;
+
+	#[runtime::pallet_index(0)]
+	pub type System = frame_system;
+
+	#[runtime::pallet_index(1)]
+	pub type Balances = pallet_balances;
+
+	#[runtime::pallet_index(2)]
+	pub type OracleRewards = pallet_oracle_rewards;
+}
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+	type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+}
+
+parameter_types! {
+	pub const OracleRewardsPalletId: PalletId = PalletId(*b"py/orrwd");
+}
+
+pub const ERA_LENGTH: u64 = 10;
+pub const ERA_PAYOUT: u64 = 1_000;
+pub const SUBMISSION_REWARD: u64 = 10;
+
+impl pallet_oracle_rewards::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type PalletId = OracleRewardsPalletId;
+	type EraLength = ConstU64<ERA_LENGTH>;
+	type EraPayout = ConstU64<ERA_PAYOUT>;
+	type MaxOperators = ConstU32<3>;
+	type RewardPerSubmission = ConstU64<SUBMISSION_REWARD>;
+	type MaxRewardedSubmissions = ConstU32<2>;
+	type WeightInfo = ();
+}
+
+/// Build test externalities with the reward pot holding `pot` units.
+pub fn new_test_ext(pot: u64) -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(OracleRewards::account_id(), pot)],
+		..Default::default()
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
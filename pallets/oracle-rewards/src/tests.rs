@@ -0,0 +1,112 @@
+use crate::{
+	mock::*, ContributionReporter, CurrentEra, EraPoints, Event, RewardedSubmissions,
+	TotalEraPoints,
+};
+
+fn end_first_era() {
+	System::run_to_block::<AllPalletsWithSystem>(ERA_LENGTH);
+}
+
+#[test]
+fn era_payout_is_split_by_points() {
+	new_test_ext(10_000).execute_with(|| {
+		OracleRewards::note_contribution(&1, 3);
+		OracleRewards::note_contribution(&2, 1);
+
+		end_first_era();
+
+		assert_eq!(Balances::free_balance(1), 750);
+		assert_eq!(Balances::free_balance(2), 250);
+		System::assert_has_event(Event::RewardPaid { era: 0, who: 1, amount: 750 }.into());
+		System::assert_last_event(
+			Event::EraPaid { era: 0, total: ERA_PAYOUT, operators: 2 }.into(),
+		);
+		assert_eq!(CurrentEra::<Test>::get(), 1);
+		assert_eq!(EraPoints::<Test>::count(), 0);
+		assert_eq!(TotalEraPoints::<Test>::get(), 0);
+	});
+}
+
+#[test]
+fn payout_is_limited_by_pot_balance() {
+	// One unit stays behind as the existential deposit.
+	new_test_ext(501).execute_with(|| {
+		OracleRewards::note_contribution(&1, 1);
+		OracleRewards::note_contribution(&2, 1);
+
+		end_first_era();
+
+		assert_eq!(Balances::free_balance(1), 250);
+		assert_eq!(Balances::free_balance(2), 250);
+		assert_eq!(Balances::free_balance(OracleRewards::account_id()), 1);
+	});
+}
+
+#[test]
+fn operators_beyond_limit_are_ignored() {
+	new_test_ext(10_000).execute_with(|| {
+		for who in 1..=4 {
+			OracleRewards::note_contribution(&who, 1);
+		}
+		// Existing operators still accrue once the limit is reached.
+		OracleRewards::note_contribution(&1, 1);
+
+		assert_eq!(EraPoints::<Test>::count(), 3);
+		assert_eq!(EraPoints::<Test>::get(1), 2);
+		assert_eq!(EraPoints::<Test>::get(4), 0);
+		assert_eq!(TotalEraPoints::<Test>::get(), 4);
+	});
+}
+
+#[test]
+fn empty_era_pays_nothing() {
+	new_test_ext(10_000).execute_with(|| {
+		end_first_era();
+
+		assert_eq!(Balances::free_balance(OracleRewards::account_id()), 10_000);
+		System::assert_last_event(Event::EraPaid { era: 0, total: 0, operators: 0 }.into());
+		assert_eq!(CurrentEra::<Test>::get(), 1);
+	});
+}
+
+#[test]
+fn submissions_are_rewarded_up_to_cap() {
+	new_test_ext(10_000).execute_with(|| {
+		for _ in 0..3 {
+			OracleRewards::note_submission(&1);
+		}
+
+		assert_eq!(Balances::free_balance(1), 2 * SUBMISSION_REWARD);
+		assert_eq!(RewardedSubmissions::<Test>::get(1), 2);
+		System::assert_last_event(
+			Event::SubmissionRewarded { era: 0, who: 1, amount: SUBMISSION_REWARD }.into(),
+		);
+	});
+}
+
+#[test]
+fn submission_cap_resets_each_era() {
+	new_test_ext(10_000).execute_with(|| {
+		OracleRewards::note_submission(&1);
+		OracleRewards::note_submission(&1);
+
+		end_first_era();
+		assert_eq!(RewardedSubmissions::<Test>::count(), 0);
+
+		OracleRewards::note_submission(&1);
+		assert_eq!(Balances::free_balance(1), 3 * SUBMISSION_REWARD);
+		System::assert_last_event(
+			Event::SubmissionRewarded { era: 1, who: 1, amount: SUBMISSION_REWARD }.into(),
+		);
+	});
+}
+
+#[test]
+fn submission_reward_is_skipped_when_pot_is_empty() {
+	new_test_ext(1).execute_with(|| {
+		OracleRewards::note_submission(&1);
+
+		assert_eq!(Balances::free_balance(1), 0);
+		assert_eq!(RewardedSubmissions::<Test>::get(1), 0);
+	});
+}
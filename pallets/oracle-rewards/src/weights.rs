@@ -0,0 +1,107 @@
+//! Placeholder weights for `pallet_oracle_rewards`.
+//!
+//! These are estimated from the storage accesses of the benchmarks in `benchmarking.rs`, not
+//! measured, so the runtime doesn't use them yet. Replace them with benchmarked weights,
+//! generated on reference hardware with
+//! `frame-omni-bencher v1 benchmark pallet --pallet pallet_oracle_rewards --extrinsic "*"
+//! --output pallets/oracle-rewards/src/weights.rs`.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use core::marker::PhantomData;
+use frame_support::{
+	traits::Get,
+	weights::{constants::RocksDbWeight, Weight},
+};
+
+/// Weight functions needed for `pallet_oracle_rewards`.
+pub trait WeightInfo {
+	fn note_contribution() -> Weight;
+	fn note_submission() -> Weight;
+	fn end_era(o: u32, s: u32) -> Weight;
+}
+
+/// Weights for `pallet_oracle_rewards` using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `OracleRewards::EraPoints` (r:1 w:1)
+	/// Storage: `OracleRewards::CounterForEraPoints` (r:1 w:1)
+	/// Storage: `OracleRewards::TotalEraPoints` (r:1 w:1)
+	fn note_contribution() -> Weight {
+		Weight::from_parts(12_000_000, 3_513)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: `OracleRewards::RewardedSubmissions` (r:1 w:1)
+	/// Storage: `OracleRewards::CounterForRewardedSubmissions` (r:1 w:1)
+	/// Storage: `OracleRewards::CurrentEra` (r:1 w:0)
+	/// Storage: `System::Account` (r:2 w:2)
+	fn note_submission() -> Weight {
+		Weight::from_parts(48_000_000, 6_196)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: `OracleRewards::CurrentEra` (r:1 w:1)
+	/// Storage: `OracleRewards::EraStart` (r:0 w:1)
+	/// Storage: `OracleRewards::CounterForEraPoints` (r:1 w:1)
+	/// Storage: `OracleRewards::CounterForRewardedSubmissions` (r:1 w:1)
+	/// Storage: `OracleRewards::TotalEraPoints` (r:1 w:1)
+	/// Storage: `OracleRewards::EraPoints` (r:o w:o)
+	/// Storage: `OracleRewards::RewardedSubmissions` (r:0 w:s)
+	/// Storage: `System::Account` (r:1+o w:1+o)
+	/// The range of component `o` is `[0, 64]`.
+	/// The range of component `s` is `[0, 64]`.
+	fn end_era(o: u32, s: u32) -> Weight {
+		Weight::from_parts(25_000_000, 3_593)
+			.saturating_add(Weight::from_parts(42_000_000, 2_603).saturating_mul(o.into()))
+			.saturating_add(Weight::from_parts(2_500_000, 0).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(o.into())))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(o.into())))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(s.into())))
+	}
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+	/// Storage: `OracleRewards::EraPoints` (r:1 w:1)
+	/// Storage: `OracleRewards::CounterForEraPoints` (r:1 w:1)
+	/// Storage: `OracleRewards::TotalEraPoints` (r:1 w:1)
+	fn note_contribution() -> Weight {
+		Weight::from_parts(12_000_000, 3_513)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: `OracleRewards::RewardedSubmissions` (r:1 w:1)
+	/// Storage: `OracleRewards::CounterForRewardedSubmissions` (r:1 w:1)
+	/// Storage: `OracleRewards::CurrentEra` (r:1 w:0)
+	/// Storage: `System::Account` (r:2 w:2)
+	fn note_submission() -> Weight {
+		Weight::from_parts(48_000_000, 6_196)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: `OracleRewards::CurrentEra` (r:1 w:1)
+	/// Storage: `OracleRewards::EraStart` (r:0 w:1)
+	/// Storage: `OracleRewards::CounterForEraPoints` (r:1 w:1)
+	/// Storage: `OracleRewards::CounterForRewardedSubmissions` (r:1 w:1)
+	/// Storage: `OracleRewards::TotalEraPoints` (r:1 w:1)
+	/// Storage: `OracleRewards::EraPoints` (r:o w:o)
+	/// Storage: `OracleRewards::RewardedSubmissions` (r:0 w:s)
+	/// Storage: `System::Account` (r:1+o w:1+o)
+	/// The range of component `o` is `[0, 64]`.
+	/// The range of component `s` is `[0, 64]`.
+	fn end_era(o: u32, s: u32) -> Weight {
+		Weight::from_parts(25_000_000, 3_593)
+			.saturating_add(Weight::from_parts(42_000_000, 2_603).saturating_mul(o.into()))
+			.saturating_add(Weight::from_parts(2_500_000, 0).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(o.into())))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(o.into())))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(s.into())))
+	}
+}
@@ -0,0 +1,136 @@
+//! Throughput of the parse path of the offchain worker, from the JSON-RPC response of a pool to
+//! its price.
+//!
+//! The offchain worker parses a response per pool and block within [`Config::MaxOcwMillis`], so
+//! parsing time adds up as protocols and pools are registered. The responses are archived in
+//! `fixtures`, so runs are deterministic and comparable across commits:
+//!
+//! ```sh
+//! cargo bench -p pallet-price-oracle --bench parse -- --save-baseline main
+//! git checkout my-branch
+//! cargo bench -p pallet-price-oracle --bench parse -- --baseline main
+//! ```
+//!
+//! [`Config::MaxOcwMillis`]: pallet_price_oracle::Config::MaxOcwMillis
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use pallet_price_oracle::{
+	fixed, json,
+	protocols::{DexProtocol, ProtocolKind},
+	types::PoolMetadata,
+};
+use std::hint::black_box;
+
+/// `slot0()` of the USDC/WETH 0.05% pool with ETH at 2500 USD.
+const UNISWAP_V3_SLOT0: &[u8] = include_bytes!("fixtures/uniswap_v3_slot0.json");
+/// Batched `slot0()` and `liquidity()` of the same pool, answered out of order.
+const UNISWAP_V3_DEPTH: &[u8] = include_bytes!("fixtures/uniswap_v3_depth.json");
+/// `getReserves()` of a USDC/WETH pool with ETH at 2000 USD.
+const UNISWAP_V2_RESERVES: &[u8] = include_bytes!("fixtures/uniswap_v2_reserves.json");
+/// `latestRoundData()` of the ETH/USD aggregator at 2500 USD.
+const CHAINLINK_LATEST_ROUND: &[u8] = include_bytes!("fixtures/chainlink_latest_round.json");
+/// `getAccountInfo` of a SOL/USDC Whirlpool with SOL at 150 USD.
+const ORCA_WHIRLPOOL: &[u8] = include_bytes!("fixtures/orca_whirlpool.json");
+
+/// USDC/WETH pools, pricing token1.
+const USDC_WETH: PoolMetadata =
+	PoolMetadata { token0_decimals: 6, token1_decimals: 18, base_is_token0: false };
+/// SOL/USDC Whirlpools, pricing token0.
+const SOL_USDC: PoolMetadata =
+	PoolMetadata { token0_decimals: 9, token1_decimals: 6, base_is_token0: true };
+/// Chainlink feeds, see `ChainlinkProtocol`.
+const FEED: PoolMetadata =
+	PoolMetadata { token0_decimals: 0, token1_decimals: 8, base_is_token0: true };
+
+/// The response, protocol and pool layout of each price read.
+fn price_reads() -> [(&'static str, &'static [u8], ProtocolKind, PoolMetadata); 5] {
+	[
+		("uniswap_v3", UNISWAP_V3_SLOT0, ProtocolKind::UniswapV3, USDC_WETH),
+		("uniswap_v2", UNISWAP_V2_RESERVES, ProtocolKind::UniswapV2, USDC_WETH),
+		("traderjoe", UNISWAP_V2_RESERVES, ProtocolKind::TraderJoe, USDC_WETH),
+		("chainlink", CHAINLINK_LATEST_ROUND, ProtocolKind::Chainlink, FEED),
+		("orca_whirlpool", ORCA_WHIRLPOOL, ProtocolKind::OrcaWhirlpool, SOL_USDC),
+	]
+}
+
+/// The return data of the price read of a pool in `response`.
+fn result_data(kind: ProtocolKind, response: &[u8]) -> Vec<u8> {
+	match kind {
+		ProtocolKind::OrcaWhirlpool => json::account_data(response, 1),
+		_ => json::hex_result(response, 1),
+	}
+	.expect("fixtures are valid responses")
+}
+
+fn extract_result_data(c: &mut Criterion) {
+	let mut group = c.benchmark_group("extract_result_data");
+	group.bench_function("eth_call", |b| {
+		b.iter(|| json::hex_result(black_box(UNISWAP_V3_SLOT0), 1))
+	});
+	group.bench_function("eth_call_batch", |b| {
+		b.iter(|| json::hex_results(black_box(UNISWAP_V3_DEPTH), [2, 3]))
+	});
+	group.bench_function("get_account_info", |b| {
+		b.iter(|| json::account_data(black_box(ORCA_WHIRLPOOL), 1))
+	});
+	group.finish();
+}
+
+fn abi_decoding(c: &mut Criterion) {
+	let data = result_data(ProtocolKind::Chainlink, CHAINLINK_LATEST_ROUND);
+	let mut group = c.benchmark_group("abi_decoding");
+	group.bench_function("word", |b| b.iter(|| fixed::word(black_box(&data), 1)));
+	group.bench_function("signed_word", |b| b.iter(|| fixed::signed_word(black_box(&data), 1)));
+	group.finish();
+}
+
+fn parse_price(c: &mut Criterion) {
+	let mut group = c.benchmark_group("parse_price");
+	for (name, response, kind, pool) in price_reads() {
+		let data = result_data(kind, response);
+		kind.parse_price(&data, &pool).expect("fixtures are priced");
+		group.bench_with_input(BenchmarkId::from_parameter(name), &data, |b, data| {
+			b.iter(|| kind.parse_price(black_box(data), &pool))
+		});
+	}
+	group.finish();
+}
+
+fn parse_depth(c: &mut Criterion) {
+	let data = json::hex_results(UNISWAP_V3_DEPTH, [2, 3]).expect("fixture is a valid batch");
+	let account = vec![result_data(ProtocolKind::OrcaWhirlpool, ORCA_WHIRLPOOL)];
+	let reserves = vec![result_data(ProtocolKind::UniswapV2, UNISWAP_V2_RESERVES)];
+	let mut group = c.benchmark_group("parse_depth");
+	for (name, data, kind, pool) in [
+		("uniswap_v3", &data, ProtocolKind::UniswapV3, USDC_WETH),
+		("uniswap_v2", &reserves, ProtocolKind::UniswapV2, USDC_WETH),
+		("orca_whirlpool", &account, ProtocolKind::OrcaWhirlpool, SOL_USDC),
+	] {
+		kind.parse_depth(data, &pool).expect("fixtures have a depth");
+		group.bench_with_input(BenchmarkId::from_parameter(name), data, |b, data| {
+			b.iter(|| kind.parse_depth(black_box(data), &pool))
+		});
+	}
+	group.finish();
+}
+
+/// The whole parse path of a price read, as the offchain worker runs it per pool.
+fn response_to_price(c: &mut Criterion) {
+	let mut group = c.benchmark_group("response_to_price");
+	for (name, response, kind, pool) in price_reads() {
+		group.bench_with_input(BenchmarkId::from_parameter(name), response, |b, response| {
+			b.iter(|| kind.parse_price(&result_data(kind, black_box(response)), &pool))
+		});
+	}
+	group.finish();
+}
+
+criterion_group!(
+	benches,
+	extract_result_data,
+	abi_decoding,
+	parse_price,
+	parse_depth,
+	response_to_price
+);
+criterion_main!(benches);
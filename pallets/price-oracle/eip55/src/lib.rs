@@ -0,0 +1,151 @@
+//! EIP-55 mixed-case checksum encoding of EVM addresses.
+//!
+//! Addresses are handled as raw 20 byte arrays and only turned into text at the edges: [`parse`]
+//! validates user supplied addresses and [`encode`] produces the canonical checksummed form.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::{fmt, str};
+use sp_crypto_hashing::keccak_256;
+
+/// A raw EVM address.
+pub type Address = [u8; 20];
+
+/// Why a string is not a valid address.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Error {
+	/// Not `0x` followed by 40 hex digits.
+	InvalidLength,
+	/// Contains a character that is not a hex digit.
+	InvalidCharacter,
+	/// Mixed case that doesn't match the EIP-55 checksum.
+	InvalidChecksum,
+}
+
+/// The checksummed textual form of an address, `0x` followed by 40 hex digits.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Checksummed([u8; 42]);
+
+impl Checksummed {
+	/// The address as a string.
+	pub fn as_str(&self) -> &str {
+		// Only ever filled with ASCII.
+		str::from_utf8(&self.0).unwrap_or_default()
+	}
+}
+
+impl fmt::Display for Checksummed {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+impl fmt::Debug for Checksummed {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+/// Encode `address` with the EIP-55 checksum.
+pub fn encode(address: &Address) -> Checksummed {
+	const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+	let mut lower = [0u8; 40];
+	for (i, byte) in address.iter().enumerate() {
+		lower[2 * i] = DIGITS[usize::from(byte >> 4)];
+		lower[2 * i + 1] = DIGITS[usize::from(byte & 0xf)];
+	}
+	let hash = keccak_256(&lower);
+
+	let mut out = [0u8; 42];
+	out[..2].copy_from_slice(b"0x");
+	for (i, digit) in lower.iter().enumerate() {
+		let nibble = if i % 2 == 0 { hash[i / 2] >> 4 } else { hash[i / 2] & 0xf };
+		out[i + 2] = if nibble >= 8 { digit.to_ascii_uppercase() } else { *digit };
+	}
+	Checksummed(out)
+}
+
+/// Parse a `0x` prefixed address.
+///
+/// All-lowercase and all-uppercase addresses carry no checksum and are accepted as is; mixed case
+/// addresses must match their EIP-55 checksum.
+pub fn parse(text: &str) -> Result<Address, Error> {
+	let digits = text.strip_prefix("0x").ok_or(Error::InvalidLength)?.as_bytes();
+	if digits.len() != 40 {
+		return Err(Error::InvalidLength)
+	}
+
+	let mut address = [0u8; 20];
+	for (i, pair) in digits.chunks(2).enumerate() {
+		address[i] = (nibble(pair[0])? << 4) | nibble(pair[1])?;
+	}
+
+	let has_lower = digits.iter().any(u8::is_ascii_lowercase);
+	let has_upper = digits.iter().any(u8::is_ascii_uppercase);
+	if has_lower && has_upper && encode(&address).0[2..] != *digits {
+		return Err(Error::InvalidChecksum)
+	}
+	Ok(address)
+}
+
+/// Parse an address and return its checksummed form.
+pub fn normalize(text: &str) -> Result<Checksummed, Error> {
+	parse(text).map(|address| encode(&address))
+}
+
+fn nibble(digit: u8) -> Result<u8, Error> {
+	match digit {
+		b'0'..=b'9' => Ok(digit - b'0'),
+		b'a'..=b'f' => Ok(digit - b'a' + 10),
+		b'A'..=b'F' => Ok(digit - b'A' + 10),
+		_ => Err(Error::InvalidCharacter),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Test vectors from the EIP-55 specification.
+	const CHECKSUMMED: &[&str] = &[
+		"0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+		"0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+		"0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+		"0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+		"0x52908400098527886E0F7030069857D2E4169EE7",
+		"0xde709f2102306220921060314715629080e2fb77",
+	];
+
+	#[test]
+	fn encodes_specification_vectors() {
+		for text in CHECKSUMMED {
+			let address = parse(text).unwrap();
+			assert_eq!(encode(&address).as_str(), *text);
+		}
+	}
+
+	#[test]
+	fn normalizes_unchecksummed_input() {
+		for text in CHECKSUMMED {
+			assert_eq!(normalize(&text.to_lowercase()).unwrap().as_str(), *text);
+			let upper = format!("0x{}", text[2..].to_uppercase());
+			assert_eq!(normalize(&upper).unwrap().as_str(), *text);
+		}
+	}
+
+	#[test]
+	fn rejects_invalid_addresses() {
+		// Flipped case of the first letter.
+		assert_eq!(
+			parse("0x5AAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"),
+			Err(Error::InvalidChecksum)
+		);
+		assert_eq!(parse("5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"), Err(Error::InvalidLength));
+		assert_eq!(parse("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA"), Err(Error::InvalidLength));
+		assert_eq!(
+			parse("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeg"),
+			Err(Error::InvalidCharacter)
+		);
+	}
+}
@@ -0,0 +1,134 @@
+//! Runtime API definition for the price oracle pallet.
+//!
+//! Lets node RPC layers and tooling query the oracle without knowing its storage layout or the
+//! values a particular runtime configured it with.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
+use pallet_price_oracle::{
+	exchanges::ExchangeId, ArbitrageOpportunity, BestVenues, ChainId, LiquidPrice, OracleStatus,
+	PairId, PairInfo, Price, PriceAggregate, ReadCounter,
+};
+use scale_info::TypeInfo;
+
+/// Effective configuration of the price oracle in a runtime.
+#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo)]
+pub struct OracleConfig {
+	/// Number of blocks between offchain worker price fetches.
+	pub update_interval: u32,
+	/// Timeout of a single offchain HTTP request, in milliseconds.
+	pub http_timeout_ms: u64,
+	/// Maximum number of exchanges the offchain worker queries in one block.
+	pub max_exchanges_per_block: u8,
+	/// Age after which a stored exchange price is ignored, in milliseconds.
+	pub max_price_age_ms: u64,
+	/// Maximum distance between the observation time of a submission and the on-chain time, in
+	/// milliseconds.
+	pub max_submission_delay_ms: u64,
+	/// The range submissions of each tracked pair are currently accepted in.
+	pub pair_bounds: Vec<PairBounds>,
+}
+
+/// Prices of a pair accepted by the oracle, including its dynamic bounds.
+#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo)]
+pub struct PairBounds {
+	/// The pair.
+	pub pair_id: PairId,
+	/// Lowest accepted price, with 18 decimals.
+	pub min_price: Price,
+	/// Highest accepted price, with 18 decimals.
+	pub max_price: Price,
+}
+
+sp_api::decl_runtime_apis! {
+	/// API for querying the price oracle.
+	pub trait PriceOracleApi {
+		/// Return the oracle configuration of the runtime.
+		fn get_oracle_config() -> OracleConfig;
+
+		/// Median price of a pair across exchanges and the observation time of its newest
+		/// source in unix milliseconds, unless that is too old to be used.
+		fn latest_price(pair_id: PairId) -> Option<(Price, u64)>;
+
+		/// The last aggregate of a pair's exchange prices, however old.
+		fn aggregated_price(pair_id: PairId) -> Option<PriceAggregate>;
+
+		/// The fresh prices of a pair on every exchange, as `(exchange_id, price, timestamp)`.
+		fn all_prices(pair_id: PairId) -> Vec<(ExchangeId, Price, u64)>;
+
+		/// The tracked pairs with their ids.
+		fn tracked_pairs() -> Vec<(PairId, PairInfo)>;
+
+		/// How often the prices of each tracked pair were read by other pallets in the current
+		/// and the previous read period.
+		fn pair_reads() -> Vec<(PairId, ReadCounter)>;
+
+		/// Page of the aggregated prices of a pair kept on-chain, as `(median, block)`, that were
+		/// aggregated in blocks `from_block..=to_block` and are at least `step` blocks apart.
+		///
+		/// At most `pallet_price_oracle::MAX_HISTORY_PAGE` entries are returned; request the next
+		/// page from `step` blocks after the last one. Older prices are only kept in the offchain
+		/// database of archive nodes with offchain indexing enabled, under
+		/// `pallet_price_oracle::history_key`.
+		fn get_history(pair_id: PairId, from_block: u32, to_block: u32, step: u32) -> Vec<(Price, u32)>;
+
+		/// The SCALE encoded `pallet_price_oracle::registry::RegistrySnapshot` of the exchanges
+		/// and tracked pairs, to replicate the configuration on another chain with the
+		/// `import_registry` call.
+		fn export_registry() -> Vec<u8>;
+
+		/// The per-chain aggregates of a pair its global aggregate was combined from, by chain id.
+		fn chain_prices(pair_id: PairId) -> Vec<(ChainId, PriceAggregate)>;
+
+		/// Time between the oldest and the newest of the latest observations of the chains a pair
+		/// is priced on, in milliseconds, if it is priced on more than one.
+		fn sample_skew(pair_id: PairId) -> Option<u64>;
+
+		/// The cheapest and the dearest exchange of a pair with their spread, unless one of their
+		/// prices is stale.
+		fn best_venues(pair_id: PairId) -> Option<BestVenues>;
+
+		/// The last aggregates of several pairs, in order, all from the same block.
+		fn read_many(pair_ids: Vec<PairId>) -> Vec<Option<PriceAggregate>>;
+
+		/// Whether the pair has a freshness SLA and its aggregated price meets it.
+		fn meets_sla(pair_id: PairId) -> bool;
+
+		/// Whether the pair is new and its price isn't served until it completes its bootstrap.
+		fn is_bootstrapping(pair_id: PairId) -> bool;
+
+		/// The spread the exchanges of the pair offered to arbitrage at its last aggregation,
+		/// unless one of their prices is stale.
+		fn arbitrage_opportunity(pair_id: PairId) -> Option<ArbitrageOpportunity>;
+
+		/// The fresh prices of a pair on every exchange with the notional in whole USD of their
+		/// pools, leaving out pools holding less than `min_liquidity`.
+		fn liquid_prices(pair_id: PairId, min_liquidity: u128) -> Vec<(ExchangeId, LiquidPrice)>;
+
+		/// Health of the whole oracle in one call: its pairs by freshness, its exchanges by
+		/// whether they have fresh prices, the time of its newest aggregate and its open
+		/// arbitrage opportunities.
+		fn oracle_status() -> OracleStatus;
+
+		/// Price of `base` in `quote` by the tickers of their tokens and its observation time in
+		/// unix milliseconds, derived as the inverse or the cross of tracked pairs if the pair
+		/// isn't tracked itself, unless a price it is derived from is too old to be used.
+		fn derived_price(base: Vec<u8>, quote: Vec<u8>) -> Option<(Price, u64)>;
+
+		/// Keys of the exchange prices of a pair accepted in blocks `from_block..=to_block` in
+		/// the offchain database of nodes with offchain indexing enabled, with their blocks,
+		/// under `pallet_price_oracle::price_key`.
+		///
+		/// The keys are those of the exchanges the pair is priced on now, not those indexed, so
+		/// callers must handle keys without a value: no price was accepted on the exchange in the
+		/// block, or the block was imported without offchain indexing.
+		///
+		/// Whole blocks of at most `pallet_price_oracle::MAX_HISTORY_PAGE` keys are returned;
+		/// request the next page from the block after the last key.
+		fn indexed_price_keys(pair_id: PairId, from_block: u32, to_block: u32) -> Vec<(u32, Vec<u8>)>;
+	}
+}
@@ -0,0 +1,110 @@
+//! Combining the prices of several exchanges into one.
+
+use crate::{
+	fixed::Price,
+	rounding::{MedianTie, Rounding, RoundingPolicy},
+	types::PriceAggregate,
+};
+use alloc::vec::Vec;
+use sp_core::U256;
+
+/// Median and mean of the `(price, timestamp)` observations of some sources, with the time of the
+/// newest one, rounded by `policy`.
+pub fn aggregate(observations: &[(Price, u64)], policy: RoundingPolicy) -> Option<PriceAggregate> {
+	let (mut prices, timestamps): (Vec<_>, Vec<_>) = observations.iter().copied().unzip();
+	Some(PriceAggregate {
+		mean: mean(&prices, policy.rounding)?,
+		median: median(&mut prices, policy)?,
+		sources: prices.len() as u32,
+		timestamp: timestamps.into_iter().max().unwrap_or_default(),
+	})
+}
+
+/// Median of `prices`, for an even count the middle value picked by `policy.median_tie`. Sorts
+/// `prices`.
+pub fn median(prices: &mut [Price], policy: RoundingPolicy) -> Option<Price> {
+	prices.sort_unstable();
+	let middle = prices.len() / 2;
+	match prices.len() {
+		0 => None,
+		len if len % 2 == 1 => Some(prices[middle]),
+		_ => Some(match policy.median_tie {
+			MedianTie::Mean => midpoint(prices[middle - 1], prices[middle], policy.rounding),
+			MedianTie::Lower => prices[middle - 1],
+			MedianTie::Upper => prices[middle],
+		}),
+	}
+}
+
+/// Arithmetic mean of `prices`, rounded by `rounding`.
+pub fn mean(prices: &[Price], rounding: Rounding) -> Option<Price> {
+	let len = prices.len() as u128;
+	if len == 0 {
+		return None
+	}
+	// Sum quotients and remainders apart so the sum can't overflow.
+	let (quotients, remainders) = prices
+		.iter()
+		.fold((0u128, 0u128), |(q, r), price| (q + price / len, r + price % len));
+	Some(rounding.round(quotients + remainders / len, remainders % len, len))
+}
+
+/// Distance of `price` from `reference` in basis points of `reference`, saturating at
+/// `u32::MAX`.
+pub fn deviation_bps(price: Price, reference: Price) -> u32 {
+	if reference == 0 {
+		return u32::MAX
+	}
+	let bps = U256::from(price.abs_diff(reference)) * U256::from(10_000u32) / U256::from(reference);
+	bps.try_into().unwrap_or(u32::MAX)
+}
+
+/// The median of `prices` and which of them are outliers: more than `mads` median absolute
+/// deviations and more than `floor_bps` basis points from the median. `None` for fewer than three
+/// prices, which have no outliers, and for `mads` of zero.
+pub fn outliers(
+	prices: &[Price],
+	mads: u32,
+	floor_bps: u32,
+	policy: RoundingPolicy,
+) -> Option<(Price, Vec<bool>)> {
+	if mads == 0 || prices.len() < 3 {
+		return None
+	}
+	let center = median(&mut prices.to_vec(), policy)?;
+	let mut deviations: Vec<_> = prices.iter().map(|price| price.abs_diff(center)).collect();
+	let limit = median(&mut deviations, policy)?.saturating_mul(mads.into());
+	let outliers = prices
+		.iter()
+		.map(|price| price.abs_diff(center) > limit && deviation_bps(*price, center) > floor_bps)
+		.collect();
+	Some((center, outliers))
+}
+
+fn midpoint(a: Price, b: Price, rounding: Rounding) -> Price {
+	rounding.round(a / 2 + b / 2 + (a % 2 + b % 2) / 2, (a % 2 + b % 2) % 2, 2)
+}
+
+/// Time-weighted average of a price over `[from, now]`.
+///
+/// `history` holds `(price, time)` points in ascending time order; each price holds until the
+/// time of the next point, the last one until `now`. The window is cut to the first point if it
+/// starts earlier. Without any elapsed time the latest price is returned. The average is rounded
+/// by `rounding`.
+pub fn twap(history: &[(Price, u64)], from: u64, now: u64, rounding: Rounding) -> Option<Price> {
+	let (latest, _) = *history.last()?;
+	let mut weighted = U256::zero();
+	let mut elapsed = 0u64;
+	let ends = history.iter().skip(1).map(|(_, time)| *time).chain(core::iter::once(now));
+	for ((price, start), end) in history.iter().zip(ends) {
+		let duration = end.min(now).saturating_sub((*start).max(from));
+		weighted += U256::from(*price) * U256::from(duration);
+		elapsed += duration;
+	}
+	if elapsed == 0 {
+		return Some(latest)
+	}
+	// The average of prices is a price, so it fits, and the remainder is below `elapsed`.
+	let (average, remainder) = weighted.div_mod(U256::from(elapsed));
+	Some(rounding.round(average.low_u128(), remainder.low_u128(), elapsed.into()))
+}
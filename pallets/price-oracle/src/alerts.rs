@@ -0,0 +1,56 @@
+//! Price alerts: one-shot notifications of a price moving far enough from a target.
+//!
+//! An account registers at most one alert per pair with [`Pallet::set_alert`], up to
+//! [`Config::MaxAlertsPerPair`] alerts per pair, and pays [`Config::AlertDeposit`] for it into
+//! [`AlertDeposits`]. Once an aggregated median moves [`PriceAlert::threshold_bps`] away from
+//! the target of an alert, [`Event::AlertTriggered`] is emitted and the alert is removed,
+//! releasing its deposit.
+
+use crate::{aggregation, AlertDeposits, Alerts, Config, Event, PairId, Pallet, Price, PriceAlert};
+use frame_support::{traits::ReservableCurrency, BoundedVec};
+use sp_runtime::traits::Zero;
+
+impl<T: Config> Pallet<T> {
+	/// Emit and remove the alerts of `pair_id` whose threshold `median` reaches, releasing
+	/// their deposits.
+	pub(crate) fn trigger_alerts(pair_id: PairId, median: Price) {
+		let mut alerts = Alerts::<T>::get(pair_id);
+		let registered = alerts.len();
+		alerts.retain(|alert| {
+			let move_bps = aggregation::deviation_bps(median, alert.target);
+			if move_bps < alert.threshold_bps {
+				return true
+			}
+			Self::release_alert_deposit(pair_id, &alert.who);
+			Self::deposit_event(Event::AlertTriggered {
+				who: alert.who.clone(),
+				pair_id,
+				move_bps,
+			});
+			false
+		});
+		if alerts.len() < registered {
+			Self::store_alerts(pair_id, alerts);
+		}
+	}
+
+	/// Unreserve the [`AlertDeposits`] of `who` for its alert on `pair_id`.
+	pub(crate) fn release_alert_deposit(pair_id: PairId, who: &T::AccountId) {
+		let deposit = AlertDeposits::<T>::take(pair_id, who);
+		if !deposit.is_zero() {
+			T::Currency::unreserve(who, deposit);
+		}
+	}
+
+	/// Store the alerts of `pair_id`, removing the entry once none are left.
+	pub(crate) fn store_alerts(
+		pair_id: PairId,
+		alerts: BoundedVec<PriceAlert<T::AccountId>, T::MaxAlertsPerPair>,
+	) {
+		if alerts.is_empty() {
+			Alerts::<T>::remove(pair_id);
+		} else {
+			Alerts::<T>::insert(pair_id, alerts);
+		}
+	}
+}
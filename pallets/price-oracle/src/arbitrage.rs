@@ -0,0 +1,81 @@
+//! Arbitrage between the exchanges of a pair.
+//!
+//! A spread between two exchange prices is only an opportunity if the cheaper exchange actually
+//! sells at its quoted price in the size of the trade. Thin or manipulated pools quote prices no
+//! trade can be filled at; the offchain worker simulates the buy leg with
+//! [`ExchangeConfig::simulate_buy`](crate::exchanges::ExchangeConfig::simulate_buy) and drops
+//! spreads the simulation doesn't [`confirm`](confirms_spread) as phantom quotes.
+//!
+//! On-chain, every aggregation compares the exchanges of the pair and records the spread between
+//! the cheapest and the dearest one as an [`opportunity`] once it reaches
+//! [`Config::MinSpreadBps`](crate::Config::MinSpreadBps) and the spread on
+//! [`Config::ArbitrageTradeSize`](crate::Config::ArbitrageTradeSize) pays for the
+//! [`gas_cost`] of swapping on both exchanges.
+
+use crate::{
+	aggregation,
+	error::OracleError,
+	fixed::{self, Price, PRICE_UNIT},
+	types::{ArbitrageOpportunity, BestVenues, GasFees, PoolMetadata},
+};
+use sp_core::U512;
+
+/// The opportunity of buying on the cheapest and selling on the dearest of `venues`, if their
+/// spread is at least `min_spread_bps` basis points.
+pub fn opportunity(venues: &BestVenues, min_spread_bps: u32) -> Option<ArbitrageOpportunity> {
+	let (ask, bid) = (venues.ask, venues.bid);
+	(ask.exchange_id != bid.exchange_id && venues.spread_bps >= min_spread_bps).then(|| {
+		ArbitrageOpportunity {
+			buy_exchange: ask.exchange_id,
+			sell_exchange: bid.exchange_id,
+			spread_bps: venues.spread_bps,
+			timestamp: ask.timestamp.min(bid.timestamp),
+		}
+	})
+}
+
+/// Price paid per whole base token when `amount_in` raw units of the quote token buy
+/// `amount_out` raw units of the base token of a pool laid out as `pool`.
+pub fn effective_price(
+	amount_in: u128,
+	amount_out: u128,
+	pool: &PoolMetadata,
+) -> Result<Price, OracleError> {
+	fixed::ratio(U512::from(amount_in), U512::from(amount_out), pool.decimals_delta())
+}
+
+/// Whether buying `amount_out` base tokens for `amount_in` quote tokens, as simulated on the
+/// buy side, and selling them at `sell_price` still leaves a spread of at least
+/// `min_spread_bps` basis points.
+///
+/// A simulation that buys nothing confirms no spread.
+pub fn confirms_spread(
+	sell_price: Price,
+	amount_in: u128,
+	amount_out: u128,
+	pool: &PoolMetadata,
+	min_spread_bps: u32,
+) -> bool {
+	match effective_price(amount_in, amount_out, pool) {
+		Ok(buy_price) if buy_price > 0 && buy_price < sell_price =>
+			aggregation::deviation_bps(sell_price, buy_price) >= min_spread_bps,
+		_ => false,
+	}
+}
+
+/// Number of decimals of the gas token of every EVM chain, wei per whole token.
+pub const NATIVE_DECIMALS: i32 = 18;
+
+/// Cost of `gas` units at the base fee and [`GasFees::competitive_tip`] of `fees`, in the quote
+/// of `native_price`, the price of the gas token. Saturates if the cost doesn't fit a [`Price`].
+pub fn gas_cost(gas: u64, fees: &GasFees, native_price: Price) -> Price {
+	let fee_per_gas = U512::from(fees.base_fee) + U512::from(fees.competitive_tip());
+	let wei_value = U512::from(gas) * fee_per_gas * U512::from(native_price);
+	fixed::ratio(wei_value, U512::from(PRICE_UNIT), -NATIVE_DECIMALS).unwrap_or(Price::MAX)
+}
+
+/// Whether a spread of `spread_bps` basis points on a trade of `trade_size` pays for `gas_cost`,
+/// both in the quote of the pair.
+pub fn covers_gas(trade_size: Price, spread_bps: u32, gas_cost: Price) -> bool {
+	trade_size.saturating_mul(Price::from(spread_bps)) / 10_000 >= gas_cost
+}
@@ -0,0 +1,1039 @@
+//! Benchmarks of the price oracle pallet.
+
+use super::*;
+use crate::{
+	exchanges::{ExchangeConfig, ExchangeId},
+	fixed::PRICE_UNIT,
+	protocols::ProtocolKind,
+	types::{
+		Confirmation, StateOverride, Symbol, MAX_OVERRIDE_CODE_LEN, MAX_OVERRIDE_SLOTS,
+		MAX_POOLS_PER_PAIR, MAX_STATE_OVERRIDES, MAX_SYMBOL_LEN, MAX_URL_LEN,
+	},
+};
+use alloc::{format, vec};
+use codec::Encode;
+use frame_benchmarking::v2::*;
+use frame_support::{
+	traits::{Currency, EnsureOrigin, Get, Hooks, ReservableCurrency, SortedMembers},
+	BoundedBTreeMap, BoundedVec,
+};
+use frame_system::{pallet_prelude::BlockNumberFor, RawOrigin};
+use sp_core::U256;
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	Permill,
+};
+
+/// On-chain time of the benchmarks, in unix milliseconds.
+const NOW: u64 = 1_700_000_000_000;
+
+/// Price submitted and kept in the history of [`bench_pair`].
+const PRICE: u128 = 2_500;
+
+/// The ETH/USD pair with the largest configuration: dynamic bounds and a pool on as many
+/// exchanges as a pair can have.
+fn bench_pair() -> PairInfo {
+	let mut pair = registry::default_pairs().remove(0);
+	let (_, pool) = pair.pools[0].clone();
+	pair.min_price = 1;
+	pair.max_price = Price::MAX;
+	pair.decimals = 0;
+	pair.dynamic_bounds = Some(DynamicBounds { deviation: Permill::from_percent(10), window: 1 });
+	pair.pools = BoundedVec::truncate_from(
+		(0..MAX_POOLS_PER_PAIR as ExchangeId).map(|id| (id, pool.clone())).collect(),
+	);
+	pair
+}
+
+/// Configuration of a registered exchange.
+fn bench_exchange() -> ExchangeConfig {
+	registry::default_exchanges().remove(0).1
+}
+
+/// SLA of a pair priced from several exchanges within a minute.
+fn bench_sla() -> FreshnessSla {
+	FreshnessSla { max_staleness: 60_000, min_sources: 3, max_spread_bps: 100 }
+}
+
+/// Register the exchanges of the pools of [`bench_pair`].
+fn register_pool_exchanges<T: Config>() {
+	for (exchange_id, _) in bench_pair().pools {
+		Exchanges::<T>::insert(exchange_id, bench_exchange());
+	}
+}
+
+/// Track [`bench_pair`] with a full price history, one entry per block up to the current block,
+/// and register exchange `0`, returning the pair.
+fn setup_submission<T: Config>() -> PairId {
+	T::BenchmarkHelper::set_now(NOW);
+	let pair = bench_pair();
+	let pair_id = Pallet::<T>::pair_id_of(&pair);
+	TrackedPairs::<T>::insert(pair_id, pair);
+	// Long-tail pairs cost the most: their bounds and pool notionals are read too.
+	LongTailPairs::<T>::insert(pair_id, 1);
+	Exchanges::<T>::insert(0, bench_exchange());
+	let blocks = T::MaxHistory::get();
+	frame_system::Pallet::<T>::set_block_number(blocks.into());
+	let history = (0..blocks).map(|block| (PRICE * PRICE_UNIT, block.into()));
+	PriceHistory::<T>::insert(pair_id, history.collect::<DeltaHistory<_, _>>());
+	pair_id
+}
+
+/// Register `who` as an operator with its [`Config::OperatorBond`] reserved.
+fn bond_operator<T: Config>(who: &T::AccountId) {
+	let bond = T::OperatorBond::get();
+	T::Currency::make_free_balance_be(who, bond.saturating_add(T::Currency::minimum_balance()));
+	T::Currency::reserve(who, bond).unwrap();
+	OperatorBonds::<T>::insert(who, bond);
+	Operators::<T>::insert(who, ());
+}
+
+/// Register all but `free` of the alerts a pair can have, each triggered by any price move and
+/// paid its deposit for.
+fn fill_alerts<T: Config>(pair_id: PairId, free: u32) {
+	let alerts = (0..T::MaxAlertsPerPair::get().saturating_sub(free))
+		.map(|i| {
+			let who = account("alert", i, 0);
+			reserve_alert_deposit::<T>(pair_id, &who);
+			PriceAlert { who, threshold_bps: 1, target: 1 }
+		})
+		.collect::<Vec<_>>();
+	Alerts::<T>::insert(pair_id, BoundedVec::truncate_from(alerts));
+}
+
+/// Fund `who` with the deposit of an alert on `pair_id` and reserve it.
+fn reserve_alert_deposit<T: Config>(pair_id: PairId, who: &T::AccountId) {
+	let deposit = T::AlertDeposit::get();
+	let free = T::Currency::free_balance(who).max(T::Currency::minimum_balance());
+	T::Currency::make_free_balance_be(who, free.saturating_add(deposit));
+	T::Currency::reserve(who, deposit).unwrap();
+	AlertDeposits::<T>::insert(pair_id, who, deposit);
+}
+
+/// The origin of a consumer served by the pallet, with its location.
+fn subscriber_origin<T: Config>() -> Result<(T::RuntimeOrigin, T::Subscriber), BenchmarkError> {
+	let origin =
+		T::SubscriberOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+	let subscriber = T::SubscriberOrigin::ensure_origin(origin.clone())
+		.map_err(|_| BenchmarkError::Weightless)?;
+	Ok((origin, subscriber))
+}
+
+fn registry_origin<T: Config>() -> Result<T::RuntimeOrigin, BenchmarkError> {
+	T::RegistryOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn submit_price_unsigned() -> Result<(), BenchmarkError> {
+		// Runtimes only accepting signed submissions never dispatch it.
+		if !T::AcceptUnsigned::get() {
+			return Err(BenchmarkError::Weightless)
+		}
+		let pair_id = setup_submission::<T>();
+
+		#[extrinsic_call]
+		_(RawOrigin::None, pair_id, 0, PRICE, 0, NOW, Some(1), Some(1));
+
+		assert_eq!(PriceData::<T>::get(pair_id, 0), Some((PRICE * PRICE_UNIT, NOW)));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn submit_price_signed() -> Result<(), BenchmarkError> {
+		// Runtimes with commit-reveal never dispatch it.
+		if T::RevealWindow::get() > 0 {
+			return Err(BenchmarkError::Weightless)
+		}
+		let pair_id = setup_submission::<T>();
+		let caller: T::AccountId = whitelisted_caller();
+		Operators::<T>::insert(&caller, ());
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), pair_id, 0, PRICE, 0, NOW, Some(1), Some(1));
+
+		assert_eq!(PriceData::<T>::get(pair_id, 0), Some((PRICE * PRICE_UNIT, NOW)));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn report_misbehavior() -> Result<(), BenchmarkError> {
+		// Runtimes without bonds never slash.
+		if T::MisbehaviorSlash::get().mul_floor(T::OperatorBond::get()).is_zero() {
+			return Err(BenchmarkError::Weightless)
+		}
+		let pair_id = setup_submission::<T>();
+		let who: T::AccountId = account("operator", 0, 0);
+		bond_operator::<T>(&who);
+		// Twice the median aggregated in the block of the price.
+		let price = 2 * PRICE * PRICE_UNIT;
+		SignedSubmissions::<T>::insert(
+			&who,
+			(pair_id, 0),
+			(price, BlockNumberFor::<T>::from(0u32)),
+		);
+		let caller: T::AccountId = whitelisted_caller();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), who.clone(), pair_id, 0);
+
+		assert!(OperatorBonds::<T>::get(&who) < T::OperatorBond::get());
+		Ok(())
+	}
+
+	#[benchmark]
+	fn commit_price() -> Result<(), BenchmarkError> {
+		// Runtimes without commit-reveal never dispatch it.
+		if T::RevealWindow::get() == 0 {
+			return Err(BenchmarkError::Weightless)
+		}
+		let pair_id = setup_submission::<T>();
+		let caller: T::AccountId = whitelisted_caller();
+		Operators::<T>::insert(&caller, ());
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), pair_id, 0, [1; 32]);
+
+		assert!(PriceCommitments::<T>::contains_key(&caller, (pair_id, 0)));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn reveal_price() -> Result<(), BenchmarkError> {
+		if T::RevealWindow::get() == 0 {
+			return Err(BenchmarkError::Weightless)
+		}
+		let pair_id = setup_submission::<T>();
+		let caller: T::AccountId = whitelisted_caller();
+		Operators::<T>::insert(&caller, ());
+		let (salt, observation) = ([1; 32], (NOW, Some(1), Some(1)));
+		let commitment = Pallet::<T>::price_commitment(PRICE, 0, observation, &salt);
+		let block = frame_system::Pallet::<T>::block_number();
+		PriceCommitments::<T>::insert(&caller, (pair_id, 0), (commitment, block));
+		frame_system::Pallet::<T>::set_block_number(block + 1u32.into());
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), pair_id, 0, PRICE, 0, NOW, Some(1), Some(1), salt);
+
+		assert_eq!(PriceData::<T>::get(pair_id, 0), Some((PRICE * PRICE_UNIT, NOW)));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn add_pair() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		let mut pair = bench_pair();
+		pair.base = BoundedVec::truncate_from(b"BENCH".to_vec());
+		let pair_id = Pallet::<T>::pair_id_of(&pair);
+		register_pool_exchanges::<T>();
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, pair);
+
+		assert!(TrackedPairs::<T>::contains_key(pair_id));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn update_pair() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		let pair = bench_pair();
+		let pair_id = Pallet::<T>::pair_id_of(&pair);
+		TrackedPairs::<T>::insert(pair_id, registry::default_pairs().remove(0));
+		register_pool_exchanges::<T>();
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, pair.clone());
+
+		assert_eq!(TrackedPairs::<T>::get(pair_id), Some(pair));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn remove_pair() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		let pair = bench_pair();
+		let pair_id = Pallet::<T>::pair_id_of(&pair);
+		for (exchange_id, pool) in &pair.pools {
+			PoolHops::<T>::insert(pair_id, exchange_id, pool);
+		}
+		TrackedPairs::<T>::insert(pair_id, pair);
+		fill_alerts::<T>(pair_id, 0);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, pair_id);
+
+		assert!(!TrackedPairs::<T>::contains_key(pair_id));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn add_exchange() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		Exchanges::<T>::remove(ExchangeId::MAX);
+		let config = ExchangeConfig {
+			name: BoundedVec::truncate_from(b"Bench".to_vec()),
+			..bench_exchange()
+		};
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, ExchangeId::MAX, config);
+
+		assert!(Exchanges::<T>::contains_key(ExchangeId::MAX));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_exchange_enabled() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		Exchanges::<T>::insert(0, bench_exchange());
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, 0, false);
+
+		assert_eq!(Exchanges::<T>::get(0).map(|config| config.enabled), Some(false));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn release_exchange() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		let until = frame_system::Pallet::<T>::block_number() + 1u32.into();
+		QuarantinedExchanges::<T>::insert(0, until);
+		ExchangeStats::<T>::insert(0, ReliabilityStats { failures: 1, ..Default::default() });
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, 0);
+
+		assert!(!QuarantinedExchanges::<T>::contains_key(0));
+		assert!(!ExchangeStats::<T>::contains_key(0));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_rpc_endpoints() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		let url = RpcUrl::truncate_from(vec![b'a'; MAX_URL_LEN as usize]);
+		let urls =
+			RpcUrls::truncate_from(vec![url; crate::types::MAX_ENDPOINTS_PER_CHAIN as usize]);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, 1, urls.clone());
+
+		assert_eq!(RpcEndpoints::<T>::get(1), Some(urls));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_router() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		Exchanges::<T>::insert(0, bench_exchange());
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, 0, Some([1; 20]));
+
+		assert_eq!(Routers::<T>::get(bench_exchange().key()), Some([1; 20]));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_pool_hop() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		let pair = bench_pair();
+		let pair_id = Pallet::<T>::pair_id_of(&pair);
+		let hop = pair.pools[0].1.clone();
+		TrackedPairs::<T>::insert(pair_id, pair);
+		Exchanges::<T>::insert(0, bench_exchange());
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, pair_id, 0, Some(hop));
+
+		assert!(PoolHops::<T>::contains_key(pair_id, 0));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_gas_tracking() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		let url = RpcUrl::truncate_from(vec![b'a'; MAX_URL_LEN as usize]);
+		RpcEndpoints::<T>::insert(1, RpcUrls::truncate_from(vec![url]));
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, 1, true);
+
+		assert!(GasTrackedChains::<T>::contains_key(1));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_builder_tips() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		GasTrackedChains::<T>::insert(1, ());
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, 1, true);
+
+		assert!(BuilderTipChains::<T>::contains_key(1));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_native_token() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		GasTrackedChains::<T>::insert(1, ());
+		let token = Symbol::truncate_from(vec![b'a'; MAX_SYMBOL_LEN as usize]);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, 1, Some(token));
+
+		assert!(NativeTokens::<T>::contains_key(1));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_multicall() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		let url = RpcUrl::truncate_from(vec![b'a'; MAX_URL_LEN as usize]);
+		RpcEndpoints::<T>::insert(1, RpcUrls::truncate_from(vec![url]));
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, 1, true);
+
+		assert!(MulticallChains::<T>::contains_key(1));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_confirmation() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		let url = RpcUrl::truncate_from(vec![b'a'; MAX_URL_LEN as usize]);
+		RpcEndpoints::<T>::insert(1, RpcUrls::truncate_from(vec![url]));
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, 1, Confirmation::Finalized);
+
+		assert_eq!(ChainConfirmations::<T>::get(1), Confirmation::Finalized);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn submit_verified_price(
+		n: Linear<2, { 2 * proofs::MAX_PROOF_NODES }>,
+	) -> Result<(), BenchmarkError> {
+		let pair_id = setup_submission::<T>();
+		let pool = bench_pair().pools[0].1.address;
+		// slot0 with ETH at 2500 USD, proven by full branch nodes split between the proofs.
+		let slot0 = U256::from(0x4e20) << 96;
+		let (account_depth, storage_depth) = ((n - n / 2 - 1) as usize, (n / 2 - 1) as usize);
+		let (state_root, account_proof, storage_proof) =
+			proofs::build_storage_proofs(&pool, U256::zero(), slot0, account_depth, storage_depth);
+		T::BenchmarkHelper::set_state_root(1, 2, state_root);
+		// Runtimes without a light client can't verify any price.
+		if <T::LightClient as proofs::LightClient>::state_root(1, 2).is_none() {
+			return Err(BenchmarkError::Weightless)
+		}
+		let verified = VerifiedPrice { price: PRICE * PRICE_UNIT, block: 1, timestamp: NOW };
+		VerifiedPrices::<T>::insert(pair_id, 0, verified);
+		let caller: T::AccountId = whitelisted_caller();
+
+		#[extrinsic_call]
+		_(
+			RawOrigin::Signed(caller),
+			pair_id,
+			0,
+			2,
+			BoundedVec::truncate_from(account_proof),
+			BoundedVec::truncate_from(storage_proof),
+		);
+
+		let verified = VerifiedPrices::<T>::get(pair_id, 0).map(|verified| verified.price);
+		assert_eq!(verified, Some(PRICE * PRICE_UNIT));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn submit_gas_fees() {
+		let caller: T::AccountId = whitelisted_caller();
+		Operators::<T>::insert(&caller, ());
+		GasTrackedChains::<T>::insert(1, ());
+		let fees = GasFees {
+			base_fee: 1,
+			priority_fee: 1,
+			builder_tip: Some(1),
+			block: 1,
+			timestamp: NOW,
+		};
+		GasData::<T>::insert(1, fees);
+		let fees = GasFees { block: 2, ..fees };
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), 1, fees);
+
+		assert_eq!(GasData::<T>::get(1), Some(fees));
+	}
+
+	#[benchmark]
+	fn report_fetch_failure() {
+		Exchanges::<T>::insert(0, bench_exchange());
+		let caller: T::AccountId = whitelisted_caller();
+		Operators::<T>::insert(&caller, ());
+
+		#[extrinsic_call]
+		_(
+			RawOrigin::Signed(caller),
+			Pallet::<T>::pair_id_of(&bench_pair()),
+			0,
+			OracleError::RpcError { code: i64::MIN },
+		);
+	}
+
+	#[benchmark]
+	fn set_twap_check() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		let pair_id = setup_submission::<T>();
+		let check = TwapCheck { window: 1_800, max_deviation: Permill::from_percent(2) };
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, pair_id, Some(check));
+
+		assert_eq!(TwapChecks::<T>::get(pair_id), Some(check));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_call_options() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		let state = StateOverride {
+			address: [0xaa; 20],
+			code: Some(BoundedVec::truncate_from(vec![0; MAX_OVERRIDE_CODE_LEN as usize])),
+			state_diff: BoundedVec::truncate_from(vec![
+				([0; 32], [0; 32]);
+				MAX_OVERRIDE_SLOTS as usize
+			]),
+		};
+		let options = CallOptions {
+			gas: Some(u64::MAX),
+			state_overrides: BoundedVec::truncate_from(vec![state; MAX_STATE_OVERRIDES as usize]),
+			confirmation: Confirmation::Finalized,
+		};
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, ProtocolKind::UniswapV3, Some(options.clone()));
+
+		assert_eq!(ProtocolCallOptions::<T>::get(ProtocolKind::UniswapV3), options);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_sla() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		let pair_id = setup_submission::<T>();
+		BreachedSlas::<T>::insert(pair_id, SlaBreach::Stale);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, pair_id, Some(bench_sla()));
+
+		assert_eq!(PairSlas::<T>::get(pair_id), Some(bench_sla()));
+		Ok(())
+	}
+
+	/// Checking the SLAs of `s` pairs, each of which starts falling short of it, the longest
+	/// path: every check reads the aggregate and the venues and records the breach.
+	#[benchmark]
+	fn check_slas(s: Linear<0, { T::MaxPairs::get() }>) {
+		T::BenchmarkHelper::set_now(NOW);
+		let median = PRICE * PRICE_UNIT;
+		let aggregate = PriceAggregate { mean: median, median, sources: 3, timestamp: NOW };
+		let quote = |exchange_id, price| VenueQuote { exchange_id, price, timestamp: NOW };
+		let venues =
+			BestVenues { ask: quote(0, median), bid: quote(1, median * 2), spread_bps: 10_000 };
+		for i in 0..s {
+			let pair_id = sp_io::hashing::blake2_256(&i.to_le_bytes());
+			PairSlas::<T>::insert(pair_id, bench_sla());
+			AggregatedPrice::<T>::insert(pair_id, aggregate);
+			PairVenues::<T>::insert(pair_id, venues);
+		}
+
+		let checked;
+		#[block]
+		{
+			checked = Pallet::<T>::check_slas();
+		}
+
+		assert_eq!(checked, s);
+		assert_eq!(BreachedSlas::<T>::iter().count() as u32, s);
+	}
+
+	/// Judging the intervals of `b` bootstrapping pairs, each of which completes its bootstrap,
+	/// the longest path: every pair's progress is removed and its price served.
+	#[benchmark]
+	fn advance_bootstraps(b: Linear<0, { T::MaxPairs::get() }>) {
+		let median = PRICE * PRICE_UNIT;
+		let aggregate = PriceAggregate { mean: median, median, sources: 3, timestamp: NOW };
+		let progress = BootstrapProgress {
+			intervals: T::BootstrapIntervals::get().saturating_sub(1),
+			last: Some((median, NOW - 1)),
+		};
+		for i in 0..b {
+			let pair_id = sp_io::hashing::blake2_256(&i.to_le_bytes());
+			Bootstrapping::<T>::insert(pair_id, progress);
+			AggregatedPrice::<T>::insert(pair_id, aggregate);
+		}
+
+		let judged;
+		#[block]
+		{
+			judged = Pallet::<T>::advance_bootstraps();
+		}
+
+		assert_eq!(judged, b);
+		assert_eq!(Bootstrapping::<T>::iter().count(), 0);
+		assert_eq!(LatestAggregates::<T>::get().len() as u32, b);
+	}
+
+	#[benchmark]
+	fn set_cross_chain_check() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		let pair_id = setup_submission::<T>();
+		let check = CrossChainCheck { threshold_bps: 50, max_skew: Some(12_000) };
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, pair_id, Some(check));
+
+		assert_eq!(CrossChainChecks::<T>::get(pair_id), Some(check));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_long_tail() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		let pair_id = setup_submission::<T>();
+		LowQualityPairs::<T>::insert(pair_id, ());
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, pair_id, None);
+
+		assert!(!LongTailPairs::<T>::contains_key(pair_id));
+		assert!(!LowQualityPairs::<T>::contains_key(pair_id));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn deprecate_pair() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		let pair_id = setup_submission::<T>();
+		let after_block = frame_system::Pallet::<T>::block_number() + 1u32.into();
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, pair_id, after_block);
+
+		assert_eq!(DeprecatedPairs::<T>::get(pair_id), Some(after_block));
+		Ok(())
+	}
+
+	/// Warning of the retirement of `d` pairs. Removing a retired pair is weighed as
+	/// [`Pallet::remove_pair`].
+	#[benchmark]
+	fn advance_deprecations(d: Linear<0, { T::MaxPairs::get() }>) {
+		let block_number = frame_system::Pallet::<T>::block_number();
+		for i in 0..d {
+			let pair_id = sp_io::hashing::blake2_256(&i.to_le_bytes());
+			DeprecatedPairs::<T>::insert(pair_id, block_number);
+		}
+
+		let advanced;
+		#[block]
+		{
+			advanced = Pallet::<T>::advance_deprecations(block_number);
+		}
+
+		assert_eq!(advanced, (d, 0));
+	}
+
+	#[benchmark]
+	fn set_fast_path() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		let pair_id = setup_submission::<T>();
+		FastPathUntil::<T>::insert(pair_id, frame_system::Pallet::<T>::block_number());
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, pair_id, None);
+
+		assert_eq!(FastPathUntil::<T>::get(pair_id), None);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_failure_mode() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		let pair_id = setup_submission::<T>();
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, pair_id, FailureMode::FailOpen);
+
+		assert_eq!(FailureModes::<T>::get(pair_id), FailureMode::FailOpen);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_pair_paused() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		let pair_id = setup_submission::<T>();
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, pair_id, true);
+
+		assert!(PausedPairs::<T>::contains_key(pair_id));
+		Ok(())
+	}
+
+	/// Remapping an exchange with a pool and a price in each of `p` tracked pairs, and a
+	/// commitment and evidence of each of `a` operators on them.
+	#[benchmark]
+	fn remap_exchange(
+		p: Linear<0, 100>,
+		a: Linear<0, { T::MaxOperators::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		T::BenchmarkHelper::set_now(NOW);
+		let _ = TrackedPairs::<T>::clear(u32::MAX, None);
+		let config = bench_exchange();
+		let key = config.key();
+		Exchanges::<T>::insert(0, config);
+		ExchangeKeys::<T>::insert(key, 0);
+		Exchanges::<T>::remove(ExchangeId::MAX);
+		let block: BlockNumberFor<T> = 1u32.into();
+		QuarantinedExchanges::<T>::insert(0, block);
+		let _ = Operators::<T>::clear(u32::MAX, None);
+		let operators: Vec<T::AccountId> = (0..a).map(|i| account("operator", i, 0)).collect();
+		for i in 0..p {
+			let mut pair = bench_pair();
+			pair.base = BoundedVec::truncate_from(i.to_le_bytes().to_vec());
+			let pair_id = Pallet::<T>::pair_id_of(&pair);
+			PoolHops::<T>::insert(pair_id, 0, &pair.pools[0].1);
+			TrackedPairs::<T>::insert(pair_id, pair);
+			PriceData::<T>::insert(pair_id, 0, (PRICE * PRICE_UNIT, NOW));
+			for who in &operators {
+				PriceCommitments::<T>::insert(who, (pair_id, 0), ([0; 32], block));
+				SignedSubmissions::<T>::insert(who, (pair_id, 0), (PRICE * PRICE_UNIT, block));
+			}
+		}
+		for who in &operators {
+			Operators::<T>::insert(who, ());
+		}
+		let accounts = a + T::Committee::sorted_members().len() as u32;
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, key, ExchangeId::MAX, p, accounts);
+
+		assert_eq!(ExchangeKeys::<T>::get(key), Some(ExchangeId::MAX));
+		assert!(QuarantinedExchanges::<T>::contains_key(ExchangeId::MAX));
+		Ok(())
+	}
+
+	/// Importing `e` exchanges with a router each and `p` pairs in place of as many others.
+	#[benchmark]
+	fn import_registry(
+		e: Linear<0, { ExchangeId::MAX as u32 + 1 }>,
+		p: Linear<0, { T::MaxPairs::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		let _ = TrackedPairs::<T>::clear(u32::MAX, None);
+		let _ = Exchanges::<T>::clear(u32::MAX, None);
+		let _ = ExchangeKeys::<T>::clear(u32::MAX, None);
+		let mut snapshot = registry::RegistrySnapshot::default();
+		for i in 0..e {
+			let mut config = bench_exchange();
+			config.name = BoundedVec::truncate_from(format!("Exchange {i}").into_bytes());
+			Exchanges::<T>::insert(i as ExchangeId, &config);
+			ExchangeKeys::<T>::insert(config.key(), i as ExchangeId);
+			Routers::<T>::insert(config.key(), [1; 20]);
+			snapshot.exchanges.push((i as ExchangeId, config));
+			snapshot.routers.push((i as ExchangeId, [2; 20]));
+		}
+		for i in 0..p {
+			let mut pair = bench_pair();
+			pair.pools.retain(|(exchange_id, _)| u32::from(*exchange_id) < e);
+			pair.base = BoundedVec::truncate_from(i.to_le_bytes().to_vec());
+			let mut old = pair.clone();
+			old.quote = BoundedVec::truncate_from(b"OLD".to_vec());
+			let old_id = Pallet::<T>::pair_id_of(&old);
+			for (exchange_id, pool) in &old.pools {
+				PoolHops::<T>::insert(old_id, exchange_id, pool);
+			}
+			TrackedPairs::<T>::insert(old_id, old);
+			fill_alerts::<T>(old_id, 0);
+			let hops = pair.pools.iter().cloned().collect();
+			snapshot.pairs.push(registry::PairSnapshot {
+				info: pair,
+				twap_check: Some(TwapCheck { window: 60, max_deviation: Permill::from_percent(1) }),
+				fast_path: Some(FastPath { threshold_bps: 100, blocks: 10 }),
+				failure_mode: FailureMode::FailOpen,
+				cross_chain_check: Some(CrossChainCheck { threshold_bps: 100, max_skew: None }),
+				sla: Some(bench_sla()),
+				min_notional: Some(1),
+				hops,
+			});
+		}
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, snapshot.encode());
+
+		assert_eq!(TrackedPairs::<T>::count(), p);
+		assert_eq!(Pallet::<T>::export_registry().exchanges.len() as u32, e);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn register_operator() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		let who: T::AccountId = account("operator", 0, 0);
+		let _ = Operators::<T>::clear(u32::MAX, None);
+		let funds = T::OperatorBond::get().saturating_add(T::Currency::minimum_balance());
+		T::Currency::make_free_balance_be(&who, funds);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, who.clone());
+
+		assert!(Operators::<T>::contains_key(&who));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn deregister_operator() -> Result<(), BenchmarkError> {
+		let origin = registry_origin::<T>()?;
+		let who: T::AccountId = account("operator", 0, 0);
+		bond_operator::<T>(&who);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, who.clone());
+
+		assert!(!Operators::<T>::contains_key(&who));
+		Ok(())
+	}
+
+	/// Replacing the alert of an account, releasing its deposit and reserving it again.
+	#[benchmark]
+	fn set_alert() {
+		let pair_id = setup_submission::<T>();
+		fill_alerts::<T>(pair_id, 1);
+		let caller: T::AccountId = whitelisted_caller();
+		let alert = PriceAlert { who: caller.clone(), threshold_bps: 1, target: 1 };
+		Alerts::<T>::try_append(pair_id, alert).unwrap();
+		reserve_alert_deposit::<T>(pair_id, &caller);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller.clone()), pair_id, 10_000, PRICE * PRICE_UNIT);
+
+		assert!(Alerts::<T>::get(pair_id).iter().any(|alert| alert.who == caller));
+	}
+
+	/// Serving the price of a paused pair that fails open to a consumer, without the message
+	/// itself, see [`PriceMessenger::send_price_weight`].
+	#[benchmark]
+	fn request_price_xcm() -> Result<(), BenchmarkError> {
+		let (origin, _) = subscriber_origin::<T>()?;
+		let pair_id = setup_submission::<T>();
+		let median = PRICE * PRICE_UNIT;
+		let aggregate = PriceAggregate { mean: median, median, sources: 1, timestamp: NOW };
+		AggregatedPrice::<T>::insert(pair_id, aggregate);
+		PausedPairs::<T>::insert(pair_id, ());
+		FailureModes::<T>::insert(pair_id, FailureMode::FailOpen);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, pair_id, 0);
+
+		assert_eq!(PairReads::<T>::get(pair_id).count, 1);
+		Ok(())
+	}
+
+	/// Subscribing to a pair whose subscriptions are all held by the consumer.
+	#[benchmark]
+	fn subscribe_price() -> Result<(), BenchmarkError> {
+		let (origin, subscriber) = subscriber_origin::<T>()?;
+		let pair_id = setup_submission::<T>();
+		let subscriptions = (0..T::MaxSubscriptionsPerPair::get())
+			.map(|i| PriceSubscription {
+				subscriber: subscriber.clone(),
+				query_id: i.into(),
+				threshold_bps: 1,
+				last: None,
+			})
+			.collect::<Vec<_>>();
+		PriceSubscriptions::<T>::insert(pair_id, BoundedVec::truncate_from(subscriptions));
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, pair_id, u64::MAX, 10_000);
+
+		let subscriptions = PriceSubscriptions::<T>::get(pair_id);
+		assert_eq!(subscriptions.len(), 1);
+		assert_eq!(subscriptions[0].query_id, u64::MAX);
+		Ok(())
+	}
+
+	/// Aggregation of a pair with fresh prices from `e` exchanges in `on_finalize`.
+	#[benchmark]
+	fn aggregate_prices(e: Linear<1, { u32::from(ExchangeId::MAX) + 1 }>) {
+		let pair_id = setup_submission::<T>();
+		for exchange_id in 0..e {
+			let exchange_id = exchange_id as ExchangeId;
+			Exchanges::<T>::insert(exchange_id, bench_exchange());
+			PriceData::<T>::insert(pair_id, exchange_id, (PRICE * PRICE_UNIT, NOW));
+			PoolNotionals::<T>::insert(pair_id, exchange_id, 1);
+		}
+		LowQualityPairs::<T>::insert(pair_id, ());
+		PendingAggregation::<T>::insert(pair_id, ());
+		fill_alerts::<T>(pair_id, 0);
+
+		#[block]
+		{
+			Pallet::<T>::on_finalize(1u32.into());
+		}
+
+		assert_eq!(AggregatedPrice::<T>::get(pair_id).map(|aggregate| aggregate.sources), Some(e));
+		assert!(!LowQualityPairs::<T>::contains_key(pair_id));
+		assert!(!Alerts::<T>::contains_key(pair_id));
+	}
+
+	/// Pruning `p` stale prices.
+	#[benchmark]
+	fn prune_stale_prices(p: Linear<0, 1_000>) {
+		T::BenchmarkHelper::set_now(NOW);
+		let _ = PriceData::<T>::clear(u32::MAX, None);
+		Exchanges::<T>::insert(0, bench_exchange());
+		for i in 0..p {
+			let pair_id = sp_io::hashing::blake2_256(&i.to_le_bytes());
+			PriceData::<T>::insert(pair_id, 0, (PRICE * PRICE_UNIT, 0));
+		}
+
+		#[block]
+		{
+			Pallet::<T>::prune_stale_prices();
+		}
+
+		assert_eq!(PriceData::<T>::iter().count(), 0);
+	}
+
+	/// Reading the aggregated price of a pair, as other pallets do through
+	/// [`Pallet::latest_price`].
+	#[benchmark]
+	fn latest_price() {
+		let pair_id = setup_submission::<T>();
+		let median = PRICE * PRICE_UNIT;
+		let aggregate = PriceAggregate { mean: median, median, sources: 1, timestamp: NOW };
+		AggregatedPrice::<T>::insert(pair_id, aggregate);
+
+		let price;
+		#[block]
+		{
+			price = Pallet::<T>::latest_price(pair_id);
+		}
+
+		assert_eq!(price, Some((median, NOW)));
+	}
+
+	/// Serving the price of a paused pair that fails open through [`PriceProvider::price`].
+	#[benchmark]
+	fn provided_price() {
+		let pair_id = setup_submission::<T>();
+		let median = PRICE * PRICE_UNIT;
+		let aggregate = PriceAggregate { mean: median, median, sources: 1, timestamp: NOW };
+		AggregatedPrice::<T>::insert(pair_id, aggregate);
+		PausedPairs::<T>::insert(pair_id, ());
+		FailureModes::<T>::insert(pair_id, FailureMode::FailOpen);
+
+		let price;
+		#[block]
+		{
+			price = <Pallet<T> as PriceProvider>::price(pair_id);
+		}
+
+		assert_eq!(
+			price,
+			Ok(ProvidedPrice { price: median, timestamp: NOW, stale: true, low_quality: false })
+		);
+	}
+
+	/// Reading a price through [`PriceGuard`], holding it for the rest of the block.
+	#[benchmark]
+	fn guarded_price() {
+		let pair_id = setup_submission::<T>();
+		let median = PRICE * PRICE_UNIT;
+		let aggregate = PriceAggregate { mean: median, median, sources: 1, timestamp: NOW };
+		AggregatedPrice::<T>::insert(pair_id, aggregate);
+		PausedPairs::<T>::insert(pair_id, ());
+		FailureModes::<T>::insert(pair_id, FailureMode::FailOpen);
+
+		let price;
+		#[block]
+		{
+			price = <Pallet<T> as PriceGuard>::guarded_price(pair_id);
+		}
+
+		assert_eq!(
+			price,
+			Ok(ProvidedPrice { price: median, timestamp: NOW, stale: true, low_quality: false })
+		);
+		assert!(PriceGuards::<T>::contains_key(pair_id));
+	}
+
+	/// Reading the prices of `e` exchanges through [`Pallet::get_prices`].
+	#[benchmark]
+	fn get_prices(e: Linear<1, { u32::from(ExchangeId::MAX) + 1 }>) {
+		let pair_id = setup_submission::<T>();
+		for exchange_id in 0..e {
+			let exchange_id = exchange_id as ExchangeId;
+			Exchanges::<T>::insert(exchange_id, bench_exchange());
+			PriceData::<T>::insert(pair_id, exchange_id, (PRICE * PRICE_UNIT, NOW));
+		}
+
+		let prices;
+		#[block]
+		{
+			prices = Pallet::<T>::get_prices(pair_id, e);
+		}
+
+		assert_eq!(prices.len() as u32, e);
+	}
+
+	/// Reading the aggregates of [`Config::MaxPairs`] pairs through
+	/// [`Pallet::latest_aggregates`].
+	#[benchmark]
+	fn latest_aggregates() {
+		let median = PRICE * PRICE_UNIT;
+		let aggregate = PriceAggregate { mean: median, median, sources: 1, timestamp: NOW };
+		let latest = (0..T::MaxPairs::get())
+			.map(|i| (sp_io::hashing::blake2_256(&i.to_le_bytes()), aggregate))
+			.collect::<alloc::collections::BTreeMap<_, _>>();
+		LatestAggregates::<T>::put(BoundedBTreeMap::try_from(latest).unwrap());
+
+		let aggregates;
+		#[block]
+		{
+			aggregates = Pallet::<T>::latest_aggregates();
+		}
+
+		assert_eq!(aggregates.len() as u32, T::MaxPairs::get());
+	}
+
+	/// Averaging a full [`PriceHistory`] through [`Pallet::twap`].
+	#[benchmark]
+	fn twap() {
+		let pair_id = setup_submission::<T>();
+		let window = T::MaxHistory::get().into();
+		frame_system::Pallet::<T>::set_block_number(window);
+
+		let average;
+		#[block]
+		{
+			average = Pallet::<T>::twap(pair_id, window);
+		}
+
+		assert_eq!(average, Some(PRICE * PRICE_UNIT));
+	}
+
+	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,50 @@
+//! Allocation of the fetch budget of the offchain worker among the exchanges.
+//!
+//! A run reads at most [`Config::MaxExchangesPerBlock`](crate::Config::MaxExchangesPerBlock)
+//! pools. Rather than the first pools in the order of the pairs, the reads are shared among the
+//! exchanges in proportion to their weight, one more than their
+//! [`contribution_scores`](crate::Pallet::contribution_scores): the number of pairs whose
+//! aggregate their fresh prices agree with. Exchanges without a score, newly registered or back
+//! from an outage, keep a share to earn one with.
+
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+use core::cmp::Ordering;
+
+/// Which of the `candidates`, the reads in the order they are due, each with the exchange it is
+/// on and the weight of the exchange, fit in `budget`.
+///
+/// Each read goes to the exchange with the fewest reads per weight so far, as seats are
+/// apportioned by the D'Hondt method, ties to the exchange whose next read is due first. Each
+/// exchange reads its pools in the order they are due, and the share of an exchange with fewer
+/// reads due goes to the others.
+pub fn allocate<E: Ord + Copy>(budget: u32, candidates: &[(E, u32)]) -> Vec<bool> {
+	// Per exchange, its weight, its reads so far and the indices of its reads left.
+	let mut exchanges = BTreeMap::<E, (u32, u32, Vec<usize>)>::new();
+	for (index, (exchange, weight)) in candidates.iter().enumerate().rev() {
+		exchanges.entry(*exchange).or_insert((*weight, 0, Vec::new())).2.push(index);
+	}
+
+	let mut selected = vec![false; candidates.len()];
+	for _ in 0..budget {
+		let next = exchanges.values_mut().filter(|(.., left)| !left.is_empty()).min_by(
+			|(weight, reads, left), (other_weight, other_reads, other_left)| {
+				per_weight(*reads, *weight, *other_reads, *other_weight)
+					.then_with(|| left.last().cmp(&other_left.last()))
+			},
+		);
+		let Some((_, reads, left)) = next else { break };
+		let Some(index) = left.pop() else { break };
+		*reads += 1;
+		selected[index] = true;
+	}
+	selected
+}
+
+/// How `reads + 1` per `weight` compares with `other_reads + 1` per `other_weight`, the reads per
+/// weight of the two exchanges after their next read. A weight of zero counts as one.
+fn per_weight(reads: u32, weight: u32, other_reads: u32, other_weight: u32) -> Ordering {
+	let next = |reads: u32| u64::from(reads) + 1;
+	let weight = u64::from(weight.max(1));
+	let other_weight = u64::from(other_weight.max(1));
+	(next(reads) * other_weight).cmp(&(next(other_reads) * weight))
+}
@@ -0,0 +1,102 @@
+//! JSON-RPC transport for EVM chains.
+
+use super::{multicall, post, ChainInterface};
+use crate::{
+	endpoints,
+	error::OracleError,
+	json::{self, FeeHistory},
+	rpc::{BlockTag, RequestBuilder},
+	types::{CallOptions, EvmAddress},
+};
+use alloc::vec::Vec;
+
+/// Any EVM compatible chain reachable through `eth_call`.
+pub struct EvmChain;
+
+impl EvmChain {
+	/// Number of the latest block, through `eth_blockNumber`.
+	pub fn block_number(&self, rpc_url: &str, timeout_ms: u64) -> Result<u64, OracleError> {
+		let id = endpoints::next_request_ids(1);
+		let body = post(rpc_url, RequestBuilder::eth_block_number().id(id).build(), timeout_ms)?;
+		let block = json::quantity_result(&body, id)?;
+		u64::try_from(block).map_err(|_| OracleError::DecodeError)
+	}
+
+	/// Timestamp of the latest block, in unix seconds, through `eth_getBlockByNumber`.
+	pub fn head_timestamp(&self, rpc_url: &str, timeout_ms: u64) -> Result<u64, OracleError> {
+		let id = endpoints::next_request_ids(1);
+		let request = RequestBuilder::eth_get_block_by_number(BlockTag::Latest, false).id(id);
+		let body = post(rpc_url, request.build(), timeout_ms)?;
+		json::block_timestamp(&body, id)
+	}
+
+	/// Base fees and priority fees at `percentiles` of the latest `block_count` blocks, through
+	/// `eth_feeHistory`.
+	pub fn fee_history(
+		&self,
+		rpc_url: &str,
+		block_count: u64,
+		percentiles: &[u8],
+		timeout_ms: u64,
+	) -> Result<FeeHistory, OracleError> {
+		let id = endpoints::next_request_ids(1);
+		let request =
+			RequestBuilder::eth_fee_history(block_count, BlockTag::Latest, percentiles).id(id);
+		let body = post(rpc_url, request.build(), timeout_ms)?;
+		json::fee_history(&body, id)
+	}
+
+	/// Execute each `(contract, data)` of `calls` in one `eth_call` through
+	/// [`multicall::MULTICALL3`], returning the return data of each call, or why it failed, in
+	/// order.
+	pub fn multicall(
+		&self,
+		rpc_url: &str,
+		calls: &[(&EvmAddress, &str)],
+		options: &CallOptions,
+		timeout_ms: u64,
+	) -> Result<Vec<Result<Vec<u8>, OracleError>>, OracleError> {
+		let data = multicall::aggregate3_call_data(calls)?;
+		let results =
+			self.call_contract(rpc_url, &multicall::MULTICALL3, &data, options, timeout_ms)?;
+		multicall::parse_aggregate3(&results, calls.len())
+	}
+}
+
+impl ChainInterface for EvmChain {
+	fn call_contract(
+		&self,
+		rpc_url: &str,
+		contract: &EvmAddress,
+		data: &str,
+		options: &CallOptions,
+		timeout_ms: u64,
+	) -> Result<Vec<u8>, OracleError> {
+		let id = endpoints::next_request_ids(1);
+		let block = options.confirmation.into();
+		let request = RequestBuilder::eth_call_with(contract, data, block, options).id(id);
+		let body = post(rpc_url, request.build(), timeout_ms)?;
+		json::hex_result(&body, id)
+	}
+
+	fn call_batch(
+		&self,
+		rpc_url: &str,
+		calls: &[(&EvmAddress, &str)],
+		options: &CallOptions,
+		timeout_ms: u64,
+	) -> Result<Vec<Vec<u8>>, OracleError> {
+		let first = endpoints::next_request_ids(calls.len() as u64);
+		let ids = first..first + calls.len() as u64;
+		let block = options.confirmation.into();
+		let requests: Vec<_> = ids
+			.clone()
+			.zip(calls)
+			.map(|(id, (contract, data))| {
+				RequestBuilder::eth_call_with(contract, data, block, options).id(id)
+			})
+			.collect();
+		let body = post(rpc_url, RequestBuilder::batch(&requests), timeout_ms)?;
+		json::hex_results(&body, ids)
+	}
+}
@@ -0,0 +1,192 @@
+//! Transports reading pool state from external chains (EVM, Solana, etc.).
+
+use crate::{
+	endpoints,
+	error::OracleError,
+	protocols::ProtocolKind,
+	proxy::Proxy,
+	types::{CallOptions, EvmAddress, SolanaAddress},
+};
+use alloc::{vec, vec::Vec};
+use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_runtime::offchain::{http, Duration};
+
+pub mod evm;
+pub mod multicall;
+pub mod solana;
+#[cfg(feature = "synthetic-prices")]
+pub mod synthetic;
+
+pub use evm::EvmChain;
+pub use solana::SolanaChain;
+
+/// POST a JSON-RPC `body` to `rpc_url`, through the configured [`Proxy`] if there is one, and
+/// return the response body.
+pub fn post(rpc_url: &str, body: Vec<u8>, timeout_ms: u64) -> Result<Vec<u8>, OracleError> {
+	let proxy = Proxy::configured()?;
+	let url = proxy.as_ref().map(|proxy| proxy.url(rpc_url));
+	let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(timeout_ms));
+	let mut request = http::Request::post(url.as_deref().unwrap_or(rpc_url), vec![body])
+		.add_header("Content-Type", "application/json")
+		.deadline(deadline);
+	if let Some(authorization) = proxy.as_ref().and_then(Proxy::authorization) {
+		request = request.add_header("Proxy-Authorization", &authorization);
+	}
+	let pending = request.send().map_err(|_| OracleError::Transport)?;
+
+	let response = pending.try_wait(deadline).map_err(|_| OracleError::Timeout)??;
+	if response.code != 200 {
+		return Err(OracleError::HttpStatus(response.code))
+	}
+	let body: Vec<u8> = response.body().collect();
+	endpoints::check_unique_response(rpc_url.as_bytes(), &body)?;
+	Ok(body)
+}
+
+/// A chain the offchain worker can read pool state from.
+pub trait ChainInterface {
+	/// Execute a read-only call of `data` against `contract` with `options` and return the raw
+	/// return data.
+	fn call_contract(
+		&self,
+		rpc_url: &str,
+		contract: &EvmAddress,
+		data: &str,
+		options: &CallOptions,
+		timeout_ms: u64,
+	) -> Result<Vec<u8>, OracleError>;
+
+	/// Execute read-only calls of each of `calls` against `contract` in one request, returning
+	/// their raw return data in order.
+	fn call_contract_batch(
+		&self,
+		rpc_url: &str,
+		contract: &EvmAddress,
+		calls: &[&str],
+		options: &CallOptions,
+		timeout_ms: u64,
+	) -> Result<Vec<Vec<u8>>, OracleError> {
+		let calls: Vec<_> = calls.iter().map(|data| (contract, *data)).collect();
+		self.call_batch(rpc_url, &calls, options, timeout_ms)
+	}
+
+	/// Execute read-only calls of `data` against `contract` for each `(contract, data)` of
+	/// `calls` in one request, returning their raw return data in order.
+	fn call_batch(
+		&self,
+		rpc_url: &str,
+		calls: &[(&EvmAddress, &str)],
+		options: &CallOptions,
+		timeout_ms: u64,
+	) -> Result<Vec<Vec<u8>>, OracleError>;
+
+	/// Read the raw data of `account` on chains keeping pool state in accounts rather than
+	/// contracts. Other chains have no accounts to read.
+	fn read_account(
+		&self,
+		_rpc_url: &str,
+		_account: &SolanaAddress,
+		_timeout_ms: u64,
+	) -> Result<Vec<u8>, OracleError> {
+		Err(OracleError::InvalidConfig)
+	}
+}
+
+/// The kinds of chain an exchange can live on, dispatching to their [`ChainInterface`].
+#[derive(
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	Debug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+	serde::Serialize,
+	serde::Deserialize,
+)]
+pub enum ChainKind {
+	/// An EVM compatible chain.
+	Evm,
+	/// No chain: prices are generated, see [`synthetic`].
+	#[cfg(feature = "synthetic-prices")]
+	Synthetic,
+	/// Solana, whose pools are accounts, see [`SolanaChain`]. Indexed explicitly so its encoding
+	/// doesn't depend on the `synthetic-prices` feature.
+	#[codec(index = 2)]
+	Solana,
+}
+
+impl ChainKind {
+	/// Whether pools of `protocol` can be read on chains of this kind.
+	pub fn supports(&self, protocol: ProtocolKind) -> bool {
+		match self {
+			ChainKind::Evm => matches!(
+				protocol,
+				ProtocolKind::UniswapV2 |
+					ProtocolKind::UniswapV3 |
+					ProtocolKind::TraderJoe |
+					ProtocolKind::Chainlink
+			),
+			ChainKind::Solana => matches!(protocol, ProtocolKind::OrcaWhirlpool),
+			// Synthetic prices are generated for any protocol.
+			#[cfg(feature = "synthetic-prices")]
+			ChainKind::Synthetic => true,
+		}
+	}
+
+	/// Whether pools on chains of this kind are accounts, set as the
+	/// [`account`](crate::exchanges::PoolConfig::account) of their pool config.
+	pub fn has_pool_accounts(&self) -> bool {
+		matches!(self, ChainKind::Solana)
+	}
+}
+
+impl ChainInterface for ChainKind {
+	fn call_contract(
+		&self,
+		rpc_url: &str,
+		contract: &EvmAddress,
+		data: &str,
+		options: &CallOptions,
+		timeout_ms: u64,
+	) -> Result<Vec<u8>, OracleError> {
+		match self {
+			ChainKind::Evm => EvmChain.call_contract(rpc_url, contract, data, options, timeout_ms),
+			ChainKind::Solana =>
+				SolanaChain.call_contract(rpc_url, contract, data, options, timeout_ms),
+			#[cfg(feature = "synthetic-prices")]
+			ChainKind::Synthetic => Err(OracleError::InvalidConfig),
+		}
+	}
+
+	fn call_batch(
+		&self,
+		rpc_url: &str,
+		calls: &[(&EvmAddress, &str)],
+		options: &CallOptions,
+		timeout_ms: u64,
+	) -> Result<Vec<Vec<u8>>, OracleError> {
+		match self {
+			ChainKind::Evm => EvmChain.call_batch(rpc_url, calls, options, timeout_ms),
+			ChainKind::Solana => SolanaChain.call_batch(rpc_url, calls, options, timeout_ms),
+			#[cfg(feature = "synthetic-prices")]
+			ChainKind::Synthetic => Err(OracleError::InvalidConfig),
+		}
+	}
+
+	fn read_account(
+		&self,
+		rpc_url: &str,
+		account: &SolanaAddress,
+		timeout_ms: u64,
+	) -> Result<Vec<u8>, OracleError> {
+		match self {
+			ChainKind::Solana => SolanaChain.read_account(rpc_url, account, timeout_ms),
+			_ => Err(OracleError::InvalidConfig),
+		}
+	}
+}
@@ -0,0 +1,86 @@
+//! Batching of contract reads through Multicall3.
+//!
+//! [Multicall3](https://www.multicall3.com) is deployed at [`MULTICALL3`] on most EVM chains. Its
+//! `aggregate3` executes calls to any number of contracts within one `eth_call`, so the pools of
+//! a chain are read in one HTTP round trip, all at the same block, instead of a request each. A
+//! call that reverts only fails its own result.
+
+use crate::{error::OracleError, fixed, protocols::address_hex, types::EvmAddress};
+use alloc::{format, string::String, vec::Vec};
+
+/// Address of Multicall3, the same on every chain it is deployed on.
+pub const MULTICALL3: EvmAddress = [
+	0xca, 0x11, 0xbd, 0xe0, 0x59, 0x77, 0xb3, 0x63, 0x11, 0x67, 0x02, 0x88, 0x62, 0xbe, 0x2a, 0x17,
+	0x39, 0x76, 0xca, 0x11,
+];
+
+/// Calldata of `getCurrentBlockTimestamp()` of Multicall3, the timestamp of the block the
+/// aggregated calls run at, in unix seconds.
+pub const GET_CURRENT_BLOCK_TIMESTAMP: &str = "0x0f28c97d";
+
+/// Calldata of `aggregate3((address,bool,bytes)[])` executing each `(contract, data)` of `calls`,
+/// allowing each to fail.
+pub fn aggregate3_call_data(calls: &[(&EvmAddress, &str)]) -> Result<String, OracleError> {
+	let calls = calls
+		.iter()
+		.map(|(contract, data)| {
+			let data = array_bytes::hex2bytes(data).map_err(|_| OracleError::InvalidConfig)?;
+			Ok((*contract, data))
+		})
+		.collect::<Result<Vec<_>, OracleError>>()?;
+
+	// The offset of the array, its length, then the offset of each call from the first offset,
+	// as the calls are tuples of dynamic size.
+	let mut head = format!("0x82ad56cb{:064x}{:064x}", 0x20, calls.len());
+	let mut tail = String::new();
+	let mut offset = 0x20 * calls.len();
+	for (contract, data) in &calls {
+		head.push_str(&format!("{offset:064x}"));
+		// The contract, allowFailure, the offset of the calldata and the padded calldata.
+		let padded = data.len().div_ceil(32) * 32;
+		let (contract, len) = (address_hex(contract), data.len());
+		tail.push_str(&format!("{contract}{:064x}{:064x}{len:064x}", 1, 0x60));
+		tail.push_str(&array_bytes::bytes2hex("", data));
+		tail.push_str(&"00".repeat(padded - data.len()));
+		offset += 0x20 * 4 + padded;
+	}
+	head.push_str(&tail);
+	Ok(head)
+}
+
+/// Split the return data of [`aggregate3_call_data`] for `count` calls into the return data of
+/// each call, in order, or [`OracleError::Reverted`] for those that failed.
+pub fn parse_aggregate3(
+	data: &[u8],
+	count: usize,
+) -> Result<Vec<Result<Vec<u8>, OracleError>>, OracleError> {
+	// (bool success, bytes returnData)[]: the offset of the array, then its length and the offset
+	// of each result from the first offset.
+	let array = word_usize(data, 0)?;
+	let results = data.get(array..).ok_or(OracleError::DecodeError)?;
+	if word_usize(results, 0)? != count {
+		return Err(OracleError::DecodeError)
+	}
+	let offsets = results.get(0x20..).ok_or(OracleError::DecodeError)?;
+	(0..count)
+		.map(|index| {
+			let result =
+				offsets.get(word_usize(offsets, index)?..).ok_or(OracleError::DecodeError)?;
+			let success = !fixed::word(result, 0)?.is_zero();
+			let return_data =
+				result.get(word_usize(result, 1)?..).ok_or(OracleError::DecodeError)?;
+			let len = word_usize(return_data, 0)?;
+			let return_data =
+				return_data.get(0x20..0x20 + len).ok_or(OracleError::DecodeError)?.to_vec();
+			Ok(match success {
+				true => Ok(return_data),
+				false => Err(OracleError::Reverted),
+			})
+		})
+		.collect()
+}
+
+/// The `index`-th ABI word of `data` as an offset or a length.
+fn word_usize(data: &[u8], index: usize) -> Result<usize, OracleError> {
+	fixed::word(data, index)?.try_into().map_err(|_| OracleError::DecodeError)
+}
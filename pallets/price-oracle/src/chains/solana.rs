@@ -0,0 +1,86 @@
+//! JSON-RPC transport for Solana.
+//!
+//! Solana has no contract calls: the state of a pool lives in an account owned by the DEX's
+//! program, which is read whole with `getAccountInfo` and decoded by the protocol. Pools are found
+//! by filtering the accounts of the program with `getProgramAccounts`.
+
+use super::{post, ChainInterface};
+use crate::{
+	endpoints,
+	error::OracleError,
+	json,
+	rpc::{AccountFilter, RequestBuilder},
+	types::{CallOptions, ChainId, EvmAddress, SolanaAddress},
+};
+use alloc::vec::Vec;
+
+/// Chain id the endpoints of Solana mainnet-beta are registered under. Solana has no chain id of
+/// its own; token lists use 101 for mainnet-beta.
+pub const MAINNET: ChainId = 101;
+
+/// The base58 encoded public key `value`, if it is one.
+pub fn parse_address(value: &str) -> Option<SolanaAddress> {
+	bs58::decode(value).into_vec().ok()?.try_into().ok()
+}
+
+/// Solana, read through its JSON-RPC API.
+pub struct SolanaChain;
+
+impl SolanaChain {
+	/// Current slot, through `getSlot`.
+	pub fn slot(&self, rpc_url: &str, timeout_ms: u64) -> Result<u64, OracleError> {
+		let id = endpoints::next_request_ids(1);
+		let body = post(rpc_url, RequestBuilder::get_slot().id(id).build(), timeout_ms)?;
+		json::u64_result(&body, id)
+	}
+
+	/// Keys and data of the accounts owned by `program` passing all of `filters`, through
+	/// `getProgramAccounts`.
+	pub fn program_accounts(
+		&self,
+		rpc_url: &str,
+		program: &SolanaAddress,
+		filters: &[AccountFilter],
+		timeout_ms: u64,
+	) -> Result<Vec<(SolanaAddress, Vec<u8>)>, OracleError> {
+		let id = endpoints::next_request_ids(1);
+		let request = RequestBuilder::get_program_accounts(program, filters).id(id);
+		let body = post(rpc_url, request.build(), timeout_ms)?;
+		json::program_accounts(&body, id)
+	}
+}
+
+impl ChainInterface for SolanaChain {
+	fn call_contract(
+		&self,
+		_rpc_url: &str,
+		_contract: &EvmAddress,
+		_data: &str,
+		_options: &CallOptions,
+		_timeout_ms: u64,
+	) -> Result<Vec<u8>, OracleError> {
+		Err(OracleError::InvalidConfig)
+	}
+
+	fn call_batch(
+		&self,
+		_rpc_url: &str,
+		_calls: &[(&EvmAddress, &str)],
+		_options: &CallOptions,
+		_timeout_ms: u64,
+	) -> Result<Vec<Vec<u8>>, OracleError> {
+		Err(OracleError::InvalidConfig)
+	}
+
+	fn read_account(
+		&self,
+		rpc_url: &str,
+		account: &SolanaAddress,
+		timeout_ms: u64,
+	) -> Result<Vec<u8>, OracleError> {
+		let id = endpoints::next_request_ids(1);
+		let body =
+			post(rpc_url, RequestBuilder::get_account_info(account).id(id).build(), timeout_ms)?;
+		json::account_data(&body, id)
+	}
+}
@@ -0,0 +1,35 @@
+//! Synthetic prices for development chains and CI runtimes without internet access.
+//!
+//! An exchange on [`ChainKind::Synthetic`](super::ChainKind::Synthetic) reads no chain. Each of
+//! its pools walks randomly away from the last aggregate of its pair, one step per fetch drawn
+//! from the hash of the parent block and the pool address. Every node of a chain generates the
+//! same prices, and pools of the same pair drift apart, so submission, aggregation and spread
+//! detection all see realistic input.
+
+use crate::{fixed::Price, types::EvmAddress};
+use codec::Encode;
+use core::ops::RangeInclusive;
+
+/// Largest move of a synthetic price in one step, in basis points.
+pub const MAX_STEP_BPS: u64 = 50;
+
+/// Step of the walk of `pool` for `seed`, in basis points within `±MAX_STEP_BPS`.
+pub fn step_bps(seed: &[u8], pool: &EvmAddress) -> i64 {
+	let hash = sp_io::hashing::blake2_256(&(seed, pool).encode());
+	let draw = u64::from_le_bytes(hash[..8].try_into().expect("hash is 32 bytes; qed"));
+	(draw % (2 * MAX_STEP_BPS + 1)) as i64 - MAX_STEP_BPS as i64
+}
+
+/// Next price of the walk of `pool` from `last`, kept within `bounds`.
+pub fn next_price(
+	last: Price,
+	seed: &[u8],
+	pool: &EvmAddress,
+	bounds: RangeInclusive<Price>,
+) -> Price {
+	let step = step_bps(seed, pool);
+	let bps = u128::from(step.unsigned_abs());
+	let delta = last / 10_000 * bps + last % 10_000 * bps / 10_000;
+	let price = if step < 0 { last.saturating_sub(delta) } else { last.saturating_add(delta) };
+	price.clamp(*bounds.start(), *bounds.end())
+}
@@ -0,0 +1,34 @@
+//! Differences between the polkadot-sdk release trains the pallet builds against.
+//!
+//! Unsigned submissions are wrapped into extrinsics with `CreateBare`, the name stable2506 gave the
+//! trait. Runtimes on stable2412 and stable2503 implement it as `CreateInherent` instead, with
+//! `create_inherent` in place of `create_bare`; the `legacy-create-inherent` feature builds the
+//! pallet against those. The signed path through `CreateSignedTransaction` is the same on both.
+//!
+//! stable2409 and older predate `CreateTransactionBase`: their runtimes implement
+//! `SendTransactionTypes`, and unsigned calls go through
+//! `SubmitTransaction::submit_unsigned_transaction`, neither of which later release trains have. A
+//! feature selecting them couldn't build against the release train of this workspace, so those
+//! release trains aren't supported.
+
+#[cfg(not(feature = "legacy-create-inherent"))]
+pub use frame_system::offchain::CreateBare as CreateUnsigned;
+#[cfg(feature = "legacy-create-inherent")]
+#[allow(deprecated)]
+pub use frame_system::offchain::CreateInherent as CreateUnsigned;
+
+/// The bare extrinsic submitting `call` unsigned.
+pub fn unsigned_extrinsic<T, LocalCall>(call: T::RuntimeCall) -> T::Extrinsic
+where
+	T: CreateUnsigned<LocalCall>,
+{
+	#[cfg(not(feature = "legacy-create-inherent"))]
+	{
+		T::create_bare(call)
+	}
+	#[cfg(feature = "legacy-create-inherent")]
+	#[allow(deprecated)]
+	{
+		T::create_inherent(call)
+	}
+}
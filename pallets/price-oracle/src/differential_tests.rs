@@ -0,0 +1,178 @@
+//! Differential tests of the fixed-point parsers against a floating point reference.
+//!
+//! The `legacy` module is an `f64` reference of the same math, written for these tests rather than
+//! kept from an earlier parser of the pallet. Both implementations run over recorded pool states
+//! and pseudo-random inputs and must agree within one micro-USD.
+
+use crate::{
+	fixed,
+	protocols::{DexProtocol, UniswapV2Protocol, UniswapV3Protocol},
+	registry,
+	types::PoolMetadata,
+};
+
+/// `f64` reference parsers, computing the prices in floating point.
+mod legacy {
+	const Q96: f64 = 79_228_162_514_264_337_593_543_950_336.0;
+
+	fn word_as_f64(data: &[u8], index: usize) -> Option<f64> {
+		let word = data.get(index * 32..(index + 1) * 32)?;
+		Some(word.iter().fold(0f64, |acc, byte| acc * 256.0 + *byte as f64))
+	}
+
+	/// ETH/USD bounds used to pick the decimal adjustment.
+	const MIN: f64 = 1_000.0;
+	const MAX: f64 = 20_000.0;
+
+	pub fn uniswap_v3_eth(data: &[u8]) -> Option<f64> {
+		let sqrt_price = word_as_f64(data, 0)? / Q96;
+		let ratio = sqrt_price * sqrt_price;
+		if ratio == 0.0 {
+			return None
+		}
+		Some(1e12 / ratio)
+	}
+
+	pub fn price_from_reserves_eth(data: &[u8]) -> Option<f64> {
+		let reserve0 = word_as_f64(data, 0)?;
+		let reserve1 = word_as_f64(data, 1)?;
+		if reserve0 == 0.0 || reserve1 == 0.0 {
+			return None
+		}
+		let ratio = reserve1 / reserve0;
+		[ratio, 1.0 / ratio, ratio * 1e12, ratio * 1e-12, 1e12 / ratio, 1e-12 / ratio]
+			.into_iter()
+			.find(|price| *price >= MIN && *price <= MAX)
+	}
+
+	pub fn to_micro(price: f64) -> u64 {
+		(price * 1_000_000.0) as u64
+	}
+}
+
+/// `slot0()` words of the USDC/WETH 0.05% pool at assorted blocks.
+const SLOT0_CORPUS: &[&str] = &[
+	"0000000000000000000000000000000000004e20000000000000000000000000",
+	"00000000000000000000000000000000000056fd1f3b2b5be0c11e8e64a1a14b",
+	"0000000000000000000000000000000000005a8f6ee2ed9c0b4e04bbe3f0a3e5",
+	"000000000000000000000000000000000000461d7a9be5f7bb1f1d7e0e5fd7c2",
+	"00000000000000000000000000000000000035e4f1e8fb2f4ae1bf3c27f6fb44",
+	"0000000000000000000000000000000000002a2f4c5a8b3d0ddb9e6f5ea67b11",
+];
+
+/// `getReserves()` words (reserve0, reserve1) of USDC/WETH V2 pools at assorted blocks.
+const RESERVES_CORPUS: &[(&str, &str)] = &[
+	(
+		"000000000000000000000000000000000000000000000000000001d1a94a2000",
+		"00000000000000000000000000000000000000000000003635c9adc5dea00000",
+	),
+	(
+		"0000000000000000000000000000000000000000000000000000104c533c0000",
+		"0000000000000000000000000000000000000000000002a5a058fc295ed00000",
+	),
+	(
+		"00000000000000000000000000000000000000000000000000000a3ba8d6e2c1",
+		"00000000000000000000000000000000000000000000016ef8c6ec4b1e9c3a1f",
+	),
+	(
+		"000000000000000000000000000000000000000000000000000000e8d4a51000",
+		"0000000000000000000000000000000000000000000000055005f0c614480000",
+	),
+];
+
+fn hex(word: &str) -> Vec<u8> {
+	array_bytes::hex2bytes(word).unwrap()
+}
+
+fn word(value: u128) -> Vec<u8> {
+	let mut word = [0u8; 32];
+	word[16..].copy_from_slice(&value.to_be_bytes());
+	word.to_vec()
+}
+
+/// xorshift64*, enough to spread inputs deterministically.
+struct Rng(u64);
+
+impl Rng {
+	fn next(&mut self) -> u64 {
+		self.0 ^= self.0 >> 12;
+		self.0 ^= self.0 << 25;
+		self.0 ^= self.0 >> 27;
+		self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+	}
+
+	fn range(&mut self, low: u128, high: u128) -> u128 {
+		let wide = (u128::from(self.next()) << 64) | u128::from(self.next());
+		low + wide % (high - low)
+	}
+}
+
+fn assert_agree(data: &[u8], fixed_price: Option<fixed::Price>, legacy_price: Option<f64>) {
+	match (fixed_price, legacy_price) {
+		(Some(fixed_price), Some(legacy_price)) => {
+			let (a, b) = (fixed::to_micro(fixed_price), legacy::to_micro(legacy_price));
+			assert!(a.abs_diff(b) <= 1, "{} != {} for {}", a, b, array_bytes::bytes2hex("", data));
+		},
+		(None, None) => {},
+		(a, b) => panic!("{:?} vs {:?} for {}", a, b, array_bytes::bytes2hex("", data)),
+	}
+}
+
+/// Token layout of the registered ETH/USD pool of `exchange_id`.
+fn pool(exchange_id: u8) -> PoolMetadata {
+	registry::default_pairs()[0].pool(exchange_id).unwrap().metadata
+}
+
+fn check_v3(data: &[u8]) {
+	assert_agree(
+		data,
+		UniswapV3Protocol.parse_price(data, &pool(0)).ok(),
+		legacy::uniswap_v3_eth(data),
+	);
+}
+
+fn check_v2(data: &[u8]) {
+	assert_agree(
+		data,
+		UniswapV2Protocol.parse_price(data, &pool(1)).ok(),
+		legacy::price_from_reserves_eth(data),
+	);
+}
+
+#[test]
+fn uniswap_v3_agrees_on_corpus() {
+	for slot0 in SLOT0_CORPUS {
+		check_v3(&hex(slot0));
+	}
+}
+
+#[test]
+fn uniswap_v2_agrees_on_corpus() {
+	for (reserve0, reserve1) in RESERVES_CORPUS {
+		check_v2(&[hex(reserve0), hex(reserve1)].concat());
+	}
+}
+
+#[test]
+fn uniswap_v3_agrees_on_random_input() {
+	let mut rng = Rng(0x5eed_0f0a_c1e5);
+	// sqrtPriceX96 of ETH between 1,000 and 20,000 USD in the USDC/WETH pool.
+	let (low, high) = (
+		560_227_709_747_861_383_862_430_604_460_032_u128,
+		2_505_414_483_750_479_227_146_068_685_750_272_u128,
+	);
+	for _ in 0..10_000 {
+		check_v3(&word(rng.range(low, high)));
+	}
+}
+
+#[test]
+fn uniswap_v2_agrees_on_random_input() {
+	let mut rng = Rng(0xdecade);
+	for _ in 0..10_000 {
+		let usdc = rng.range(1_000_000_000, 100_000_000_000_000);
+		let price = rng.range(1_000, 20_000);
+		let weth = usdc * 1_000_000_000_000 / price;
+		check_v2(&[word(usdc), word(weth)].concat());
+	}
+}
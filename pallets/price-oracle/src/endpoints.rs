@@ -0,0 +1,165 @@
+//! Choosing the RPC endpoint of a chain in the offchain worker.
+//!
+//! A chain can have several endpoints. They are tried in order of their recorded failures, fewest
+//! first, and the next one is used when a request fails at the endpoint. The failure count of each
+//! endpoint is kept in the node's persistent offchain storage, so an endpoint that keeps failing
+//! sinks to the end of the list without affecting other nodes.
+
+use crate::{error::OracleError, types::ChainId, Price};
+use alloc::{collections::BTreeMap, format, vec::Vec};
+use sp_core::offchain::StorageKind;
+use sp_runtime::offchain::storage::StorageValueRef;
+
+/// Key of the node-local RPC endpoint override of `chain_id` in the persistent offchain storage.
+///
+/// The value is the raw UTF-8 URL. It is tried before the on-chain
+/// [`RpcEndpoints`](crate::RpcEndpoints) of the chain.
+pub fn rpc_override_key(chain_id: ChainId) -> Vec<u8> {
+	format!("price-oracle/rpc/{chain_id}").into_bytes()
+}
+
+/// Key of the failure counter of the endpoint `url` in the persistent offchain storage.
+pub fn failure_key(url: &[u8]) -> Vec<u8> {
+	[&b"price-oracle/rpc-failures/"[..], url].concat()
+}
+
+/// Number of failed requests recorded for `url` since its last success.
+pub fn failures(url: &[u8]) -> u32 {
+	StorageValueRef::persistent(&failure_key(url))
+		.get::<u32>()
+		.ok()
+		.flatten()
+		.unwrap_or(0)
+}
+
+/// Record the outcome of a request to `url`: a success clears its failures, a failure of the
+/// endpoint adds one.
+pub fn record<T>(url: &[u8], result: &Result<T, OracleError>) {
+	let key = failure_key(url);
+	let mut counter = StorageValueRef::persistent(&key);
+	match result {
+		Ok(_) => counter.clear(),
+		Err(e) if e.is_endpoint_failure() => counter.set(&failures(url).saturating_add(1)),
+		Err(_) => {},
+	}
+}
+
+/// The endpoints of `chain_id` in the order they should be tried: the local override first, then
+/// `urls` by their recorded failures, keeping their order among equals.
+pub fn ranked(chain_id: ChainId, urls: impl IntoIterator<Item = Vec<u8>>) -> Vec<Vec<u8>> {
+	let mut ranked: Vec<_> = urls.into_iter().map(|url| (failures(&url), url)).collect();
+	ranked.sort_by_key(|(failures, _)| *failures);
+
+	let local =
+		sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, &rpc_override_key(chain_id));
+	local.into_iter().chain(ranked.into_iter().map(|(_, url)| url)).collect()
+}
+
+/// Run `request` against `urls` in order until one of them doesn't fail at the endpoint, recording
+/// the outcome of every attempt.
+///
+/// Returns the last error if all endpoints fail, and [`OracleError::InvalidConfig`] if there are
+/// none.
+pub fn with_failover<T>(
+	urls: &[Vec<u8>],
+	mut request: impl FnMut(&[u8]) -> Result<T, OracleError>,
+) -> Result<T, OracleError> {
+	let mut result = Err(OracleError::InvalidConfig);
+	for url in urls {
+		result = request(url);
+		record(url, &result);
+		match &result {
+			Err(e) if e.is_endpoint_failure() => continue,
+			_ => break,
+		}
+	}
+	result
+}
+
+/// An endpoint with the timestamp of the chain head it served, in unix seconds.
+pub type ServedHead = (Vec<u8>, u64);
+
+/// A price read from an endpoint, with the [`ServedHead`] if it was asked for.
+pub type ServedPrice = Result<(Price, Option<ServedHead>), OracleError>;
+
+/// Which of `heads`, the chain and the head timestamp in seconds each observation of an offchain
+/// worker run was read with, lag the newest head of their chain in the run by more than
+/// `tolerance` seconds.
+///
+/// An endpoint serving an old head answers from stale state, like an archive node or a node that
+/// stopped syncing, however well-formed its responses are.
+pub fn lagging_heads(heads: &[(ChainId, u64)], tolerance: u64) -> Vec<bool> {
+	let mut newest = BTreeMap::<ChainId, u64>::new();
+	for (chain_id, head) in heads {
+		let newest = newest.entry(*chain_id).or_default();
+		*newest = (*newest).max(*head);
+	}
+	heads
+		.iter()
+		.map(|(chain_id, head)| head.saturating_add(tolerance) < newest[chain_id])
+		.collect()
+}
+
+/// Key of the id of the last JSON-RPC request sent, in the persistent offchain storage.
+pub const REQUEST_ID_KEY: &[u8] = b"price-oracle/rpc-request-id";
+
+/// Key of the hashes of the latest responses and the endpoints they came from, in the persistent
+/// offchain storage.
+pub const RECENT_RESPONSES_KEY: &[u8] = b"price-oracle/rpc-recent-responses";
+
+/// Number of responses remembered by [`check_unique_response`], enough to cover the requests of
+/// an offchain worker run.
+pub const RECENT_RESPONSES: usize = 16;
+
+/// Hash of a response body and the endpoint it came from.
+type SeenResponse = ([u8; 16], Vec<u8>);
+
+/// Reserve `count` consecutive JSON-RPC request ids and return the first one.
+///
+/// Ids keep increasing across runs, so a response can only match the request it was sent for.
+pub fn next_request_ids(count: u64) -> u64 {
+	let mut next = 1;
+	let _ = StorageValueRef::persistent(REQUEST_ID_KEY).mutate(|last: Result<Option<u64>, _>| {
+		next = last.ok().flatten().unwrap_or(0).wrapping_add(1).max(1);
+		Ok::<_, ()>(next.wrapping_add(count.saturating_sub(1)))
+	});
+	next
+}
+
+/// Check that `body`, received from `url`, isn't identical to a recent response from another
+/// endpoint.
+///
+/// Every request carries its own id, so two endpoints returning the same bytes points at a load
+/// balancer replaying responses. The repeat is logged and rejected with
+/// [`OracleError::DuplicateResponse`], which counts as a failure of the endpoint.
+pub fn check_unique_response(url: &[u8], body: &[u8]) -> Result<(), OracleError> {
+	let hash = sp_io::hashing::blake2_128(body);
+	let mut duplicate = None;
+	let _ = StorageValueRef::persistent(RECENT_RESPONSES_KEY).mutate(
+		|recent: Result<Option<Vec<SeenResponse>>, _>| {
+			let mut recent = recent.ok().flatten().unwrap_or_default();
+			duplicate = recent
+				.iter()
+				.find(|(seen, from)| *seen == hash && from.as_slice() != url)
+				.map(|(_, from)| from.clone());
+			if recent.len() >= RECENT_RESPONSES {
+				recent.remove(0);
+			}
+			recent.push((hash, url.to_vec()));
+			Ok::<_, ()>(recent)
+		},
+	);
+
+	match duplicate {
+		Some(from) => {
+			log::warn!(
+				target: crate::LOG_TARGET,
+				"{} returned the same response as {}",
+				core::str::from_utf8(url).unwrap_or("<invalid url>"),
+				core::str::from_utf8(&from).unwrap_or("<invalid url>"),
+			);
+			Err(OracleError::DuplicateResponse)
+		},
+		None => Ok(()),
+	}
+}
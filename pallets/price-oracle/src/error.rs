@@ -0,0 +1,108 @@
+//! Errors of the price fetch path.
+
+use crate::fixed::Price;
+use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_runtime::{offchain::http, DispatchError, RuntimeDebug};
+
+/// Why an exchange could not produce a price.
+///
+/// Shared by [`ChainInterface`](crate::chains::ChainInterface),
+/// [`DexProtocol`](crate::protocols::DexProtocol) and
+/// [`ExchangeConfig`](crate::exchanges::ExchangeConfig) so a failure can be traced back to the
+/// layer it happened in.
+#[derive(
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub enum OracleError {
+	/// The HTTP request could not be sent or its response could not be read.
+	Transport,
+	/// The endpoint did not answer before the deadline.
+	Timeout,
+	/// The endpoint answered with a non-200 status code.
+	HttpStatus(u16),
+	/// The endpoint answered with a JSON-RPC error object.
+	RpcError { code: i64 },
+	/// The response or the contract return data could not be decoded.
+	DecodeError,
+	/// The exchange has no pool for the pair.
+	UnsupportedPair,
+	/// The price lies outside of the pair's bounds.
+	PriceOutOfBounds { value: Price },
+	/// The observation is older than allowed.
+	StaleData,
+	/// The exchange or pool configuration is unusable, e.g. a URL that isn't UTF-8.
+	InvalidConfig,
+	/// The spot price of a pool deviates too far from the pool's own TWAP.
+	SpotDeviatesFromTwap { spot: Price, twap: Price },
+	/// The response does not answer the request it was sent for.
+	ResponseIdMismatch,
+	/// The response is byte-for-byte identical to one received from another endpoint.
+	DuplicateResponse,
+	/// The endpoint serves a chain head older than other endpoints of the chain do.
+	LaggingEndpoint,
+	/// The call to the pool reverted within a batch of calls that otherwise succeeded.
+	Reverted,
+}
+
+impl OracleError {
+	/// Short description of the error, for logs and dispatch errors.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			OracleError::Transport => "transport failure",
+			OracleError::Timeout => "request timed out",
+			OracleError::HttpStatus(_) => "unexpected HTTP status",
+			OracleError::RpcError { .. } => "JSON-RPC error",
+			OracleError::DecodeError => "malformed response",
+			OracleError::UnsupportedPair => "pair not supported by exchange",
+			OracleError::PriceOutOfBounds { .. } => "price out of bounds",
+			OracleError::StaleData => "stale data",
+			OracleError::InvalidConfig => "invalid exchange configuration",
+			OracleError::SpotDeviatesFromTwap { .. } => "spot price deviates from pool TWAP",
+			OracleError::ResponseIdMismatch => "response id does not match request",
+			OracleError::DuplicateResponse => "response duplicates another endpoint's",
+			OracleError::LaggingEndpoint => "endpoint lags behind the chain",
+			OracleError::Reverted => "call reverted",
+		}
+	}
+
+	/// Whether the error lies with the RPC endpoint rather than the pool, so another endpoint of
+	/// the chain might succeed.
+	pub fn is_endpoint_failure(&self) -> bool {
+		matches!(
+			self,
+			OracleError::Transport |
+				OracleError::Timeout |
+				OracleError::HttpStatus(_) |
+				OracleError::RpcError { .. } |
+				OracleError::InvalidConfig |
+				OracleError::ResponseIdMismatch |
+				OracleError::DuplicateResponse |
+				OracleError::LaggingEndpoint
+		)
+	}
+}
+
+impl From<http::Error> for OracleError {
+	fn from(e: http::Error) -> Self {
+		match e {
+			http::Error::DeadlineReached => OracleError::Timeout,
+			http::Error::IoError | http::Error::Unknown => OracleError::Transport,
+		}
+	}
+}
+
+impl From<OracleError> for DispatchError {
+	fn from(e: OracleError) -> Self {
+		DispatchError::Other(e.as_str())
+	}
+}
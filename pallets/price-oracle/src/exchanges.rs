@@ -0,0 +1,460 @@
+//! Exchanges: a protocol deployed on a chain.
+//!
+//! Exchanges are plain data. The chain transport and the protocol parser are selected by the
+//! [`ChainKind`] and [`ProtocolKind`] of an [`ExchangeConfig`], so the exchange set can be encoded,
+//! stored and changed without touching code.
+
+use crate::{
+	chains::{ChainInterface, ChainKind},
+	error::OracleError,
+	fixed::{self, Price},
+	pool_cache::{self, PoolTokens},
+	protocols::{DexProtocol, ProtocolKind, UniswapV3Protocol},
+	types::{
+		CallOptions, ChainId, EvmAddress, Name, PairInfo, PoolMetadata, SolanaAddress, TwapCheck,
+	},
+};
+use alloc::{format, string::String, vec, vec::Vec};
+use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+
+/// Identifier the prices of an exchange are stored under.
+pub type ExchangeId = u8;
+
+/// Stable identifier of an exchange, kept when its [`ExchangeId`] is remapped: `blake2_256` of
+/// its name and protocol, see [`exchange_key`].
+pub type ExchangeKey = [u8; 32];
+
+/// [`ExchangeKey`] of the exchange named `name` with pools of the `protocol`.
+///
+/// A venue with several pool types, e.g. the classic and the concentrated liquidity pools of
+/// SushiSwap, registers an exchange per type under the same name. Each gets its own
+/// [`ExchangeId`], so their prices are separate observations.
+pub fn exchange_key(name: &[u8], protocol: ProtocolKind) -> ExchangeKey {
+	sp_io::hashing::blake2_256(&(name, protocol).encode())
+}
+
+/// A price source the offchain worker can query.
+#[derive(
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+	serde::Serialize,
+	serde::Deserialize,
+)]
+pub struct ExchangeConfig {
+	/// Name of the venue, for logging. Shared by the exchanges of the pool types of a venue.
+	pub name: Name,
+	/// Kind of chain the exchange is deployed on.
+	pub chain: ChainKind,
+	/// Identifier of the chain, selecting its endpoint in
+	/// [`RpcEndpoints`](crate::RpcEndpoints).
+	pub chain_id: ChainId,
+	/// Protocol of the exchange's pools.
+	pub protocol: ProtocolKind,
+	/// Whether the offchain worker queries the exchange.
+	pub enabled: bool,
+	/// Number of seconds of the pools' TWAP the exchange is priced at, instead of their spot
+	/// price, which can be moved within a block. Only Uniswap V3 pools keep a TWAP.
+	#[serde(default)]
+	pub twap_window: Option<u32>,
+	/// Age after which the prices of the exchange are ignored and pruned, in milliseconds,
+	/// instead of [`Config::MaxPriceAge`](crate::Config::MaxPriceAge). Reference feeds updating
+	/// hourly would otherwise go stale on the schedule of the pools.
+	#[serde(default)]
+	pub max_price_age: Option<u64>,
+}
+
+/// A pool of an exchange pricing one pair.
+#[derive(
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+	serde::Serialize,
+	serde::Deserialize,
+)]
+pub struct PoolConfig {
+	/// Contract address of the pool. Unused, and usually zero, for pools with an `account`.
+	pub address: EvmAddress,
+	/// Token layout of the pool.
+	pub metadata: PoolMetadata,
+	/// Account holding the pool state on chains whose pools are accounts instead of contracts,
+	/// such as a Whirlpool on Solana. Pools on EVM chains have none.
+	#[serde(default)]
+	pub account: Option<SolanaAddress>,
+}
+
+impl ExchangeConfig {
+	/// Stable identifier of the exchange.
+	pub fn key(&self) -> ExchangeKey {
+		exchange_key(&self.name, self.protocol)
+	}
+
+	/// Name of the exchange, for logging.
+	pub fn name(&self) -> &str {
+		core::str::from_utf8(&self.name).unwrap_or("<invalid name>")
+	}
+
+	/// Name and protocol of the exchange, telling the pool types of a venue apart in logs.
+	pub fn label(&self) -> String {
+		format!("{} ({})", self.name(), self.protocol.name())
+	}
+
+	/// Calldata pricing a pool of the exchange: `observe` over the exchange's TWAP window, or the
+	/// spot price call of its protocol.
+	fn price_call_data(&self) -> String {
+		match self.twap_window {
+			Some(window) => UniswapV3Protocol.observe_call_data(window),
+			None => self.protocol.call_data().into(),
+		}
+	}
+
+	/// Turn the return data of [`Self::price_call_data`] into the price of `pool`.
+	fn parse_pool_price(&self, data: &[u8], pool: &PoolMetadata) -> Result<Price, OracleError> {
+		match self.twap_window {
+			Some(window) => UniswapV3Protocol.parse_twap(data, window, pool),
+			None => self.protocol.parse_price(data, pool),
+		}
+	}
+
+	/// Fetch the current price of `pair` from `pool` through the chain endpoint `rpc_url`,
+	/// rejecting prices outside of the pair's bounds. The calls are sent with the `options` of
+	/// the exchange's protocol, as are those of the other reads. Pools with an account are read
+	/// whole instead of called, and exchanges with a `twap_window` are priced at the pool's TWAP.
+	pub fn fetch_price(
+		&self,
+		rpc_url: &[u8],
+		pair: &PairInfo,
+		pool: &PoolConfig,
+		options: &CallOptions,
+		timeout_ms: u64,
+	) -> Result<Price, OracleError> {
+		let rpc_url = core::str::from_utf8(rpc_url).map_err(|_| OracleError::InvalidConfig)?;
+
+		let data = match &pool.account {
+			Some(account) => self.chain.read_account(rpc_url, account, timeout_ms)?,
+			None => self.chain.call_contract(
+				rpc_url,
+				&pool.address,
+				&self.price_call_data(),
+				options,
+				timeout_ms,
+			)?,
+		};
+		let price = match &pool.account {
+			Some(_) => self.protocol.parse_price(&data, &pool.metadata)?,
+			None => self.parse_pool_price(&data, &pool.metadata)?,
+		};
+
+		if !pair.in_bounds(price) {
+			return Err(OracleError::PriceOutOfBounds { value: price })
+		}
+
+		Ok(price)
+	}
+
+	/// Fetch the current price of `pair` through two pools of the exchange, for pairs without a
+	/// pool of their own: `pool` prices the base of the pair in an intermediate token and `hop`
+	/// that token in the quote of the pair, e.g. WBTC/WETH and WETH/USDC. Both pools are read in
+	/// one request and only the composed price is checked against the pair's bounds.
+	pub fn fetch_routed_price(
+		&self,
+		rpc_url: &[u8],
+		pair: &PairInfo,
+		pool: &PoolConfig,
+		hop: &PoolConfig,
+		options: &CallOptions,
+		timeout_ms: u64,
+	) -> Result<Price, OracleError> {
+		if pool.account.is_some() || hop.account.is_some() {
+			return Err(OracleError::UnsupportedPair)
+		}
+		let rpc_url = core::str::from_utf8(rpc_url).map_err(|_| OracleError::InvalidConfig)?;
+
+		let data = self.price_call_data();
+		let calls = [(&pool.address, data.as_str()), (&hop.address, data.as_str())];
+		let results = self.chain.call_batch(rpc_url, &calls, options, timeout_ms)?;
+		let [base, intermediate] = &results[..] else { return Err(OracleError::DecodeError) };
+		let price = fixed::product(
+			self.parse_pool_price(base, &pool.metadata)?,
+			self.parse_pool_price(intermediate, &hop.metadata)?,
+		)?;
+
+		if !pair.in_bounds(price) {
+			return Err(OracleError::PriceOutOfBounds { value: price })
+		}
+
+		Ok(price)
+	}
+
+	/// The pool of `pools` and the calldata pricing it, if the price of the pair they quote is
+	/// read with a single contract call that can be batched with the calls of the other
+	/// exchanges of the chain, see [`crate::chains::multicall`]. Pools read whole as accounts,
+	/// the fee tiers of a Uniswap V3 exchange and spot prices checked against the pool's TWAP take
+	/// more than a call.
+	pub fn single_price_call<'a>(
+		&self,
+		pools: &[&'a PoolConfig],
+		check: Option<&TwapCheck>,
+	) -> Option<(&'a PoolConfig, String)> {
+		let [pool, ..] = pools else { return None };
+		let concentrated = self.protocol == ProtocolKind::UniswapV3;
+		let tiered = concentrated && pools.len() > 1;
+		let checked = concentrated && check.is_some() && self.twap_window.is_none();
+		if self.chain != ChainKind::Evm || pool.account.is_some() || tiered || checked {
+			return None
+		}
+		Some((pool, self.price_call_data()))
+	}
+
+	/// The price of `pair` in the return `data` of the [`Self::single_price_call`] to `pool`,
+	/// rejecting prices outside of the pair's bounds.
+	pub fn parse_call_price(
+		&self,
+		pair: &PairInfo,
+		pool: &PoolConfig,
+		data: &[u8],
+	) -> Result<Price, OracleError> {
+		let price = self.parse_pool_price(data, &pool.metadata)?;
+		if !pair.in_bounds(price) {
+			return Err(OracleError::PriceOutOfBounds { value: price })
+		}
+		Ok(price)
+	}
+
+	/// Notional of `pool` in whole units of its quote token, i.e. in USD for pairs quoted in USD:
+	/// twice the quote token it holds at its current price, see [`DexProtocol::parse_depth`].
+	pub fn fetch_notional(
+		&self,
+		rpc_url: &[u8],
+		pool: &PoolConfig,
+		options: &CallOptions,
+		timeout_ms: u64,
+	) -> Result<u128, OracleError> {
+		let rpc_url = core::str::from_utf8(rpc_url).map_err(|_| OracleError::InvalidConfig)?;
+
+		let data = match &pool.account {
+			Some(account) => vec![self.chain.read_account(rpc_url, account, timeout_ms)?],
+			None => {
+				let calls = self.protocol.depth_calls();
+				if calls.is_empty() {
+					return Err(OracleError::UnsupportedPair)
+				}
+				let data = self.chain.call_contract_batch(
+					rpc_url,
+					&pool.address,
+					calls,
+					options,
+					timeout_ms,
+				)?;
+				if data.len() != calls.len() {
+					return Err(OracleError::DecodeError)
+				}
+				data
+			},
+		};
+		let reserve = self.protocol.parse_depth(&data, &pool.metadata)?;
+		let decimals = match pool.metadata.base_is_token0 {
+			true => pool.metadata.token1_decimals,
+			false => pool.metadata.token0_decimals,
+		};
+		Ok(reserve.saturating_mul(2) / 10u128.pow(decimals.into()))
+	}
+
+	/// Like [`Self::fetch_price`], but for Uniswap V3 exchanges also read the pool's own TWAP
+	/// over `check.window` in the same request, rejecting spot prices that deviate from it by
+	/// more than `check.max_deviation`. Other exchanges have no TWAP to check against, and those
+	/// priced at the TWAP have no spot price to check.
+	pub fn fetch_checked_price(
+		&self,
+		rpc_url: &[u8],
+		pair: &PairInfo,
+		pool: &PoolConfig,
+		check: &TwapCheck,
+		options: &CallOptions,
+		timeout_ms: u64,
+	) -> Result<Price, OracleError> {
+		if self.protocol != ProtocolKind::UniswapV3 || self.twap_window.is_some() {
+			return self.fetch_price(rpc_url, pair, pool, options, timeout_ms)
+		}
+		let rpc_url = core::str::from_utf8(rpc_url).map_err(|_| OracleError::InvalidConfig)?;
+
+		let observe = UniswapV3Protocol.observe_call_data(check.window);
+		let data = self.chain.call_contract_batch(
+			rpc_url,
+			&pool.address,
+			&[self.protocol.call_data(), &observe],
+			options,
+			timeout_ms,
+		)?;
+		let [slot0, observations] = &data[..] else { return Err(OracleError::DecodeError) };
+		let spot = self.protocol.parse_price(slot0, &pool.metadata)?;
+		let twap = UniswapV3Protocol.parse_twap(observations, check.window, &pool.metadata)?;
+		Self::check_spot(pair, spot, Some((twap, check)))
+	}
+
+	/// Fetch the current price of `pair` from the pool of `pools` with the most in-range
+	/// liquidity, checking it against the pool's TWAP if there is a `check`.
+	///
+	/// The pools are the fee tiers of the pair on a Uniswap V3 exchange, whose spot prices and
+	/// liquidity are read in one request, so the price follows the liquidity as it moves between
+	/// tiers. Other protocols have no tiers and are priced from the first pool.
+	pub fn fetch_tiered_price(
+		&self,
+		rpc_url: &[u8],
+		pair: &PairInfo,
+		pools: &[&PoolConfig],
+		check: Option<&TwapCheck>,
+		options: &CallOptions,
+		timeout_ms: u64,
+	) -> Result<Price, OracleError> {
+		let (pool, tiered) = match pools {
+			[] => return Err(OracleError::UnsupportedPair),
+			[pool, ..] if pools.len() == 1 || self.protocol != ProtocolKind::UniswapV3 =>
+				(pool, false),
+			[pool, ..] => (pool, true),
+		};
+		if !tiered {
+			return match check {
+				Some(check) =>
+					self.fetch_checked_price(rpc_url, pair, pool, check, options, timeout_ms),
+				None => self.fetch_price(rpc_url, pair, pool, options, timeout_ms),
+			}
+		}
+		let rpc_url = core::str::from_utf8(rpc_url).map_err(|_| OracleError::InvalidConfig)?;
+
+		let price_call = self.price_call_data();
+		let check = check.filter(|_| self.twap_window.is_none());
+		let observe = check.map(|check| UniswapV3Protocol.observe_call_data(check.window));
+		let per_pool = 2 + usize::from(observe.is_some());
+		let calls: Vec<_> = pools
+			.iter()
+			.flat_map(|pool| {
+				[
+					Some(price_call.as_str()),
+					Some(UniswapV3Protocol.liquidity_call_data()),
+					observe.as_deref(),
+				]
+				.into_iter()
+				.flatten()
+				.map(|data| (&pool.address, data))
+			})
+			.collect();
+		let data = self.chain.call_batch(rpc_url, &calls, options, timeout_ms)?;
+		if data.len() != calls.len() {
+			return Err(OracleError::DecodeError)
+		}
+
+		let mut best: Option<(u128, &&PoolConfig, &[Vec<u8>])> = None;
+		for (pool, results) in pools.iter().zip(data.chunks(per_pool)) {
+			let liquidity = UniswapV3Protocol.parse_liquidity(&results[1])?;
+			if best.is_none_or(|(most, ..)| liquidity > most) {
+				best = Some((liquidity, pool, results));
+			}
+		}
+		let (_, pool, results) = best.ok_or(OracleError::DecodeError)?;
+		let spot = self.parse_pool_price(&results[0], &pool.metadata)?;
+		let twap = match (check, results.get(2)) {
+			(Some(check), Some(observations)) => Some((
+				UniswapV3Protocol.parse_twap(observations, check.window, &pool.metadata)?,
+				check,
+			)),
+			_ => None,
+		};
+		Self::check_spot(pair, spot, twap)
+	}
+
+	/// Simulate buying the base token of `pool` with `amount_in` raw units of its quote token
+	/// through the venue's `router` (its Uniswap V3 quoter), returning the raw base amount
+	/// bought.
+	///
+	/// The tokens and fee tier of the pool are read from the pool itself, or the
+	/// [`pool_cache`](crate::pool_cache), so the quote prices the same pool the exchange price was
+	/// read from.
+	pub fn simulate_buy(
+		&self,
+		rpc_url: &[u8],
+		router: &EvmAddress,
+		pool: &PoolConfig,
+		amount_in: u128,
+		options: &CallOptions,
+		timeout_ms: u64,
+	) -> Result<u128, OracleError> {
+		let rpc_url = core::str::from_utf8(rpc_url).map_err(|_| OracleError::InvalidConfig)?;
+
+		let PoolTokens { token0, token1, fee } =
+			self.pool_tokens(rpc_url, pool, options, timeout_ms)?;
+		let (base, quote) =
+			if pool.metadata.base_is_token0 { (token0, token1) } else { (token1, token0) };
+
+		let quote_call = self.protocol.quote_call_data(&quote, &base, fee, amount_in);
+		let data = self.chain.call_contract(rpc_url, router, &quote_call, options, timeout_ms)?;
+		self.protocol.parse_quote(&data)
+	}
+
+	/// The tokens and fee of `pool`, read in one request unless they are in the
+	/// [`pool_cache`](crate::pool_cache).
+	pub fn pool_tokens(
+		&self,
+		rpc_url: &str,
+		pool: &PoolConfig,
+		options: &CallOptions,
+		timeout_ms: u64,
+	) -> Result<PoolTokens, OracleError> {
+		pool_cache::cached("tokens", self.chain_id, &pool.address, || {
+			let calls = self.protocol.token_calls();
+			let data = self.chain.call_contract_batch(
+				rpc_url,
+				&pool.address,
+				calls,
+				options,
+				timeout_ms,
+			)?;
+			if data.len() != calls.len() {
+				return Err(OracleError::DecodeError)
+			}
+			let fee = match data.get(2) {
+				Some(fee) =>
+					fixed::word(fee, 0)?.try_into().map_err(|_| OracleError::DecodeError)?,
+				None => 0,
+			};
+			Ok(PoolTokens {
+				token0: fixed::address_word(&data[0], 0)?,
+				token1: fixed::address_word(&data[1], 0)?,
+				fee,
+			})
+		})
+	}
+
+	/// Reject a `spot` price outside of the bounds of `pair` or, if given, too far from the
+	/// pool's TWAP.
+	fn check_spot(
+		pair: &PairInfo,
+		spot: Price,
+		twap: Option<(Price, &TwapCheck)>,
+	) -> Result<Price, OracleError> {
+		if let Some((twap, check)) = twap {
+			if spot.abs_diff(twap) > check.max_deviation.mul_floor(twap) {
+				return Err(OracleError::SpotDeviatesFromTwap { spot, twap })
+			}
+		}
+		if !pair.in_bounds(spot) {
+			return Err(OracleError::PriceOutOfBounds { value: spot })
+		}
+
+		Ok(spot)
+	}
+}
@@ -0,0 +1,216 @@
+//! Fixed-point price arithmetic.
+//!
+//! Prices are unsigned integers with [`PRICE_DECIMALS`] decimals. Pool state is converted with
+//! 512 bit intermediate products, so results are exact up to the final truncation and identical on
+//! every platform.
+
+use crate::{error::OracleError, rounding::Rounding, types::EvmAddress};
+use sp_core::{U256, U512};
+
+/// A USD price with [`PRICE_DECIMALS`] decimals.
+pub type Price = u128;
+
+/// Number of decimals of a [`Price`].
+pub const PRICE_DECIMALS: u8 = 18;
+
+/// One USD as a [`Price`].
+pub const PRICE_UNIT: Price = 1_000_000_000_000_000_000;
+
+/// Number of [`Price`] units in one micro-USD.
+pub const MICRO: Price = PRICE_UNIT / 1_000_000;
+
+/// Read the `index`-th 32 byte ABI word of `data`.
+pub fn word(data: &[u8], index: usize) -> Result<U256, OracleError> {
+	data.get(index * 32..(index + 1) * 32)
+		.map(U256::from_big_endian)
+		.ok_or(OracleError::DecodeError)
+}
+
+/// `numerator / denominator * 10^exponent` as a [`Price`].
+///
+/// Fails if the denominator is zero, `10^exponent` overflows 512 bits or the result does not fit a
+/// [`Price`].
+pub fn ratio(numerator: U512, denominator: U512, exponent: i32) -> Result<Price, OracleError> {
+	let exponent = exponent.saturating_add(i32::from(PRICE_DECIMALS));
+	let scale = U512::from(10u8)
+		.checked_pow(U512::from(exponent.unsigned_abs()))
+		.ok_or(OracleError::DecodeError)?;
+	let (numerator, denominator) = if exponent >= 0 {
+		(numerator.checked_mul(scale), Some(denominator))
+	} else {
+		(Some(numerator), denominator.checked_mul(scale))
+	};
+	let (numerator, denominator) = numerator.zip(denominator).ok_or(OracleError::DecodeError)?;
+	if denominator.is_zero() {
+		return Err(OracleError::DecodeError)
+	}
+	u128::try_from(numerator / denominator).map_err(|_| OracleError::DecodeError)
+}
+
+/// Price implied by a Uniswap V3 `sqrtPriceX96`, scaled by `10^decimals_delta` to convert raw
+/// token amounts into whole tokens.
+///
+/// `sqrtPriceX96` encodes the raw amount of token1 per raw token0, which is the price of token0
+/// in token1. With `invert` the price of token1 in token0 is returned instead.
+pub fn price_from_sqrt_x96(
+	sqrt_price_x96: U256,
+	decimals_delta: i32,
+	invert: bool,
+) -> Result<Price, OracleError> {
+	let squared = U512::from(sqrt_price_x96) * U512::from(sqrt_price_x96);
+	let q192 = U512::one() << 192;
+	if invert {
+		ratio(q192, squared, decimals_delta)
+	} else {
+		ratio(squared, q192, decimals_delta)
+	}
+}
+
+/// Raw amount of token1, or with `of_token0` of token0, backing `liquidity` at the Uniswap V3
+/// `sqrtPriceX96`: the virtual reserves of a concentrated liquidity pool, `L * sqrtP` of token1
+/// and `L / sqrtP` of token0.
+pub fn virtual_reserve(
+	liquidity: u128,
+	sqrt_price_x96: U256,
+	of_token0: bool,
+) -> Result<u128, OracleError> {
+	if sqrt_price_x96.is_zero() {
+		return Err(OracleError::DecodeError)
+	}
+	let (liquidity, sqrt_price) = (U512::from(liquidity), U512::from(sqrt_price_x96));
+	let reserve =
+		if of_token0 { (liquidity << 96) / sqrt_price } else { (liquidity * sqrt_price) >> 96 };
+	u128::try_from(reserve).map_err(|_| OracleError::DecodeError)
+}
+
+/// Lowest tick of a Uniswap V3 pool.
+pub const MIN_TICK: i32 = -887_272;
+
+/// Highest tick of a Uniswap V3 pool.
+pub const MAX_TICK: i32 = -MIN_TICK;
+
+/// `sqrtPriceX96` at `tick`, i.e. `sqrt(1.0001^tick) * 2^96`, rounded up exactly like Uniswap V3's
+/// `TickMath.getSqrtRatioAtTick`.
+pub fn sqrt_ratio_at_tick(tick: i32) -> Result<U256, OracleError> {
+	// `2^128 / sqrt(1.0001^(2^i))` in Q128.128 for each bit `i` of the tick.
+	const FACTORS: [&str; 19] = [
+		"fff97272373d413259a46990580e213a",
+		"fff2e50f5f656932ef12357cf3c7fdcc",
+		"ffe5caca7e10e4e61c3624eaa0941cd0",
+		"ffcb9843d60f6159c9db58835c926644",
+		"ff973b41fa98c081472e6896dfb254c0",
+		"ff2ea16466c96a3843ec78b326b52861",
+		"fe5dee046a99a2a811c461f1969c3053",
+		"fcbe86c7900a88aedcffc83b479aa3a4",
+		"f987a7253ac413176f2b074cf7815e54",
+		"f3392b0822b70005940c7a398e4b70f3",
+		"e7159475a2c29b7443b29c7fa6e889d9",
+		"d097f3bdfd2022b8845ad8f792aa5825",
+		"a9f746462d870fdf8a65dc1f90e061e5",
+		"70d869a156d2a1b890bb3df62baf32f7",
+		"31be135f97d08fd981231505542fcfa6",
+		"9aa508b5b7a84e1c677de54f3e99bc9",
+		"5d6af8dedb81196699c329225ee604",
+		"2216e584f5fa1ea926041bedfe98",
+		"48a170391f7dc42444e8fa2",
+	];
+	let abs_tick = tick.unsigned_abs();
+	if abs_tick > MAX_TICK.unsigned_abs() {
+		return Err(OracleError::DecodeError)
+	}
+	let factor = |hex: &str| U256::from_str_radix(hex, 16).expect("constants are valid hex");
+	let mut ratio = match abs_tick & 1 {
+		0 => U256::one() << 128,
+		_ => factor("fffcb933bd6fad37aa2d162d1a594001"),
+	};
+	for (bit, hex) in FACTORS.iter().enumerate() {
+		if abs_tick & (2 << bit) != 0 {
+			ratio = (ratio * factor(hex)) >> 128;
+		}
+	}
+	if tick > 0 {
+		ratio = U256::MAX / ratio;
+	}
+	// Q128.128 to Q64.96, rounding up.
+	let round_up = !(ratio % (U256::one() << 32)).is_zero();
+	Ok((ratio >> 32) + U256::from(u8::from(round_up)))
+}
+
+/// Read the `index`-th 32 byte ABI word of `data` as an address.
+pub fn address_word(data: &[u8], index: usize) -> Result<EvmAddress, OracleError> {
+	let word = data.get(index * 32..(index + 1) * 32).ok_or(OracleError::DecodeError)?;
+	let (padding, address) = word.split_at(12);
+	if padding.iter().any(|byte| *byte != 0) {
+		return Err(OracleError::DecodeError)
+	}
+	address.try_into().map_err(|_| OracleError::DecodeError)
+}
+
+/// Read the `index`-th 32 byte ABI word of `data` as a two's complement signed integer.
+pub fn signed_word(data: &[u8], index: usize) -> Result<i128, OracleError> {
+	let word = word(data, index)?;
+	if word.bit(255) {
+		let magnitude = (!word).overflowing_add(U256::one()).0;
+		i128::try_from(magnitude).map(|magnitude| -magnitude)
+	} else {
+		i128::try_from(word)
+	}
+	.map_err(|_| OracleError::DecodeError)
+}
+
+/// Raw amount of a token with `quote_decimals` worth `amount` raw units of a token with
+/// `base_decimals` priced at `price` in it, truncated.
+///
+/// Fails if the result does not fit a `u128`.
+pub fn convert(
+	amount: u128,
+	price: Price,
+	base_decimals: u8,
+	quote_decimals: u8,
+) -> Result<u128, OracleError> {
+	// `ratio` returns [`PRICE_DECIMALS`] more decimals than asked for, as prices have them.
+	let exponent = i32::from(quote_decimals) - i32::from(base_decimals);
+	let unit = U512::from(PRICE_UNIT);
+	ratio(U512::from(amount) * U512::from(price), unit * unit, exponent)
+}
+
+/// `numerator / denominator` of two [`Price`]s as a [`Price`], truncated, e.g. the price of a
+/// token in another from their prices in a third.
+///
+/// Fails if the denominator is zero or the result does not fit a [`Price`].
+pub fn quotient(numerator: Price, denominator: Price) -> Result<Price, OracleError> {
+	ratio(U512::from(numerator), U512::from(denominator), 0)
+}
+
+/// `a * b` of two [`Price`]s as a [`Price`], truncated, e.g. the price of a token in a third one
+/// from its price in a second one and the price of that in the third.
+///
+/// Fails if the result does not fit a [`Price`].
+pub fn product(a: Price, b: Price) -> Result<Price, OracleError> {
+	let unit = U512::from(PRICE_UNIT);
+	ratio(U512::from(a) * U512::from(b), unit * unit, 0)
+}
+
+/// Scale `value`, a price with `exponent` decimals, to a [`Price`].
+///
+/// Returns `None` if `exponent` exceeds [`PRICE_DECIMALS`] or the result overflows.
+pub fn normalize(value: u128, exponent: u8) -> Option<Price> {
+	let shift = PRICE_DECIMALS.checked_sub(exponent)?;
+	value.checked_mul(10u128.pow(shift.into()))
+}
+
+/// Cut `price` to `exponent` decimals for submission, rounded by `rounding`, returning the value
+/// with `exponent` decimals. `exponent` must not exceed [`PRICE_DECIMALS`].
+pub fn to_precision(price: Price, exponent: u8, rounding: Rounding) -> u128 {
+	rounding.divide(price, 10u128.pow(u32::from(PRICE_DECIMALS - exponent)))
+}
+
+/// Convert a [`Price`] into micro-USD, truncating.
+pub fn to_micro(price: Price) -> u64 {
+	u64::try_from(price / MICRO).unwrap_or(u64::MAX)
+}
+
+/// Convert micro-USD into a [`Price`].
+pub const fn from_micro(micro: u64) -> Price {
+	micro as Price * MICRO
+}
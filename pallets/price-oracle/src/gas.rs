@@ -0,0 +1,62 @@
+//! Fee market of EIP-1559 chains, read with `eth_feeHistory`.
+//!
+//! The cost of getting a transaction included depends on the base fee of the next block and on
+//! the tip paid to the block producer, which follows its own dynamics. Both are kept per chain
+//! in [`GasData`](crate::GasData).
+//!
+//! Bundles racing for the top of a block pay far more than the typical tip. For the chains in
+//! [`BuilderTipChains`](crate::BuilderTipChains) a high percentile of the tips is sampled as
+//! well, so profitability checks can price in what competitive inclusion costs.
+
+use crate::{
+	aggregation, error::OracleError, json::FeeHistory, rounding::RoundingPolicy, types::GasFees,
+};
+use alloc::vec::Vec;
+
+/// Number of the latest blocks the priority fees are sampled from.
+pub const FEE_HISTORY_BLOCKS: u64 = 10;
+
+/// Percentile of the priority fees paid in a block that is sampled as its typical tip.
+pub const PRIORITY_FEE_PERCENTILE: u8 = 50;
+
+/// Percentile of the priority fees paid in a block that is sampled as the tip of the
+/// transactions builders put first.
+pub const BUILDER_TIP_PERCENTILE: u8 = 90;
+
+/// Percentiles of the priority fees to request, with the builder tips or without.
+pub fn percentiles(builder_tips: bool) -> &'static [u8] {
+	if builder_tips {
+		&[PRIORITY_FEE_PERCENTILE, BUILDER_TIP_PERCENTILE]
+	} else {
+		&[PRIORITY_FEE_PERCENTILE]
+	}
+}
+
+/// Fees of the block after `history`, requested with [`percentiles`]: its base fee, which the
+/// history already knows, and the median of the [`PRIORITY_FEE_PERCENTILE`] priority fees of the
+/// sampled blocks, and of the [`BUILDER_TIP_PERCENTILE`] ones if `builder_tips`.
+///
+/// Blocks without transactions report zero tips, which count like any other. The medians follow
+/// `policy`.
+pub fn gas_fees(
+	history: &FeeHistory,
+	builder_tips: bool,
+	timestamp: u64,
+	policy: RoundingPolicy,
+) -> Result<GasFees, OracleError> {
+	let base_fee = *history.base_fees.last().ok_or(OracleError::DecodeError)?;
+	// Median over the blocks of the tips at the `index`-th requested percentile.
+	let median_tip = |index: usize| {
+		let mut tips: Vec<_> =
+			history.rewards.iter().filter_map(|tips| tips.get(index).copied()).collect();
+		aggregation::median(&mut tips, policy).unwrap_or_default()
+	};
+	let blocks = history.base_fees.len() as u64 - 1;
+	Ok(GasFees {
+		base_fee,
+		priority_fee: median_tip(0),
+		builder_tip: builder_tips.then(|| median_tip(1)),
+		block: history.oldest_block.saturating_add(blocks),
+		timestamp,
+	})
+}
@@ -0,0 +1,306 @@
+//! Decoding of JSON-RPC responses.
+
+use crate::{chains::solana, error::OracleError, types::SolanaAddress};
+use alloc::{string::String, vec::Vec};
+use base64::Engine;
+use lite_json::{JsonObject, JsonValue};
+use sp_runtime::RuntimeDebug;
+
+/// The `error` object of a JSON-RPC response.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct RpcError {
+	pub code: i64,
+	pub message: String,
+}
+
+/// A decoded JSON-RPC response.
+#[derive(Clone, PartialEq, RuntimeDebug)]
+pub struct RpcResponse {
+	/// Id of the request the response answers, if the endpoint echoed a numeric one.
+	pub id: Option<u64>,
+	/// The `result` member, or the `error` object if the call failed.
+	pub result: Result<JsonValue, RpcError>,
+}
+
+impl RpcResponse {
+	/// Decode a single JSON-RPC response object.
+	pub fn parse(body: &[u8]) -> Result<Self, OracleError> {
+		let body = core::str::from_utf8(body).map_err(|_| OracleError::DecodeError)?;
+		match lite_json::parse_json(body).map_err(|_| OracleError::DecodeError)? {
+			JsonValue::Object(object) => Self::from_object(object),
+			_ => Err(OracleError::DecodeError),
+		}
+	}
+
+	/// Decode a batch of JSON-RPC responses, which may come in any order.
+	pub fn parse_batch(body: &[u8]) -> Result<Vec<Self>, OracleError> {
+		let body = core::str::from_utf8(body).map_err(|_| OracleError::DecodeError)?;
+		match lite_json::parse_json(body).map_err(|_| OracleError::DecodeError)? {
+			JsonValue::Array(responses) => responses
+				.into_iter()
+				.map(|response| match response {
+					JsonValue::Object(object) => Self::from_object(object),
+					_ => Err(OracleError::DecodeError),
+				})
+				.collect(),
+			_ => Err(OracleError::DecodeError),
+		}
+	}
+
+	fn from_object(object: JsonObject) -> Result<Self, OracleError> {
+		let mut id = None;
+		let mut result = None;
+		let mut error = None;
+		for (key, value) in object {
+			if is_key(&key, "id") {
+				id = as_u64(&value);
+			} else if is_key(&key, "result") {
+				result = Some(value);
+			} else if is_key(&key, "error") {
+				error = Some(value);
+			}
+		}
+
+		let result = match (result, error) {
+			(_, Some(JsonValue::Object(error))) => Err(parse_error(&error)?),
+			(Some(result), None) => Ok(result),
+			_ => return Err(OracleError::DecodeError),
+		};
+		Ok(RpcResponse { id, result })
+	}
+
+	/// The `result` of the response, logging and converting an error object.
+	pub fn into_result(self) -> Result<JsonValue, OracleError> {
+		self.result.map_err(|e| {
+			log::debug!(target: crate::LOG_TARGET, "JSON-RPC error {}: {}", e.code, e.message);
+			OracleError::RpcError { code: e.code }
+		})
+	}
+}
+
+fn parse_error(object: &JsonObject) -> Result<RpcError, OracleError> {
+	let code = field(object, "code").and_then(as_i64).ok_or(OracleError::DecodeError)?;
+	let message = field(object, "message").and_then(as_string).unwrap_or_default();
+	Ok(RpcError { code, message })
+}
+
+/// Decode the `0x` prefixed hex string returned by calls like `eth_call`.
+pub fn hex_bytes(value: &JsonValue) -> Result<Vec<u8>, OracleError> {
+	let hex = as_string(value).ok_or(OracleError::DecodeError)?;
+	let hex = hex.strip_prefix("0x").ok_or(OracleError::DecodeError)?;
+	array_bytes::hex2bytes(hex).map_err(|_| OracleError::DecodeError)
+}
+
+/// Decode the response to the request `id` whose `result` is hex encoded bytes.
+///
+/// A response echoing another id is rejected with [`OracleError::ResponseIdMismatch`] before its
+/// result is looked at.
+pub fn hex_result(body: &[u8], id: u64) -> Result<Vec<u8>, OracleError> {
+	let response = RpcResponse::parse(body)?;
+	if response.id != Some(id) {
+		return Err(OracleError::ResponseIdMismatch)
+	}
+	hex_bytes(&response.into_result()?)
+}
+
+/// Decode the response to the request `id` whose `result` is a hex quantity, like the block
+/// number returned by `eth_blockNumber`.
+pub fn quantity_result(body: &[u8], id: u64) -> Result<u128, OracleError> {
+	let response = RpcResponse::parse(body)?;
+	if response.id != Some(id) {
+		return Err(OracleError::ResponseIdMismatch)
+	}
+	as_quantity(&response.into_result()?).ok_or(OracleError::DecodeError)
+}
+
+/// Decode the `timestamp` of the block returned by the `eth_getBlockByNumber` request `id`, in
+/// unix seconds.
+pub fn block_timestamp(body: &[u8], id: u64) -> Result<u64, OracleError> {
+	let response = RpcResponse::parse(body)?;
+	if response.id != Some(id) {
+		return Err(OracleError::ResponseIdMismatch)
+	}
+	let JsonValue::Object(block) = response.into_result()? else {
+		return Err(OracleError::DecodeError)
+	};
+	field(&block, "timestamp")
+		.and_then(as_quantity)
+		.and_then(|timestamp| u64::try_from(timestamp).ok())
+		.ok_or(OracleError::DecodeError)
+}
+
+/// Decode the response to the request `id` whose `result` is an integer, like the slot returned by
+/// Solana's `getSlot`.
+pub fn u64_result(body: &[u8], id: u64) -> Result<u64, OracleError> {
+	let response = RpcResponse::parse(body)?;
+	if response.id != Some(id) {
+		return Err(OracleError::ResponseIdMismatch)
+	}
+	as_u64(&response.into_result()?).ok_or(OracleError::DecodeError)
+}
+
+/// Decode the data of the account returned by the Solana `getAccountInfo` request `id`.
+pub fn account_data(body: &[u8], id: u64) -> Result<Vec<u8>, OracleError> {
+	let response = RpcResponse::parse(body)?;
+	if response.id != Some(id) {
+		return Err(OracleError::ResponseIdMismatch)
+	}
+	account_info_data(&response.into_result()?)
+}
+
+/// Decode the account data of the `result` of a Solana `getAccountInfo` request.
+///
+/// An account that doesn't exist has no data and is rejected like malformed data.
+pub fn account_info_data(result: &JsonValue) -> Result<Vec<u8>, OracleError> {
+	let JsonValue::Object(result) = result else { return Err(OracleError::DecodeError) };
+	match field(result, "value") {
+		Some(JsonValue::Object(account)) =>
+			field(account, "data").ok_or(OracleError::DecodeError).and_then(base64_data),
+		_ => Err(OracleError::DecodeError),
+	}
+}
+
+/// Decode the keys and data of the accounts returned by the Solana `getProgramAccounts` request
+/// `id`.
+pub fn program_accounts(
+	body: &[u8],
+	id: u64,
+) -> Result<Vec<(SolanaAddress, Vec<u8>)>, OracleError> {
+	let response = RpcResponse::parse(body)?;
+	if response.id != Some(id) {
+		return Err(OracleError::ResponseIdMismatch)
+	}
+	let JsonValue::Array(accounts) = response.into_result()? else {
+		return Err(OracleError::DecodeError)
+	};
+	accounts
+		.iter()
+		.map(|entry| {
+			let JsonValue::Object(entry) = entry else { return Err(OracleError::DecodeError) };
+			let key = field(entry, "pubkey")
+				.and_then(as_string)
+				.and_then(|key| solana::parse_address(&key))
+				.ok_or(OracleError::DecodeError)?;
+			let Some(JsonValue::Object(account)) = field(entry, "account") else {
+				return Err(OracleError::DecodeError)
+			};
+			Ok((key, base64_data(field(account, "data").ok_or(OracleError::DecodeError)?)?))
+		})
+		.collect()
+}
+
+/// Decode Solana account data read with the `base64` encoding, a `[data, "base64"]` pair.
+pub fn base64_data(value: &JsonValue) -> Result<Vec<u8>, OracleError> {
+	let JsonValue::Array(pair) = value else { return Err(OracleError::DecodeError) };
+	match &pair[..] {
+		[data, encoding] if as_string(encoding).as_deref() == Some("base64") => {
+			let data = as_string(data).ok_or(OracleError::DecodeError)?;
+			base64::engine::general_purpose::STANDARD
+				.decode(data)
+				.map_err(|_| OracleError::DecodeError)
+		},
+		_ => Err(OracleError::DecodeError),
+	}
+}
+
+/// Decode the response to a batch of requests with the ids `ids` whose `result`s are hex encoded
+/// bytes, in request order.
+pub fn hex_results(
+	body: &[u8],
+	ids: impl IntoIterator<Item = u64>,
+) -> Result<Vec<Vec<u8>>, OracleError> {
+	let mut responses = RpcResponse::parse_batch(body)?;
+	ids.into_iter()
+		.map(|id| {
+			let index = responses
+				.iter()
+				.position(|response| response.id == Some(id))
+				.ok_or(OracleError::ResponseIdMismatch)?;
+			hex_bytes(&responses.swap_remove(index).into_result()?)
+		})
+		.collect()
+}
+
+/// The result of `eth_feeHistory`.
+#[derive(Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct FeeHistory {
+	/// Number of the oldest block of the history.
+	pub oldest_block: u64,
+	/// Base fee per gas of each block of the history and of the block after it, in wei.
+	pub base_fees: Vec<u128>,
+	/// Priority fees per gas paid at each requested percentile, per block, in wei.
+	pub rewards: Vec<Vec<u128>>,
+}
+
+/// Decode the response to the `eth_feeHistory` request `id`.
+pub fn fee_history(body: &[u8], id: u64) -> Result<FeeHistory, OracleError> {
+	let response = RpcResponse::parse(body)?;
+	if response.id != Some(id) {
+		return Err(OracleError::ResponseIdMismatch)
+	}
+	let JsonValue::Object(result) = response.into_result()? else {
+		return Err(OracleError::DecodeError)
+	};
+	let quantities = |value: &JsonValue| match value {
+		JsonValue::Array(values) => values.iter().map(as_quantity).collect::<Option<Vec<_>>>(),
+		_ => None,
+	};
+	let oldest_block = field(&result, "oldestBlock")
+		.and_then(as_quantity)
+		.and_then(|block| u64::try_from(block).ok());
+	let base_fees = field(&result, "baseFeePerGas").and_then(quantities);
+	// Without requested percentiles the rewards are left out.
+	let rewards = match field(&result, "reward") {
+		Some(JsonValue::Array(blocks)) => blocks.iter().map(quantities).collect(),
+		Some(_) => None,
+		None => Some(Vec::new()),
+	};
+	match (oldest_block, base_fees, rewards) {
+		(Some(oldest_block), Some(base_fees), Some(rewards)) =>
+			Ok(FeeHistory { oldest_block, base_fees, rewards }),
+		_ => Err(OracleError::DecodeError),
+	}
+}
+
+/// The member `name` of a JSON object.
+pub fn field<'a>(object: &'a JsonObject, name: &str) -> Option<&'a JsonValue> {
+	object.iter().find(|(key, _)| is_key(key, name)).map(|(_, value)| value)
+}
+
+fn is_key(key: &[char], name: &str) -> bool {
+	key.iter().copied().eq(name.chars())
+}
+
+/// A JSON string as a Rust string.
+pub fn as_string(value: &JsonValue) -> Option<String> {
+	match value {
+		JsonValue::String(chars) => Some(chars.iter().collect()),
+		_ => None,
+	}
+}
+
+/// A `0x` prefixed hex quantity that fits `u128`, as returned for block numbers and fees.
+pub fn as_quantity(value: &JsonValue) -> Option<u128> {
+	let hex = as_string(value)?;
+	u128::from_str_radix(hex.strip_prefix("0x")?, 16).ok()
+}
+
+/// A JSON integer that fits `u64`.
+pub fn as_u64(value: &JsonValue) -> Option<u64> {
+	match value {
+		JsonValue::Number(n) if !n.negative && n.fraction == 0 && n.exponent == 0 =>
+			Some(n.integer),
+		_ => None,
+	}
+}
+
+/// A JSON integer that fits `i64`.
+pub fn as_i64(value: &JsonValue) -> Option<i64> {
+	match value {
+		JsonValue::Number(n) if n.fraction == 0 && n.exponent == 0 => {
+			let integer = i64::try_from(n.integer).ok()?;
+			Some(if n.negative { -integer } else { integer })
+		},
+		_ => None,
+	}
+}
@@ -0,0 +1,4528 @@
+//! # Price Oracle Pallet
+//!
+//! Brings DEX prices from external chains on-chain.
+//!
+//! Every [`Config::UpdateInterval`] blocks the offchain worker reads the pools of the pairs in
+//! [`TrackedPairs`] on the enabled [`Exchanges`] over HTTP JSON-RPC and submits their prices,
+//! signed by a registered operator, see [`operators`], or unsigned where
+//! [`Config::AcceptUnsigned`] allows it. The fetch path is layered: [`chains`] call contracts on
+//! a chain, [`protocols`] turn the state of a pool into a price and [`exchanges`] combine both.
+//! The node side of it is covered by [`endpoints`], [`proxy`] and [`telemetry`], and the fees of
+//! the chains are read along with the prices, see [`gas`].
+//!
+//! Prices are kept per pair and exchange in [`PriceData`] and combined at the end of each block
+//! into the [`AggregatedPrice`] of the pair, see [`aggregation`]. Unreliable exchanges are set
+//! aside by [`quarantine`], spreads between exchanges are checked in [`arbitrage`], and
+//! [`VerifiedPrices`] are derived from storage proofs instead, see [`proofs`].
+//!
+//! Other pallets read prices through [`PriceProvider`], consumers on other consensus systems get
+//! them pushed through [`subscriptions`], and accounts watching a price are notified through
+//! [`alerts`]. The pairs and exchanges are managed by [`Config::RegistryOrigin`] and carried over
+//! to other chains with [`registry`].
+//!
+//! ## Features
+//!
+//! - `synthetic-prices`: exchanges on the synthetic chain generate a deterministic random walk
+//!   instead, so development chains and CI runtimes run the whole pipeline offline.
+//! - `legacy-create-inherent`: builds against polkadot-sdk stable2412 and stable2503 rather than
+//!   stable2506 and later, see [`compat`].
+//! - `xcm`: adds `xcm_messenger`, sending prices to consumers on other consensus systems over XCM.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub use pallet::*;
+
+pub mod aggregation;
+pub mod alerts;
+pub mod arbitrage;
+pub mod budget;
+pub mod chains;
+pub mod compat;
+pub mod endpoints;
+pub mod error;
+pub mod exchanges;
+pub mod fixed;
+pub mod gas;
+pub mod json;
+pub mod operators;
+pub mod pool_cache;
+pub mod proofs;
+pub mod protocols;
+pub mod proxy;
+pub mod quarantine;
+pub mod registry;
+#[cfg(feature = "std")]
+pub mod replay;
+pub mod rounding;
+pub mod rpc;
+pub mod subscriptions;
+pub mod telemetry;
+pub mod types;
+#[cfg(feature = "xcm")]
+pub mod xcm_messenger;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(test)]
+mod differential_tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+pub use endpoints::rpc_override_key;
+pub use error::OracleError;
+pub use fixed::Price;
+pub use rounding::RoundingPolicy;
+pub use subscriptions::PriceMessenger;
+pub use types::{
+	pair_id, ArbitrageOpportunity, BestVenues, Blake2PairHasher, BootstrapProgress, CallOptions,
+	ChainId, Confirmation, CrossChainCheck, DeltaHistory, DynamicBounds, FailureMode, FastPath,
+	FreshnessSla, GasFees, LiquidPrice, OracleStatus, PairHasher, PairId, PairInfo, PriceAggregate,
+	PriceAlert, PriceSubscription, ProvidedPrice, ReadCounter, ReliabilityStats, RpcUrl, RpcUrls,
+	SlaBreach, SubmissionSummary, TwapCheck, VenueQuote, VerifiedPrice,
+};
+
+const LOG_TARGET: &str = "runtime::price-oracle";
+
+/// [`InvalidTransaction::Custom`](sp_runtime::transaction_validity::InvalidTransaction::Custom)
+/// code of submissions whose price can't be scaled to [`fixed::PRICE_DECIMALS`] decimals.
+pub const INVALID_EXPONENT: u8 = 1;
+
+/// [`InvalidTransaction::Custom`](sp_runtime::transaction_validity::InvalidTransaction::Custom)
+/// code of submissions for an exchange that isn't registered in [`Exchanges`].
+pub const UNKNOWN_EXCHANGE: u8 = 2;
+
+/// [`InvalidTransaction::Custom`](sp_runtime::transaction_validity::InvalidTransaction::Custom)
+/// code of submissions for a pair that isn't tracked.
+pub const UNTRACKED_PAIR: u8 = 3;
+
+/// [`InvalidTransaction::Custom`](sp_runtime::transaction_validity::InvalidTransaction::Custom)
+/// code of submissions outside of the pair's bounds.
+pub const PRICE_OUT_OF_BOUNDS: u8 = 4;
+
+/// Maximum number of entries returned by one [`Pallet::history`] call.
+pub const MAX_HISTORY_PAGE: u32 = 256;
+
+/// Key of the aggregated price of `pair_id` in block `block` in the offchain database, written
+/// through offchain indexing.
+///
+/// The value is the SCALE encoded `(median, block)`.
+pub fn history_key<BlockNumber: codec::Encode>(
+	pair_id: &PairId,
+	block: BlockNumber,
+) -> alloc::vec::Vec<u8> {
+	[&b"price-oracle/history/"[..], pair_id, &block.encode()].concat()
+}
+
+/// Key of the price of `pair_id` on `exchange_id` accepted in block `block` in the offchain
+/// database, written through offchain indexing: `price-oracle/price/`, the pair id, the SCALE
+/// encoded block and the exchange id. [`Pallet::indexed_price_keys`] lists them.
+///
+/// The value is the SCALE encoded `(price, timestamp)` of the last price of the exchange accepted
+/// in the block.
+pub fn price_key<BlockNumber: codec::Encode>(
+	pair_id: &PairId,
+	block: BlockNumber,
+	exchange_id: exchanges::ExchangeId,
+) -> alloc::vec::Vec<u8> {
+	[&b"price-oracle/price/"[..], pair_id, &block.encode(), &[exchange_id]].concat()
+}
+
+/// Key of the number of offchain worker runs cut short by [`Config::MaxOcwMillis`], in the
+/// persistent offchain storage.
+///
+/// The value is a SCALE encoded `u32`.
+pub const OCW_OVERRUNS_KEY: &[u8] = b"price-oracle/ocw-overruns";
+
+/// Key of the number of transactions of the offchain worker the transaction pool refused since
+/// the last run that submitted prices, in the persistent offchain storage. A run following
+/// refusals submits less, see [`Pallet::congested`].
+///
+/// The value is a SCALE encoded `u32`.
+pub const REFUSED_SUBMISSIONS_KEY: &[u8] = b"price-oracle/refused-submissions";
+
+/// Key of the reads the offchain worker left out of its last run for its budget or deadline, in
+/// the persistent offchain storage. They are due in every run after until they are read, see
+/// [`Config::MaxReadDelay`].
+///
+/// The value is a SCALE encoded `Vec<(PairId, ExchangeId, u32)>`: each read with the block it was
+/// first due in.
+pub const CARRIED_READS_KEY: &[u8] = b"price-oracle/carried-reads";
+
+/// Key of the prices the offchain worker committed to and has yet to reveal, see
+/// [`Config::RevealWindow`], in the persistent offchain storage. They are revealed by the next
+/// run.
+///
+/// The value is a SCALE encoded `Vec<(u32, Call<T>)>`: each [`Pallet::reveal_price`] call with
+/// the block of the run that committed to its price.
+pub const PENDING_REVEALS_KEY: &[u8] = b"price-oracle/pending-reveals";
+
+/// Key of the lock an offchain worker holds on `pair_id` while it reads the pair, in the
+/// persistent offchain storage. The lock of a run that never finished lapses in a later block,
+/// once the run would have reached its [`Config::MaxOcwMillis`].
+pub fn pair_lock_key(pair_id: &PairId) -> alloc::vec::Vec<u8> {
+	[&b"price-oracle/pair-lock/"[..], pair_id].concat()
+}
+
+/// Key of the last block the offchain worker read `pair_id` in, in the persistent offchain
+/// storage. Other runs of that block, on sibling forks or after a restart, leave the pair alone.
+///
+/// The value is a SCALE encoded `u32`.
+pub fn pair_read_key(pair_id: &PairId) -> alloc::vec::Vec<u8> {
+	[&b"price-oracle/pair-read/"[..], pair_id].concat()
+}
+
+/// A pool the offchain worker reads the price of a pair from in a run.
+struct PoolRead<'a> {
+	pair_id: PairId,
+	pair: &'a PairInfo,
+	twap_check: Option<TwapCheck>,
+	/// Whether the pair is read on its [`FastPath`], out of [`Config::FastPathBudget`].
+	fast: bool,
+	/// Block the read was first due in, before the run for reads carried over from earlier runs.
+	due_since: u32,
+	/// Index of the exchange among the enabled exchanges of the run.
+	exchange: usize,
+	/// The pools of the pair on the exchange.
+	pools: alloc::vec::Vec<&'a exchanges::PoolConfig>,
+	/// The pool the first of `pools` is routed through, see [`PoolHops`].
+	hop: Option<exchanges::PoolConfig>,
+}
+
+/// Runtime state the benchmarks can't set up through the pallet.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper {
+	/// Set the time returned by [`Config::UnixTime`], in unix milliseconds.
+	fn set_now(millis: u64);
+
+	/// Make [`Config::LightClient`] track `state_root` for block `block_number` of `chain_id`,
+	/// where it can.
+	fn set_state_root(chain_id: ChainId, block_number: u64, state_root: sp_core::H256);
+}
+
+/// Prices of the oracle for other pallets.
+///
+/// Pairs that aren't tracked are derived from tracked ones by [`Pallet::get_derived_price`], and
+/// several pairs are read at once with [`Pallet::read_many`]. Consumers acting on a price in
+/// several steps of a block read it through [`PriceGuard`] to hold it until the end of the block.
+pub trait PriceProvider {
+	/// Latest aggregated median price of `pair_id`.
+	///
+	/// A price older than [`Config::MaxPriceAge`] or of a paused pair fails with
+	/// [`Error::PriceUnavailable`] under [`FailureMode::FailClosed`] and is served flagged as
+	/// stale under [`FailureMode::FailOpen`]. The price of a pair still in [`Bootstrapping`] fails
+	/// with [`Error::Bootstrapping`] under either mode.
+	fn price(pair_id: PairId) -> Result<ProvidedPrice, sp_runtime::DispatchError>;
+
+	/// [`Self::price`] of the pair of `base` priced in `quote`, by the tickers of its tokens, e.g.
+	/// `b"ETH"` and `b"USD"`, for consumers that don't keep pair ids.
+	fn price_of(base: &[u8], quote: &[u8]) -> Result<ProvidedPrice, sp_runtime::DispatchError>;
+
+	/// Raw amount of the quote token of `pair_id` worth `amount` raw units of its base token, for
+	/// tokens with `base_decimals` and `quote_decimals`, at [`Self::price`], truncated. Fails like
+	/// [`Self::price`], so stale prices convert under [`FailureMode::FailOpen`], and with
+	/// [`Error::ConversionOverflow`] for amounts that don't fit a `u128` or decimals too far apart
+	/// to scale by.
+	fn convert(
+		pair_id: PairId,
+		amount: u128,
+		base_decimals: u8,
+		quote_decimals: u8,
+	) -> Result<u128, sp_runtime::DispatchError>;
+
+	/// Worst case weight of [`Self::price`], [`Self::price_of`] and [`Self::convert`].
+	fn price_weight() -> frame_support::weights::Weight;
+}
+
+impl<T: Config> PriceProvider for Pallet<T> {
+	fn price(pair_id: PairId) -> Result<ProvidedPrice, sp_runtime::DispatchError> {
+		Self::note_read(pair_id);
+		frame_support::ensure!(
+			!Bootstrapping::<T>::contains_key(pair_id),
+			Error::<T>::Bootstrapping
+		);
+		let aggregate = AggregatedPrice::<T>::get(pair_id).ok_or(Error::<T>::NoPrice)?;
+		Self::provided_price(pair_id, &aggregate)
+	}
+
+	fn price_of(base: &[u8], quote: &[u8]) -> Result<ProvidedPrice, sp_runtime::DispatchError> {
+		Self::price(T::PairHasher::pair_id(base, quote))
+	}
+
+	fn convert(
+		pair_id: PairId,
+		amount: u128,
+		base_decimals: u8,
+		quote_decimals: u8,
+	) -> Result<u128, sp_runtime::DispatchError> {
+		let ProvidedPrice { price, .. } = Self::price(pair_id)?;
+		fixed::convert(amount, price, base_decimals, quote_decimals)
+			.map_err(|_| Error::<T>::ConversionOverflow.into())
+	}
+
+	fn price_weight() -> frame_support::weights::Weight {
+		T::WeightInfo::provided_price()
+	}
+}
+
+/// Prices of the oracle held still for the rest of the block they are read in, for consumers
+/// that read a price and act on it later in the block, e.g. liquidations settled in
+/// `on_finalize`.
+pub trait PriceGuard: PriceProvider {
+	/// [`PriceProvider::price`] of `pair_id`, guarding it in [`PriceGuards`]: the pair isn't
+	/// aggregated at the end of the block, so the aggregate returned stays its price until the
+	/// next block, which catches up on the deferred aggregation. A pair guarded in more than
+	/// [`Config::MaxAggregationDeferrals`] blocks in a row is aggregated regardless.
+	fn guarded_price(pair_id: PairId) -> Result<ProvidedPrice, sp_runtime::DispatchError>;
+
+	/// Worst case weight of [`Self::guarded_price`].
+	fn guarded_price_weight() -> frame_support::weights::Weight;
+}
+
+impl<T: Config> PriceGuard for Pallet<T> {
+	fn guarded_price(pair_id: PairId) -> Result<ProvidedPrice, sp_runtime::DispatchError> {
+		let price = Self::price(pair_id)?;
+		PriceGuards::<T>::insert(pair_id, ());
+		Ok(price)
+	}
+
+	fn guarded_price_weight() -> frame_support::weights::Weight {
+		T::WeightInfo::guarded_price()
+	}
+}
+
+/// Key type of the operator keys the offchain worker signs submissions with.
+pub const KEY_TYPE: sp_core::crypto::KeyTypeId = sp_core::crypto::KeyTypeId(*b"orcl");
+
+/// Operator keys of the offchain worker.
+pub mod crypto {
+	use super::KEY_TYPE;
+	use sp_runtime::{
+		app_crypto::{app_crypto, sr25519},
+		MultiSignature, MultiSigner,
+	};
+
+	app_crypto!(sr25519, KEY_TYPE);
+
+	/// Signs submissions with the node's `orcl` sr25519 key.
+	pub struct OracleAuthId;
+
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for OracleAuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+}
+
+// The submissions take every field of an observation, and so do the `Call` constructors the
+// pallet macro generates for them.
+#[allow(clippy::too_many_arguments)]
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use crate::{
+		chains::{multicall, ChainInterface, ChainKind, EvmChain, SolanaChain},
+		exchanges::{ExchangeConfig, ExchangeId, ExchangeKey, PoolConfig},
+		proofs::LightClient,
+		protocols::{DexProtocol, ProtocolKind},
+		types::{EvmAddress, SolanaAddress, Symbol},
+	};
+	use alloc::{
+		collections::{BTreeMap, BTreeSet},
+		vec::Vec,
+	};
+	use codec::DecodeLimit;
+	use core::ops::RangeInclusive;
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{Currency, ReservableCurrency, SortedMembers, UnixTime},
+	};
+	use frame_system::{
+		offchain::{
+			AppCrypto, CreateSignedTransaction, SendSignedTransaction, Signer, SubmitTransaction,
+		},
+		pallet_prelude::*,
+	};
+	use pallet_oracle_rewards::ContributionReporter;
+	use sp_runtime::{
+		offchain::{
+			storage::StorageValueRef,
+			storage_lock::{BlockAndTime, StorageLock},
+			Duration,
+		},
+		traits::{One, Saturating, UniqueSaturatedInto, Zero},
+		Permill,
+	};
+
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config:
+		compat::CreateUnsigned<Call<Self>>
+		+ CreateSignedTransaction<Call<Self>>
+		+ frame_system::Config
+	{
+		/// Overarching event type. Declared rather than bounded on `frame_system::Config` for the
+		/// release trains of [`compat`], which require it.
+		#[allow(deprecated)]
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// Origin allowed to manage the tracked pairs, exchanges and operators.
+		type RegistryOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// Key the offchain worker signs submissions with.
+		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+
+		/// Whether submissions without a registered operator's signature are accepted.
+		#[pallet::constant]
+		type AcceptUnsigned: Get<bool>;
+
+		/// Maximum number of registered operators.
+		#[pallet::constant]
+		type MaxOperators: Get<u32>;
+
+		/// Maximum number of tracked pairs.
+		#[pallet::constant]
+		type MaxPairs: Get<u32>;
+
+		/// Accounts allowed to submit signed prices besides the registered [`Operators`], e.g.
+		/// the members of a `pallet-membership` instance governing the oracle committee.
+		type Committee: SortedMembers<Self::AccountId>;
+
+		/// Credited with a contribution point and a submission reward for every price an operator
+		/// gets accepted.
+		type ContributionReporter: ContributionReporter<Self::AccountId>;
+
+		/// Currency operators bond while they are registered, and alerts are paid deposits in.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// Amount reserved from an account when it is registered as an operator, and slashed when
+		/// it submits deviant prices, see [`Pallet::report_misbehavior`].
+		#[pallet::constant]
+		type OperatorBond: Get<BalanceOf<Self>>;
+
+		/// Distance of a signed price from the aggregated median of its pair in the same
+		/// [`Config::UpdateInterval`], in basis points, beyond which its operator is slashed.
+		#[pallet::constant]
+		type SlashDeviationBps: Get<u32>;
+
+		/// Share of its bond an operator loses for every deviant price reported.
+		#[pallet::constant]
+		type MisbehaviorSlash: Get<Permill>;
+
+		/// Share of a slash paid to the account reporting the misbehavior. The rest is burned.
+		#[pallet::constant]
+		type ReporterReward: Get<Permill>;
+
+		/// Number of blocks between two price fetches of the offchain worker.
+		#[pallet::constant]
+		type UpdateInterval: Get<u32>;
+
+		/// Timeout of a single HTTP request, in milliseconds.
+		#[pallet::constant]
+		type HttpTimeout: Get<u64>;
+
+		/// Maximum number of exchanges queried in one offchain worker run.
+		#[pallet::constant]
+		type MaxExchangesPerBlock: Get<u8>;
+
+		/// Time after which an offchain worker run starts no more fetches, in milliseconds, so it
+		/// doesn't compete with block authoring on the same node.
+		///
+		/// A fetch already under way finishes, which takes up to [`Config::HttpTimeout`] per
+		/// request.
+		#[pallet::constant]
+		type MaxOcwMillis: Get<u64>;
+
+		/// Largest lag of the chain head an endpoint serves behind the newest head of the same
+		/// chain seen in the same offchain worker run, in seconds. Prices read from an endpoint
+		/// lagging further are discarded as stale state and the endpoint is ranked down, see
+		/// [`endpoints::lagging_heads`].
+		///
+		/// Reading the head costs a request per exchange; with zero the check is off.
+		#[pallet::constant]
+		type ChainTimeTolerance: Get<u64>;
+
+		/// Source of the on-chain time submissions are checked against.
+		type UnixTime: UnixTime;
+
+		/// Age after which a stored exchange price is ignored and pruned, in milliseconds.
+		#[pallet::constant]
+		type MaxPriceAge: Get<u64>;
+
+		/// Number of aggregated prices kept per pair in [`PriceHistory`].
+		#[pallet::constant]
+		type MaxHistory: Get<u32>;
+
+		/// Maximum distance between the observation time of a submission and the on-chain time,
+		/// in milliseconds.
+		#[pallet::constant]
+		type MaxSubmissionDelay: Get<u64>;
+
+		/// Number of consecutive submissions of the same price after which the price of a pair on
+		/// an exchange is considered frozen, e.g. by a caching RPC endpoint, and left out of the
+		/// aggregation until it changes. The offchain worker submits every read of an unchanged
+		/// price, so this counts reads of the pair. Zero disables the check.
+		#[pallet::constant]
+		type FrozenPriceThreshold: Get<u32>;
+
+		/// Scheme the ids of the tracked pairs are derived from their symbols with. Changing it
+		/// requires migrating every storage item keyed by [`PairId`].
+		type PairHasher: PairHasher;
+
+		/// Number of blocks the reads of a pair are counted over in [`PairReads`].
+		#[pallet::constant]
+		type ReadPeriod: Get<u32>;
+
+		/// Maximum number of deviation alerts registered on a pair.
+		#[pallet::constant]
+		type MaxAlertsPerPair: Get<u32>;
+
+		/// Amount reserved from an account for each of its alerts, released when the alert is
+		/// removed, triggered or dropped with its pair.
+		#[pallet::constant]
+		type AlertDeposit: Get<BalanceOf<Self>>;
+
+		/// Location of a consumer on another consensus system, e.g. a sibling parachain.
+		type Subscriber: Parameter + MaxEncodedLen;
+
+		/// Origin of the consumers served by [`Pallet::request_price_xcm`] and
+		/// [`Pallet::subscribe_price`], e.g. `pallet_xcm::EnsureXcm` of sibling parachains in a
+		/// parachain runtime, returning their location.
+		type SubscriberOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = Self::Subscriber>;
+
+		/// Sends prices to the consumers.
+		type PriceMessenger: PriceMessenger<Self::Subscriber>;
+
+		/// Maximum number of consumers subscribed to the price of a pair.
+		#[pallet::constant]
+		type MaxSubscriptionsPerPair: Get<u32>;
+
+		/// Maximum number of pools queried in one offchain worker run for pairs on their
+		/// [`FastPath`], on top of [`Config::MaxExchangesPerBlock`].
+		#[pallet::constant]
+		type FastPathBudget: Get<u32>;
+
+		/// How prices are rounded when they are cut to the precision of their pair and averaged,
+		/// and which price the median of an even number of sources is. See [`rounding`].
+		#[pallet::constant]
+		type Rounding: Get<RoundingPolicy>;
+
+		/// Number of consecutive [`Config::UpdateInterval`]s of consistent data from several
+		/// exchanges a newly added pair collects before its price is served, see
+		/// [`Bootstrapping`]. With zero, prices of new pairs are served at once.
+		#[pallet::constant]
+		type BootstrapIntervals: Get<u32>;
+
+		/// Largest move of the median of a bootstrapping pair from one interval to the next, in
+		/// basis points, for the intervals to count as consistent.
+		#[pallet::constant]
+		type BootstrapMaxMoveBps: Get<u32>;
+
+		/// Whether the prices submitted for a pair in a block are reported in one
+		/// [`Event::PricesSummarized`] at the end of the block instead of an
+		/// [`Event::PriceSubmitted`] or [`Event::PriceRejected`] each, keeping the event log of
+		/// chains with many pairs and exchanges small.
+		#[pallet::constant]
+		type SummarizeSourceEvents: Get<bool>;
+
+		/// Smallest spread between the cheapest and the dearest exchange of a pair, in basis
+		/// points, recorded in [`ArbitrageOpportunities`].
+		#[pallet::constant]
+		type MinSpreadBps: Get<u32>;
+
+		/// Gas units a swap on an EVM exchange is assumed to use. A spread is only recorded in
+		/// [`ArbitrageOpportunities`] if it pays for a swap on both of its exchanges at the
+		/// [`GasData`] of their chains. Zero records spreads regardless of gas.
+		#[pallet::constant]
+		type ArbitrageGasUnits: Get<u64>;
+
+		/// Notional of the trade an arbitrage spread is weighed against the gas of, in the quote
+		/// of the pair with [`fixed::PRICE_DECIMALS`] decimals.
+		#[pallet::constant]
+		type ArbitrageTradeSize: Get<Price>;
+
+		/// Largest distance of an exchange price from the price of a reference feed of its pair,
+		/// such as a Chainlink aggregator, in basis points of the reference, before an
+		/// [`Event::ReferenceDeviation`] flags it.
+		#[pallet::constant]
+		type MaxReferenceDeviationBps: Get<u32>;
+
+		/// Largest number of blocks the sampling of an exchange is delayed past the
+		/// [`Pallet::fetch_phase`] of its pair, drawn anew every interval from the hash of the
+		/// block before it, see [`Pallet::sample_phase`]. Keeps the block an exchange is read in
+		/// from being known ahead, so a pool can't be moved for exactly that block. Zero samples
+		/// every exchange in the phase of its pair. Must be below [`Config::UpdateInterval`].
+		#[pallet::constant]
+		type SamplingJitter: Get<u32>;
+
+		/// Smallest deviation from the TWAP the [`DynamicBounds`] of a long-tail pair allow, wider
+		/// than blue-chip pairs need since thin pools move further, see [`LongTailPairs`].
+		#[pallet::constant]
+		type LongTailDeviation: Get<Permill>;
+
+		/// Whether the offchain worker reads the notional of the pools of every pair along with
+		/// their prices, from `liquidity()` of Uniswap V3 pools and the reserves of V2 forks,
+		/// rather than only for [`LongTailPairs`]. Costs a request per pool and lets consumers
+		/// leave out thin pools, see [`Pallet::liquid_prices`].
+		#[pallet::constant]
+		type ReportLiquidity: Get<bool>;
+
+		/// Whether the offchain worker sends the single calls pricing the pools of a chain outside
+		/// [`MulticallChains`] in one JSON-RPC batch per endpoint rather than a request each.
+		/// Endpoints refusing batches fail them, and the pools are then read one by one.
+		#[pallet::constant]
+		type BatchRpcCalls: Get<bool>;
+
+		/// Number of blocks after which a read the offchain worker left out of a run for its
+		/// budget or deadline is read ahead of the reads shared by contribution. Such reads are
+		/// carried over under [`CARRIED_READS_KEY`] and due in every run until they are read, so
+		/// every pool is read within that many blocks of being due while the overdue reads fit
+		/// [`Config::MaxExchangesPerBlock`]. Zero leaves them to the next interval of their pair.
+		#[pallet::constant]
+		type MaxReadDelay: Get<u32>;
+
+		/// Smallest move of the price of a pair on an exchange from its stored price, in basis
+		/// points, for the offchain worker to submit the price it read. Prices closer to the stored
+		/// one are only submitted once that is [`Config::SubmissionHeartbeat`] old, except for the
+		/// stored price itself, which is submitted for [`Config::FrozenPriceThreshold`] to count.
+		/// Zero submits every price read.
+		#[pallet::constant]
+		type SubmissionDeviationBps: Get<u32>;
+
+		/// Age of the stored price of a pair on an exchange, in milliseconds, after which the
+		/// offchain worker submits the price it reads even if it didn't move by
+		/// [`Config::SubmissionDeviationBps`]. Must be below [`Config::MaxPriceAge`], or flat
+		/// prices would go stale between submissions.
+		#[pallet::constant]
+		type SubmissionHeartbeat: Get<u64>;
+
+		/// Largest distance of a fresh exchange price from the aggregated median of its pair, in
+		/// basis points, for the price to count towards the [`Pallet::contribution_scores`] the
+		/// offchain worker shares its budget by.
+		#[pallet::constant]
+		type ConsensusBps: Get<u32>;
+
+		/// Number of median absolute deviations from the median of the fresh exchange prices of a
+		/// pair beyond which a price is an outlier, left out of its aggregate and counted in
+		/// [`OutlierCounts`]. Prices within [`Config::ConsensusBps`] of the median are never
+		/// outliers, nor are the prices of pairs with fewer than three. Zero keeps every price.
+		#[pallet::constant]
+		type OutlierMads: Get<u32>;
+
+		/// Share of failed reads, or of aggregations leaving its price out as an outlier, in basis
+		/// points, beyond which an exchange is quarantined, see [`QuarantinedExchanges`]. 10_000
+		/// never quarantines.
+		#[pallet::constant]
+		type QuarantineBps: Get<u32>;
+
+		/// Number of reads, and of aggregations judging its prices, an exchange is judged on for
+		/// quarantine, see [`ExchangeStats`].
+		#[pallet::constant]
+		type QuarantineMinSamples: Get<u32>;
+
+		/// Number of blocks an exchange stays in quarantine, unless
+		/// [`Pallet::release_exchange`] ends it sooner.
+		#[pallet::constant]
+		type QuarantinePeriod: Get<u32>;
+
+		/// Number of blocks after the block of its commitment an operator can reveal a price in,
+		/// see [`Pallet::commit_price`]. Zero disables commit-reveal: prices are submitted in the
+		/// clear with [`Pallet::submit_price_signed`] or [`Pallet::submit_price_unsigned`].
+		#[pallet::constant]
+		type RevealWindow: Get<u32>;
+
+		/// Headers of the EVM chains the prices of [`Pallet::submit_verified_price`] are proven
+		/// against. `()` tracks none, so no price can be verified.
+		type LightClient: proofs::LightClient;
+
+		/// Number of blocks in a row the aggregation of a pair can be deferred for by
+		/// [`PriceGuard::guarded_price`], so pairs guarded in every block still get new prices.
+		#[pallet::constant]
+		type MaxAggregationDeferrals: Get<u32>;
+
+		/// Weight information for the extrinsics and hooks of this pallet.
+		type WeightInfo: WeightInfo;
+
+		/// Sets up the runtime state the benchmarks need.
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper;
+	}
+
+	/// Latest price of a pair reported by an exchange, keyed by pair id and exchange id.
+	///
+	/// The value is `(price, observation time in unix milliseconds)`.
+	#[pallet::storage]
+	pub type PriceData<T> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		PairId,
+		Blake2_128Concat,
+		ExchangeId,
+		(Price, u64),
+		OptionQuery,
+	>;
+
+	/// Block of its chain the latest price of a pair on an exchange was read at, keyed by pair id
+	/// and exchange id. Only known for the pairs with a [`CrossChainCheck`].
+	#[pallet::storage]
+	pub type SourceBlocks<T> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		PairId,
+		Blake2_128Concat,
+		ExchangeId,
+		u64,
+		OptionQuery,
+	>;
+
+	/// Number of consecutive submissions of an exchange that repeated its stored price of a
+	/// pair, keyed by pair id and exchange id. Absent for prices that changed with the last
+	/// submission.
+	#[pallet::storage]
+	pub type RepeatedPrices<T> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		PairId,
+		Blake2_128Concat,
+		ExchangeId,
+		u32,
+		ValueQuery,
+	>;
+
+	/// Number of prices of each exchange left out of an aggregate as outliers, see
+	/// [`Config::OutlierMads`].
+	#[pallet::storage]
+	pub type OutlierCounts<T> = StorageMap<_, Blake2_128Concat, ExchangeId, u32, ValueQuery>;
+
+	/// Recent outcomes of the reads and prices of each exchange, judged for quarantine against
+	/// [`Config::QuarantineBps`]. The counts halve once they reach twice
+	/// [`Config::QuarantineMinSamples`], and are cleared when the exchange is quarantined.
+	#[pallet::storage]
+	pub type ExchangeStats<T> =
+		StorageMap<_, Blake2_128Concat, ExchangeId, ReliabilityStats, ValueQuery>;
+
+	/// Block until which an exchange is quarantined: the offchain worker doesn't read it and its
+	/// prices are left out of the aggregates. Cleared by the first read after it.
+	#[pallet::storage]
+	pub type QuarantinedExchanges<T: Config> =
+		StorageMap<_, Blake2_128Concat, ExchangeId, BlockNumberFor<T>, OptionQuery>;
+
+	/// Median and mean of the latest prices of a pair across exchanges.
+	///
+	/// Aggregated at the end of each block the pair received prices in, in two tiers: the
+	/// exchanges of each chain into [`ChainAggregates`], then the medians of the chains. Outliers
+	/// among the prices of all the exchanges are left out first, see [`Config::OutlierMads`].
+	#[pallet::storage]
+	pub type AggregatedPrice<T> =
+		StorageMap<_, Blake2_128Concat, PairId, PriceAggregate, OptionQuery>;
+
+	/// The exchanges with the lowest and the highest of the prices of a pair the last aggregation
+	/// combined, kept with it so arbitrage engines read them in one go.
+	#[pallet::storage]
+	pub type PairVenues<T> = StorageMap<_, Blake2_128Concat, PairId, BestVenues, OptionQuery>;
+
+	/// Pairs whose exchanges were at least [`Config::MinSpreadBps`] apart at their last
+	/// aggregation, by enough to pay for the gas of both swaps, see [`arbitrage`].
+	#[pallet::storage]
+	pub type ArbitrageOpportunities<T> =
+		StorageMap<_, Blake2_128Concat, PairId, ArbitrageOpportunity, OptionQuery>;
+
+	/// Median and mean of the latest prices of a pair across the exchanges of each chain, the
+	/// first tier of [`AggregatedPrice`].
+	#[pallet::storage]
+	pub type ChainAggregates<T> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		PairId,
+		Twox64Concat,
+		ChainId,
+		PriceAggregate,
+		OptionQuery,
+	>;
+
+	/// The [`AggregatedPrice`] of every tracked pair in a single value, so consumers reading many
+	/// pairs pay for one storage read.
+	#[pallet::storage]
+	pub type LatestAggregates<T: Config> =
+		StorageValue<_, BoundedBTreeMap<PairId, PriceAggregate, T::MaxPairs>, ValueQuery>;
+
+	/// The last [`Config::MaxHistory`] aggregated median prices of a pair with the block they were
+	/// aggregated in, oldest first, delta encoded.
+	#[pallet::storage]
+	pub type PriceHistory<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		PairId,
+		DeltaHistory<BlockNumberFor<T>, T::MaxHistory>,
+		ValueQuery,
+	>;
+
+	/// Pairs with new prices in the current block, aggregated and cleared in `on_finalize`.
+	#[pallet::storage]
+	pub type PendingAggregation<T> = StorageMap<_, Blake2_128Concat, PairId, (), OptionQuery>;
+
+	/// Pairs whose price was read through [`PriceGuard`] in the current block. Their
+	/// [`PendingAggregation`] is deferred to the next block; cleared in `on_finalize`.
+	#[pallet::storage]
+	pub type PriceGuards<T> = StorageMap<_, Blake2_128Concat, PairId, (), OptionQuery>;
+
+	/// Number of blocks in a row the aggregation of each pair was deferred for, up to
+	/// [`Config::MaxAggregationDeferrals`]. Cleared once the pair is aggregated.
+	#[pallet::storage]
+	pub type AggregationDeferrals<T> = StorageMap<_, Blake2_128Concat, PairId, u32, ValueQuery>;
+
+	/// Prices submitted for each pair in the current block, reported and cleared in
+	/// `on_finalize`. Only kept with [`Config::SummarizeSourceEvents`].
+	#[pallet::storage]
+	pub type SubmissionSummaries<T> =
+		StorageMap<_, Blake2_128Concat, PairId, SubmissionSummary, ValueQuery>;
+
+	/// Accounts allowed to submit signed prices.
+	#[pallet::storage]
+	pub type Operators<T: Config> =
+		CountedStorageMap<_, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+	/// Prices operators committed to for a pair on an exchange and have yet to reveal, see
+	/// [`Pallet::commit_price`].
+	///
+	/// The value is `(commitment, block of the commitment)`.
+	#[pallet::storage]
+	pub type PriceCommitments<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		(PairId, ExchangeId),
+		([u8; 32], BlockNumberFor<T>),
+		OptionQuery,
+	>;
+
+	/// Bond reserved from each registered operator, less what was slashed of it. Operators of the
+	/// genesis config are registered without a bond.
+	#[pallet::storage]
+	pub type OperatorBonds<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	/// Latest signed price of each operator accepted for a pair on an exchange, kept as evidence
+	/// for [`Pallet::report_misbehavior`].
+	///
+	/// The value is `(price, block it was stored in)`.
+	#[pallet::storage]
+	pub type SignedSubmissions<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		(PairId, ExchangeId),
+		(Price, BlockNumberFor<T>),
+		OptionQuery,
+	>;
+
+	/// The pairs the offchain worker fetches prices for.
+	#[pallet::storage]
+	pub type TrackedPairs<T> =
+		CountedStorageMap<_, Blake2_128Concat, PairId, PairInfo, OptionQuery>;
+
+	/// The exchanges prices can be fetched from.
+	#[pallet::storage]
+	pub type Exchanges<T> = StorageMap<_, Twox64Concat, ExchangeId, ExchangeConfig, OptionQuery>;
+
+	/// Pairs whose Uniswap V3 spot prices are checked against the pools' own TWAP before they
+	/// are submitted.
+	#[pallet::storage]
+	pub type TwapChecks<T> = StorageMap<_, Blake2_128Concat, PairId, TwapCheck, OptionQuery>;
+
+	/// Pairs whose chain medians are checked against each other, see [`CrossChainCheck`].
+	#[pallet::storage]
+	pub type CrossChainChecks<T> =
+		StorageMap<_, Blake2_128Concat, PairId, CrossChainCheck, OptionQuery>;
+
+	/// The freshness each pair promises its consumers, checked every [`Config::UpdateInterval`]
+	/// blocks, see [`Pallet::meets_sla`].
+	#[pallet::storage]
+	pub type PairSlas<T> = StorageMap<_, Blake2_128Concat, PairId, FreshnessSla, OptionQuery>;
+
+	/// The pairs that fell short of their [`FreshnessSla`] at the last check, with how.
+	#[pallet::storage]
+	pub type BreachedSlas<T> = StorageMap<_, Blake2_128Concat, PairId, SlaBreach, OptionQuery>;
+
+	/// Long-tail pairs, priced from thin pools, with the smallest notional in whole USD each pool
+	/// aggregated into their price must hold, see [`Pallet::set_long_tail`]. The offchain worker
+	/// reads the notional of their pools along with their prices.
+	#[pallet::storage]
+	pub type LongTailPairs<T> = StorageMap<_, Blake2_128Concat, PairId, u128, OptionQuery>;
+
+	/// Notional in whole USD of the pool the latest price of a pair on an exchange was read from,
+	/// keyed by pair id and exchange id. Read for long-tail pairs, and for every pair with
+	/// [`Config::ReportLiquidity`].
+	#[pallet::storage]
+	pub type PoolNotionals<T> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		PairId,
+		Blake2_128Concat,
+		ExchangeId,
+		u128,
+		OptionQuery,
+	>;
+
+	/// Pool pricing the quote of the first pool of a pair on an exchange in the quote of the
+	/// pair, keyed by pair id and exchange id, for exchanges without a pool of the pair itself:
+	/// the offchain worker prices the pair through both, e.g. WBTC/WETH and WETH/USDC.
+	#[pallet::storage]
+	pub type PoolHops<T> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		PairId,
+		Twox64Concat,
+		ExchangeId,
+		PoolConfig,
+		OptionQuery,
+	>;
+
+	/// Long-tail pairs whose last aggregated price combined a pool of less than their minimum
+	/// notional, or of an unknown one. [`PriceProvider`] serves them flagged as
+	/// [`ProvidedPrice::low_quality`].
+	#[pallet::storage]
+	pub type LowQualityPairs<T> = StorageMap<_, Blake2_128Concat, PairId, (), OptionQuery>;
+
+	/// Pairs being retired, with the block after which they are removed. Their configuration is
+	/// read-only and they take no new alerts, but they are priced and served until then.
+	#[pallet::storage]
+	pub type DeprecatedPairs<T: Config> =
+		StorageMap<_, Blake2_128Concat, PairId, BlockNumberFor<T>, OptionQuery>;
+
+	/// Pairs added since genesis whose prices are collected and aggregated but not served yet,
+	/// so the first, possibly garbage, observations of a pair don't become its canonical price.
+	///
+	/// A pair leaves the map once its aggregated price combined several exchanges and moved by
+	/// at most [`Config::BootstrapMaxMoveBps`] for [`Config::BootstrapIntervals`] consecutive
+	/// update intervals, see [`Pallet::advance_bootstraps`].
+	#[pallet::storage]
+	pub type Bootstrapping<T> =
+		StorageMap<_, Blake2_128Concat, PairId, BootstrapProgress, OptionQuery>;
+
+	/// How [`PriceProvider`] serves each pair without a fresh price.
+	#[pallet::storage]
+	pub type FailureModes<T> = StorageMap<_, Blake2_128Concat, PairId, FailureMode, ValueQuery>;
+
+	/// Pairs whose prices [`PriceProvider`] serves as stale, e.g. while their sources are
+	/// investigated. They keep being fetched and aggregated.
+	#[pallet::storage]
+	pub type PausedPairs<T> = StorageMap<_, Blake2_128Concat, PairId, (), OptionQuery>;
+
+	/// Critical pairs that are fetched in every block for a while after a large move of their
+	/// median.
+	#[pallet::storage]
+	pub type FastPaths<T> = StorageMap<_, Blake2_128Concat, PairId, FastPath, OptionQuery>;
+
+	/// Last block of the open [`FastPath`] of each pair.
+	#[pallet::storage]
+	pub type FastPathUntil<T: Config> =
+		StorageMap<_, Blake2_128Concat, PairId, BlockNumberFor<T>, OptionQuery>;
+
+	/// Current [`ExchangeId`] of each registered exchange, keyed by its stable
+	/// [`ExchangeKey`].
+	#[pallet::storage]
+	pub type ExchangeKeys<T> =
+		StorageMap<_, Blake2_128Concat, ExchangeKey, ExchangeId, OptionQuery>;
+
+	/// Chains the offchain worker reads the fees of, see [`gas`].
+	#[pallet::storage]
+	pub type GasTrackedChains<T> = StorageMap<_, Twox64Concat, ChainId, (), OptionQuery>;
+
+	/// Chains in [`GasTrackedChains`] the offchain worker also samples the builder tips of, see
+	/// [`gas`].
+	#[pallet::storage]
+	pub type BuilderTipChains<T> = StorageMap<_, Twox64Concat, ChainId, (), OptionQuery>;
+
+	/// Chains whose pools the offchain worker reads in one `eth_call` through Multicall3, see
+	/// [`chains::multicall`]. Enabled per chain, as Multicall3 isn't deployed everywhere.
+	#[pallet::storage]
+	pub type MulticallChains<T> = StorageMap<_, Twox64Concat, ChainId, (), OptionQuery>;
+
+	/// Block the offchain worker reads the pools of each chain at, at least. Rollups reorg when
+	/// their sequencer misbehaves, so their pools can be read at the blocks posted to L1 instead
+	/// of their head. Chains without an entry are read at their head, unless the
+	/// [`ProtocolCallOptions`] of an exchange ask for more confirmations.
+	#[pallet::storage]
+	pub type ChainConfirmations<T> = StorageMap<_, Twox64Concat, ChainId, Confirmation, ValueQuery>;
+
+	/// Latest price of a pair on an exchange proven against the storage of its pool, see
+	/// [`Pallet::submit_verified_price`]. Kept apart from the prices reported by the operators,
+	/// for consumers that don't trust them.
+	#[pallet::storage]
+	pub type VerifiedPrices<T> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		PairId,
+		Twox64Concat,
+		ExchangeId,
+		VerifiedPrice,
+		OptionQuery,
+	>;
+
+	/// Fees of the next block of each chain in [`GasTrackedChains`].
+	#[pallet::storage]
+	pub type GasData<T> = StorageMap<_, Twox64Concat, ChainId, GasFees, OptionQuery>;
+
+	/// Ticker of the token the gas of each chain in [`GasTrackedChains`] is paid in, priced
+	/// through its pair with the quote of an arbitrage to weigh the spread against the gas.
+	#[pallet::storage]
+	pub type NativeTokens<T> = StorageMap<_, Twox64Concat, ChainId, Symbol, OptionQuery>;
+
+	/// Extra fields of the `eth_call`s the offchain worker sends to the exchanges of each
+	/// protocol. Protocols without an entry are called without them.
+	#[pallet::storage]
+	pub type ProtocolCallOptions<T> =
+		StorageMap<_, Twox64Concat, ProtocolKind, CallOptions, ValueQuery>;
+
+	/// Router of each venue, or quoter for Uniswap V3 exchanges, the offchain worker simulates
+	/// trades through, keyed by the [`ExchangeKey`] so it is kept when the exchange is remapped.
+	#[pallet::storage]
+	pub type Routers<T> = StorageMap<_, Blake2_128Concat, ExchangeKey, EvmAddress, OptionQuery>;
+
+	/// How often the prices of each pair were read through the getters of this pallet in the
+	/// current and the previous [`Config::ReadPeriod`], so feeds nobody reads can be pruned.
+	#[pallet::storage]
+	pub type PairReads<T> = StorageMap<_, Blake2_128Concat, PairId, ReadCounter, ValueQuery>;
+
+	/// Deviation alerts of each pair, checked whenever the pair is aggregated.
+	#[pallet::storage]
+	pub type Alerts<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		PairId,
+		BoundedVec<PriceAlert<T::AccountId>, T::MaxAlertsPerPair>,
+		ValueQuery,
+	>;
+
+	/// Deposit reserved from each account for its alert on a pair, see [`Config::AlertDeposit`].
+	#[pallet::storage]
+	pub type AlertDeposits<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		PairId,
+		Blake2_128Concat,
+		T::AccountId,
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// Consumers on other consensus systems pushed the price of each pair, checked whenever the
+	/// pair is aggregated.
+	#[pallet::storage]
+	pub type PriceSubscriptions<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		PairId,
+		BoundedVec<PriceSubscription<T::Subscriber>, T::MaxSubscriptionsPerPair>,
+		ValueQuery,
+	>;
+
+	/// JSON-RPC endpoints of each chain, keyed by chain id.
+	#[pallet::storage]
+	pub type RpcEndpoints<T> = StorageMap<_, Twox64Concat, ChainId, RpcUrls, OptionQuery>;
+
+	#[pallet::genesis_config]
+	#[derive(frame_support::DefaultNoBound)]
+	pub struct GenesisConfig<T: Config> {
+		/// Exchanges registered at genesis.
+		pub exchanges: Vec<(ExchangeId, ExchangeConfig)>,
+		/// Pairs tracked from genesis on.
+		pub pairs: Vec<PairInfo>,
+		/// Pools the genesis pairs are routed through, see [`PoolHops`].
+		pub pool_hops: Vec<(PairId, ExchangeId, PoolConfig)>,
+		/// JSON-RPC endpoints of each chain.
+		pub rpc_endpoints: Vec<(ChainId, RpcUrls)>,
+		/// Accounts allowed to submit signed prices, without a bond.
+		pub operators: Vec<T::AccountId>,
+		#[serde(skip)]
+		pub _config: core::marker::PhantomData<T>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			for (exchange_id, config) in &self.exchanges {
+				assert!(
+					!Exchanges::<T>::contains_key(exchange_id),
+					"exchange {exchange_id} registered twice"
+				);
+				assert!(
+					!ExchangeKeys::<T>::contains_key(config.key()),
+					"exchange name and protocol of {exchange_id} registered twice"
+				);
+				Pallet::<T>::ensure_valid_exchange(config)
+					.expect("genesis exchanges must be valid");
+				Exchanges::<T>::insert(exchange_id, config);
+				ExchangeKeys::<T>::insert(config.key(), exchange_id);
+			}
+			for pair in &self.pairs {
+				Pallet::<T>::ensure_valid_pair(pair).expect("genesis pairs must be valid");
+				Pallet::<T>::ensure_pools_on(pair, |exchange_id| {
+					self.exchanges
+						.iter()
+						.find(|(registered, _)| registered == exchange_id)
+						.map(|(_, config)| config.chain)
+				})
+				.expect("genesis pairs must be priced on registered exchanges");
+				let pair_id = Pallet::<T>::pair_id_of(pair);
+				assert!(!TrackedPairs::<T>::contains_key(pair_id), "pair tracked twice");
+				TrackedPairs::<T>::insert(pair_id, pair);
+			}
+			assert!(TrackedPairs::<T>::count() <= T::MaxPairs::get(), "too many genesis pairs");
+			for (pair_id, exchange_id, hop) in &self.pool_hops {
+				let pair = TrackedPairs::<T>::get(pair_id).expect("hops must route a genesis pair");
+				let chain = self
+					.exchanges
+					.iter()
+					.find(|(registered, _)| registered == exchange_id)
+					.map(|(_, config)| config.chain)
+					.expect("hops must be on registered exchanges");
+				Pallet::<T>::ensure_valid_hop(&pair, *exchange_id, chain, hop)
+					.expect("genesis hops must be valid");
+				PoolHops::<T>::insert(pair_id, exchange_id, hop);
+			}
+			for (chain_id, urls) in &self.rpc_endpoints {
+				assert!(
+					urls.iter().all(|url| core::str::from_utf8(url).is_ok()),
+					"RPC endpoint of {chain_id} isn't UTF-8"
+				);
+				RpcEndpoints::<T>::insert(chain_id, urls);
+			}
+			assert!(
+				self.operators.len() <= T::MaxOperators::get() as usize,
+				"too many genesis operators"
+			);
+			for who in &self.operators {
+				Operators::<T>::insert(who, ());
+			}
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An exchange price was stored.
+		PriceSubmitted { pair_id: PairId, exchange_id: ExchangeId, price: Price, timestamp: u64 },
+		/// A submitted exchange price was discarded.
+		PriceRejected { pair_id: PairId, exchange_id: ExchangeId, reason: OracleError },
+		/// The exchange prices of a pair submitted in the block, in place of their
+		/// [`Event::PriceSubmitted`] and [`Event::PriceRejected`], see
+		/// [`Config::SummarizeSourceEvents`].
+		PricesSummarized { pair_id: PairId, summary: SubmissionSummary },
+		/// An offchain worker failed to fetch the price of a pair from an exchange.
+		ExchangeFetchFailed { pair_id: PairId, exchange_id: ExchangeId, error: OracleError },
+		/// An exchange submitted the same price of a pair [`Config::FrozenPriceThreshold`] times
+		/// in a row. Its price is left out of the aggregation until it changes.
+		PriceFrozen { pair_id: PairId, exchange_id: ExchangeId },
+		/// The frozen price of a pair on an exchange changed and is aggregated again.
+		PriceUnfrozen { pair_id: PairId, exchange_id: ExchangeId },
+		/// The prices of a pair were aggregated across exchanges.
+		PriceAggregated { pair_id: PairId, median: Price, mean: Price, sources: u32 },
+		/// The price of an exchange was left out of the aggregate of a pair, as it lies too far
+		/// from the median of the prices of the pair, see [`Config::OutlierMads`].
+		PriceOutlierRejected {
+			pair_id: PairId,
+			exchange_id: ExchangeId,
+			price: Price,
+			median: Price,
+		},
+		/// The aggregation of a pair was deferred to the next block, as a consumer guarded its
+		/// price in this one.
+		AggregationDeferred { pair_id: PairId },
+		/// A pair is now tracked.
+		PairAdded { pair_id: PairId },
+		/// The configuration of a tracked pair changed.
+		PairUpdated { pair_id: PairId },
+		/// A pair is no longer tracked. Its last prices stay readable.
+		PairRemoved { pair_id: PairId },
+		/// An account may now submit signed prices.
+		OperatorRegistered { who: T::AccountId },
+		/// An account may no longer submit signed prices.
+		OperatorDeregistered { who: T::AccountId },
+		/// An operator committed to a price of a pair on an exchange, to be revealed within
+		/// [`Config::RevealWindow`] blocks.
+		PriceCommitted { who: T::AccountId, pair_id: PairId, exchange_id: ExchangeId },
+		/// `amount` of the bond of an operator was slashed for a deviant price of a pair on an
+		/// exchange, `reward` of it paid to the reporter.
+		OperatorSlashed {
+			who: T::AccountId,
+			reporter: T::AccountId,
+			pair_id: PairId,
+			exchange_id: ExchangeId,
+			amount: BalanceOf<T>,
+			reward: BalanceOf<T>,
+		},
+		/// An exchange was registered.
+		ExchangeAdded { exchange_id: ExchangeId },
+		/// The TWAP check of a pair was set or, with `None`, removed.
+		TwapCheckSet { pair_id: PairId, check: Option<TwapCheck> },
+		/// The fast path of a pair was set or, with `None`, removed.
+		FastPathSet { pair_id: PairId, fast_path: Option<FastPath> },
+		/// The cross-chain check of a pair was set or, with `None`, removed.
+		CrossChainCheckSet { pair_id: PairId, check: Option<CrossChainCheck> },
+		/// The medians of a pair on the chains `low` and `high` are `spread_bps` basis points
+		/// apart, from observations made `skew` milliseconds apart.
+		CrossChainDiscrepancy {
+			pair_id: PairId,
+			low: ChainId,
+			high: ChainId,
+			spread_bps: u32,
+			skew: u64,
+		},
+		/// The exchanges of a pair are at least [`Config::MinSpreadBps`] apart: the base token is
+		/// bought on `buy_exchange` and sold on `sell_exchange` for `spread_bps` basis points.
+		ArbitrageOpportunity {
+			pair_id: PairId,
+			buy_exchange: ExchangeId,
+			sell_exchange: ExchangeId,
+			spread_bps: u32,
+			timestamp: u64,
+		},
+		/// The `price` of a pair on `exchange_id` is `deviation_bps` basis points from the
+		/// `reference` price of its reference feed, more than
+		/// [`Config::MaxReferenceDeviationBps`].
+		ReferenceDeviation {
+			pair_id: PairId,
+			exchange_id: ExchangeId,
+			price: Price,
+			reference: Price,
+			deviation_bps: u32,
+		},
+		/// The minimum pool notional of a long-tail pair was set or, with `None`, the pair is
+		/// served as a blue-chip pair again.
+		LongTailSet { pair_id: PairId, min_notional: Option<u128> },
+		/// The aggregated price of a long-tail pair combined a pool short of its minimum notional
+		/// and is served as low quality.
+		PairLowQuality { pair_id: PairId },
+		/// All pools of a long-tail pair hold its minimum notional again.
+		PairQualityRestored { pair_id: PairId },
+		/// A pair is being retired and is removed in the first update interval after
+		/// `after_block`.
+		PairDeprecated { pair_id: PairId, after_block: BlockNumberFor<T> },
+		/// Warning, every update interval until it is removed, that a pair is being retired.
+		PairDeprecationPending { pair_id: PairId, after_block: BlockNumberFor<T> },
+		/// The median of a pair moved `move_bps` basis points, so it is fetched in every block
+		/// up to and including `until`.
+		FastPathOpened { pair_id: PairId, move_bps: u32, until: BlockNumberFor<T> },
+		/// The failure mode of a pair was set.
+		FailureModeSet { pair_id: PairId, mode: FailureMode },
+		/// A pair was paused.
+		PairPaused { pair_id: PairId },
+		/// A paused pair was resumed.
+		PairResumed { pair_id: PairId },
+		/// The router of an exchange was set or, with `None`, removed.
+		RouterSet { exchange_id: ExchangeId, router: Option<EvmAddress> },
+		/// The freshness SLA of a pair was set or, with `None`, removed.
+		SlaSet { pair_id: PairId, sla: Option<FreshnessSla> },
+		/// A pair stopped meeting its freshness SLA, or falls short of it in another way.
+		SlaBreached { pair_id: PairId, breach: SlaBreach },
+		/// A pair meets its freshness SLA again.
+		SlaRestored { pair_id: PairId },
+		/// A new pair collected enough consistent data and its price is served from now on.
+		BootstrapCompleted { pair_id: PairId },
+		/// The `eth_call` options of a protocol were set or, with `None`, removed.
+		CallOptionsSet { protocol: ProtocolKind, options: Option<CallOptions> },
+		/// Reading the fees of a chain was enabled or disabled.
+		GasTrackingSet { chain_id: ChainId, tracked: bool },
+		/// Sampling the builder tips of a chain was enabled or disabled.
+		BuilderTipsSet { chain_id: ChainId, enabled: bool },
+		/// The gas token of a chain was set or, with `None`, removed.
+		NativeTokenSet { chain_id: ChainId, token: Option<Symbol> },
+		/// Reading the pools of a chain through Multicall3 was enabled or disabled.
+		MulticallSet { chain_id: ChainId, enabled: bool },
+		/// The block the pools of a chain are read at was set.
+		ConfirmationSet { chain_id: ChainId, confirmation: Confirmation },
+		/// A price of a pair on an exchange was proven against the storage of its pool.
+		VerifiedPriceSubmitted {
+			pair_id: PairId,
+			exchange_id: ExchangeId,
+			price: Price,
+			block: u64,
+		},
+		/// The registry was replaced by a snapshot with `exchanges` exchanges and `pairs` pairs.
+		RegistryImported { exchanges: u32, pairs: u32 },
+		/// The fees of the next block of a chain were submitted.
+		GasFeesSubmitted { chain_id: ChainId, fees: GasFees },
+		/// The prices and pools of an exchange moved to a new id.
+		ExchangeRemapped { key: ExchangeKey, from: ExchangeId, to: ExchangeId },
+		/// An exchange was enabled or disabled.
+		ExchangeEnabled { exchange_id: ExchangeId, enabled: bool },
+		/// An exchange failed or deviated too often, and is quarantined until block `until`.
+		ExchangeQuarantined { exchange_id: ExchangeId, until: BlockNumberFor<T> },
+		/// The quarantine of an exchange ended.
+		ExchangeReleased { exchange_id: ExchangeId },
+		/// The pool a pair is routed through on an exchange was set or, with `None`, removed.
+		PoolHopSet { pair_id: PairId, exchange_id: ExchangeId, hop: Option<PoolConfig> },
+		/// The RPC endpoints of a chain were replaced.
+		RpcEndpointsSet { chain_id: ChainId, urls: RpcUrls },
+		/// A deviation alert was registered, replaced or, with a zero threshold, removed.
+		AlertSet { who: T::AccountId, pair_id: PairId, threshold_bps: u32, target: Price },
+		/// The aggregated median of a pair moved `move_bps` basis points away from the target of
+		/// an alert of `who`. The alert is removed.
+		AlertTriggered { who: T::AccountId, pair_id: PairId, move_bps: u32 },
+		/// The price of a pair was sent to a consumer in response to its query.
+		PriceServed { subscriber: T::Subscriber, pair_id: PairId, query_id: u64 },
+		/// A consumer subscribed to the price of a pair or, with a zero threshold, unsubscribed.
+		PriceSubscribed {
+			subscriber: T::Subscriber,
+			pair_id: PairId,
+			query_id: u64,
+			threshold_bps: u32,
+		},
+		/// The aggregated median of a pair was pushed to a subscribed consumer.
+		PricePushed { subscriber: T::Subscriber, pair_id: PairId, price: Price },
+		/// [`LatestAggregates`] was full, so the stalest aggregate in it, of `pair_id`, was
+		/// dropped to make room for a newer one.
+		LatestAggregateEvicted { pair_id: PairId },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The submitted price has more decimals than [`fixed::PRICE_DECIMALS`].
+		InvalidExponent,
+		/// The submitted price does not fit a [`Price`] once scaled.
+		PriceOverflow,
+		/// The pair is already tracked.
+		PairAlreadyTracked,
+		/// The pair is not tracked.
+		PairNotTracked,
+		/// The pair's minimum price is not below its maximum price.
+		InvalidBounds,
+		/// An exchange with this id, or with the same name and protocol, is already registered.
+		ExchangeAlreadyRegistered,
+		/// No exchange with this id is registered.
+		UnknownExchange,
+		/// The RPC endpoint URL isn't valid UTF-8.
+		InvalidUrl,
+		/// The submission was observed too long before or after the current on-chain time.
+		StaleSubmission,
+		/// A price observed later, or at the same time with the same or a higher price, is already
+		/// stored.
+		OutdatedSubmission,
+		/// The submitted price is outside of the pair's bounds.
+		PriceOutOfBounds,
+		/// The account is not a registered operator.
+		NotOperator,
+		/// The account is already a registered operator.
+		AlreadyOperator,
+		/// [`Config::MaxOperators`] operators are registered.
+		TooManyOperators,
+		/// [`Config::MaxPairs`] pairs are tracked.
+		PairLimitReached,
+		/// The runtime only accepts signed submissions.
+		UnsignedDisabled,
+		/// [`Config::MaxAlertsPerPair`] alerts are registered on the pair.
+		TooManyAlerts,
+		/// The target price of an alert must be non-zero.
+		InvalidTarget,
+		/// More pairs are tracked than the witness given to [`Pallet::remap_exchange`].
+		TooManyPairs,
+		/// The registry snapshot doesn't decode.
+		InvalidSnapshot,
+		/// The exchange name is empty or isn't valid UTF-8.
+		InvalidExchangeName,
+		/// The chain id of an exchange must be non-zero.
+		InvalidChainId,
+		/// The exchange's protocol can't be read on its kind of chain.
+		UnsupportedProtocol,
+		/// A pool or router address is the zero address.
+		ZeroAddress,
+		/// A pool token has more than [`types::MAX_TOKEN_DECIMALS`] decimals.
+		InvalidPoolDecimals,
+		/// The pair lists the same pool of an exchange twice.
+		DuplicatePool,
+		/// The TWAP window of a check or an exchange must be non-zero, and only exchanges of
+		/// Uniswap V3 pools can be priced at their TWAP.
+		InvalidTwapWindow,
+		/// The threshold and the length of a fast path must be non-zero.
+		InvalidFastPath,
+		/// The threshold of a cross-chain check must be non-zero.
+		InvalidCrossChainCheck,
+		/// The staleness and the source count of an SLA must be non-zero.
+		InvalidSla,
+		/// The pair has no aggregated price.
+		NoPrice,
+		/// The pair is new and its price isn't served until it completes its bootstrap.
+		Bootstrapping,
+		/// The price of the pair is stale or the pair is paused, and the pair fails closed.
+		PriceUnavailable,
+		/// The chain has no RPC endpoints.
+		UnknownChain,
+		/// The fees of the chain aren't tracked.
+		GasNotTracked,
+		/// A pool has an account on an exchange whose chain has contract pools, or none on one
+		/// whose chain keeps pools in accounts.
+		InvalidPoolAccount,
+		/// The minimum pool notional of a long-tail pair is zero.
+		InvalidMinNotional,
+		/// The pair is being retired, so its configuration is read-only and it takes no new
+		/// alerts.
+		PairDeprecated,
+		/// A pair can only be retired after the current block.
+		InvalidDeprecation,
+		/// The maximum price age of an exchange must be non-zero.
+		InvalidMaxPriceAge,
+		/// The price of the pair on the exchange can't be proven against the storage of a pool:
+		/// the exchange isn't on an EVM chain, is priced at a TWAP or its protocol doesn't keep
+		/// the price in one storage slot.
+		UnverifiableSource,
+		/// The light client doesn't track the header of the block.
+		UnknownHeader,
+		/// The proofs don't prove the storage of the pool against the state root of the block.
+		InvalidProof,
+		/// The exchange isn't quarantined.
+		NotQuarantined,
+		/// The converted amount does not fit a `u128`.
+		ConversionOverflow,
+		/// The pair has no pool on the exchange to route through a hop.
+		HopWithoutPool,
+		/// Prices must be committed to before they are revealed, see [`Config::RevealWindow`].
+		CommitRequired,
+		/// [`Config::RevealWindow`] is zero, so prices are submitted without a commitment.
+		CommitRevealDisabled,
+		/// The operator has no commitment to a price of the pair on the exchange.
+		NoCommitment,
+		/// A price can only be revealed after the block of its commitment.
+		RevealTooEarly,
+		/// The commitment is more than [`Config::RevealWindow`] blocks old.
+		CommitmentExpired,
+		/// The revealed price and salt don't hash to the commitment.
+		CommitmentMismatch,
+		/// The operator has no signed price of the pair on the exchange to report.
+		NoSubmission,
+		/// The [`PriceHistory`] keeps no aggregated median of the pair from the update interval of
+		/// the reported price.
+		NoMedian,
+		/// The reported price is within [`Config::SlashDeviationBps`] of the aggregated median.
+		NotDeviant,
+		/// The operator has no bond left to slash.
+		NotBonded,
+		/// [`Config::MaxSubscriptionsPerPair`] consumers are subscribed to the pair.
+		TooManySubscriptions,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn offchain_worker(block_number: BlockNumberFor<T>) {
+			let deadline = sp_io::offchain::timestamp()
+				.unix_millis()
+				.saturating_add(T::MaxOcwMillis::get());
+			Self::reveal_prices(block_number);
+			let finished = Self::fetch_and_submit_prices(block_number, deadline) &&
+				Self::fetch_and_submit_gas_fees(block_number, deadline);
+			if !finished {
+				Self::note_overrun(block_number);
+			}
+		}
+
+		fn on_initialize(block_number: BlockNumberFor<T>) -> Weight {
+			// `on_finalize` clears the markers of the pairs it aggregates; the aggregation itself
+			// is paid for by the submissions.
+			let weight = T::DbWeight::get().reads(1);
+			let interval: BlockNumberFor<T> = T::UpdateInterval::get().into();
+			if !(block_number % interval).is_zero() {
+				return weight
+			}
+			let pruned = Self::prune_stale_prices();
+			let bootstrapping = Self::advance_bootstraps();
+			let checked = Self::check_slas();
+			let (deprecated, removed) = Self::advance_deprecations(block_number);
+			weight
+				.saturating_add(T::WeightInfo::prune_stale_prices(pruned))
+				.saturating_add(T::WeightInfo::advance_bootstraps(bootstrapping))
+				.saturating_add(T::WeightInfo::check_slas(checked))
+				.saturating_add(T::WeightInfo::advance_deprecations(deprecated))
+				.saturating_add(T::WeightInfo::remove_pair().saturating_mul(removed.into()))
+		}
+
+		fn on_finalize(_: BlockNumberFor<T>) {
+			for (pair_id, summary) in SubmissionSummaries::<T>::drain() {
+				Self::deposit_event(Event::PricesSummarized { pair_id, summary });
+			}
+			let guarded: BTreeSet<_> =
+				PriceGuards::<T>::drain().map(|(pair_id, ())| pair_id).collect();
+			let pending: Vec<_> = PendingAggregation::<T>::drain().collect();
+			for (pair_id, ()) in pending {
+				let deferrals = AggregationDeferrals::<T>::get(pair_id);
+				if !guarded.contains(&pair_id) || deferrals >= T::MaxAggregationDeferrals::get() {
+					AggregationDeferrals::<T>::remove(pair_id);
+					Self::aggregate_prices(pair_id);
+					continue
+				}
+				// Paid for by the submissions of this block, like any aggregation.
+				PendingAggregation::<T>::insert(pair_id, ());
+				AggregationDeferrals::<T>::insert(pair_id, deferrals + 1);
+				Self::deposit_event(Event::AggregationDeferred { pair_id });
+			}
+		}
+
+		fn integrity_test() {
+			assert!(T::UpdateInterval::get() > 0, "UpdateInterval must be non-zero");
+			assert!(T::ReadPeriod::get() > 0, "ReadPeriod must be non-zero");
+			assert!(T::MaxOcwMillis::get() > 0, "MaxOcwMillis must be non-zero");
+			assert!(T::MinSpreadBps::get() > 0, "MinSpreadBps must be non-zero");
+			assert!(
+				T::SamplingJitter::get() < T::UpdateInterval::get(),
+				"SamplingJitter must be below UpdateInterval"
+			);
+			assert!(
+				T::FrozenPriceThreshold::get() != 1,
+				"FrozenPriceThreshold of one would freeze every price"
+			);
+			assert!(
+				T::SubmissionDeviationBps::get() == 0 ||
+					T::SubmissionHeartbeat::get() < T::MaxPriceAge::get(),
+				"SubmissionHeartbeat must be below MaxPriceAge"
+			);
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Store a price observed by the offchain worker.
+		///
+		/// `price` is a fixed-point value with `exponent` decimals, i.e. `price / 10^exponent` USD.
+		/// `timestamp` must be within [`Config::MaxSubmissionDelay`] of the on-chain time and later
+		/// than the stored price of the exchange, so submissions can't be replayed. `source_block`
+		/// is the block of the exchange's chain the price was read at, if it is known, and
+		/// `notional` the depth of the pool in whole USD, read for long-tail pairs. Prices
+		/// outside of the pair's bounds, which may have changed since the submission was
+		/// validated, are discarded with [`Event::PriceRejected`]. The pair is aggregated
+		/// at the end of the block.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::submit_price_unsigned()
+			.saturating_add(Pallet::<T>::aggregation_weight()))]
+		pub fn submit_price_unsigned(
+			origin: OriginFor<T>,
+			pair_hash: [u8; 32],
+			exchange_id: ExchangeId,
+			price: u128,
+			exponent: u8,
+			timestamp: u64,
+			source_block: Option<u64>,
+			notional: Option<u128>,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+			ensure!(T::AcceptUnsigned::get(), Error::<T>::UnsignedDisabled);
+			ensure!(T::RevealWindow::get() == 0, Error::<T>::CommitRequired);
+			let observation = (timestamp, source_block, notional);
+			Self::accept_price(pair_hash, exchange_id, price, exponent, observation)?;
+			Ok(())
+		}
+
+		/// Start tracking a pair.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::add_pair())]
+		pub fn add_pair(origin: OriginFor<T>, info: PairInfo) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			Self::ensure_valid_pair(&info)?;
+			Self::ensure_known_pools(&info)?;
+			let pair_id = Self::pair_id_of(&info);
+			ensure!(!TrackedPairs::<T>::contains_key(pair_id), Error::<T>::PairAlreadyTracked);
+			ensure!(TrackedPairs::<T>::count() < T::MaxPairs::get(), Error::<T>::PairLimitReached);
+
+			TrackedPairs::<T>::insert(pair_id, info);
+			Self::start_bootstrap(pair_id);
+			Self::deposit_event(Event::PairAdded { pair_id });
+			Ok(())
+		}
+
+		/// Replace the configuration of a tracked pair. The pair is identified by the symbols in
+		/// `info`.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::update_pair())]
+		pub fn update_pair(origin: OriginFor<T>, info: PairInfo) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			Self::ensure_valid_pair(&info)?;
+			Self::ensure_known_pools(&info)?;
+			let pair_id = Self::pair_id_of(&info);
+			Self::ensure_configurable(pair_id)?;
+
+			TrackedPairs::<T>::insert(pair_id, info);
+			Self::deposit_event(Event::PairUpdated { pair_id });
+			Ok(())
+		}
+
+		/// Stop tracking a pair.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::remove_pair())]
+		pub fn remove_pair(origin: OriginFor<T>, pair_id: PairId) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			ensure!(TrackedPairs::<T>::contains_key(pair_id), Error::<T>::PairNotTracked);
+			Self::untrack_pair(pair_id);
+			Self::deposit_event(Event::PairRemoved { pair_id });
+			Ok(())
+		}
+
+		/// Register an exchange under `exchange_id`.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::add_exchange())]
+		pub fn add_exchange(
+			origin: OriginFor<T>,
+			exchange_id: ExchangeId,
+			config: ExchangeConfig,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			Self::ensure_valid_exchange(&config)?;
+			ensure!(
+				!Exchanges::<T>::contains_key(exchange_id) &&
+					!ExchangeKeys::<T>::contains_key(config.key()),
+				Error::<T>::ExchangeAlreadyRegistered
+			);
+
+			ExchangeKeys::<T>::insert(config.key(), exchange_id);
+			Exchanges::<T>::insert(exchange_id, config);
+			Self::deposit_event(Event::ExchangeAdded { exchange_id });
+			Ok(())
+		}
+
+		/// Enable or disable querying an exchange. Its stored prices are kept.
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::set_exchange_enabled())]
+		pub fn set_exchange_enabled(
+			origin: OriginFor<T>,
+			exchange_id: ExchangeId,
+			enabled: bool,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			Exchanges::<T>::try_mutate(exchange_id, |config| {
+				let config = config.as_mut().ok_or(Error::<T>::UnknownExchange)?;
+				config.enabled = enabled;
+				Ok::<_, DispatchError>(())
+			})?;
+			Self::deposit_event(Event::ExchangeEnabled { exchange_id, enabled });
+			Ok(())
+		}
+
+		/// End the quarantine of an exchange before [`Config::QuarantinePeriod`] elapses, with
+		/// fresh [`ExchangeStats`].
+		#[pallet::call_index(29)]
+		#[pallet::weight(T::WeightInfo::release_exchange())]
+		pub fn release_exchange(origin: OriginFor<T>, exchange_id: ExchangeId) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			QuarantinedExchanges::<T>::take(exchange_id).ok_or(Error::<T>::NotQuarantined)?;
+			ExchangeStats::<T>::remove(exchange_id);
+			Self::deposit_event(Event::ExchangeReleased { exchange_id });
+			Ok(())
+		}
+
+		/// Replace the RPC endpoints of `chain_id`, in order of preference. An empty list removes
+		/// them.
+		///
+		/// Exchanges on a chain without an endpoint aren't queried.
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::set_rpc_endpoints())]
+		pub fn set_rpc_endpoints(
+			origin: OriginFor<T>,
+			chain_id: ChainId,
+			urls: RpcUrls,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			ensure!(
+				urls.iter().all(|url| core::str::from_utf8(url).is_ok()),
+				Error::<T>::InvalidUrl
+			);
+			if urls.is_empty() {
+				RpcEndpoints::<T>::remove(chain_id);
+			} else {
+				RpcEndpoints::<T>::insert(chain_id, &urls);
+			}
+			Self::deposit_event(Event::RpcEndpointsSet { chain_id, urls });
+			Ok(())
+		}
+
+		/// Report that the offchain worker couldn't fetch the price of a pair from an exchange.
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::report_fetch_failure()
+			.saturating_add(T::DbWeight::get().reads(1)))]
+		pub fn report_fetch_failure(
+			origin: OriginFor<T>,
+			pair_id: PairId,
+			exchange_id: ExchangeId,
+			error: OracleError,
+		) -> DispatchResult {
+			match ensure_signed(origin.clone()) {
+				Ok(who) => ensure!(Self::is_operator(&who), Error::<T>::NotOperator),
+				Err(_) => {
+					ensure_none(origin)?;
+					ensure!(T::AcceptUnsigned::get(), Error::<T>::UnsignedDisabled);
+				},
+			}
+			ensure!(Exchanges::<T>::contains_key(exchange_id), Error::<T>::UnknownExchange);
+			Self::deposit_event(Event::ExchangeFetchFailed { pair_id, exchange_id, error });
+			Self::note_reliability(exchange_id, |stats| stats.failures.saturating_inc());
+			Ok(())
+		}
+
+		/// Allow `who` to submit signed prices, reserving [`Config::OperatorBond`] from it.
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::WeightInfo::register_operator())]
+		pub fn register_operator(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			ensure!(!Operators::<T>::contains_key(&who), Error::<T>::AlreadyOperator);
+			ensure!(Operators::<T>::count() < T::MaxOperators::get(), Error::<T>::TooManyOperators);
+
+			let bond = T::OperatorBond::get();
+			if !bond.is_zero() {
+				T::Currency::reserve(&who, bond)?;
+				OperatorBonds::<T>::insert(&who, bond);
+			}
+			Operators::<T>::insert(&who, ());
+			Self::deposit_event(Event::OperatorRegistered { who });
+			Ok(())
+		}
+
+		/// Revoke the right of `who` to submit signed prices and return what is left of its bond.
+		/// Its stored prices are kept.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::deregister_operator())]
+		pub fn deregister_operator(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			Operators::<T>::take(&who).ok_or(Error::<T>::NotOperator)?;
+			T::Currency::unreserve(&who, OperatorBonds::<T>::take(&who));
+			Self::deposit_event(Event::OperatorDeregistered { who });
+			Ok(())
+		}
+
+		/// Store a price observed by a registered operator. If it is accepted, the operator is
+		/// credited with a contribution point and rewarded for the submission.
+		///
+		/// Checked like [`Pallet::submit_price_unsigned`].
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::submit_price_signed()
+			.saturating_add(Pallet::<T>::aggregation_weight())
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::ContributionReporter::note_contribution_weight())
+			.saturating_add(T::ContributionReporter::note_submission_weight()))]
+		pub fn submit_price_signed(
+			origin: OriginFor<T>,
+			pair_hash: PairId,
+			exchange_id: ExchangeId,
+			price: u128,
+			exponent: u8,
+			timestamp: u64,
+			source_block: Option<u64>,
+			notional: Option<u128>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::is_operator(&who), Error::<T>::NotOperator);
+			ensure!(T::RevealWindow::get() == 0, Error::<T>::CommitRequired);
+			let observation = (timestamp, source_block, notional);
+			if Self::accept_price(pair_hash, exchange_id, price, exponent, observation)? {
+				Self::note_signed_price(&who, pair_hash, exchange_id);
+			}
+			Ok(())
+		}
+
+		/// Slash [`Config::MisbehaviorSlash`] of the bond of the operator `who` for its latest
+		/// signed price of `pair_id` on `exchange_id` in [`SignedSubmissions`], if the price lies
+		/// more than [`Config::SlashDeviationBps`] from the first aggregated median of the pair
+		/// within [`Config::UpdateInterval`] blocks of it. The reporter is paid
+		/// [`Config::ReporterReward`] of the slash.
+		///
+		/// A price can be reported once, while the median is kept in the [`PriceHistory`] and
+		/// until the operator's next price of the pair on the exchange replaces it.
+		#[pallet::call_index(35)]
+		#[pallet::weight(T::WeightInfo::report_misbehavior())]
+		pub fn report_misbehavior(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+			pair_id: PairId,
+			exchange_id: ExchangeId,
+		) -> DispatchResult {
+			let reporter = ensure_signed(origin)?;
+			let (price, block) = SignedSubmissions::<T>::get(&who, (pair_id, exchange_id))
+				.ok_or(Error::<T>::NoSubmission)?;
+			let window = block..block.saturating_add(T::UpdateInterval::get().into());
+			let (median, _) = PriceHistory::<T>::get(pair_id)
+				.iter()
+				.find(|(_, aggregated_in)| window.contains(aggregated_in))
+				.ok_or(Error::<T>::NoMedian)?;
+			ensure!(
+				aggregation::deviation_bps(price, median) > T::SlashDeviationBps::get(),
+				Error::<T>::NotDeviant
+			);
+			Self::slash_operator(who, reporter, pair_id, exchange_id)
+		}
+
+		/// Commit to a price of `pair_hash` on `exchange_id` observed by a registered operator,
+		/// to be revealed with [`Pallet::reveal_price`] in one of the next
+		/// [`Config::RevealWindow`] blocks. `commitment` is the [`Pallet::price_commitment`] of
+		/// the price, which stays hidden from the transaction pool until then.
+		///
+		/// Replaces an earlier commitment of the operator on the pair and exchange.
+		#[pallet::call_index(33)]
+		#[pallet::weight(T::WeightInfo::commit_price())]
+		pub fn commit_price(
+			origin: OriginFor<T>,
+			pair_hash: PairId,
+			exchange_id: ExchangeId,
+			commitment: [u8; 32],
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::is_operator(&who), Error::<T>::NotOperator);
+			ensure!(T::RevealWindow::get() > 0, Error::<T>::CommitRevealDisabled);
+			ensure!(Exchanges::<T>::contains_key(exchange_id), Error::<T>::UnknownExchange);
+			ensure!(TrackedPairs::<T>::contains_key(pair_hash), Error::<T>::PairNotTracked);
+
+			let now = frame_system::Pallet::<T>::block_number();
+			PriceCommitments::<T>::insert(&who, (pair_hash, exchange_id), (commitment, now));
+			Self::deposit_event(Event::PriceCommitted { who, pair_id: pair_hash, exchange_id });
+			Ok(())
+		}
+
+		/// Reveal the price of a commitment made with [`Pallet::commit_price`] in an earlier
+		/// block, at most [`Config::RevealWindow`] blocks ago. The price and `salt` must hash to
+		/// the commitment, which is consumed.
+		///
+		/// Checked and rewarded like [`Pallet::submit_price_signed`].
+		#[pallet::call_index(34)]
+		#[pallet::weight(T::WeightInfo::reveal_price()
+			.saturating_add(Pallet::<T>::aggregation_weight())
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::ContributionReporter::note_contribution_weight())
+			.saturating_add(T::ContributionReporter::note_submission_weight()))]
+		pub fn reveal_price(
+			origin: OriginFor<T>,
+			pair_hash: PairId,
+			exchange_id: ExchangeId,
+			price: u128,
+			exponent: u8,
+			timestamp: u64,
+			source_block: Option<u64>,
+			notional: Option<u128>,
+			salt: [u8; 32],
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::is_operator(&who), Error::<T>::NotOperator);
+			let (commitment, block) = PriceCommitments::<T>::get(&who, (pair_hash, exchange_id))
+				.ok_or(Error::<T>::NoCommitment)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(block < now, Error::<T>::RevealTooEarly);
+			ensure!(
+				now <= block.saturating_add(T::RevealWindow::get().into()),
+				Error::<T>::CommitmentExpired
+			);
+			let observation = (timestamp, source_block, notional);
+			ensure!(
+				Self::price_commitment(price, exponent, observation, &salt) == commitment,
+				Error::<T>::CommitmentMismatch
+			);
+
+			PriceCommitments::<T>::remove(&who, (pair_hash, exchange_id));
+			if Self::accept_price(pair_hash, exchange_id, price, exponent, observation)? {
+				Self::note_signed_price(&who, pair_hash, exchange_id);
+			}
+			Ok(())
+		}
+
+		/// Move the exchange with the stable `key` to the unused id `to`, together with its pools
+		/// in the tracked pairs and their prices, its quarantine and statistics, and the
+		/// commitments and evidence of the operators on it.
+		///
+		/// `pair_count` is the number of tracked pairs and `account_count` that of the registered
+		/// operators and committee members, bounding the weight. Prices of pairs that are no
+		/// longer tracked stay under the old id until they are pruned as stale.
+		#[pallet::call_index(12)]
+		#[pallet::weight(T::WeightInfo::remap_exchange(*pair_count, *account_count))]
+		pub fn remap_exchange(
+			origin: OriginFor<T>,
+			key: ExchangeKey,
+			to: ExchangeId,
+			pair_count: u32,
+			account_count: u32,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			let from = ExchangeKeys::<T>::get(key).ok_or(Error::<T>::UnknownExchange)?;
+			ensure!(!Exchanges::<T>::contains_key(to), Error::<T>::ExchangeAlreadyRegistered);
+			let accounts: Vec<T::AccountId> =
+				Operators::<T>::iter_keys().chain(T::Committee::sorted_members()).collect();
+			ensure!(accounts.len() as u32 <= account_count, Error::<T>::TooManyOperators);
+
+			let config = Exchanges::<T>::take(from).ok_or(Error::<T>::UnknownExchange)?;
+			Exchanges::<T>::insert(to, config);
+			ExchangeKeys::<T>::insert(key, to);
+			if let Some(until) = QuarantinedExchanges::<T>::take(from) {
+				QuarantinedExchanges::<T>::insert(to, until);
+			}
+			if ExchangeStats::<T>::contains_key(from) {
+				ExchangeStats::<T>::insert(to, ExchangeStats::<T>::take(from));
+			}
+			let outliers = OutlierCounts::<T>::take(from);
+			if outliers > 0 {
+				OutlierCounts::<T>::insert(to, outliers);
+			}
+			let mut pairs = 0u32;
+			for (pair_id, mut pair) in TrackedPairs::<T>::iter() {
+				pairs += 1;
+				ensure!(pairs <= pair_count, Error::<T>::TooManyPairs);
+				if let Some(verified) = VerifiedPrices::<T>::take(pair_id, from) {
+					VerifiedPrices::<T>::insert(pair_id, to, verified);
+				}
+				for who in &accounts {
+					if let Some(commitment) = PriceCommitments::<T>::take(who, (pair_id, from)) {
+						PriceCommitments::<T>::insert(who, (pair_id, to), commitment);
+					}
+					if let Some(evidence) = SignedSubmissions::<T>::take(who, (pair_id, from)) {
+						SignedSubmissions::<T>::insert(who, (pair_id, to), evidence);
+					}
+				}
+				if let Some(price) = PriceData::<T>::take(pair_id, from) {
+					PriceData::<T>::insert(pair_id, to, price);
+					let repeats = RepeatedPrices::<T>::take(pair_id, from);
+					if repeats > 0 {
+						RepeatedPrices::<T>::insert(pair_id, to, repeats);
+					}
+					if let Some(block) = SourceBlocks::<T>::take(pair_id, from) {
+						SourceBlocks::<T>::insert(pair_id, to, block);
+					}
+					if let Some(notional) = PoolNotionals::<T>::take(pair_id, from) {
+						PoolNotionals::<T>::insert(pair_id, to, notional);
+					}
+				}
+				if let Some(hop) = PoolHops::<T>::take(pair_id, from) {
+					PoolHops::<T>::insert(pair_id, to, hop);
+				}
+				let mut remapped = false;
+				for (exchange_id, _) in pair.pools.iter_mut().filter(|(id, _)| *id == from) {
+					*exchange_id = to;
+					remapped = true;
+				}
+				if remapped {
+					TrackedPairs::<T>::insert(pair_id, pair);
+				}
+			}
+			Self::deposit_event(Event::ExchangeRemapped { key, from, to });
+			Ok(())
+		}
+
+		/// Have the offchain worker check the spot prices of the Uniswap V3 pools of `pair_id`
+		/// against the pools' own TWAP before submitting them, or stop checking with `None`.
+		#[pallet::call_index(13)]
+		#[pallet::weight(T::WeightInfo::set_twap_check())]
+		pub fn set_twap_check(
+			origin: OriginFor<T>,
+			pair_id: PairId,
+			check: Option<TwapCheck>,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			Self::ensure_configurable(pair_id)?;
+			ensure!(check.is_none_or(|check| check.window > 0), Error::<T>::InvalidTwapWindow);
+
+			TwapChecks::<T>::set(pair_id, check);
+			Self::deposit_event(Event::TwapCheckSet { pair_id, check });
+			Ok(())
+		}
+
+		/// Make `pair_id` a critical pair the offchain worker fetches in every block for a while
+		/// after a large move of its median, or stop with `None`.
+		#[pallet::call_index(14)]
+		#[pallet::weight(T::WeightInfo::set_fast_path())]
+		pub fn set_fast_path(
+			origin: OriginFor<T>,
+			pair_id: PairId,
+			fast_path: Option<FastPath>,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			Self::ensure_configurable(pair_id)?;
+			ensure!(
+				fast_path
+					.is_none_or(|fast_path| fast_path.threshold_bps > 0 && fast_path.blocks > 0),
+				Error::<T>::InvalidFastPath
+			);
+
+			FastPaths::<T>::set(pair_id, fast_path);
+			if fast_path.is_none() {
+				FastPathUntil::<T>::remove(pair_id);
+			}
+			Self::deposit_event(Event::FastPathSet { pair_id, fast_path });
+			Ok(())
+		}
+
+		/// Set how [`PriceProvider`] serves `pair_id` without a fresh price.
+		#[pallet::call_index(15)]
+		#[pallet::weight(T::WeightInfo::set_failure_mode())]
+		pub fn set_failure_mode(
+			origin: OriginFor<T>,
+			pair_id: PairId,
+			mode: FailureMode,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			Self::ensure_configurable(pair_id)?;
+
+			FailureModes::<T>::insert(pair_id, mode);
+			Self::deposit_event(Event::FailureModeSet { pair_id, mode });
+			Ok(())
+		}
+
+		/// Pause `pair_id`, so [`PriceProvider`] serves its price as stale, or resume it.
+		#[pallet::call_index(16)]
+		#[pallet::weight(T::WeightInfo::set_pair_paused())]
+		pub fn set_pair_paused(
+			origin: OriginFor<T>,
+			pair_id: PairId,
+			paused: bool,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			ensure!(TrackedPairs::<T>::contains_key(pair_id), Error::<T>::PairNotTracked);
+
+			if paused {
+				PausedPairs::<T>::insert(pair_id, ());
+				Self::deposit_event(Event::PairPaused { pair_id });
+			} else {
+				PausedPairs::<T>::remove(pair_id);
+				Self::deposit_event(Event::PairResumed { pair_id });
+			}
+			Ok(())
+		}
+
+		/// Set the router of the venue of `exchange_id`, or its quoter for a Uniswap V3 exchange,
+		/// that trades are simulated through, or remove it with `None`.
+		#[pallet::call_index(17)]
+		#[pallet::weight(T::WeightInfo::set_router())]
+		pub fn set_router(
+			origin: OriginFor<T>,
+			exchange_id: ExchangeId,
+			router: Option<EvmAddress>,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			let exchange = Exchanges::<T>::get(exchange_id).ok_or(Error::<T>::UnknownExchange)?;
+			ensure!(router != Some(EvmAddress::default()), Error::<T>::ZeroAddress);
+
+			Routers::<T>::set(exchange.key(), router);
+			Self::deposit_event(Event::RouterSet { exchange_id, router });
+			Ok(())
+		}
+
+		/// Price `pair_id` on `exchange_id` through `hop`, a pool pricing the quote of the first
+		/// pool of the pair on the exchange in the quote of the pair, or price it from its own
+		/// pools again with `None`.
+		#[pallet::call_index(30)]
+		#[pallet::weight(T::WeightInfo::set_pool_hop())]
+		pub fn set_pool_hop(
+			origin: OriginFor<T>,
+			pair_id: PairId,
+			exchange_id: ExchangeId,
+			hop: Option<PoolConfig>,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			Self::ensure_configurable(pair_id)?;
+			let exchange = Exchanges::<T>::get(exchange_id).ok_or(Error::<T>::UnknownExchange)?;
+			if let Some(hop) = &hop {
+				let pair = TrackedPairs::<T>::get(pair_id).ok_or(Error::<T>::PairNotTracked)?;
+				Self::ensure_valid_hop(&pair, exchange_id, exchange.chain, hop)?;
+			}
+
+			PoolHops::<T>::set(pair_id, exchange_id, hop.clone());
+			Self::deposit_event(Event::PoolHopSet { pair_id, exchange_id, hop });
+			Ok(())
+		}
+
+		/// Store the fees of the next block of `chain_id` read by the offchain worker, signed by a
+		/// registered operator or unsigned where [`Config::AcceptUnsigned`] allows it.
+		///
+		/// The fees must be of a later block than the stored ones.
+		#[pallet::call_index(18)]
+		#[pallet::weight(T::WeightInfo::submit_gas_fees()
+			.saturating_add(T::DbWeight::get().reads(1)))]
+		pub fn submit_gas_fees(
+			origin: OriginFor<T>,
+			chain_id: ChainId,
+			fees: GasFees,
+		) -> DispatchResult {
+			match ensure_signed(origin.clone()) {
+				Ok(who) => ensure!(Self::is_operator(&who), Error::<T>::NotOperator),
+				Err(_) => {
+					ensure_none(origin)?;
+					ensure!(T::AcceptUnsigned::get(), Error::<T>::UnsignedDisabled);
+				},
+			}
+			Self::check_gas_fees(chain_id, &fees)?;
+
+			GasData::<T>::insert(chain_id, fees);
+			Self::deposit_event(Event::GasFeesSubmitted { chain_id, fees });
+			Ok(())
+		}
+
+		/// Start or stop reading the fees of `chain_id`, which must have RPC endpoints to be
+		/// tracked. The stored fees, the builder tip sampling and the gas token are removed with
+		/// the tracking.
+		#[pallet::call_index(19)]
+		#[pallet::weight(T::WeightInfo::set_gas_tracking())]
+		pub fn set_gas_tracking(
+			origin: OriginFor<T>,
+			chain_id: ChainId,
+			tracked: bool,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+
+			if tracked {
+				ensure!(RpcEndpoints::<T>::contains_key(chain_id), Error::<T>::UnknownChain);
+				GasTrackedChains::<T>::insert(chain_id, ());
+			} else {
+				GasTrackedChains::<T>::remove(chain_id);
+				BuilderTipChains::<T>::remove(chain_id);
+				GasData::<T>::remove(chain_id);
+				NativeTokens::<T>::remove(chain_id);
+			}
+			Self::deposit_event(Event::GasTrackingSet { chain_id, tracked });
+			Ok(())
+		}
+
+		/// Start or stop sampling the builder tips of `chain_id`, whose fees must be tracked, into
+		/// [`GasFees::builder_tip`].
+		#[pallet::call_index(20)]
+		#[pallet::weight(T::WeightInfo::set_builder_tips())]
+		pub fn set_builder_tips(
+			origin: OriginFor<T>,
+			chain_id: ChainId,
+			enabled: bool,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+
+			if enabled {
+				ensure!(GasTrackedChains::<T>::contains_key(chain_id), Error::<T>::GasNotTracked);
+				BuilderTipChains::<T>::insert(chain_id, ());
+			} else {
+				BuilderTipChains::<T>::remove(chain_id);
+			}
+			Self::deposit_event(Event::BuilderTipsSet { chain_id, enabled });
+			Ok(())
+		}
+
+		/// Set or, with `None`, remove the ticker of the token the gas of `chain_id` is paid in,
+		/// whose fees must be tracked to set one. Arbitrage spreads are only weighed against the
+		/// gas of chains with a gas token, see [`Config::ArbitrageGasUnits`].
+		#[pallet::call_index(31)]
+		#[pallet::weight(T::WeightInfo::set_native_token())]
+		pub fn set_native_token(
+			origin: OriginFor<T>,
+			chain_id: ChainId,
+			token: Option<Symbol>,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+
+			match &token {
+				Some(token) => {
+					ensure!(
+						GasTrackedChains::<T>::contains_key(chain_id),
+						Error::<T>::GasNotTracked
+					);
+					NativeTokens::<T>::insert(chain_id, token);
+				},
+				None => NativeTokens::<T>::remove(chain_id),
+			}
+			Self::deposit_event(Event::NativeTokenSet { chain_id, token });
+			Ok(())
+		}
+
+		/// Start or stop reading the pools of `chain_id`, which must have RPC endpoints to be
+		/// enabled, in one `eth_call` through Multicall3. Multicall3 must be deployed on the chain.
+		#[pallet::call_index(27)]
+		#[pallet::weight(T::WeightInfo::set_multicall())]
+		pub fn set_multicall(
+			origin: OriginFor<T>,
+			chain_id: ChainId,
+			enabled: bool,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+
+			if enabled {
+				ensure!(RpcEndpoints::<T>::contains_key(chain_id), Error::<T>::UnknownChain);
+				MulticallChains::<T>::insert(chain_id, ());
+			} else {
+				MulticallChains::<T>::remove(chain_id);
+			}
+			Self::deposit_event(Event::MulticallSet { chain_id, enabled });
+			Ok(())
+		}
+
+		/// Read the pools of `chain_id`, which must have RPC endpoints for more than
+		/// [`Confirmation::Latest`], at blocks of at least `confirmation`.
+		#[pallet::call_index(32)]
+		#[pallet::weight(T::WeightInfo::set_confirmation())]
+		pub fn set_confirmation(
+			origin: OriginFor<T>,
+			chain_id: ChainId,
+			confirmation: Confirmation,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+
+			if confirmation == Confirmation::Latest {
+				ChainConfirmations::<T>::remove(chain_id);
+			} else {
+				ensure!(RpcEndpoints::<T>::contains_key(chain_id), Error::<T>::UnknownChain);
+				ChainConfirmations::<T>::insert(chain_id, confirmation);
+			}
+			Self::deposit_event(Event::ConfirmationSet { chain_id, confirmation });
+			Ok(())
+		}
+
+		/// Prove the price of `pair_hash` on `exchange_id` at block `block` of the exchange's
+		/// chain with the `account_proof` of the pair's pool and the `storage_proof` of its
+		/// [`DexProtocol::price_slot`], as returned by
+		/// `eth_getProof`, against the state root [`Config::LightClient`] tracks for the block.
+		///
+		/// The price is derived from the proven storage, so anyone can submit it. It must be of a
+		/// later block than the stored one and within the pair's bounds.
+		#[pallet::call_index(28)]
+		#[pallet::weight(T::WeightInfo::submit_verified_price(
+			account_proof.len().saturating_add(storage_proof.len()) as u32,
+		)
+		.saturating_add(T::LightClient::state_root_weight()))]
+		pub fn submit_verified_price(
+			origin: OriginFor<T>,
+			pair_hash: PairId,
+			exchange_id: ExchangeId,
+			block: u64,
+			account_proof: proofs::Proof,
+			storage_proof: proofs::Proof,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			let pair = TrackedPairs::<T>::get(pair_hash).ok_or(Error::<T>::PairNotTracked)?;
+			let exchange = Exchanges::<T>::get(exchange_id).ok_or(Error::<T>::UnknownExchange)?;
+			let (Some(pool), Some(slot)) =
+				(pair.pools_of(exchange_id).next(), exchange.protocol.price_slot())
+			else {
+				return Err(Error::<T>::UnverifiableSource.into())
+			};
+			ensure!(
+				exchange.chain == ChainKind::Evm && exchange.twap_window.is_none(),
+				Error::<T>::UnverifiableSource
+			);
+			ensure!(
+				VerifiedPrices::<T>::get(pair_hash, exchange_id)
+					.is_none_or(|verified| verified.block < block),
+				Error::<T>::OutdatedSubmission
+			);
+
+			let state_root = T::LightClient::state_root(exchange.chain_id, block)
+				.ok_or(Error::<T>::UnknownHeader)?;
+			let value = proofs::verify_storage(
+				&state_root,
+				&pool.address,
+				slot,
+				&account_proof,
+				&storage_proof,
+			)
+			.ok_or(Error::<T>::InvalidProof)?;
+			let price = exchange
+				.protocol
+				.parse_price(&exchange.protocol.slot_return_data(value), &pool.metadata)
+				.map_err(|_| Error::<T>::InvalidProof)?;
+			ensure!(Self::price_bounds(&pair).contains(&price), Error::<T>::PriceOutOfBounds);
+
+			let timestamp = T::UnixTime::now().as_millis() as u64;
+			VerifiedPrices::<T>::insert(
+				pair_hash,
+				exchange_id,
+				VerifiedPrice { price, block, timestamp },
+			);
+			Self::deposit_event(Event::VerifiedPriceSubmitted {
+				pair_id: pair_hash,
+				exchange_id,
+				price,
+				block,
+			});
+			Ok(())
+		}
+
+		/// Replace the exchanges, routers and tracked pairs with their settings by the SCALE
+		/// encoded [`registry::RegistrySnapshot`] `blob`, as exported by
+		/// [`Pallet::export_registry`].
+		///
+		/// Pairs that aren't in the snapshot are removed like with [`Pallet::remove_pair`]. Prices
+		/// stay under their exchange ids until they are pruned as stale.
+		#[pallet::call_index(21)]
+		#[pallet::weight(T::WeightInfo::import_registry(
+			ExchangeId::MAX as u32 + 1,
+			T::MaxPairs::get(),
+		))]
+		pub fn import_registry(origin: OriginFor<T>, blob: Vec<u8>) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			let snapshot = registry::RegistrySnapshot::decode_all_with_depth_limit(
+				registry::MAX_SNAPSHOT_DEPTH,
+				&mut &blob[..],
+			)
+			.map_err(|_| Error::<T>::InvalidSnapshot)?;
+			let pair_ids = Self::check_snapshot(&snapshot)?;
+
+			for pair_id in TrackedPairs::<T>::iter_keys().collect::<Vec<_>>() {
+				if !pair_ids.contains(&pair_id) {
+					Self::untrack_pair(pair_id);
+				}
+			}
+			let _ = Exchanges::<T>::clear(u32::MAX, None);
+			let _ = ExchangeKeys::<T>::clear(u32::MAX, None);
+			let _ = Routers::<T>::clear(u32::MAX, None);
+			for (exchange_id, config) in &snapshot.exchanges {
+				ExchangeKeys::<T>::insert(config.key(), exchange_id);
+				Exchanges::<T>::insert(exchange_id, config);
+			}
+			for (exchange_id, router) in snapshot.routers {
+				if let Some(config) = Exchanges::<T>::get(exchange_id) {
+					Routers::<T>::insert(config.key(), router);
+				}
+			}
+			for (pair_id, pair) in pair_ids.into_iter().zip(snapshot.pairs) {
+				if !TrackedPairs::<T>::contains_key(pair_id) {
+					Self::start_bootstrap(pair_id);
+				}
+				TrackedPairs::<T>::insert(pair_id, pair.info);
+				TwapChecks::<T>::set(pair_id, pair.twap_check);
+				FastPaths::<T>::set(pair_id, pair.fast_path);
+				FailureModes::<T>::insert(pair_id, pair.failure_mode);
+				CrossChainChecks::<T>::set(pair_id, pair.cross_chain_check);
+				PairSlas::<T>::set(pair_id, pair.sla);
+				BreachedSlas::<T>::remove(pair_id);
+				LongTailPairs::<T>::set(pair_id, pair.min_notional);
+				LowQualityPairs::<T>::remove(pair_id);
+				let _ = PoolHops::<T>::clear_prefix(pair_id, u32::MAX, None);
+				for (exchange_id, hop) in pair.hops {
+					PoolHops::<T>::insert(pair_id, exchange_id, hop);
+				}
+			}
+			Self::deposit_event(Event::RegistryImported {
+				exchanges: snapshot.exchanges.len() as u32,
+				pairs: TrackedPairs::<T>::count(),
+			});
+			Ok(())
+		}
+
+		/// Send the `eth_call`s to the exchanges of `protocol` with the gas limit, the state
+		/// overrides and at the confirmation of `options`, or without them at the head of their
+		/// chains with `None`.
+		#[pallet::call_index(23)]
+		#[pallet::weight(T::WeightInfo::set_call_options())]
+		pub fn set_call_options(
+			origin: OriginFor<T>,
+			protocol: ProtocolKind,
+			options: Option<CallOptions>,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+
+			ProtocolCallOptions::<T>::set(protocol, options.clone().unwrap_or_default());
+			Self::deposit_event(Event::CallOptionsSet { protocol, options });
+			Ok(())
+		}
+
+		/// Declare the freshness `pair_id` promises its consumers, or drop it with `None`.
+		///
+		/// The SLA is checked every [`Config::UpdateInterval`] blocks, emitting
+		/// [`Event::SlaBreached`] when the pair falls short of it and [`Event::SlaRestored`]
+		/// when it recovers. Consumers check it on demand with [`Pallet::meets_sla`].
+		#[pallet::call_index(24)]
+		#[pallet::weight(T::WeightInfo::set_sla())]
+		pub fn set_sla(
+			origin: OriginFor<T>,
+			pair_id: PairId,
+			sla: Option<FreshnessSla>,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			Self::ensure_configurable(pair_id)?;
+			ensure!(sla.is_none_or(|sla| sla.is_valid()), Error::<T>::InvalidSla);
+
+			PairSlas::<T>::set(pair_id, sla);
+			// The new SLA is judged afresh at the next check.
+			BreachedSlas::<T>::remove(pair_id);
+			Self::deposit_event(Event::SlaSet { pair_id, sla });
+			Ok(())
+		}
+
+		/// Report spreads between the chains `pair_id` is priced on with
+		/// [`Event::CrossChainDiscrepancy`], or stop with `None`. While a check is set the
+		/// offchain worker also submits the block each price was read at.
+		#[pallet::call_index(22)]
+		#[pallet::weight(T::WeightInfo::set_cross_chain_check())]
+		pub fn set_cross_chain_check(
+			origin: OriginFor<T>,
+			pair_id: PairId,
+			check: Option<CrossChainCheck>,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			Self::ensure_configurable(pair_id)?;
+			ensure!(
+				check.is_none_or(|check| check.threshold_bps > 0),
+				Error::<T>::InvalidCrossChainCheck
+			);
+
+			CrossChainChecks::<T>::set(pair_id, check);
+			Self::deposit_event(Event::CrossChainCheckSet { pair_id, check });
+			Ok(())
+		}
+
+		/// Serve `pair_id` as a long-tail pair whose aggregated pools must each hold at least
+		/// `min_notional` USD, or as a blue-chip pair again with `None`.
+		///
+		/// A long-tail pair aggregating a thinner pool is served flagged as
+		/// [`ProvidedPrice::low_quality`] instead of like any other price, and its dynamic bounds
+		/// allow at least [`Config::LongTailDeviation`].
+		#[pallet::call_index(25)]
+		#[pallet::weight(T::WeightInfo::set_long_tail())]
+		pub fn set_long_tail(
+			origin: OriginFor<T>,
+			pair_id: PairId,
+			min_notional: Option<u128>,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			Self::ensure_configurable(pair_id)?;
+			ensure!(min_notional != Some(0), Error::<T>::InvalidMinNotional);
+
+			LongTailPairs::<T>::set(pair_id, min_notional);
+			if min_notional.is_none() {
+				LowQualityPairs::<T>::remove(pair_id);
+			}
+			Self::deposit_event(Event::LongTailSet { pair_id, min_notional });
+			Ok(())
+		}
+
+		/// Retire `pair_id` without breaking its consumers mid-block: its configuration becomes
+		/// read-only and it takes no new alerts, but it is priced and served as before.
+		/// [`Event::PairDeprecationPending`] warns of the retirement every update interval until
+		/// `after_block`, and the first interval after it the pair is removed like with
+		/// [`Pallet::remove_pair`].
+		#[pallet::call_index(26)]
+		#[pallet::weight(T::WeightInfo::deprecate_pair())]
+		pub fn deprecate_pair(
+			origin: OriginFor<T>,
+			pair_id: PairId,
+			after_block: BlockNumberFor<T>,
+		) -> DispatchResult {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			Self::ensure_configurable(pair_id)?;
+			ensure!(
+				after_block > frame_system::Pallet::<T>::block_number(),
+				Error::<T>::InvalidDeprecation
+			);
+
+			DeprecatedPairs::<T>::insert(pair_id, after_block);
+			Self::deposit_event(Event::PairDeprecated { pair_id, after_block });
+			Ok(())
+		}
+
+		/// Get notified with [`Event::AlertTriggered`] once the aggregated median of `pair_id`
+		/// moves at least `threshold_bps` basis points away from `target`, a price with
+		/// [`fixed::PRICE_DECIMALS`] decimals.
+		///
+		/// Replaces the caller's alert on the pair; a zero `threshold_bps` removes it. Each alert
+		/// reserves [`Config::AlertDeposit`] from the caller until it is removed or triggered.
+		#[pallet::call_index(11)]
+		#[pallet::weight(T::WeightInfo::set_alert())]
+		pub fn set_alert(
+			origin: OriginFor<T>,
+			pair_id: PairId,
+			threshold_bps: u32,
+			target: Price,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(TrackedPairs::<T>::contains_key(pair_id), Error::<T>::PairNotTracked);
+			ensure!(target > 0, Error::<T>::InvalidTarget);
+			ensure!(
+				threshold_bps == 0 || !DeprecatedPairs::<T>::contains_key(pair_id),
+				Error::<T>::PairDeprecated
+			);
+
+			let mut alerts = Alerts::<T>::get(pair_id);
+			alerts.retain(|alert| alert.who != who);
+			Self::release_alert_deposit(pair_id, &who);
+			if threshold_bps > 0 {
+				let alert = PriceAlert { who: who.clone(), threshold_bps, target };
+				alerts.try_push(alert).map_err(|_| Error::<T>::TooManyAlerts)?;
+				let deposit = T::AlertDeposit::get();
+				if !deposit.is_zero() {
+					T::Currency::reserve(&who, deposit)?;
+					AlertDeposits::<T>::insert(pair_id, &who, deposit);
+				}
+			}
+			Self::store_alerts(pair_id, alerts);
+			Self::deposit_event(Event::AlertSet { who, pair_id, threshold_bps, target });
+			Ok(())
+		}
+
+		/// Send the [`PriceProvider::price`] of `pair_id` to the consumer of the origin, e.g. a
+		/// sibling parachain `Transact`ing the call, through [`Config::PriceMessenger`] in
+		/// response to its query `query_id`.
+		#[pallet::call_index(36)]
+		#[pallet::weight(T::WeightInfo::request_price_xcm()
+			.saturating_add(T::PriceMessenger::send_price_weight()))]
+		pub fn request_price_xcm(
+			origin: OriginFor<T>,
+			pair_id: PairId,
+			query_id: u64,
+		) -> DispatchResult {
+			let subscriber = T::SubscriberOrigin::ensure_origin(origin)?;
+			let price = Self::price(pair_id)?;
+			T::PriceMessenger::send_price(&subscriber, query_id, pair_id, price)?;
+			Self::deposit_event(Event::PriceServed { subscriber, pair_id, query_id });
+			Ok(())
+		}
+
+		/// Get the aggregated median of `pair_id` pushed to the consumer of the origin in
+		/// response to its query `query_id` after the next aggregation of the pair, and after
+		/// every later one moving it at least `threshold_bps` basis points away from the last
+		/// price pushed.
+		///
+		/// Replaces the consumer's subscription to the pair; a zero `threshold_bps` removes it.
+		#[pallet::call_index(37)]
+		#[pallet::weight(T::WeightInfo::subscribe_price())]
+		pub fn subscribe_price(
+			origin: OriginFor<T>,
+			pair_id: PairId,
+			query_id: u64,
+			threshold_bps: u32,
+		) -> DispatchResult {
+			let subscriber = T::SubscriberOrigin::ensure_origin(origin)?;
+			ensure!(TrackedPairs::<T>::contains_key(pair_id), Error::<T>::PairNotTracked);
+			ensure!(
+				threshold_bps == 0 || !DeprecatedPairs::<T>::contains_key(pair_id),
+				Error::<T>::PairDeprecated
+			);
+
+			let mut subscriptions = PriceSubscriptions::<T>::get(pair_id);
+			subscriptions.retain(|subscription| subscription.subscriber != subscriber);
+			if threshold_bps > 0 {
+				let subscription = PriceSubscription {
+					subscriber: subscriber.clone(),
+					query_id,
+					threshold_bps,
+					last: None,
+				};
+				subscriptions
+					.try_push(subscription)
+					.map_err(|_| Error::<T>::TooManySubscriptions)?;
+			}
+			Self::store_subscriptions(pair_id, subscriptions);
+			Self::deposit_event(Event::PriceSubscribed {
+				subscriber,
+				pair_id,
+				query_id,
+				threshold_bps,
+			});
+			Ok(())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			if !T::AcceptUnsigned::get() {
+				return InvalidTransaction::Call.into()
+			}
+			match call {
+				Call::submit_price_unsigned {
+					pair_hash,
+					exchange_id,
+					price,
+					exponent,
+					timestamp,
+					..
+				} => {
+					if T::RevealWindow::get() > 0 {
+						return InvalidTransaction::Call.into()
+					}
+					let (pair, price) = Self::check_submission(
+						*pair_hash,
+						*exchange_id,
+						*price,
+						*exponent,
+						*timestamp,
+					)
+					.map_err(|e| match e {
+						Error::<T>::UnknownExchange => InvalidTransaction::Custom(UNKNOWN_EXCHANGE),
+						Error::<T>::PairNotTracked => InvalidTransaction::Custom(UNTRACKED_PAIR),
+						Error::<T>::StaleSubmission | Error::<T>::OutdatedSubmission =>
+							InvalidTransaction::Stale,
+						_ => InvalidTransaction::Custom(INVALID_EXPONENT),
+					})?;
+					if !Self::price_bounds(&pair).contains(&price) {
+						return InvalidTransaction::Custom(PRICE_OUT_OF_BOUNDS).into()
+					}
+					// One submission per pair and exchange per block.
+					ValidTransaction::with_tag_prefix("PriceOracle")
+						.priority(TransactionPriority::MAX)
+						.and_provides((
+							pair_hash,
+							exchange_id,
+							frame_system::Pallet::<T>::block_number(),
+						))
+						.longevity(5)
+						.propagate(true)
+						.build()
+				},
+				Call::submit_gas_fees { chain_id, fees } => {
+					Self::check_gas_fees(*chain_id, fees).map_err(|e| match e {
+						Error::<T>::GasNotTracked => InvalidTransaction::Call,
+						_ => InvalidTransaction::Stale,
+					})?;
+					ValidTransaction::with_tag_prefix("PriceOracleGas")
+						.priority(TransactionPriority::MAX / 2)
+						.and_provides((chain_id, fees.block))
+						.longevity(5)
+						.propagate(true)
+						.build()
+				},
+				Call::report_fetch_failure { pair_id, exchange_id, error } => {
+					if !Exchanges::<T>::contains_key(exchange_id) {
+						return InvalidTransaction::Custom(UNKNOWN_EXCHANGE).into()
+					}
+					ValidTransaction::with_tag_prefix("PriceOracleFailure")
+						.priority(TransactionPriority::MAX / 2)
+						.and_provides((
+							pair_id,
+							exchange_id,
+							error,
+							frame_system::Pallet::<T>::block_number(),
+						))
+						.longevity(5)
+						.propagate(true)
+						.build()
+				},
+				_ => InvalidTransaction::Call.into(),
+			}
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Query the pools of the tracked pairs due in `block_number` and submit the prices they
+		/// return.
+		///
+		/// Each exchange of a pair is due once every [`Config::UpdateInterval`] blocks, in the
+		/// block of the interval given by its [`Pallet::sample_phase`]. At most
+		/// [`Config::MaxExchangesPerBlock`] pools are queried per run, shared among the exchanges
+		/// by their [`Pallet::contribution_scores`], see [`budget`]. Pairs with an open
+		/// [`FastPath`] are due in every block, out of the separate [`Config::FastPathBudget`].
+		/// With [`Config::MaxReadDelay`] the reads left out for the budget or the deadline are
+		/// carried over to the next runs, and read first once they waited that many blocks. A price
+		/// read is submitted only if it moved by [`Config::SubmissionDeviationBps`] from the stored
+		/// one or that is [`Config::SubmissionHeartbeat`] old. With [`Config::RevealWindow`] it is
+		/// committed to instead, and revealed by the next run.
+		///
+		/// A run reads a pair under its lock at [`pair_lock_key`], and only if no other run read
+		/// it in the same block, see [`pair_read_key`], so workers running side by side on sibling
+		/// forks or after a restart don't submit the prices of a pair twice.
+		///
+		/// While the transaction pool refuses the transactions of the offchain worker, see
+		/// [`Pallet::congested`], the prices of [`LongTailPairs`] are deferred to their next
+		/// interval, every other pair gets only the price of its median exchange submitted and
+		/// failures aren't reported.
+		///
+		/// Returns `false` if fetches were left when the run reached `deadline`.
+		fn fetch_and_submit_prices(block_number: BlockNumberFor<T>, deadline: u64) -> bool {
+			let interval: BlockNumberFor<T> = T::UpdateInterval::get().into();
+			let phase: u32 = (block_number % interval).unique_saturated_into();
+			let seed = Self::sampling_seed(block_number);
+			let sampled = |pair_id: PairId, exchange_id: ExchangeId| {
+				Self::sample_phase(pair_id, exchange_id, &seed) == phase
+			};
+			let now: u32 = block_number.unique_saturated_into();
+			let max_delay = T::MaxReadDelay::get();
+			let carried = Self::carried_reads(max_delay);
+			let mut due: Vec<_> = TrackedPairs::<T>::iter()
+				.filter_map(|(pair_id, pair)| {
+					if pair.pools.iter().any(|(exchange_id, _)| {
+						sampled(pair_id, *exchange_id) ||
+							carried.contains_key(&(pair_id, *exchange_id))
+					}) {
+						Some((pair_id, pair, false))
+					} else if Self::on_fast_path(pair_id, block_number) {
+						Some((pair_id, pair, true))
+					} else {
+						None
+					}
+				})
+				.collect();
+			if due.is_empty() {
+				return true
+			}
+			let congested = Self::congested();
+
+			let timeout = T::HttpTimeout::get();
+
+			let signer = Signer::<T, T::AuthorityId>::any_account();
+			let signed = signer.can_sign();
+			// Only operators can commit to their prices.
+			if !signed && (!T::AcceptUnsigned::get() || T::RevealWindow::get() > 0) {
+				log::warn!(target: LOG_TARGET, "No operator key in the keystore, not fetching");
+				return true
+			}
+
+			let lock_keys: Vec<_> =
+				due.iter().map(|(pair_id, ..)| pair_lock_key(pair_id)).collect();
+			let expiry = Duration::from_millis(T::MaxOcwMillis::get());
+			let mut locks: Vec<StorageLock<BlockAndTime<frame_system::Pallet<T>>>> = lock_keys
+				.iter()
+				.map(|key| StorageLock::with_block_and_time_deadline(key, 0, expiry))
+				.collect();
+			let guards: Vec<_> = locks
+				.iter_mut()
+				.zip(&due)
+				.map(|(lock, (pair_id, ..))| {
+					let guard = lock.try_lock().ok()?;
+					let read_in = StorageValueRef::persistent(&pair_read_key(pair_id)).get::<u32>();
+					(read_in.ok().flatten() != Some(now)).then_some(guard)
+				})
+				.collect();
+			let mut held = guards.iter().map(Option::is_some);
+			due.retain(|_| held.next().unwrap_or_default());
+
+			let mut exchanges: Vec<_> = Exchanges::<T>::iter()
+				.filter(|(exchange_id, exchange)| {
+					exchange.enabled && !Self::is_quarantined(*exchange_id)
+				})
+				.filter_map(|(exchange_id, exchange)| {
+					#[cfg(feature = "synthetic-prices")]
+					if exchange.chain == ChainKind::Synthetic {
+						return Some((exchange_id, exchange, Vec::new()))
+					}
+					let urls = endpoints::ranked(
+						exchange.chain_id,
+						RpcEndpoints::<T>::get(exchange.chain_id)
+							.unwrap_or_default()
+							.into_iter()
+							.map(Into::into),
+					);
+					if urls.is_empty() {
+						log::warn!(
+							target: LOG_TARGET,
+							"No RPC endpoint for chain {} of {}",
+							exchange.chain_id,
+							exchange.label(),
+						);
+						return None
+					}
+					Some((exchange_id, exchange, urls))
+				})
+				.collect();
+			exchanges.sort_by_key(|(exchange_id, ..)| *exchange_id);
+
+			// Pick the pools read in this run, sharing the budgets among the exchanges, then read
+			// those taking a single call in one request per chain before the others.
+			let mut reads = Vec::new();
+			for &(pair_id, ref pair, fast) in &due {
+				let twap_check = TwapChecks::<T>::get(pair_id);
+				for (exchange, (exchange_id, ..)) in exchanges.iter().enumerate() {
+					let pools: Vec<_> = pair.pools_of(*exchange_id).collect();
+					let waiting = carried.get(&(pair_id, *exchange_id)).copied();
+					let is_due = fast || sampled(pair_id, *exchange_id) || waiting.is_some();
+					if pools.is_empty() || !is_due {
+						continue
+					}
+					let due_since = waiting.unwrap_or(now);
+					let hop = PoolHops::<T>::get(pair_id, exchange_id);
+					reads.push(PoolRead {
+						pair_id,
+						pair,
+						twap_check,
+						fast,
+						due_since,
+						exchange,
+						pools,
+						hop,
+					});
+				}
+			}
+			// The budget goes to the reads due first, so carried reads go before the others.
+			reads.sort_by_key(|read| read.due_since);
+			let scores = Self::contribution_scores();
+			let mut selected = vec![false; reads.len()];
+			for (fast, mut budget) in [
+				(false, u32::from(T::MaxExchangesPerBlock::get())),
+				(true, T::FastPathBudget::get()),
+			] {
+				// Reads that waited `MaxReadDelay` blocks first, the longest waiting first.
+				for (index, read) in reads.iter().enumerate() {
+					let overdue = max_delay > 0 && now.saturating_sub(read.due_since) >= max_delay;
+					if read.fast == fast && overdue && budget > 0 {
+						selected[index] = true;
+						budget -= 1;
+					}
+				}
+				let (indices, candidates): (Vec<_>, Vec<_>) = reads
+					.iter()
+					.enumerate()
+					.filter(|(index, read)| read.fast == fast && !selected[*index])
+					.map(|(index, read)| {
+						let score = scores.get(&exchanges[read.exchange].0).copied();
+						(index, (read.exchange, score.unwrap_or_default().saturating_add(1)))
+					})
+					.unzip();
+				let allocated = budget::allocate(budget, &candidates);
+				for (index, chosen) in indices.into_iter().zip(allocated) {
+					selected[index] = chosen;
+				}
+			}
+			let mut carry: Vec<_> = reads
+				.iter()
+				.zip(&selected)
+				.filter(|(read, chosen)| !read.fast && !**chosen)
+				.map(|(read, _)| (read.pair_id, exchanges[read.exchange].0, read.due_since))
+				.collect();
+			let mut selected = selected.into_iter();
+			reads.retain(|_| selected.next().unwrap_or_default());
+			let tolerance = T::ChainTimeTolerance::get();
+			let mut batched =
+				Self::batched_prices(&reads, &exchanges, tolerance > 0, deadline, timeout);
+
+			// Prices are submitted at the end of the run, once the heads their endpoints served
+			// were compared.
+			let mut observations = Vec::new();
+			let mut finished = true;
+			for (index, read) in reads.iter().enumerate() {
+				let PoolRead { pair_id, pair, twap_check, ref pools, ref hop, .. } = *read;
+				let (exchange_id, exchange, urls) = &exchanges[read.exchange];
+				let (fetched, millis) = match batched.remove(&index) {
+					Some(batched) => batched,
+					None => {
+						if Self::out_of_time(deadline) {
+							if !read.fast {
+								carry.push((pair_id, *exchange_id, read.due_since));
+							}
+							finished = false;
+							continue
+						}
+						let started = sp_io::offchain::timestamp().unix_millis();
+						let fetched = Self::fetch_exchange_price(
+							exchange,
+							urls,
+							pair,
+							pools,
+							hop.as_ref(),
+							twap_check.as_ref(),
+							tolerance > 0,
+							timeout,
+						);
+						(
+							fetched,
+							sp_io::offchain::timestamp().unix_millis().saturating_sub(started),
+						)
+					},
+				};
+				let fetched = fetched.and_then(|(price, head)| {
+					match Self::price_bounds(pair).contains(&price) {
+						true => Ok((price, head)),
+						false => Err(OracleError::PriceOutOfBounds { value: price }),
+					}
+				});
+				telemetry::record_fetch(*exchange_id, millis, fetched.is_ok());
+				let (price, head) = match fetched {
+					Ok(fetched) => fetched,
+					Err(e) => {
+						log::warn!(
+							target: LOG_TARGET,
+							"Failed to fetch {} from {}: {} ({:?})",
+							pair.symbol(),
+							exchange.label(),
+							e.as_str(),
+							e,
+						);
+						if !congested {
+							let (exchange_id, error) = (*exchange_id, e);
+							let report = Call::report_fetch_failure { pair_id, exchange_id, error };
+							Self::submit(&signer, report);
+						}
+						continue
+					},
+				};
+
+				let (pair_hash, exchange_id) = (pair_id, *exchange_id);
+				if !Self::worth_submitting(pair_id, exchange_id, price) {
+					log::debug!(
+						target: LOG_TARGET,
+						"Not submitting {} from {}: unchanged since the last heartbeat",
+						pair.symbol(),
+						exchange.label(),
+					);
+					continue
+				}
+
+				// Submit with the pair's precision; `decimals` is validated on registration.
+				let exponent = pair.decimals.min(fixed::PRICE_DECIMALS);
+				let price = fixed::to_precision(price, exponent, T::Rounding::get().rounding);
+				let timestamp = sp_io::offchain::timestamp().unix_millis();
+				let source_block = CrossChainChecks::<T>::contains_key(pair_id)
+					.then(|| Self::chain_head(exchange, urls, timeout))
+					.flatten();
+				let report_liquidity =
+					LongTailPairs::<T>::contains_key(pair_id) || T::ReportLiquidity::get();
+				// The pools of a routed read price other pairs, so their notional isn't the pair's.
+				let direct = hop.is_none() && !exchange.protocol.is_reference();
+				let notional = (report_liquidity && direct)
+					.then(|| Self::pool_notional(exchange, urls, pools, timeout))
+					.flatten();
+				let call = match signed {
+					true => Call::submit_price_signed {
+						pair_hash,
+						exchange_id,
+						price,
+						exponent,
+						timestamp,
+						source_block,
+						notional,
+					},
+					false => Call::submit_price_unsigned {
+						pair_hash,
+						exchange_id,
+						price,
+						exponent,
+						timestamp,
+						source_block,
+						notional,
+					},
+				};
+				observations.push((pair_id, exchange_id, exchange.chain_id, head, price, call));
+			}
+
+			let heads: Vec<_> = observations
+				.iter()
+				.filter_map(|(.., chain_id, head, _, _)| Some((*chain_id, head.as_ref()?.1)))
+				.collect();
+			let mut lagging = endpoints::lagging_heads(&heads, tolerance).into_iter();
+			let mut submissions = Vec::new();
+			for (pair_id, exchange_id, chain_id, head, price, call) in observations {
+				let Some((url, time)) = head else {
+					submissions.push((pair_id, price, call));
+					continue
+				};
+				if !lagging.next().unwrap_or(false) {
+					submissions.push((pair_id, price, call));
+					continue
+				}
+				log::warn!(
+					target: LOG_TARGET,
+					"Discarding the price of exchange {} read at head time {} from a lagging endpoint of chain {}",
+					exchange_id,
+					time,
+					chain_id,
+				);
+				let error = OracleError::LaggingEndpoint;
+				endpoints::record(&url, &Err::<(), _>(error));
+				if !congested {
+					let report = Call::report_fetch_failure { pair_id, exchange_id, error };
+					Self::submit(&signer, report);
+				}
+			}
+			if congested {
+				submissions = Self::shed_submissions(submissions);
+			}
+			for (.., call) in submissions {
+				Self::submit(&signer, Self::commit_to(call, now));
+			}
+			if max_delay > 0 {
+				StorageValueRef::persistent(CARRIED_READS_KEY).set(&carry);
+			}
+			for (pair_id, ..) in &due {
+				StorageValueRef::persistent(&pair_read_key(pair_id)).set(&now);
+			}
+			drop(guards);
+			finished
+		}
+
+		/// Whether `price`, read by the offchain worker for `pair_id` on `exchange_id`, is worth a
+		/// transaction: there is no stored price, `price` repeats it, the stored price is at least
+		/// [`Config::SubmissionHeartbeat`] old, or `price` moved from it by at least
+		/// [`Config::SubmissionDeviationBps`].
+		fn worth_submitting(pair_id: PairId, exchange_id: ExchangeId, price: Price) -> bool {
+			let threshold = T::SubmissionDeviationBps::get();
+			let Some((stored, timestamp)) = PriceData::<T>::get(pair_id, exchange_id) else {
+				return true
+			};
+			let age = sp_io::offchain::timestamp().unix_millis().saturating_sub(timestamp);
+			threshold == 0 ||
+				price == stored ||
+				age >= T::SubmissionHeartbeat::get() ||
+				aggregation::deviation_bps(price, stored) >= threshold
+		}
+
+		/// The reads carried over under [`CARRIED_READS_KEY`], with the block each was first due
+		/// in. None without [`Config::MaxReadDelay`].
+		fn carried_reads(max_delay: u32) -> BTreeMap<(PairId, ExchangeId), u32> {
+			if max_delay == 0 {
+				return BTreeMap::new()
+			}
+			StorageValueRef::persistent(CARRIED_READS_KEY)
+				.get::<Vec<(PairId, ExchangeId, u32)>>()
+				.ok()
+				.flatten()
+				.unwrap_or_default()
+				.into_iter()
+				.map(|(pair_id, exchange_id, due_since)| ((pair_id, exchange_id), due_since))
+				.collect()
+		}
+
+		/// Whether the transaction pool refused transactions of the offchain worker since the
+		/// last run that submitted prices, counted under [`REFUSED_SUBMISSIONS_KEY`]. Starts the
+		/// count of the current run over.
+		fn congested() -> bool {
+			let refused = StorageValueRef::persistent(REFUSED_SUBMISSIONS_KEY);
+			let congested = refused.get::<u32>().ok().flatten().is_some_and(|refused| refused > 0);
+			refused.set(&0u32);
+			congested
+		}
+
+		/// The `submissions` of a run while the transaction pool is congested: none of the
+		/// [`LongTailPairs`], whose prices wait for their next interval, and of each other pair
+		/// the one with the median price, the lower one of an even number.
+		fn shed_submissions(
+			submissions: Vec<(PairId, Price, Call<T>)>,
+		) -> Vec<(PairId, Price, Call<T>)> {
+			let mut pairs = BTreeMap::<PairId, Vec<_>>::new();
+			for submission in submissions {
+				if !LongTailPairs::<T>::contains_key(submission.0) {
+					pairs.entry(submission.0).or_default().push(submission);
+				}
+			}
+			pairs
+				.into_values()
+				.map(|mut submissions| {
+					submissions.sort_by_key(|(_, price, _)| *price);
+					submissions.swap_remove((submissions.len() - 1) / 2)
+				})
+				.collect()
+		}
+
+		/// The [`ProtocolCallOptions`] of `exchange`, at blocks of at least the
+		/// [`ChainConfirmations`] of its chain.
+		fn call_options(exchange: &ExchangeConfig) -> CallOptions {
+			let options = ProtocolCallOptions::<T>::get(exchange.protocol);
+			let chain = ChainConfirmations::<T>::get(exchange.chain_id);
+			CallOptions { confirmation: options.confirmation.max(chain), ..options }
+		}
+
+		/// Fetch the price of `pair` from `pools` of `exchange`, or from the first of them routed
+		/// through `hop`, failing over between its `urls`, or generate it on a synthetic exchange.
+		///
+		/// With `read_head`, also returns the endpoint the price was read from with the timestamp
+		/// of the chain head it served right after. Solana endpoints serve no head timestamp in
+		/// the same request, so their prices aren't checked for lag.
+		fn fetch_exchange_price(
+			exchange: &ExchangeConfig,
+			urls: &[Vec<u8>],
+			pair: &PairInfo,
+			pools: &[&exchanges::PoolConfig],
+			hop: Option<&exchanges::PoolConfig>,
+			twap_check: Option<&TwapCheck>,
+			read_head: bool,
+			timeout: u64,
+		) -> endpoints::ServedPrice {
+			#[cfg(feature = "synthetic-prices")]
+			if exchange.chain == ChainKind::Synthetic {
+				return Self::synthetic_price(pair, pools).map(|price| (price, None))
+			}
+			let options = Self::call_options(exchange);
+			endpoints::with_failover(urls, |url| {
+				let price = match (hop, pools) {
+					(Some(hop), [pool, ..]) =>
+						exchange.fetch_routed_price(url, pair, pool, hop, &options, timeout)?,
+					_ => exchange
+						.fetch_tiered_price(url, pair, pools, twap_check, &options, timeout)?,
+				};
+				if !read_head || exchange.chain == ChainKind::Solana {
+					return Ok((price, None))
+				}
+				let rpc_url = core::str::from_utf8(url).map_err(|_| OracleError::InvalidConfig)?;
+				let time = EvmChain.head_timestamp(rpc_url, timeout)?;
+				Ok((price, Some((url.to_vec(), time))))
+			})
+		}
+
+		/// Read the prices of the `reads` taking a single contract call together, in one request
+		/// per chain and call options: through Multicall3 on the chains in [`MulticallChains`],
+		/// and in a JSON-RPC batch on the others if [`Config::BatchRpcCalls`] is set. Batches fail
+		/// over between the endpoints of their chain like [`Pallet::fetch_exchange_price`].
+		///
+		/// Returns the outcome of each batched read by its index in `reads`, with the time the
+		/// request took. Reads whose batch couldn't be sent, or was left alone in it, aren't
+		/// returned: they are fetched on their own.
+		fn batched_prices(
+			reads: &[PoolRead],
+			exchanges: &[(ExchangeId, ExchangeConfig, Vec<Vec<u8>>)],
+			read_head: bool,
+			deadline: u64,
+			timeout: u64,
+		) -> BTreeMap<usize, (endpoints::ServedPrice, u64)> {
+			let batch_rpc_calls = T::BatchRpcCalls::get();
+			let mut batches: Vec<(ChainId, CallOptions, bool, Vec<_>)> = Vec::new();
+			for (index, read) in reads.iter().enumerate() {
+				let (_, exchange, urls) = &exchanges[read.exchange];
+				let multicall = MulticallChains::<T>::contains_key(exchange.chain_id);
+				if !(multicall || batch_rpc_calls) || read.hop.is_some() {
+					continue
+				}
+				let Some((pool, data)) =
+					exchange.single_price_call(&read.pools, read.twap_check.as_ref())
+				else {
+					continue
+				};
+				let options = Self::call_options(exchange);
+				let call = (index, exchange, pool, data);
+				match batches.iter_mut().find(|(chain_id, batch_options, ..)| {
+					*chain_id == exchange.chain_id && *batch_options == options
+				}) {
+					Some((.., calls)) => calls.push((call, urls)),
+					None =>
+						batches.push((exchange.chain_id, options, multicall, vec![(call, urls)])),
+				}
+			}
+
+			let mut prices = BTreeMap::new();
+			for (chain_id, options, multicall, batch) in batches {
+				// A read alone gains nothing from the batch.
+				let [(_, urls), _, ..] = &batch[..] else { continue };
+				if Self::out_of_time(deadline) {
+					break
+				}
+				let mut calls: Vec<_> = batch
+					.iter()
+					.map(|((_, _, pool, data), _)| (&pool.address, data.as_str()))
+					.collect();
+				if read_head && multicall {
+					calls.push((&multicall::MULTICALL3, multicall::GET_CURRENT_BLOCK_TIMESTAMP));
+				}
+				let started = sp_io::offchain::timestamp().unix_millis();
+				let results = endpoints::with_failover(urls, |url| {
+					let rpc_url =
+						core::str::from_utf8(url).map_err(|_| OracleError::InvalidConfig)?;
+					if !multicall {
+						let results = EvmChain.call_batch(rpc_url, &calls, &options, timeout)?;
+						let head = match read_head {
+							true =>
+								Some((url.to_vec(), EvmChain.head_timestamp(rpc_url, timeout)?)),
+							false => None,
+						};
+						return Ok((results.into_iter().map(Ok).collect(), head))
+					}
+					let mut results = EvmChain.multicall(rpc_url, &calls, &options, timeout)?;
+					let head = match read_head {
+						true => {
+							let time = results.pop().ok_or(OracleError::DecodeError)??;
+							let time = fixed::word(&time, 0)?
+								.try_into()
+								.map_err(|_| OracleError::DecodeError)?;
+							Some((url.to_vec(), time))
+						},
+						false => None,
+					};
+					Ok((results, head))
+				});
+				let millis = sp_io::offchain::timestamp().unix_millis().saturating_sub(started);
+				let (results, head) = match results {
+					Ok(results) => results,
+					Err(e) => {
+						log::warn!(
+							target: LOG_TARGET,
+							"Failed to read the pools of chain {} {}, reading them one by one: {} \
+							 ({:?})",
+							chain_id,
+							if multicall { "through Multicall3" } else { "in a batch" },
+							e.as_str(),
+							e,
+						);
+						continue
+					},
+				};
+				for (((index, exchange, pool, _), _), result) in batch.iter().zip(results) {
+					let price = result.and_then(|data| {
+						exchange.parse_call_price(reads[*index].pair, pool, &data)
+					});
+					prices.insert(*index, (price.map(|price| (price, head.clone())), millis));
+				}
+			}
+			prices
+		}
+
+		/// Number of the latest block of the chain of `exchange`, or its slot on Solana, read right
+		/// after its price so the prices of different chains can be aligned.
+		fn chain_head(exchange: &ExchangeConfig, urls: &[Vec<u8>], timeout: u64) -> Option<u64> {
+			#[cfg(feature = "synthetic-prices")]
+			if exchange.chain == ChainKind::Synthetic {
+				return None
+			}
+			endpoints::with_failover(urls, |url| {
+				let url = core::str::from_utf8(url).map_err(|_| OracleError::InvalidConfig)?;
+				match exchange.chain {
+					ChainKind::Solana => SolanaChain.slot(url, timeout),
+					_ => EvmChain.block_number(url, timeout),
+				}
+			})
+			.inspect_err(|e| {
+				log::warn!(
+					target: LOG_TARGET,
+					"Failed to read the head of chain {}: {} ({:?})",
+					exchange.chain_id,
+					e.as_str(),
+					e,
+				)
+			})
+			.ok()
+		}
+
+		/// Notional in whole USD of the deepest of `pools` of `exchange`, read right after their
+		/// price for long-tail pairs, see [`LongTailPairs`], and with [`Config::ReportLiquidity`].
+		fn pool_notional(
+			exchange: &ExchangeConfig,
+			urls: &[Vec<u8>],
+			pools: &[&exchanges::PoolConfig],
+			timeout: u64,
+		) -> Option<u128> {
+			#[cfg(feature = "synthetic-prices")]
+			if exchange.chain == ChainKind::Synthetic {
+				return None
+			}
+			let options = Self::call_options(exchange);
+			endpoints::with_failover(urls, |url| {
+				pools.iter().try_fold(0, |deepest, pool| {
+					Ok(deepest.max(exchange.fetch_notional(url, pool, &options, timeout)?))
+				})
+			})
+			.inspect_err(|e| {
+				log::warn!(
+					target: LOG_TARGET,
+					"Failed to read the pool notional of {}: {} ({:?})",
+					exchange.label(),
+					e.as_str(),
+					e,
+				)
+			})
+			.ok()
+		}
+
+		/// Next price of the random walk of the first of `pools` of a synthetic exchange, from
+		/// the last aggregate of `pair` or the middle of its bounds, seeded with the parent block
+		/// hash.
+		#[cfg(feature = "synthetic-prices")]
+		fn synthetic_price(
+			pair: &PairInfo,
+			pools: &[&exchanges::PoolConfig],
+		) -> Result<Price, OracleError> {
+			let pool = pools.first().ok_or(OracleError::UnsupportedPair)?;
+			let bounds = Self::price_bounds(pair);
+			let last = AggregatedPrice::<T>::get(Self::pair_id_of(pair)).map_or_else(
+				|| bounds.start() + (bounds.end() - bounds.start()) / 2,
+				|aggregate| aggregate.median,
+			);
+			let seed = frame_system::Pallet::<T>::parent_hash();
+			Ok(chains::synthetic::next_price(last, seed.as_ref(), &pool.address, bounds))
+		}
+
+		/// Read the fees of the next block of every chain in [`GasTrackedChains`] and submit them,
+		/// in the first block of each [`Config::UpdateInterval`].
+		///
+		/// Returns `false` if chains were left when the run reached `deadline`.
+		fn fetch_and_submit_gas_fees(block_number: BlockNumberFor<T>, deadline: u64) -> bool {
+			let interval: BlockNumberFor<T> = T::UpdateInterval::get().into();
+			if !(block_number % interval).is_zero() {
+				return true
+			}
+			let signer = Signer::<T, T::AuthorityId>::any_account();
+			if !signer.can_sign() && !T::AcceptUnsigned::get() {
+				return true
+			}
+
+			let timeout = T::HttpTimeout::get();
+			for chain_id in GasTrackedChains::<T>::iter_keys() {
+				let Some(urls) = RpcEndpoints::<T>::get(chain_id) else { continue };
+				if Self::out_of_time(deadline) {
+					return false
+				}
+				let builder_tips = BuilderTipChains::<T>::contains_key(chain_id);
+				let urls = endpoints::ranked(chain_id, urls.into_iter().map(Into::into));
+				let history = endpoints::with_failover(&urls, |url| {
+					let url = core::str::from_utf8(url).map_err(|_| OracleError::InvalidConfig)?;
+					EvmChain.fee_history(
+						url,
+						gas::FEE_HISTORY_BLOCKS,
+						gas::percentiles(builder_tips),
+						timeout,
+					)
+				});
+				let timestamp = sp_io::offchain::timestamp().unix_millis();
+				let policy = T::Rounding::get();
+				match history
+					.and_then(|history| gas::gas_fees(&history, builder_tips, timestamp, policy))
+				{
+					Ok(fees) => Self::submit(&signer, Call::submit_gas_fees { chain_id, fees }),
+					Err(e) => log::warn!(
+						target: LOG_TARGET,
+						"Failed to fetch the fees of chain {}: {} ({:?})",
+						chain_id,
+						e.as_str(),
+						e,
+					),
+				}
+			}
+			true
+		}
+
+		/// Whether the offchain worker run has reached `deadline`.
+		fn out_of_time(deadline: u64) -> bool {
+			sp_io::offchain::timestamp().unix_millis() >= deadline
+		}
+
+		/// Log a run cut short by [`Config::MaxOcwMillis`] and count it under
+		/// [`OCW_OVERRUNS_KEY`].
+		fn note_overrun(block_number: BlockNumberFor<T>) {
+			log::warn!(
+				target: LOG_TARGET,
+				"Offchain worker of block {:?} ran out of time after {} ms, skipping the remaining fetches",
+				block_number,
+				T::MaxOcwMillis::get(),
+			);
+			let _ = StorageValueRef::persistent(OCW_OVERRUNS_KEY).mutate(
+				|overruns: Result<Option<u32>, _>| {
+					Ok::<_, ()>(overruns.ok().flatten().unwrap_or(0).saturating_add(1))
+				},
+			);
+		}
+
+		/// Check that the fees of `chain_id` are tracked and `fees` are newer than the stored ones.
+		fn check_gas_fees(chain_id: ChainId, fees: &GasFees) -> Result<(), Error<T>> {
+			ensure!(GasTrackedChains::<T>::contains_key(chain_id), Error::<T>::GasNotTracked);
+			ensure!(
+				GasData::<T>::get(chain_id).is_none_or(|stored| fees.block > stored.block),
+				Error::<T>::StaleSubmission
+			);
+			Ok(())
+		}
+
+		/// Block within each [`Config::UpdateInterval`] the offchain worker fetches the prices of
+		/// `pair_id` in, spreading the pairs over the interval.
+		pub fn fetch_phase(pair_id: PairId) -> u32 {
+			let [a, b, c, d, ..] = pair_id;
+			u32::from_le_bytes([a, b, c, d]) % T::UpdateInterval::get()
+		}
+
+		/// Block within the [`Config::UpdateInterval`] the offchain worker samples `exchange_id`
+		/// for `pair_id` in: the [`Pallet::fetch_phase`] of the pair delayed by up to
+		/// [`Config::SamplingJitter`] blocks, drawn from `seed`.
+		pub fn sample_phase(pair_id: PairId, exchange_id: ExchangeId, seed: &T::Hash) -> u32 {
+			let jitter = match T::SamplingJitter::get() {
+				0 => 0,
+				max => {
+					let hash = sp_io::hashing::blake2_256(&(seed, pair_id, exchange_id).encode());
+					let [a, b, c, d, ..] = hash;
+					u32::from_le_bytes([a, b, c, d]) % (max + 1)
+				},
+			};
+			(Self::fetch_phase(pair_id) + jitter) % T::UpdateInterval::get()
+		}
+
+		/// Seed of the [`Pallet::sample_phase`]s of the interval of `block_number`, the hash of
+		/// the block before the interval, which is unknown until the interval starts.
+		pub fn sampling_seed(block_number: BlockNumberFor<T>) -> T::Hash {
+			let interval: BlockNumberFor<T> = T::UpdateInterval::get().into();
+			let start = block_number - block_number % interval;
+			frame_system::Pallet::<T>::block_hash(start.saturating_sub(One::one()))
+		}
+
+		/// Whether the [`FastPath`] of `pair_id` is open in `block_number`.
+		pub fn on_fast_path(pair_id: PairId, block_number: BlockNumberFor<T>) -> bool {
+			FastPathUntil::<T>::get(pair_id).is_some_and(|until| block_number <= until)
+		}
+
+		/// Open the [`FastPath`] of `pair_id` if its median moved far enough from `previous`,
+		/// and drop it once it has expired.
+		fn note_volatility(pair_id: PairId, previous: Option<Price>, median: Price) {
+			let Some(fast_path) = FastPaths::<T>::get(pair_id) else { return };
+			let now = frame_system::Pallet::<T>::block_number();
+			let move_bps =
+				previous.map_or(0, |previous| aggregation::deviation_bps(median, previous));
+			if move_bps >= fast_path.threshold_bps {
+				let until = now.saturating_add(fast_path.blocks.into());
+				FastPathUntil::<T>::insert(pair_id, until);
+				Self::deposit_event(Event::FastPathOpened { pair_id, move_bps, until });
+			} else if FastPathUntil::<T>::get(pair_id).is_some_and(|until| until < now) {
+				FastPathUntil::<T>::remove(pair_id);
+			}
+		}
+
+		/// Submit `call` signed by an operator key of the node if there is one, unsigned
+		/// otherwise.
+		pub(crate) fn submit(signer: &Signer<T, T::AuthorityId>, call: Call<T>) {
+			let submitted = if signer.can_sign() {
+				matches!(signer.send_signed_transaction(|_| call.clone()), Some((_, Ok(()))))
+			} else {
+				let xt = compat::unsigned_extrinsic::<T, Call<T>>(call.into());
+				SubmitTransaction::<T, Call<T>>::submit_transaction(xt).is_ok()
+			};
+			telemetry::record_submission(submitted);
+			if !submitted {
+				log::warn!(target: LOG_TARGET, "Failed to submit oracle transaction");
+				let _ = StorageValueRef::persistent(REFUSED_SUBMISSIONS_KEY).mutate(
+					|refused: Result<Option<u32>, _>| {
+						Ok::<_, ()>(refused.ok().flatten().unwrap_or(0).saturating_add(1))
+					},
+				);
+			}
+		}
+
+		/// Weight of aggregating the pair of a submission in `on_finalize`, charged to every
+		/// submission.
+		fn aggregation_weight() -> Weight {
+			let pushes = T::PriceMessenger::send_price_weight()
+				.saturating_mul(T::MaxSubscriptionsPerPair::get().into());
+			T::WeightInfo::aggregate_prices(u32::from(ExchangeId::MAX) + 1).saturating_add(pushes)
+		}
+
+		/// Store a submitted price, observed at the `(timestamp, source block, pool notional)` of
+		/// `observation`, unless it is outside of the pair's bounds. Returns whether the price was
+		/// stored.
+		fn accept_price(
+			pair_id: PairId,
+			exchange_id: ExchangeId,
+			price: u128,
+			exponent: u8,
+			(timestamp, source_block, notional): (u64, Option<u64>, Option<u128>),
+		) -> Result<bool, DispatchError> {
+			let (pair, price) =
+				Self::check_submission(pair_id, exchange_id, price, exponent, timestamp)?;
+
+			if !Self::price_bounds(&pair).contains(&price) {
+				let reason = OracleError::PriceOutOfBounds { value: price };
+				Self::report_submission(pair_id, exchange_id, Err(reason));
+				Self::note_reliability(exchange_id, |stats| stats.failures.saturating_inc());
+				return Ok(false)
+			}
+
+			Self::note_repetition(pair_id, exchange_id, price);
+			PriceData::<T>::insert(pair_id, exchange_id, (price, timestamp));
+			let now = frame_system::Pallet::<T>::block_number();
+			sp_io::offchain_index::set(
+				&price_key(&pair_id, now, exchange_id),
+				&(price, timestamp).encode(),
+			);
+			SourceBlocks::<T>::set(pair_id, exchange_id, source_block);
+			PoolNotionals::<T>::set(pair_id, exchange_id, notional);
+			PendingAggregation::<T>::insert(pair_id, ());
+			Self::report_submission(pair_id, exchange_id, Ok((price, timestamp)));
+			Self::note_reliability(exchange_id, |stats| stats.successes.saturating_inc());
+			Ok(true)
+		}
+
+		/// Report a price of `pair_id` from `exchange_id` that was stored with its timestamp, or
+		/// discarded for `reason`: in its own event, or in the summary of the pair at the end of
+		/// the block with [`Config::SummarizeSourceEvents`].
+		fn report_submission(
+			pair_id: PairId,
+			exchange_id: ExchangeId,
+			outcome: Result<(Price, u64), OracleError>,
+		) {
+			if T::SummarizeSourceEvents::get() {
+				SubmissionSummaries::<T>::mutate(pair_id, |summary| {
+					summary.record(exchange_id, outcome.ok().map(|(price, _)| price))
+				});
+				return
+			}
+			match outcome {
+				Ok((price, timestamp)) => Self::deposit_event(Event::PriceSubmitted {
+					pair_id,
+					exchange_id,
+					price,
+					timestamp,
+				}),
+				Err(reason) =>
+					Self::deposit_event(Event::PriceRejected { pair_id, exchange_id, reason }),
+			}
+		}
+
+		/// Count whether `price` repeats the stored price of `pair_id` on `exchange_id` in
+		/// [`RepeatedPrices`], announcing when the price freezes or thaws.
+		fn note_repetition(pair_id: PairId, exchange_id: ExchangeId, price: Price) {
+			let was_frozen = Self::is_frozen(pair_id, exchange_id);
+			match PriceData::<T>::get(pair_id, exchange_id) {
+				Some((previous, _)) if previous == price =>
+					RepeatedPrices::<T>::mutate(pair_id, exchange_id, |repeats| {
+						repeats.saturating_inc()
+					}),
+				_ => RepeatedPrices::<T>::remove(pair_id, exchange_id),
+			}
+			match (was_frozen, Self::is_frozen(pair_id, exchange_id)) {
+				(false, true) => Self::deposit_event(Event::PriceFrozen { pair_id, exchange_id }),
+				(true, false) => Self::deposit_event(Event::PriceUnfrozen { pair_id, exchange_id }),
+				_ => {},
+			}
+		}
+
+		/// Whether the last [`Config::FrozenPriceThreshold`] submissions of `exchange_id` for
+		/// `pair_id` all carried the same price.
+		pub fn is_frozen(pair_id: PairId, exchange_id: ExchangeId) -> bool {
+			let threshold = T::FrozenPriceThreshold::get();
+			// A run of `n` equal submissions holds `n - 1` repetitions.
+			threshold > 0 && RepeatedPrices::<T>::get(pair_id, exchange_id) >= threshold - 1
+		}
+
+		/// Combine the latest prices of `pair_id` across the registered exchanges into its
+		/// [`AggregatedPrice`].
+		///
+		/// The aggregate is recomputed from all fresh prices of the pair rather than updated with
+		/// the latest submission, so it doesn't depend on the order of the submissions. Running it
+		/// again in the same block replaces the block's entry in the [`PriceHistory`].
+		pub fn aggregate_prices(pair_id: PairId) -> Option<PriceAggregate> {
+			let mut observed = Vec::new();
+			let mut reference: Option<(Price, u64)> = None;
+			for (exchange_id, (price, timestamp)) in PriceData::<T>::iter_prefix(pair_id) {
+				let Some(exchange) = Exchanges::<T>::get(exchange_id) else { continue };
+				let max_age = exchange.max_price_age.unwrap_or_else(T::MaxPriceAge::get);
+				if !Self::is_fresh_within(max_age, timestamp) ||
+					Self::is_frozen(pair_id, exchange_id) ||
+					Self::is_quarantined(exchange_id)
+				{
+					continue
+				}
+				if exchange.protocol.is_reference() {
+					if reference.is_none_or(|(_, newest)| timestamp > newest) {
+						reference = Some((price, timestamp));
+					}
+					continue
+				}
+				observed.push((exchange_id, exchange.chain_id, price, timestamp));
+			}
+
+			let mut chains = BTreeMap::<ChainId, Vec<(Price, u64)>>::new();
+			let mut venues: Option<(VenueQuote, VenueQuote)> = None;
+			let mut prices = Vec::new();
+			let observed = Self::reject_outliers(pair_id, observed);
+			for (exchange_id, chain_id, price, timestamp) in observed {
+				prices.push((exchange_id, price));
+				chains.entry(chain_id).or_default().push((price, timestamp));
+				let quote = VenueQuote { exchange_id, price, timestamp };
+				venues = Some(match venues {
+					Some((ask, bid)) => (
+						if price < ask.price { quote } else { ask },
+						if price > bid.price { quote } else { bid },
+					),
+					None => (quote, quote),
+				});
+			}
+
+			// First the venues of each chain, then the chains.
+			let _ = ChainAggregates::<T>::clear_prefix(pair_id, u32::MAX, None);
+			let mut tier = Vec::with_capacity(chains.len());
+			let mut per_chain = Vec::with_capacity(chains.len());
+			let mut sources = 0;
+			let policy = T::Rounding::get();
+			for (chain_id, observations) in chains {
+				let chain = aggregation::aggregate(&observations, policy)?;
+				ChainAggregates::<T>::insert(pair_id, chain_id, chain);
+				tier.push((chain.median, chain.timestamp));
+				per_chain.push((chain_id, chain));
+				sources += chain.sources;
+			}
+			let aggregate = PriceAggregate { sources, ..aggregation::aggregate(&tier, policy)? };
+			if let Some((ask, bid)) = venues {
+				let spread_bps = aggregation::deviation_bps(bid.price, ask.price);
+				let venues = BestVenues { ask, bid, spread_bps };
+				PairVenues::<T>::insert(pair_id, venues);
+				Self::note_arbitrage(pair_id, &venues);
+			}
+			Self::check_cross_chain(pair_id, &per_chain);
+			if let Some((reference, _)) = reference {
+				Self::check_reference(pair_id, reference, &prices);
+			}
+			Self::note_quality(pair_id, &prices);
+
+			let previous = AggregatedPrice::<T>::get(pair_id).map(|previous| previous.median);
+			AggregatedPrice::<T>::insert(pair_id, aggregate);
+			if !Bootstrapping::<T>::contains_key(pair_id) {
+				Self::note_latest(pair_id, aggregate);
+			}
+			PriceHistory::<T>::mutate(pair_id, |history| {
+				let now = frame_system::Pallet::<T>::block_number();
+				history.record(aggregate.median, now);
+				sp_io::offchain_index::set(
+					&history_key(&pair_id, now),
+					&(aggregate.median, now).encode(),
+				);
+			});
+			Self::deposit_event(Event::PriceAggregated {
+				pair_id,
+				median: aggregate.median,
+				mean: aggregate.mean,
+				sources: aggregate.sources,
+			});
+			Self::trigger_alerts(pair_id, aggregate.median);
+			Self::push_subscriptions(pair_id, &aggregate);
+			Self::note_volatility(pair_id, previous, aggregate.median);
+			Some(aggregate)
+		}
+
+		/// The `(exchange_id, chain_id, price, timestamp)` observations of `pair_id` other than
+		/// its outliers, see [`Config::OutlierMads`], counting and announcing those left out. Each
+		/// judged price counts towards the [`ExchangeStats`] of its exchange.
+		fn reject_outliers(
+			pair_id: PairId,
+			observed: Vec<(ExchangeId, ChainId, Price, u64)>,
+		) -> Vec<(ExchangeId, ChainId, Price, u64)> {
+			let prices: Vec<_> = observed.iter().map(|(_, _, price, _)| *price).collect();
+			let Some((median, outliers)) = aggregation::outliers(
+				&prices,
+				T::OutlierMads::get(),
+				T::ConsensusBps::get(),
+				T::Rounding::get(),
+			) else {
+				return observed
+			};
+			observed
+				.into_iter()
+				.zip(outliers)
+				.filter_map(|(observation, outlier)| {
+					let (exchange_id, _, price, _) = observation;
+					Self::note_reliability(exchange_id, |stats| {
+						stats.judged.saturating_inc();
+						if outlier {
+							stats.outliers.saturating_inc();
+						}
+					});
+					if !outlier {
+						return Some(observation)
+					}
+					OutlierCounts::<T>::mutate(exchange_id, |count| count.saturating_inc());
+					Self::deposit_event(Event::PriceOutlierRejected {
+						pair_id,
+						exchange_id,
+						price,
+						median,
+					});
+					None
+				})
+				.collect()
+		}
+
+		/// Record the spread of `venues`, the exchanges of `pair_id` at its new aggregation, in
+		/// [`ArbitrageOpportunities`] if it is wide enough to trade on.
+		fn note_arbitrage(pair_id: PairId, venues: &BestVenues) {
+			let opportunity = arbitrage::opportunity(venues, T::MinSpreadBps::get())
+				.filter(|opportunity| Self::covers_gas(pair_id, opportunity));
+			ArbitrageOpportunities::<T>::set(pair_id, opportunity);
+			if let Some(ArbitrageOpportunity {
+				buy_exchange,
+				sell_exchange,
+				spread_bps,
+				timestamp,
+			}) = opportunity
+			{
+				Self::deposit_event(Event::ArbitrageOpportunity {
+					pair_id,
+					buy_exchange,
+					sell_exchange,
+					spread_bps,
+					timestamp,
+				});
+			}
+		}
+
+		/// Whether the spread of `opportunity` on [`Config::ArbitrageTradeSize`] of `pair_id` pays
+		/// for a swap of [`Config::ArbitrageGasUnits`] on both of its exchanges.
+		fn covers_gas(pair_id: PairId, opportunity: &ArbitrageOpportunity) -> bool {
+			let gas = T::ArbitrageGasUnits::get();
+			let Some(pair) = TrackedPairs::<T>::get(pair_id).filter(|_| gas > 0) else {
+				return true
+			};
+			let cost = Self::swap_cost(opportunity.buy_exchange, &pair.quote, gas)
+				.saturating_add(Self::swap_cost(opportunity.sell_exchange, &pair.quote, gas));
+			arbitrage::covers_gas(T::ArbitrageTradeSize::get(), opportunity.spread_bps, cost)
+		}
+
+		/// Cost of `gas` units on the chain of `exchange_id` in `quote`, zero while the fees,
+		/// the gas token or a fresh price of the gas token in `quote` are unknown.
+		fn swap_cost(exchange_id: ExchangeId, quote: &Symbol, gas: u64) -> Price {
+			let cost = || {
+				let chain_id = Exchanges::<T>::get(exchange_id)?.chain_id;
+				let fees = GasData::<T>::get(chain_id)?;
+				let native = NativeTokens::<T>::get(chain_id)?;
+				let native_price = if native == *quote {
+					fixed::PRICE_UNIT
+				} else {
+					AggregatedPrice::<T>::get(T::PairHasher::pair_id(&native, quote))
+						.filter(|aggregate| Self::is_fresh(aggregate.timestamp))?
+						.median
+				};
+				Some(arbitrage::gas_cost(gas, &fees, native_price))
+			};
+			cost().unwrap_or_default()
+		}
+
+		/// Flag the long-tail `pair_id` in [`LowQualityPairs`] while the pool of any of its
+		/// aggregated exchange `prices` holds less than its minimum notional, or an unknown one,
+		/// announcing when the flag changes.
+		fn note_quality(pair_id: PairId, prices: &[(ExchangeId, Price)]) {
+			let low_quality = LongTailPairs::<T>::get(pair_id).is_some_and(|min_notional| {
+				prices.iter().any(|(exchange_id, _)| {
+					PoolNotionals::<T>::get(pair_id, exchange_id)
+						.is_none_or(|notional| notional < min_notional)
+				})
+			});
+			match (LowQualityPairs::<T>::contains_key(pair_id), low_quality) {
+				(false, true) => {
+					LowQualityPairs::<T>::insert(pair_id, ());
+					Self::deposit_event(Event::PairLowQuality { pair_id });
+				},
+				(true, false) => {
+					LowQualityPairs::<T>::remove(pair_id);
+					Self::deposit_event(Event::PairQualityRestored { pair_id });
+				},
+				_ => {},
+			}
+		}
+
+		/// Emit [`Event::ReferenceDeviation`] for each of the exchange `prices` of `pair_id`
+		/// further than [`Config::MaxReferenceDeviationBps`] from its `reference` price.
+		fn check_reference(pair_id: PairId, reference: Price, prices: &[(ExchangeId, Price)]) {
+			for &(exchange_id, price) in prices {
+				let deviation_bps = aggregation::deviation_bps(price, reference);
+				if deviation_bps > T::MaxReferenceDeviationBps::get() {
+					Self::deposit_event(Event::ReferenceDeviation {
+						pair_id,
+						exchange_id,
+						price,
+						reference,
+						deviation_bps,
+					});
+				}
+			}
+		}
+
+		/// Emit [`Event::CrossChainDiscrepancy`] if the lowest and the highest of the `chains`
+		/// medians of `pair_id` are further apart than its [`CrossChainCheck`] allows, from
+		/// observations close enough in time.
+		fn check_cross_chain(pair_id: PairId, chains: &[(ChainId, PriceAggregate)]) {
+			let Some(check) = CrossChainChecks::<T>::get(pair_id) else { return };
+			let low = chains.iter().min_by_key(|(_, chain)| chain.median);
+			let high = chains.iter().max_by_key(|(_, chain)| chain.median);
+			let (Some((low, low_chain)), Some((high, high_chain))) = (low, high) else { return };
+			let spread_bps = aggregation::deviation_bps(high_chain.median, low_chain.median);
+			if low == high || spread_bps < check.threshold_bps {
+				return
+			}
+			let skew = high_chain.timestamp.abs_diff(low_chain.timestamp);
+			if check.max_skew.is_some_and(|max_skew| skew > max_skew) {
+				log::debug!(
+					target: LOG_TARGET,
+					"Spread of {} bps between chains {} and {} sampled {} ms apart",
+					spread_bps,
+					low,
+					high,
+					skew,
+				);
+				return
+			}
+			Self::deposit_event(Event::CrossChainDiscrepancy {
+				pair_id,
+				low: *low,
+				high: *high,
+				spread_bps,
+				skew,
+			});
+		}
+
+		/// [`PriceProvider::price`] of `pair_id` at `aggregate`, its aggregated price.
+		pub(crate) fn provided_price(
+			pair_id: PairId,
+			aggregate: &PriceAggregate,
+		) -> Result<ProvidedPrice, DispatchError> {
+			let stale =
+				!Self::is_fresh(aggregate.timestamp) || PausedPairs::<T>::contains_key(pair_id);
+			if stale && FailureModes::<T>::get(pair_id) == FailureMode::FailClosed {
+				return Err(Error::<T>::PriceUnavailable.into())
+			}
+			Ok(ProvidedPrice {
+				price: aggregate.median,
+				timestamp: aggregate.timestamp,
+				stale,
+				low_quality: LowQualityPairs::<T>::contains_key(pair_id),
+			})
+		}
+
+		/// Check a submission against everything but the pair's bounds, returning the pair and
+		/// the price scaled to [`fixed::PRICE_DECIMALS`] decimals.
+		fn check_submission(
+			pair_id: PairId,
+			exchange_id: ExchangeId,
+			price: u128,
+			exponent: u8,
+			timestamp: u64,
+		) -> Result<(PairInfo, Price), Error<T>> {
+			ensure!(Exchanges::<T>::contains_key(exchange_id), Error::<T>::UnknownExchange);
+			ensure!(exponent <= fixed::PRICE_DECIMALS, Error::<T>::InvalidExponent);
+			let price = fixed::normalize(price, exponent).ok_or(Error::<T>::PriceOverflow)?;
+			let pair = TrackedPairs::<T>::get(pair_id).ok_or(Error::<T>::PairNotTracked)?;
+
+			let now = T::UnixTime::now().as_millis() as u64;
+			ensure!(
+				timestamp.abs_diff(now) <= T::MaxSubmissionDelay::get(),
+				Error::<T>::StaleSubmission
+			);
+			// Of two observations made at the same time the higher price wins, so the stored price
+			// doesn't depend on the order the submissions of a block are applied in.
+			if let Some(stored) = PriceData::<T>::get(pair_id, exchange_id) {
+				ensure!((timestamp, price) > (stored.1, stored.0), Error::<T>::OutdatedSubmission);
+			}
+			Ok((pair, price))
+		}
+
+		fn ensure_valid_pair(info: &PairInfo) -> Result<(), Error<T>> {
+			ensure!(info.decimals <= fixed::PRICE_DECIMALS, Error::<T>::InvalidExponent);
+			ensure!(info.min_price < info.max_price, Error::<T>::InvalidBounds);
+			ensure!(
+				info.dynamic_bounds.is_none_or(|bounds| bounds.window > 0),
+				Error::<T>::InvalidBounds
+			);
+			for (index, (exchange_id, pool)) in info.pools.iter().enumerate() {
+				let zero = match pool.account {
+					Some(account) => account == SolanaAddress::default(),
+					None => pool.address == EvmAddress::default(),
+				};
+				ensure!(!zero, Error::<T>::ZeroAddress);
+				let metadata = &pool.metadata;
+				ensure!(
+					metadata.token0_decimals <= types::MAX_TOKEN_DECIMALS &&
+						metadata.token1_decimals <= types::MAX_TOKEN_DECIMALS,
+					Error::<T>::InvalidPoolDecimals
+				);
+				ensure!(
+					!info.pools[..index].iter().any(|(other, earlier)| other == exchange_id &&
+						earlier.address == pool.address &&
+						earlier.account == pool.account),
+					Error::<T>::DuplicatePool
+				);
+			}
+			Ok(())
+		}
+
+		/// Check that the pools of `info` are on registered exchanges.
+		fn ensure_known_pools(info: &PairInfo) -> Result<(), Error<T>> {
+			Self::ensure_pools_on(info, |exchange_id| {
+				Exchanges::<T>::get(exchange_id).map(|exchange| exchange.chain)
+			})
+		}
+
+		/// Check that the pools of `info` are on exchanges `chain_of` knows the chain of, with
+		/// an account exactly where that chain keeps pools in accounts.
+		fn ensure_pools_on(
+			info: &PairInfo,
+			chain_of: impl Fn(&ExchangeId) -> Option<ChainKind>,
+		) -> Result<(), Error<T>> {
+			for (exchange_id, pool) in &info.pools {
+				let chain = chain_of(exchange_id).ok_or(Error::<T>::UnknownExchange)?;
+				ensure!(
+					chain.has_pool_accounts() == pool.account.is_some(),
+					Error::<T>::InvalidPoolAccount
+				);
+			}
+			Ok(())
+		}
+
+		/// Check that `hop` can route `pair` on `exchange_id`, an exchange on `chain`: the pair
+		/// has a pool there to route and the hop is a contract pool with valid token decimals.
+		fn ensure_valid_hop(
+			pair: &PairInfo,
+			exchange_id: ExchangeId,
+			chain: ChainKind,
+			hop: &PoolConfig,
+		) -> Result<(), Error<T>> {
+			ensure!(pair.pools_of(exchange_id).next().is_some(), Error::<T>::HopWithoutPool);
+			ensure!(
+				!chain.has_pool_accounts() && hop.account.is_none(),
+				Error::<T>::InvalidPoolAccount
+			);
+			ensure!(hop.address != EvmAddress::default(), Error::<T>::ZeroAddress);
+			ensure!(
+				hop.metadata.token0_decimals <= types::MAX_TOKEN_DECIMALS &&
+					hop.metadata.token1_decimals <= types::MAX_TOKEN_DECIMALS,
+				Error::<T>::InvalidPoolDecimals
+			);
+			Ok(())
+		}
+
+		/// Check `pair_id` is tracked and, not being retired, can be configured.
+		fn ensure_configurable(pair_id: PairId) -> Result<(), Error<T>> {
+			ensure!(TrackedPairs::<T>::contains_key(pair_id), Error::<T>::PairNotTracked);
+			ensure!(!DeprecatedPairs::<T>::contains_key(pair_id), Error::<T>::PairDeprecated);
+			Ok(())
+		}
+
+		/// Check an exchange before it is registered: it needs a name to be told apart by, an
+		/// endpoint to be reached through, a protocol its chain can read, to be priced at a TWAP,
+		/// pools keeping one and, if it has its own maximum price age, a non-zero one.
+		fn ensure_valid_exchange(config: &ExchangeConfig) -> Result<(), Error<T>> {
+			ensure!(
+				!config.name.is_empty() && core::str::from_utf8(&config.name).is_ok(),
+				Error::<T>::InvalidExchangeName
+			);
+			ensure!(config.chain_id != 0, Error::<T>::InvalidChainId);
+			ensure!(config.chain.supports(config.protocol), Error::<T>::UnsupportedProtocol);
+			ensure!(
+				config.twap_window.is_none_or(|window| {
+					window > 0 && config.protocol == ProtocolKind::UniswapV3
+				}),
+				Error::<T>::InvalidTwapWindow
+			);
+			ensure!(config.max_price_age != Some(0), Error::<T>::InvalidMaxPriceAge);
+			Ok(())
+		}
+
+		/// Whether a price observed at `timestamp` is younger than [`Config::MaxPriceAge`].
+		pub fn is_fresh(timestamp: u64) -> bool {
+			Self::is_fresh_within(T::MaxPriceAge::get(), timestamp)
+		}
+
+		/// Whether a price of `exchange_id` observed at `timestamp` is younger than the exchange's
+		/// own maximum price age, or [`Config::MaxPriceAge`] if it has none.
+		pub fn is_fresh_from(exchange_id: ExchangeId, timestamp: u64) -> bool {
+			let max_age =
+				Exchanges::<T>::get(exchange_id).and_then(|exchange| exchange.max_price_age);
+			Self::is_fresh_within(max_age.unwrap_or_else(T::MaxPriceAge::get), timestamp)
+		}
+
+		/// Score of each exchange by its contribution to the aggregates: the number of pairs its
+		/// price is fresh for, not frozen and within [`Config::ConsensusBps`] of the aggregated
+		/// median. Exchanges without such a price have no score.
+		pub fn contribution_scores() -> BTreeMap<ExchangeId, u32> {
+			let consensus_bps = T::ConsensusBps::get();
+			let mut scores = BTreeMap::new();
+			for (pair_id, aggregate) in AggregatedPrice::<T>::iter() {
+				for (exchange_id, (price, timestamp)) in PriceData::<T>::iter_prefix(pair_id) {
+					if Self::is_fresh_from(exchange_id, timestamp) &&
+						!Self::is_frozen(pair_id, exchange_id) &&
+						aggregation::deviation_bps(price, aggregate.median) <= consensus_bps
+					{
+						scores.entry(exchange_id).or_insert(0u32).saturating_inc();
+					}
+				}
+			}
+			scores
+		}
+
+		/// Whether a price observed at `timestamp` is at most `max_age` milliseconds old.
+		fn is_fresh_within(max_age: u64, timestamp: u64) -> bool {
+			let now = T::UnixTime::now().as_millis() as u64;
+			now.saturating_sub(timestamp) <= max_age
+		}
+
+		/// Remove the exchange prices older than the maximum price age of their exchange,
+		/// returning the number of prices looked at.
+		pub fn prune_stale_prices() -> u32 {
+			let mut seen = 0u32;
+			let stale: Vec<_> = PriceData::<T>::iter()
+				.inspect(|_| seen.saturating_inc())
+				.filter(|(_, exchange_id, (_, timestamp))| {
+					!Self::is_fresh_from(*exchange_id, *timestamp)
+				})
+				.map(|(pair_id, exchange_id, _)| (pair_id, exchange_id))
+				.collect();
+			for (pair_id, exchange_id) in stale {
+				PriceData::<T>::remove(pair_id, exchange_id);
+				RepeatedPrices::<T>::remove(pair_id, exchange_id);
+				SourceBlocks::<T>::remove(pair_id, exchange_id);
+				PoolNotionals::<T>::remove(pair_id, exchange_id);
+			}
+			seen
+		}
+
+		/// Latest price of `pair_id` reported by `exchange_id`, as `(price, timestamp)`, unless it
+		/// is older than the maximum price age of the exchange.
+		///
+		/// Like the other price getters, counts a read of the pair in [`PairReads`], adding a read
+		/// and a write to the weight of the caller.
+		pub fn get_price(pair_id: PairId, exchange_id: ExchangeId) -> Option<(Price, u64)> {
+			Self::note_read(pair_id);
+			PriceData::<T>::get(pair_id, exchange_id)
+				.filter(|(_, timestamp)| Self::is_fresh_from(exchange_id, *timestamp))
+		}
+
+		/// Price of `pair_id` aggregated across exchanges at the end of the last block it was
+		/// submitted in.
+		pub fn get_aggregated_price(pair_id: PairId) -> Option<PriceAggregate> {
+			Self::note_read(pair_id);
+			AggregatedPrice::<T>::get(pair_id)
+		}
+
+		/// The cheapest and the dearest exchange of `pair_id` at its last aggregation, with their
+		/// spread, unless one of their prices is older than [`Config::MaxPriceAge`].
+		pub fn best_venues(pair_id: PairId) -> Option<BestVenues> {
+			Self::note_read(pair_id);
+			PairVenues::<T>::get(pair_id).filter(|venues| {
+				Self::is_fresh(venues.ask.timestamp) && Self::is_fresh(venues.bid.timestamp)
+			})
+		}
+
+		/// The spread `pair_id` offered to arbitrage at its last aggregation, unless one of its
+		/// prices is older than [`Config::MaxPriceAge`].
+		pub fn arbitrage_opportunity(pair_id: PairId) -> Option<ArbitrageOpportunity> {
+			ArbitrageOpportunities::<T>::get(pair_id)
+				.filter(|opportunity| Self::is_fresh(opportunity.timestamp))
+		}
+
+		/// Health of the oracle as a whole: its pairs by freshness, its enabled exchanges by
+		/// whether they have a fresh price, the time of the newest aggregated price and the
+		/// number of open [`ArbitrageOpportunities`].
+		///
+		/// Reads every tracked pair and exchange price, so it is meant for runtime API calls, not
+		/// for other pallets.
+		pub fn oracle_status() -> OracleStatus {
+			let mut status = OracleStatus::default();
+			for pair_id in TrackedPairs::<T>::iter_keys() {
+				status.pairs_total.saturating_inc();
+				let aggregate = AggregatedPrice::<T>::get(pair_id);
+				if let Some(aggregate) = aggregate {
+					status.last_update = status.last_update.max(Some(aggregate.timestamp));
+				}
+				if PausedPairs::<T>::contains_key(pair_id) {
+					status.pairs_paused.saturating_inc();
+				} else if aggregate.is_some_and(|aggregate| Self::is_fresh(aggregate.timestamp)) {
+					status.pairs_fresh.saturating_inc();
+				} else {
+					status.pairs_stale.saturating_inc();
+				}
+				if Self::arbitrage_opportunity(pair_id).is_some() {
+					status.open_opportunities.saturating_inc();
+				}
+			}
+			let fresh: BTreeSet<_> = PriceData::<T>::iter()
+				.filter(|(_, exchange_id, (_, timestamp))| {
+					Self::is_fresh_from(*exchange_id, *timestamp)
+				})
+				.map(|(_, exchange_id, _)| exchange_id)
+				.collect();
+			for (exchange_id, exchange) in Exchanges::<T>::iter() {
+				let healthy = fresh.contains(&exchange_id) && !Self::is_quarantined(exchange_id);
+				match (exchange.enabled, healthy) {
+					(false, _) => {},
+					(true, true) => status.exchanges_healthy.saturating_inc(),
+					(true, false) => status.exchanges_unhealthy.saturating_inc(),
+				}
+			}
+			status
+		}
+
+		/// The latest [`AggregatedPrice`] of every tracked pair, read at the cost of
+		/// [`WeightInfo::latest_aggregates`].
+		///
+		/// Unlike the getters of a single pair, reads through it aren't counted in [`PairReads`].
+		pub fn latest_aggregates() -> BoundedBTreeMap<PairId, PriceAggregate, T::MaxPairs> {
+			LatestAggregates::<T>::get()
+		}
+
+		/// The latest [`AggregatedPrice`] of each of `pair_ids`, in order, `None` for a pair
+		/// without one.
+		///
+		/// All aggregates come from one read of [`LatestAggregates`], so they are consistent with
+		/// each other, e.g. for the cross rates of a portfolio. Costs
+		/// [`WeightInfo::latest_aggregates`] and, like it, isn't counted in [`PairReads`].
+		pub fn read_many(pair_ids: &[PairId]) -> Vec<Option<PriceAggregate>> {
+			let latest = LatestAggregates::<T>::get();
+			pair_ids.iter().map(|pair_id| latest.get(pair_id).copied()).collect()
+		}
+
+		/// Median price of `pair_id` across exchanges with the observation time of its newest
+		/// source, as `(price, timestamp)`, unless that is older than [`Config::MaxPriceAge`] or
+		/// the pair is still in [`Bootstrapping`].
+		///
+		/// Costs [`WeightInfo::latest_price`].
+		pub fn latest_price(pair_id: PairId) -> Option<(Price, u64)> {
+			Self::note_read(pair_id);
+			Self::fresh_price(pair_id)
+		}
+
+		/// [`Pallet::latest_price`] without counting the read in [`PairReads`].
+		fn fresh_price(pair_id: PairId) -> Option<(Price, u64)> {
+			if Bootstrapping::<T>::contains_key(pair_id) {
+				return None
+			}
+			AggregatedPrice::<T>::get(pair_id)
+				.filter(|aggregate| Self::is_fresh(aggregate.timestamp))
+				.map(|aggregate| (aggregate.median, aggregate.timestamp))
+		}
+
+		/// Price of `base` in `quote` by the tickers of their tokens, e.g. `b"ETH"` and `b"BTC"`,
+		/// as `(price, timestamp)`: the [`Pallet::latest_price`] of the pair if it is tracked,
+		/// otherwise the inverse of the price of `quote` in `base`, or the cross of the prices of
+		/// both tokens in a third one they are tracked against, e.g. ETH/BTC from ETH/USD and
+		/// BTC/USD. Every price a derived price is computed from must be fresh, and the derived
+		/// price has the observation time of the oldest.
+		///
+		/// Looking for a cross reads at most [`Config::MaxPairs`] tracked pairs, and the prices of
+		/// both tokens in each token `base` is tracked against. None of the prices read count in
+		/// [`PairReads`].
+		pub fn get_derived_price(base: &[u8], quote: &[u8]) -> Option<(Price, u64)> {
+			if let Some(price) = Self::direct_or_inverse_price(base, quote) {
+				return Some(price)
+			}
+			TrackedPairs::<T>::iter_values()
+				.take(T::MaxPairs::get() as usize)
+				.filter_map(|pair| match (&pair.base[..], &pair.quote[..]) {
+					(token, other) | (other, token) if token == base && other != quote =>
+						Some(other.to_vec()),
+					_ => None,
+				})
+				.find_map(|via| {
+					let (base_price, base_time) = Self::direct_or_inverse_price(base, &via)?;
+					let (quote_price, quote_time) = Self::direct_or_inverse_price(quote, &via)?;
+					let price = fixed::quotient(base_price, quote_price).ok()?;
+					Some((price, base_time.min(quote_time)))
+				})
+		}
+
+		/// [`Pallet::fresh_price`] of the pair of `base` in `quote`, or the inverse of that of the
+		/// pair of `quote` in `base`.
+		fn direct_or_inverse_price(base: &[u8], quote: &[u8]) -> Option<(Price, u64)> {
+			if let Some(price) = Self::fresh_price(T::PairHasher::pair_id(base, quote)) {
+				return Some(price)
+			}
+			let (price, timestamp) = Self::fresh_price(T::PairHasher::pair_id(quote, base))?;
+			Some((fixed::quotient(fixed::PRICE_UNIT, price).ok()?, timestamp))
+		}
+
+		/// Number of reads of the prices of `pair_id` in the current and the previous
+		/// [`Config::ReadPeriod`].
+		pub fn pair_reads(pair_id: PairId) -> ReadCounter {
+			let block: u32 = frame_system::Pallet::<T>::block_number().unique_saturated_into();
+			let period = block / T::ReadPeriod::get();
+			let reads = PairReads::<T>::get(pair_id);
+			match period.saturating_sub(reads.period) {
+				0 => reads,
+				1 => ReadCounter { period, count: 0, previous: reads.count },
+				_ => ReadCounter { period, count: 0, previous: 0 },
+			}
+		}
+
+		/// Count a read of the prices of `pair_id` in [`PairReads`].
+		pub(crate) fn note_read(pair_id: PairId) {
+			let mut reads = Self::pair_reads(pair_id);
+			reads.count.saturating_inc();
+			PairReads::<T>::insert(pair_id, reads);
+		}
+
+		/// Whether `pair_id` has a [`FreshnessSla`] and its aggregated price meets it, the one
+		/// health signal consumers gate their operations on. A pair without an SLA promises
+		/// nothing, so it doesn't meet one.
+		pub fn meets_sla(pair_id: PairId) -> bool {
+			PairSlas::<T>::get(pair_id).is_some_and(|sla| Self::sla_breach(pair_id, &sla).is_none())
+		}
+
+		/// How the aggregated price of `pair_id` falls short of `sla`, if it does.
+		fn sla_breach(pair_id: PairId, sla: &FreshnessSla) -> Option<SlaBreach> {
+			let Some(aggregate) = AggregatedPrice::<T>::get(pair_id) else {
+				return Some(SlaBreach::NoPrice)
+			};
+			let now = T::UnixTime::now().as_millis() as u64;
+			if now.saturating_sub(aggregate.timestamp) > sla.max_staleness {
+				Some(SlaBreach::Stale)
+			} else if aggregate.sources < sla.min_sources {
+				Some(SlaBreach::TooFewSources)
+			} else if PairVenues::<T>::get(pair_id)
+				.is_some_and(|venues| venues.spread_bps > sla.max_spread_bps)
+			{
+				Some(SlaBreach::SpreadTooWide)
+			} else {
+				None
+			}
+		}
+
+		/// Keep the prices of `pair_id` from being served until it completes its bootstrap, if
+		/// the runtime bootstraps new pairs.
+		fn start_bootstrap(pair_id: PairId) {
+			if T::BootstrapIntervals::get() > 0 {
+				Bootstrapping::<T>::insert(pair_id, BootstrapProgress::default());
+			}
+		}
+
+		/// Put `aggregate`, the new aggregated price of `pair_id`, into [`LatestAggregates`].
+		///
+		/// Only tracked pairs are in the map and at most [`Config::MaxPairs`] are tracked, but a
+		/// lowered bound can leave it full: the stalest aggregate, the lowest pair id among equally
+		/// stale ones, is then evicted with [`Event::LatestAggregateEvicted`].
+		fn note_latest(pair_id: PairId, aggregate: PriceAggregate) {
+			let evicted = LatestAggregates::<T>::mutate(|latest| {
+				let (pair_id, aggregate) = latest.try_insert(pair_id, aggregate).err()?;
+				let (&stalest, _) =
+					latest.iter().min_by_key(|(id, stored)| (stored.timestamp, **id))?;
+				latest.remove(&stalest);
+				let _ = latest.try_insert(pair_id, aggregate);
+				Some(stalest)
+			});
+			if let Some(pair_id) = evicted {
+				Self::deposit_event(Event::LatestAggregateEvicted { pair_id });
+			}
+		}
+
+		/// Judge the update interval that ended for each pair in [`Bootstrapping`], serving the
+		/// prices of the pairs that completed their bootstrap. Returns the number of pairs
+		/// judged.
+		///
+		/// An interval without a new aggregated price doesn't count either way. One whose
+		/// aggregated price comes from a single exchange or moved further than
+		/// [`Config::BootstrapMaxMoveBps`] starts the count over.
+		pub fn advance_bootstraps() -> u32 {
+			let mut judged = 0u32;
+			for (pair_id, mut progress) in Bootstrapping::<T>::iter() {
+				judged.saturating_inc();
+				let Some(aggregate) = AggregatedPrice::<T>::get(pair_id) else { continue };
+				if progress.last.is_some_and(|(_, timestamp)| aggregate.timestamp <= timestamp) {
+					continue
+				}
+				let consistent = aggregate.sources > 1 &&
+					progress.last.is_none_or(|(median, _)| {
+						aggregation::deviation_bps(aggregate.median, median) <=
+							T::BootstrapMaxMoveBps::get()
+					});
+				progress.intervals = match consistent {
+					true => progress.intervals.saturating_add(1),
+					false => 0,
+				};
+				progress.last = Some((aggregate.median, aggregate.timestamp));
+				if progress.intervals < T::BootstrapIntervals::get() {
+					Bootstrapping::<T>::insert(pair_id, progress);
+					continue
+				}
+				Bootstrapping::<T>::remove(pair_id);
+				Self::note_latest(pair_id, aggregate);
+				Self::deposit_event(Event::BootstrapCompleted { pair_id });
+			}
+			judged
+		}
+
+		/// Warn of the retirement of the pairs in [`DeprecatedPairs`], removing the ones retired
+		/// before `block_number`. Returns the number of pairs warned of and removed.
+		pub fn advance_deprecations(block_number: BlockNumberFor<T>) -> (u32, u32) {
+			let (mut warned, mut removed) = (0u32, 0u32);
+			for (pair_id, after_block) in DeprecatedPairs::<T>::iter().collect::<Vec<_>>() {
+				if block_number <= after_block {
+					warned.saturating_inc();
+					Self::deposit_event(Event::PairDeprecationPending { pair_id, after_block });
+					continue
+				}
+				removed.saturating_inc();
+				Self::untrack_pair(pair_id);
+				Self::deposit_event(Event::PairRemoved { pair_id });
+			}
+			(warned, removed)
+		}
+
+		/// Check the pairs in [`PairSlas`] against their SLA, reporting the ones whose breach
+		/// changed since the last check. Returns the number of pairs checked.
+		pub fn check_slas() -> u32 {
+			let mut checked = 0u32;
+			for (pair_id, sla) in PairSlas::<T>::iter() {
+				checked.saturating_inc();
+				let breach = Self::sla_breach(pair_id, &sla);
+				if breach == BreachedSlas::<T>::get(pair_id) {
+					continue
+				}
+				BreachedSlas::<T>::set(pair_id, breach);
+				match breach {
+					Some(breach) => Self::deposit_event(Event::SlaBreached { pair_id, breach }),
+					None => Self::deposit_event(Event::SlaRestored { pair_id }),
+				}
+			}
+			checked
+		}
+
+		/// Time between the oldest and the newest of the latest observations of the chains
+		/// `pair_id` is priced on, in milliseconds, or `None` if it is priced on a single chain.
+		pub fn sample_skew(pair_id: PairId) -> Option<u64> {
+			let timestamps = ChainAggregates::<T>::iter_prefix_values(pair_id)
+				.map(|chain| chain.timestamp)
+				.collect::<Vec<_>>();
+			let (oldest, newest) = (timestamps.iter().min()?, timestamps.iter().max()?);
+			(timestamps.len() > 1).then(|| newest - oldest)
+		}
+
+		/// The per-chain aggregates of `pair_id` its [`AggregatedPrice`] was combined from, by
+		/// chain id.
+		pub fn chain_prices(pair_id: PairId) -> Vec<(ChainId, PriceAggregate)> {
+			let mut chains: Vec<_> = ChainAggregates::<T>::iter_prefix(pair_id).collect();
+			chains.sort_by_key(|(chain_id, _)| *chain_id);
+			chains
+		}
+
+		/// The tracked pairs with their ids.
+		pub fn tracked_pairs() -> Vec<(PairId, PairInfo)> {
+			TrackedPairs::<T>::iter().collect()
+		}
+
+		/// Snapshot of the exchanges, routers and tracked pairs with their settings, for
+		/// [`Pallet::import_registry`] on another chain.
+		pub fn export_registry() -> registry::RegistrySnapshot {
+			let exchanges: Vec<_> = Exchanges::<T>::iter().collect();
+			let routers = exchanges
+				.iter()
+				.filter_map(|(exchange_id, config)| {
+					Routers::<T>::get(config.key()).map(|router| (*exchange_id, router))
+				})
+				.collect();
+			let pairs = TrackedPairs::<T>::iter()
+				.map(|(pair_id, info)| registry::PairSnapshot {
+					info,
+					twap_check: TwapChecks::<T>::get(pair_id),
+					fast_path: FastPaths::<T>::get(pair_id),
+					failure_mode: FailureModes::<T>::get(pair_id),
+					cross_chain_check: CrossChainChecks::<T>::get(pair_id),
+					sla: PairSlas::<T>::get(pair_id),
+					min_notional: LongTailPairs::<T>::get(pair_id),
+					hops: PoolHops::<T>::iter_prefix(pair_id).collect(),
+				})
+				.collect();
+			registry::RegistrySnapshot { exchanges, routers, pairs }
+		}
+
+		/// Check that `snapshot` is a consistent registry, returning the ids of its pairs.
+		fn check_snapshot(snapshot: &registry::RegistrySnapshot) -> Result<Vec<PairId>, Error<T>> {
+			ensure!(
+				snapshot.pairs.len() <= T::MaxPairs::get() as usize,
+				Error::<T>::PairLimitReached
+			);
+			let mut exchange_ids = BTreeSet::new();
+			let mut keys = BTreeSet::new();
+			for (exchange_id, config) in &snapshot.exchanges {
+				Self::ensure_valid_exchange(config)?;
+				ensure!(
+					exchange_ids.insert(*exchange_id) && keys.insert(config.key()),
+					Error::<T>::ExchangeAlreadyRegistered
+				);
+			}
+			ensure!(
+				snapshot
+					.routers
+					.iter()
+					.all(|(exchange_id, _)| exchange_ids.contains(exchange_id)),
+				Error::<T>::UnknownExchange
+			);
+			ensure!(
+				snapshot.routers.iter().all(|(_, router)| *router != EvmAddress::default()),
+				Error::<T>::ZeroAddress
+			);
+			let mut pair_ids = Vec::with_capacity(snapshot.pairs.len());
+			for pair in &snapshot.pairs {
+				Self::ensure_valid_pair(&pair.info)?;
+				ensure!(pair.sla.is_none_or(|sla| sla.is_valid()), Error::<T>::InvalidSla);
+				Self::ensure_pools_on(&pair.info, |exchange_id| {
+					snapshot
+						.exchanges
+						.iter()
+						.find(|(registered, _)| registered == exchange_id)
+						.map(|(_, config)| config.chain)
+				})?;
+				for (exchange_id, hop) in &pair.hops {
+					let (_, exchange) = snapshot
+						.exchanges
+						.iter()
+						.find(|(registered, _)| registered == exchange_id)
+						.ok_or(Error::<T>::UnknownExchange)?;
+					Self::ensure_valid_hop(&pair.info, *exchange_id, exchange.chain, hop)?;
+				}
+				let pair_id = Self::pair_id_of(&pair.info);
+				ensure!(!pair_ids.contains(&pair_id), Error::<T>::PairAlreadyTracked);
+				pair_ids.push(pair_id);
+			}
+			Ok(pair_ids)
+		}
+
+		/// Stop tracking `pair_id` and drop its settings, reads, alerts and subscriptions, and
+		/// its aggregation pending in the block.
+		fn untrack_pair(pair_id: PairId) {
+			TrackedPairs::<T>::remove(pair_id);
+			PendingAggregation::<T>::remove(pair_id);
+			PriceGuards::<T>::remove(pair_id);
+			AggregationDeferrals::<T>::remove(pair_id);
+			PairReads::<T>::remove(pair_id);
+			for alert in Alerts::<T>::take(pair_id) {
+				Self::release_alert_deposit(pair_id, &alert.who);
+			}
+			PriceSubscriptions::<T>::remove(pair_id);
+			TwapChecks::<T>::remove(pair_id);
+			FastPaths::<T>::remove(pair_id);
+			FastPathUntil::<T>::remove(pair_id);
+			FailureModes::<T>::remove(pair_id);
+			PausedPairs::<T>::remove(pair_id);
+			CrossChainChecks::<T>::remove(pair_id);
+			PairSlas::<T>::remove(pair_id);
+			BreachedSlas::<T>::remove(pair_id);
+			Bootstrapping::<T>::remove(pair_id);
+			PairVenues::<T>::remove(pair_id);
+			ArbitrageOpportunities::<T>::remove(pair_id);
+			let _ = ChainAggregates::<T>::clear_prefix(pair_id, u32::MAX, None);
+			let _ = SourceBlocks::<T>::clear_prefix(pair_id, u32::MAX, None);
+			LongTailPairs::<T>::remove(pair_id);
+			LowQualityPairs::<T>::remove(pair_id);
+			let _ = PoolNotionals::<T>::clear_prefix(pair_id, u32::MAX, None);
+			let _ = PoolHops::<T>::clear_prefix(pair_id, u32::MAX, None);
+			DeprecatedPairs::<T>::remove(pair_id);
+			LatestAggregates::<T>::mutate(|latest| latest.remove(&pair_id));
+		}
+
+		/// Storage key of `pair` under [`Config::PairHasher`].
+		pub fn pair_id_of(pair: &PairInfo) -> PairId {
+			pair.id_with::<T::PairHasher>()
+		}
+
+		/// Range prices of `pair` are accepted in: its static bounds, narrowed to the allowed
+		/// deviation around the TWAP if the pair has dynamic bounds and a price history. Long-tail
+		/// pairs allow at least [`Config::LongTailDeviation`].
+		pub fn price_bounds(pair: &PairInfo) -> RangeInclusive<Price> {
+			let (min, max) = (pair.min_price, pair.max_price);
+			let pair_id = Self::pair_id_of(pair);
+			let twap = pair
+				.dynamic_bounds
+				.and_then(|dynamic| Some((dynamic, Self::twap(pair_id, dynamic.window.into())?)));
+			match twap {
+				Some((dynamic, twap)) => {
+					let deviation = match LongTailPairs::<T>::contains_key(pair_id) {
+						true => dynamic.deviation.max(T::LongTailDeviation::get()),
+						false => dynamic.deviation,
+					};
+					let band = deviation.mul_floor(twap);
+					min.max(twap.saturating_sub(band))..=max.min(twap.saturating_add(band))
+				},
+				None => min..=max,
+			}
+		}
+
+		/// Time-weighted average of the aggregated price of `pair_id` over the last `window_blocks`
+		/// blocks, as far back as [`PriceHistory`] reaches.
+		///
+		/// Costs [`WeightInfo::twap`].
+		pub fn twap(pair_id: PairId, window_blocks: BlockNumberFor<T>) -> Option<Price> {
+			let block = |number: BlockNumberFor<T>| -> u64 { number.unique_saturated_into() };
+			let now = frame_system::Pallet::<T>::block_number();
+			let history: Vec<_> = PriceHistory::<T>::get(pair_id)
+				.iter()
+				.map(|(price, number)| (price, block(number)))
+				.collect();
+			aggregation::twap(
+				&history,
+				block(now.saturating_sub(window_blocks)),
+				block(now),
+				T::Rounding::get().rounding,
+			)
+		}
+
+		/// Page of the aggregated prices of `pair_id` in [`PriceHistory`] aggregated in blocks
+		/// `from..=to`, oldest first.
+		///
+		/// Consecutive entries are at least `step` blocks apart; a zero `step` returns every
+		/// entry. At most [`MAX_HISTORY_PAGE`] entries are returned; the next page starts `step`
+		/// blocks after the last entry.
+		pub fn history(
+			pair_id: PairId,
+			from: BlockNumberFor<T>,
+			to: BlockNumberFor<T>,
+			step: BlockNumberFor<T>,
+		) -> Vec<(Price, BlockNumberFor<T>)> {
+			let mut next = from;
+			PriceHistory::<T>::get(pair_id)
+				.iter()
+				.filter(|(_, block)| {
+					if *block < next || *block > to {
+						return false
+					}
+					next = block.saturating_add(step);
+					true
+				})
+				.take(MAX_HISTORY_PAGE as usize)
+				.collect()
+		}
+
+		/// Keys of the prices of `pair_id` accepted in blocks `from..=to` in the offchain
+		/// database, see [`price_key`], with their blocks, oldest first.
+		///
+		/// Every block lists the key of every exchange the tracked pair has a pool or a
+		/// [`PoolHops`] entry on now, so callers must expect keys without a value: the exchange
+		/// had no price accepted in the block, or the block was imported before the prices were
+		/// indexed or without offchain indexing. Only whole blocks are returned, at most
+		/// [`MAX_HISTORY_PAGE`] keys; the next page starts the block after the last key.
+		pub fn indexed_price_keys(
+			pair_id: PairId,
+			from: BlockNumberFor<T>,
+			to: BlockNumberFor<T>,
+		) -> Vec<(BlockNumberFor<T>, Vec<u8>)> {
+			let Some(pair) = TrackedPairs::<T>::get(pair_id) else { return Vec::new() };
+			let exchanges: BTreeSet<_> = pair
+				.pools
+				.iter()
+				.map(|(exchange_id, _)| *exchange_id)
+				.chain(PoolHops::<T>::iter_key_prefix(pair_id))
+				.collect();
+			if exchanges.is_empty() {
+				return Vec::new()
+			}
+			let blocks = (MAX_HISTORY_PAGE as usize / exchanges.len()).max(1);
+			let mut keys = Vec::new();
+			let mut block = from;
+			for _ in 0..blocks {
+				if block > to {
+					break
+				}
+				keys.extend(
+					exchanges
+						.iter()
+						.map(|exchange_id| (block, price_key(&pair_id, block, *exchange_id))),
+				);
+				if block == to {
+					break
+				}
+				block.saturating_inc();
+			}
+			keys
+		}
+
+		/// Latest prices of `pair_id` from every exchange, as `(exchange_id, price, timestamp)`,
+		/// leaving out prices older than the maximum price age of their exchange.
+		///
+		/// Reads a price of every exchange, which costs [`WeightInfo::get_prices`] with
+		/// `u32::from(ExchangeId::MAX) + 1`. Dispatchables should use [`Pallet::get_prices`]
+		/// instead.
+		pub fn get_all_prices(pair_id: PairId) -> Vec<(ExchangeId, Price, u64)> {
+			Self::get_prices(pair_id, u32::from(ExchangeId::MAX) + 1)
+		}
+
+		/// Like [`Pallet::get_all_prices`], but reading at most `limit` exchange prices, fresh or
+		/// not, so it costs at most [`WeightInfo::get_prices`] with `limit`.
+		pub fn get_prices(pair_id: PairId, limit: u32) -> Vec<(ExchangeId, Price, u64)> {
+			Self::note_read(pair_id);
+			PriceData::<T>::iter_prefix(pair_id)
+				.take(limit as usize)
+				.filter(|(exchange_id, (_, timestamp))| {
+					Self::is_fresh_from(*exchange_id, *timestamp)
+				})
+				.map(|(exchange_id, (price, timestamp))| (exchange_id, price, timestamp))
+				.collect()
+		}
+
+		/// Like [`Pallet::get_prices`], with the notional of the pool each price was read from,
+		/// leaving out the pools known to hold less than `min_liquidity` whole USD. Pools of
+		/// unknown notional are only left out if `min_liquidity` isn't zero.
+		pub fn liquid_prices(
+			pair_id: PairId,
+			min_liquidity: u128,
+			limit: u32,
+		) -> Vec<(ExchangeId, LiquidPrice)> {
+			Self::get_prices(pair_id, limit)
+				.into_iter()
+				.map(|(exchange_id, price, timestamp)| {
+					let liquidity = PoolNotionals::<T>::get(pair_id, exchange_id);
+					(exchange_id, LiquidPrice { price, liquidity, timestamp })
+				})
+				.filter(|(_, quote)| min_liquidity == 0 || quote.liquidity >= Some(min_liquidity))
+				.collect()
+		}
+	}
+}
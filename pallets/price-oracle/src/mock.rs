@@ -0,0 +1,341 @@
+use crate as pallet_price_oracle;
+use core::{cell::Cell, time::Duration};
+use frame_support::{
+	derive_impl,
+	traits::{ConstU128, ConstU32, ConstU64, SortedMembers, UnixTime},
+	weights::Weight,
+};
+use frame_system::{offchain::AppCrypto, EnsureRoot, EnsureSigned};
+use sp_core::H256;
+use sp_runtime::{
+	generic,
+	testing::{Header, TestSignature, UintAuthorityId},
+	BuildStorage, Permill,
+};
+
+pub type Extrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test, TestSignature>;
+type Block = generic::Block<Header, Extrinsic>;
+
+#[frame_support::runtime]
+mod runtime {
+	#[runtime::runtime]
+	#[runtime::derive(
+		RuntimeCall,
+		RuntimeEvent,
+		RuntimeError,
+		RuntimeOrigin,
+		RuntimeFreezeReason,
+		RuntimeHoldReason,
+		RuntimeSlashReason,
+		RuntimeLockId,
+		RuntimeTask,
+		RuntimeViewFunction
+	)]
+	pub struct Test;
+
+	#[runtime::pallet_index(0)]
+	pub type System = frame_system;
+
+	#[runtime::pallet_index(1)]
+	pub type PriceOracle = pallet_price_oracle;
+
+	#[runtime::pallet_index(2)]
+	pub type Balances = pallet_balances;
+}
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+	type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+}
+
+impl<LocalCall> frame_system::offchain::CreateTransactionBase<LocalCall> for Test
+where
+	RuntimeCall: From<LocalCall>,
+{
+	type RuntimeCall = RuntimeCall;
+	type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> frame_system::offchain::CreateBare<LocalCall> for Test
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_bare(call: Self::RuntimeCall) -> Self::Extrinsic {
+		Extrinsic::new_bare(call)
+	}
+}
+
+impl frame_system::offchain::SigningTypes for Test {
+	type Public = UintAuthorityId;
+	type Signature = TestSignature;
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Test
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_signed_transaction<C: AppCrypto<Self::Public, Self::Signature>>(
+		call: RuntimeCall,
+		public: UintAuthorityId,
+		account: u64,
+		_nonce: u32,
+	) -> Option<Extrinsic> {
+		let signature = C::sign(&codec::Encode::encode(&call), public)?;
+		Some(Extrinsic::new_signed(call, account, signature, ()))
+	}
+}
+
+/// The oracle committee of [`CommitteeMembers`].
+pub struct MockCommittee;
+
+impl SortedMembers<u64> for MockCommittee {
+	fn sorted_members() -> Vec<u64> {
+		let mut members = CommitteeMembers::get();
+		members.sort();
+		members
+	}
+}
+
+/// Records the reported contributions in [`Contributions`] and submissions in [`Submissions`].
+pub struct MockReporter;
+
+impl pallet_oracle_rewards::ContributionReporter<u64> for MockReporter {
+	fn note_contribution(who: &u64, points: u32) {
+		Contributions::mutate(|c| c.push((*who, points)));
+	}
+
+	fn note_submission(who: &u64) {
+		Submissions::mutate(|s| s.push(*who));
+	}
+
+	fn note_contribution_weight() -> Weight {
+		Weight::zero()
+	}
+
+	fn note_submission_weight() -> Weight {
+		Weight::zero()
+	}
+}
+
+/// Messenger of the mock runtime, recording the prices sent in [`SentPrices`] unless
+/// [`MessengerDown`].
+pub struct MockMessenger;
+
+impl crate::PriceMessenger<u64> for MockMessenger {
+	fn send_price(
+		dest: &u64,
+		query_id: u64,
+		pair_id: crate::PairId,
+		price: crate::ProvidedPrice,
+	) -> sp_runtime::DispatchResult {
+		if MessengerDown::get() {
+			return Err(sp_runtime::DispatchError::Unavailable)
+		}
+		SentPrices::mutate(|sent| sent.push((*dest, query_id, pair_id, price.price)));
+		Ok(())
+	}
+
+	fn send_price_weight() -> Weight {
+		Weight::zero()
+	}
+}
+
+/// Light client of the mock runtime, tracking the [`StateRoots`].
+pub struct MockLightClient;
+
+impl crate::proofs::LightClient for MockLightClient {
+	fn state_root(chain_id: crate::ChainId, block_number: u64) -> Option<H256> {
+		StateRoots::get()
+			.into_iter()
+			.find(|(chain, block, _)| (*chain, *block) == (chain_id, block_number))
+			.map(|(.., root)| root)
+	}
+
+	fn state_root_weight() -> Weight {
+		Weight::zero()
+	}
+}
+
+/// Operator keys of the mock runtime, made available with [`UintAuthorityId::set_all_keys`].
+pub struct TestAuthId;
+
+impl AppCrypto<UintAuthorityId, TestSignature> for TestAuthId {
+	type RuntimeAppPublic = UintAuthorityId;
+	type GenericPublic = UintAuthorityId;
+	type GenericSignature = TestSignature;
+}
+
+pub const UPDATE_INTERVAL: u32 = 3;
+
+/// Number of blocks pair reads are counted over in the mock runtime.
+pub const READ_PERIOD: u32 = 10;
+
+/// Maximum number of pairs tracked in the mock runtime.
+pub const MAX_PAIRS: u32 = 8;
+
+/// Registered operator of the default genesis.
+pub const OPERATOR: u64 = 7;
+
+frame_support::parameter_types! {
+	pub static AcceptUnsigned: bool = true;
+	pub static CommitteeMembers: Vec<u64> = vec![];
+	pub static Contributions: Vec<(u64, u32)> = vec![];
+	pub static Submissions: Vec<u64> = vec![];
+	pub static MaxOcwMillis: u64 = 30_000;
+	pub static ChainTimeTolerance: u64 = 0;
+	pub static MaxExchangesPerBlock: u8 = 1;
+	pub static Rounding: crate::RoundingPolicy = crate::RoundingPolicy::FLOOR;
+	pub static BootstrapIntervals: u32 = 0;
+	pub static SummarizeSourceEvents: bool = false;
+	pub static SamplingJitter: u32 = 0;
+	pub static ReportLiquidity: bool = false;
+	pub static BatchRpcCalls: bool = false;
+	pub static MaxReadDelay: u32 = 0;
+	pub static SubmissionDeviationBps: u32 = 0;
+	pub static OutlierMads: u32 = 0;
+	pub static QuarantineBps: u32 = 10_000;
+	pub static ArbitrageGasUnits: u64 = 0;
+	pub static RevealWindow: u32 = 0;
+	pub static OperatorBond: u64 = 0;
+	pub static AlertDeposit: u64 = 0;
+	pub static MessengerDown: bool = false;
+	pub static SentPrices: Vec<(u64, u64, crate::PairId, crate::Price)> = vec![];
+	pub static StateRoots: Vec<(crate::ChainId, u64, H256)> = vec![];
+	pub const LongTailDeviation: Permill = Permill::from_percent(20);
+	pub const MisbehaviorSlash: Permill = Permill::from_percent(50);
+	pub const ReporterReward: Permill = Permill::from_percent(10);
+}
+
+/// Maximum price age of the mock runtime, in milliseconds.
+pub const MAX_PRICE_AGE: u64 = 600_000;
+
+/// Maximum submission delay of the mock runtime, in milliseconds.
+pub const MAX_SUBMISSION_DELAY: u64 = 60_000;
+
+thread_local! {
+	static NOW: Cell<u64> = const { Cell::new(0) };
+}
+
+/// On-chain time of the mock runtime, set with [`set_now`].
+pub struct MockTime;
+
+impl UnixTime for MockTime {
+	fn now() -> Duration {
+		Duration::from_millis(NOW.get())
+	}
+}
+
+/// Set the on-chain time, in unix milliseconds.
+pub fn set_now(millis: u64) {
+	NOW.set(millis);
+}
+
+impl pallet_price_oracle::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type RegistryOrigin = EnsureRoot<u64>;
+	type AuthorityId = TestAuthId;
+	type AcceptUnsigned = AcceptUnsigned;
+	type MaxOperators = ConstU32<2>;
+	type MaxPairs = ConstU32<MAX_PAIRS>;
+	type Committee = MockCommittee;
+	type ContributionReporter = MockReporter;
+	type Currency = Balances;
+	type OperatorBond = OperatorBond;
+	type SlashDeviationBps = ConstU32<1_000>;
+	type MisbehaviorSlash = MisbehaviorSlash;
+	type ReporterReward = ReporterReward;
+	type UpdateInterval = ConstU32<UPDATE_INTERVAL>;
+	type HttpTimeout = ConstU64<1_000>;
+	type MaxExchangesPerBlock = MaxExchangesPerBlock;
+	type MaxOcwMillis = MaxOcwMillis;
+	type ChainTimeTolerance = ChainTimeTolerance;
+	type MaxPriceAge = ConstU64<MAX_PRICE_AGE>;
+	type MaxHistory = ConstU32<4>;
+	type UnixTime = MockTime;
+	type MaxSubmissionDelay = ConstU64<MAX_SUBMISSION_DELAY>;
+	type FrozenPriceThreshold = ConstU32<3>;
+	type PairHasher = crate::Blake2PairHasher;
+	type ReadPeriod = ConstU32<READ_PERIOD>;
+	type MaxAlertsPerPair = ConstU32<2>;
+	type AlertDeposit = AlertDeposit;
+	// Accounts stand in for the locations of sibling parachains.
+	type Subscriber = u64;
+	type SubscriberOrigin = EnsureSigned<u64>;
+	type PriceMessenger = MockMessenger;
+	type MaxSubscriptionsPerPair = ConstU32<2>;
+	type FastPathBudget = ConstU32<1>;
+	type Rounding = Rounding;
+	type BootstrapIntervals = BootstrapIntervals;
+	type BootstrapMaxMoveBps = ConstU32<500>;
+	type SummarizeSourceEvents = SummarizeSourceEvents;
+	type MinSpreadBps = ConstU32<1_000>;
+	type ArbitrageGasUnits = ArbitrageGasUnits;
+	type ArbitrageTradeSize = ConstU128<{ 10_000 * crate::fixed::PRICE_UNIT }>;
+	type MaxReferenceDeviationBps = ConstU32<500>;
+	type SamplingJitter = SamplingJitter;
+	type LongTailDeviation = LongTailDeviation;
+	type ReportLiquidity = ReportLiquidity;
+	type BatchRpcCalls = BatchRpcCalls;
+	type MaxReadDelay = MaxReadDelay;
+	type SubmissionDeviationBps = SubmissionDeviationBps;
+	type SubmissionHeartbeat = ConstU64<{ MAX_PRICE_AGE / 2 }>;
+	type ConsensusBps = ConstU32<100>;
+	type OutlierMads = OutlierMads;
+	type QuarantineBps = QuarantineBps;
+	type QuarantineMinSamples = ConstU32<4>;
+	type QuarantinePeriod = ConstU32<10>;
+	type RevealWindow = RevealWindow;
+	type LightClient = MockLightClient;
+	type MaxAggregationDeferrals = ConstU32<2>;
+	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = MockBenchmarkHelper;
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct MockBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::BenchmarkHelper for MockBenchmarkHelper {
+	fn set_now(millis: u64) {
+		set_now(millis);
+	}
+
+	fn set_state_root(chain_id: crate::ChainId, block_number: u64, state_root: H256) {
+		StateRoots::mutate(|roots| {
+			roots.retain(|(chain, block, _)| (*chain, *block) != (chain_id, block_number));
+			roots.push((chain_id, block_number, state_root));
+		});
+	}
+}
+
+/// The ETH/USD and SOL/USD pairs of [`crate::registry::default_pairs`] the tests are written
+/// against, tracked by [`new_test_ext`].
+pub fn genesis_pairs() -> Vec<crate::PairInfo> {
+	crate::registry::default_pairs()[..2].to_vec()
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	new_test_ext_with(crate::GenesisConfig {
+		exchanges: crate::registry::default_exchanges(),
+		pairs: genesis_pairs(),
+		rpc_endpoints: crate::registry::default_rpc_endpoints(),
+		operators: vec![OPERATOR],
+		..Default::default()
+	})
+}
+
+pub fn new_test_ext_with(genesis: crate::GenesisConfig<Test>) -> sp_io::TestExternalities {
+	let t = RuntimeGenesisConfig { price_oracle: genesis, ..Default::default() }
+		.build_storage()
+		.unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,160 @@
+//! Accountability of the operators signing prices.
+//!
+//! Operators are registered in [`Operators`] with a bond reserved from their account, or are
+//! members of the [`Config::Committee`]. Each of their accepted prices is kept in
+//! [`SignedSubmissions`] for a while, so anyone can report one that deviates from the median it
+//! was aggregated into with [`Pallet::report_misbehavior`] and have a share of the bond slashed
+//! for it.
+//!
+//! With [`Config::RevealWindow`] the offchain worker doesn't submit prices in the clear: it
+//! commits to each price with [`Pallet::commit_price`], keeps the reveal under
+//! [`PENDING_REVEALS_KEY`] and submits it with [`Pallet::reveal_price`] in its next run, so the
+//! prices can't be front-run from the transaction pool.
+
+use crate::{
+	exchanges::ExchangeId, Call, Config, Error, Event, OperatorBonds, Operators, PairId, Pallet,
+	PriceData, SignedSubmissions, PENDING_REVEALS_KEY,
+};
+use alloc::vec::Vec;
+use codec::Encode;
+use frame_support::{
+	ensure,
+	traits::{Currency, Get, Imbalance, ReservableCurrency, SortedMembers},
+};
+use frame_system::{offchain::Signer, pallet_prelude::BlockNumberFor};
+use pallet_oracle_rewards::ContributionReporter;
+use sp_runtime::{
+	offchain::storage::StorageValueRef,
+	traits::{Saturating, UniqueSaturatedInto, Zero},
+	DispatchResult,
+};
+
+/// Reveals kept under [`PENDING_REVEALS_KEY`], with the block of the run that committed to them.
+type PendingReveals<T> = Vec<(u32, Call<T>)>;
+
+impl<T: Config> Pallet<T> {
+	/// With [`Config::RevealWindow`], the [`Pallet::commit_price`] call committing to the
+	/// price of the signed submission `call` of the run in `block`, whose reveal is stored
+	/// under [`PENDING_REVEALS_KEY`] for the next run. Any other call is submitted as it is.
+	pub(crate) fn commit_to(call: Call<T>, block: u32) -> Call<T> {
+		if T::RevealWindow::get() == 0 {
+			return call
+		}
+		let Call::submit_price_signed {
+			pair_hash,
+			exchange_id,
+			price,
+			exponent,
+			timestamp,
+			source_block,
+			notional,
+		} = call
+		else {
+			return call
+		};
+		let salt = sp_io::offchain::random_seed();
+		let observation = (timestamp, source_block, notional);
+		let commitment = Self::price_commitment(price, exponent, observation, &salt);
+		let reveal = Call::reveal_price {
+			pair_hash,
+			exchange_id,
+			price,
+			exponent,
+			timestamp,
+			source_block,
+			notional,
+			salt,
+		};
+		let _ = StorageValueRef::persistent(PENDING_REVEALS_KEY).mutate(
+			|pending: Result<Option<PendingReveals<T>>, _>| {
+				let mut pending = pending.ok().flatten().unwrap_or_default();
+				pending.push((block, reveal));
+				Ok::<_, ()>(pending)
+			},
+		);
+		Call::commit_price { pair_hash, exchange_id, commitment }
+	}
+
+	/// Submit the reveals under [`PENDING_REVEALS_KEY`] of the prices committed to before
+	/// `block_number`, dropping those whose commitment is too old to be revealed.
+	pub(crate) fn reveal_prices(block_number: BlockNumberFor<T>) {
+		let window = T::RevealWindow::get();
+		if window == 0 {
+			return
+		}
+		let now: u32 = block_number.unique_saturated_into();
+		let pending = StorageValueRef::persistent(PENDING_REVEALS_KEY);
+		let Ok(Some(reveals)) = pending.get::<PendingReveals<T>>() else { return };
+		let (due, later): (Vec<_>, Vec<_>) =
+			reveals.into_iter().partition(|(block, _)| *block < now);
+		pending.set(&later);
+
+		let signer = Signer::<T, T::AuthorityId>::any_account();
+		for (block, reveal) in due {
+			if now <= block.saturating_add(window) {
+				Self::submit(&signer, reveal);
+			}
+		}
+	}
+
+	/// Credit `who` for its price of `pair_id` on `exchange_id` just accepted, and keep the
+	/// price in [`SignedSubmissions`] for [`Pallet::report_misbehavior`].
+	pub(crate) fn note_signed_price(who: &T::AccountId, pair_id: PairId, exchange_id: ExchangeId) {
+		T::ContributionReporter::note_contribution(who, 1);
+		T::ContributionReporter::note_submission(who);
+		if let Some((price, _)) = PriceData::<T>::get(pair_id, exchange_id) {
+			let now = frame_system::Pallet::<T>::block_number();
+			SignedSubmissions::<T>::insert(who, (pair_id, exchange_id), (price, now));
+		}
+	}
+
+	/// Slash [`Config::MisbehaviorSlash`] of the bond of `who` for its deviant price of
+	/// `pair_id` on `exchange_id`, paying [`Config::ReporterReward`] of it to `reporter` and
+	/// burning the rest. The price can't be reported again.
+	pub(crate) fn slash_operator(
+		who: T::AccountId,
+		reporter: T::AccountId,
+		pair_id: PairId,
+		exchange_id: ExchangeId,
+	) -> DispatchResult {
+		let bond = OperatorBonds::<T>::get(&who);
+		let slash = T::MisbehaviorSlash::get().mul_floor(bond);
+		ensure!(!slash.is_zero(), Error::<T>::NotBonded);
+
+		let (slashed, _) = T::Currency::slash_reserved(&who, slash);
+		let amount = slashed.peek();
+		// The part of the slash not paid to the reporter is dropped, which burns it.
+		let (reward, _) = slashed.split(T::ReporterReward::get().mul_floor(amount));
+		let reward_amount = reward.peek();
+		T::Currency::resolve_creating(&reporter, reward);
+		OperatorBonds::<T>::insert(&who, bond.saturating_sub(amount));
+		SignedSubmissions::<T>::remove(&who, (pair_id, exchange_id));
+		Self::deposit_event(Event::OperatorSlashed {
+			who,
+			reporter,
+			pair_id,
+			exchange_id,
+			amount,
+			reward: reward_amount,
+		});
+		Ok(())
+	}
+
+	/// Whether `who` may submit signed prices: a registered operator or a member of the
+	/// [`Config::Committee`].
+	pub fn is_operator(who: &T::AccountId) -> bool {
+		Operators::<T>::contains_key(who) || T::Committee::contains(who)
+	}
+
+	/// Commitment of [`Pallet::commit_price`] to a price with `exponent` decimals, observed
+	/// at the `(timestamp, source block, pool notional)` of `observation`: the blake2-256
+	/// hash of them and the `salt` [`Pallet::reveal_price`] reveals them with, SCALE encoded.
+	pub fn price_commitment(
+		price: u128,
+		exponent: u8,
+		observation: (u64, Option<u64>, Option<u128>),
+		salt: &[u8; 32],
+	) -> [u8; 32] {
+		sp_io::hashing::blake2_256(&(price, exponent, observation, salt).encode())
+	}
+}
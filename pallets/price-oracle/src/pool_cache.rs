@@ -0,0 +1,52 @@
+//! Cache of pool introspection in the offchain worker.
+//!
+//! The tokens and fee tier of a pool never change, yet reading them takes a request to the
+//! chain. Lookups are kept in the node's persistent offchain storage, keyed by the chain and
+//! address of the pool, and reused until they are [`POOL_CACHE_TTL`] old, so they don't eat into
+//! the requests of every update interval. Failed lookups aren't cached.
+
+use crate::{
+	error::OracleError,
+	types::{ChainId, EvmAddress},
+};
+use alloc::{format, vec::Vec};
+use codec::{Decode, Encode};
+use sp_core::hexdisplay::HexDisplay;
+use sp_runtime::{offchain::storage::StorageValueRef, RuntimeDebug};
+
+/// Time a cached lookup is reused for, in milliseconds: a day.
+pub const POOL_CACHE_TTL: u64 = 24 * 60 * 60 * 1000;
+
+/// The tokens of a pool and, for Uniswap V3 pools, its fee in hundredths of a basis point.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode)]
+pub struct PoolTokens {
+	pub token0: EvmAddress,
+	pub token1: EvmAddress,
+	pub fee: u32,
+}
+
+/// Key of the `lookup` of the pool at `address` on `chain_id` in the persistent offchain storage.
+pub fn pool_cache_key(lookup: &str, chain_id: ChainId, address: &EvmAddress) -> Vec<u8> {
+	format!("price-oracle/pools/{lookup}/{chain_id}/0x{}", HexDisplay::from(address)).into_bytes()
+}
+
+/// The result of the `lookup` of the pool at `address` on `chain_id`, from the cache if it was
+/// stored less than [`POOL_CACHE_TTL`] ago, otherwise from `read`, caching it on success.
+pub fn cached<V: Encode + Decode>(
+	lookup: &str,
+	chain_id: ChainId,
+	address: &EvmAddress,
+	read: impl FnOnce() -> Result<V, OracleError>,
+) -> Result<V, OracleError> {
+	let key = pool_cache_key(lookup, chain_id, address);
+	let entry = StorageValueRef::persistent(&key);
+	let now = sp_io::offchain::timestamp().unix_millis();
+	if let Ok(Some((value, stored))) = entry.get::<(V, u64)>() {
+		if now.saturating_sub(stored) < POOL_CACHE_TTL {
+			return Ok(value)
+		}
+	}
+	let value = read()?;
+	entry.set(&(&value, now));
+	Ok(value)
+}
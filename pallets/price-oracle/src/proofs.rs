@@ -0,0 +1,257 @@
+//! Prices proven against the state of their chain.
+//!
+//! Reported prices are as trustworthy as the operators reporting them. A verified price is instead
+//! derived on-chain from the storage of its pool, proven by the Merkle Patricia proofs
+//! `eth_getProof` returns against the state root of a block header tracked by a [`LightClient`],
+//! see [`crate::Pallet::submit_verified_price`]. The mode is experimental: the runtime provides the
+//! light client, and only the protocols keeping the price of a pool in one storage slot can be
+//! verified, see [`DexProtocol::price_slot`](crate::protocols::DexProtocol::price_slot).
+
+use crate::types::{ChainId, EvmAddress};
+use alloc::vec::Vec;
+use frame_support::{traits::ConstU32, weights::Weight, BoundedVec};
+use sp_core::{H256, U256};
+use sp_io::hashing::keccak_256;
+
+/// Maximum length of a node of a proof: a branch node with 16 child hashes.
+pub const MAX_NODE_LEN: u32 = 532;
+
+/// Maximum number of nodes of a proof, beyond the depth of the tries of Ethereum.
+pub const MAX_PROOF_NODES: u32 = 16;
+
+/// A node of a trie, RLP encoded.
+pub type ProofNode = BoundedVec<u8, ConstU32<MAX_NODE_LEN>>;
+
+/// The nodes on the path from the root of a trie to a key, as `eth_getProof` returns them.
+pub type Proof = BoundedVec<ProofNode, ConstU32<MAX_PROOF_NODES>>;
+
+/// Headers of the chains prices are proven on, e.g. tracked by a light client pallet.
+pub trait LightClient {
+	/// State root of block `block_number` of the EVM chain `chain_id`, if its header is tracked
+	/// and final.
+	fn state_root(chain_id: ChainId, block_number: u64) -> Option<H256>;
+
+	/// Worst case weight of [`Self::state_root`].
+	fn state_root_weight() -> Weight;
+}
+
+/// Tracks no header, so no price can be verified.
+impl LightClient for () {
+	fn state_root(_chain_id: ChainId, _block_number: u64) -> Option<H256> {
+		None
+	}
+
+	fn state_root_weight() -> Weight {
+		Weight::zero()
+	}
+}
+
+/// Value of storage `slot` of `contract` in the state with `state_root`, proven by the
+/// `account_proof` of the contract and the `storage_proof` of the slot. Slots proven empty hold
+/// zero, as in the EVM.
+pub fn verify_storage(
+	state_root: &H256,
+	contract: &EvmAddress,
+	slot: U256,
+	account_proof: &[ProofNode],
+	storage_proof: &[ProofNode],
+) -> Option<U256> {
+	let account = verify_proof(state_root.as_fixed_bytes(), &keccak_256(contract), account_proof)??;
+	// The account is [nonce, balance, storageRoot, codeHash].
+	let [_, _, Item::String(storage_root), _] = list_items(account)?[..] else { return None };
+	let storage_root = storage_root.try_into().ok()?;
+	let key = keccak_256(&slot.to_big_endian());
+	let Some(value) = verify_proof(storage_root, &key, storage_proof)? else {
+		return Some(U256::zero())
+	};
+	match decode_item(value)? {
+		(Item::String(value), []) if value.len() <= 32 => Some(U256::from_big_endian(value)),
+		_ => None,
+	}
+}
+
+/// The value at `key` of the trie with `root`, proven by the nodes of `proof` on the path to it,
+/// or `None` within if the proof shows there is none. Fails on a proof that doesn't lead from the
+/// root to the key. Nodes shorter than a hash are inlined in their parent, which only happens
+/// deep in tries with keys shorter than hashes, so proofs with them fail too.
+fn verify_proof<'a>(
+	root: &[u8; 32],
+	key: &[u8; 32],
+	proof: &'a [ProofNode],
+) -> Option<Option<&'a [u8]>> {
+	let path: Vec<u8> = key.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect();
+	let mut path = &path[..];
+	let mut hash = *root;
+	for node in proof {
+		if keccak_256(node) != hash {
+			return None
+		}
+		let items = list_items(node)?;
+		let next = match &items[..] {
+			[children @ .., Item::String(value)] if children.len() == 16 => {
+				let Some((nibble, rest)) = path.split_first() else {
+					return Some((!value.is_empty()).then_some(*value))
+				};
+				path = rest;
+				children[usize::from(*nibble)]
+			},
+			[Item::String(encoded_path), next] => {
+				let (leaf, nibbles) = decode_path(encoded_path)?;
+				if leaf {
+					let Item::String(value) = next else { return None };
+					return Some((path == nibbles).then_some(*value))
+				}
+				let Some(rest) = path.strip_prefix(&nibbles[..]) else { return Some(None) };
+				path = rest;
+				*next
+			},
+			_ => return None,
+		};
+		match next {
+			Item::String([]) => return Some(None),
+			Item::String(child) => hash = child.try_into().ok()?,
+			Item::List(_) => return None,
+		}
+	}
+	None
+}
+
+/// The nibbles of a hex-prefix encoded path of a leaf or extension node, and whether it is a
+/// leaf's.
+fn decode_path(encoded: &[u8]) -> Option<(bool, Vec<u8>)> {
+	let (first, rest) = encoded.split_first()?;
+	let flags = first >> 4;
+	if flags > 3 {
+		return None
+	}
+	let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+	if flags & 1 == 1 {
+		nibbles.push(first & 0x0f);
+	}
+	nibbles.extend(rest.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]));
+	Some((flags & 2 == 2, nibbles))
+}
+
+/// The payload of an RLP item.
+#[derive(Clone, Copy)]
+enum Item<'a> {
+	String(&'a [u8]),
+	List(&'a [u8]),
+}
+
+/// The RLP item at the start of `data` and the data after it.
+fn decode_item(data: &[u8]) -> Option<(Item<'_>, &[u8])> {
+	let (&prefix, rest) = data.split_first()?;
+	let (len, rest) = match prefix {
+		0x00..=0x7f => return Some((Item::String(&data[..1]), rest)),
+		0x80..=0xb7 | 0xc0..=0xf7 => (usize::from(prefix & 0x3f), rest),
+		_ => {
+			let len_of_len = usize::from((prefix & 0x3f) - 0x37);
+			let len = rest.get(..len_of_len)?;
+			let len = len
+				.iter()
+				.try_fold(0usize, |len, byte| Some(len.checked_mul(256)? + usize::from(*byte)))?;
+			(len, &rest[len_of_len..])
+		},
+	};
+	let payload = rest.get(..len)?;
+	let item = match prefix >= 0xc0 {
+		true => Item::List(payload),
+		false => Item::String(payload),
+	};
+	Some((item, &rest[len..]))
+}
+
+/// The items of the RLP list making up the whole of `data`.
+fn list_items(data: &[u8]) -> Option<Vec<Item<'_>>> {
+	let (Item::List(mut payload), []) = decode_item(data)? else { return None };
+	let mut items = Vec::new();
+	while !payload.is_empty() {
+		let (item, rest) = decode_item(payload)?;
+		items.push(item);
+		payload = rest;
+	}
+	Some(items)
+}
+
+/// The state root of a state where storage `slot` of `contract` holds `value`, with the proof of
+/// the account and the proof of the slot, below `account_depth` and `storage_depth` full branch
+/// nodes, as [`verify_storage`] takes them.
+#[cfg(any(test, feature = "runtime-benchmarks"))]
+pub fn build_storage_proofs(
+	contract: &EvmAddress,
+	slot: U256,
+	value: U256,
+	account_depth: usize,
+	storage_depth: usize,
+) -> (H256, Vec<ProofNode>, Vec<ProofNode>) {
+	use encode::{list, string, trimmed};
+
+	let slot_key = keccak_256(&slot.to_big_endian());
+	let value = string(trimmed(&value.to_big_endian()));
+	let (storage_root, storage_proof) = build_proof(&slot_key, &value, storage_depth);
+	// [nonce, balance, storageRoot, codeHash]
+	let account = list(&[string(&[1]), string(&[]), string(&storage_root), string(&[0xc0; 32])]);
+	let (state_root, account_proof) = build_proof(&keccak_256(contract), &account, account_depth);
+	(H256(state_root), account_proof, storage_proof)
+}
+
+/// The root of a trie holding `value` at `key` below `depth` branch nodes, each with the 16
+/// children of a full node, and the proof of the key.
+#[cfg(any(test, feature = "runtime-benchmarks"))]
+fn build_proof(key: &[u8; 32], value: &[u8], depth: usize) -> ([u8; 32], Vec<ProofNode>) {
+	use encode::{list, string};
+
+	let path: Vec<u8> = key.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect();
+	let (above, below) = path.split_at(depth);
+	let mut encoded_path = match below.len() % 2 {
+		1 => alloc::vec![0x30 | below[0]],
+		_ => alloc::vec![0x20],
+	};
+	encoded_path.extend(below[below.len() % 2..].chunks(2).map(|pair| pair[0] << 4 | pair[1]));
+	let mut nodes = alloc::vec![list(&[string(&encoded_path), string(value)])];
+	for (level, nibble) in above.iter().enumerate().rev() {
+		let child = keccak_256(&nodes[0]);
+		let mut items: Vec<_> = (0..16u8)
+			.map(|index| match index == *nibble {
+				true => string(&child),
+				false => string(&[level as u8 + 1; 32]),
+			})
+			.collect();
+		items.push(string(&[]));
+		nodes.insert(0, list(&items));
+	}
+	(keccak_256(&nodes[0]), nodes.into_iter().map(BoundedVec::truncate_from).collect())
+}
+
+/// RLP encoding, for building proofs.
+#[cfg(any(test, feature = "runtime-benchmarks"))]
+mod encode {
+	use alloc::{vec, vec::Vec};
+
+	pub fn string(data: &[u8]) -> Vec<u8> {
+		match data {
+			[byte] if *byte < 0x80 => data.to_vec(),
+			_ => [header(0x80, data.len()), data.to_vec()].concat(),
+		}
+	}
+
+	pub fn list(items: &[Vec<u8>]) -> Vec<u8> {
+		let payload = items.concat();
+		[header(0xc0, payload.len()), payload].concat()
+	}
+
+	/// `data` without its leading zeros, as RLP encodes integers.
+	pub fn trimmed(data: &[u8]) -> &[u8] {
+		&data[data.iter().take_while(|byte| **byte == 0).count()..]
+	}
+
+	fn header(offset: u8, len: usize) -> Vec<u8> {
+		if len < 56 {
+			return vec![offset + len as u8]
+		}
+		let len = (len as u64).to_be_bytes();
+		let len = trimmed(&len);
+		[&[offset + 55 + len.len() as u8][..], len].concat()
+	}
+}
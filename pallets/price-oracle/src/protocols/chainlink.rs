@@ -0,0 +1,67 @@
+//! Chainlink price feeds, read as a reference for the prices of the DEX pools.
+
+use super::DexProtocol;
+use crate::{
+	error::OracleError,
+	fixed::{self, Price},
+	types::{EvmAddress, PoolMetadata},
+};
+use alloc::string::String;
+use sp_core::U512;
+
+/// Chainlink aggregators, priced from `latestRoundData()`.
+///
+/// A feed is registered as a pool whose token0 is the base with no decimals and whose token1 is
+/// the quote with the decimals of the feed's answer, so one unit of the base trades for the
+/// answer. Feeds have no router, so quotes aren't supported.
+pub struct ChainlinkProtocol;
+
+impl DexProtocol for ChainlinkProtocol {
+	fn name(&self) -> &'static str {
+		"Chainlink"
+	}
+
+	fn call_data(&self) -> &'static str {
+		// latestRoundData()
+		"0xfeaf968c"
+	}
+
+	fn parse_price(&self, data: &[u8], pool: &PoolMetadata) -> Result<Price, OracleError> {
+		// (roundId, answer, startedAt, updatedAt, answeredInRound)
+		let round = fixed::word(data, 0)?;
+		let answer = fixed::signed_word(data, 1)?;
+		let answered_in_round = fixed::word(data, 4)?;
+		if answered_in_round < round {
+			return Err(OracleError::StaleData)
+		}
+		let answer = u128::try_from(answer)
+			.ok()
+			.filter(|answer| *answer > 0)
+			.ok_or(OracleError::DecodeError)?;
+
+		let (base, quote) = if pool.base_is_token0 {
+			(U512::one(), U512::from(answer))
+		} else {
+			(U512::from(answer), U512::one())
+		};
+		fixed::ratio(quote, base, pool.decimals_delta())
+	}
+
+	fn token_calls(&self) -> &'static [&'static str] {
+		&[]
+	}
+
+	fn quote_call_data(
+		&self,
+		_token_in: &EvmAddress,
+		_token_out: &EvmAddress,
+		_fee: u32,
+		_amount_in: u128,
+	) -> String {
+		String::new()
+	}
+
+	fn parse_quote(&self, _data: &[u8]) -> Result<u128, OracleError> {
+		Err(OracleError::UnsupportedPair)
+	}
+}
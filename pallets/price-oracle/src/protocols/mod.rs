@@ -0,0 +1,252 @@
+//! DEX protocols the oracle knows how to read prices from.
+
+use crate::{
+	error::OracleError,
+	fixed::Price,
+	types::{EvmAddress, PoolMetadata},
+};
+use alloc::{string::String, vec::Vec};
+use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_core::U256;
+
+pub mod chainlink;
+pub mod orca;
+pub mod traderjoe;
+pub mod uniswap_v2;
+pub mod uniswap_v3;
+
+pub use chainlink::ChainlinkProtocol;
+pub use orca::OrcaWhirlpoolProtocol;
+pub use traderjoe::TraderJoeProtocol;
+pub use uniswap_v2::UniswapV2Protocol;
+pub use uniswap_v3::UniswapV3Protocol;
+
+/// A DEX whose pool state can be turned into a USD price.
+pub trait DexProtocol {
+	/// Name of the protocol, for logging.
+	fn name(&self) -> &'static str;
+
+	/// Calldata of the view call returning the pool state.
+	fn call_data(&self) -> &'static str;
+
+	/// Turn the return data of [`Self::call_data`] for a pool laid out as `pool` into a USD price.
+	fn parse_price(&self, data: &[u8], pool: &PoolMetadata) -> Result<Price, OracleError>;
+
+	/// Calldata of the view calls returning the depth of a pool, see [`Self::parse_depth`].
+	/// Pools read whole as accounts need none.
+	fn depth_calls(&self) -> &'static [&'static str] {
+		&[]
+	}
+
+	/// Turn the return data of [`Self::depth_calls`], or the account of a pool, for a pool laid
+	/// out as `pool` into the raw amount of the quote token it holds: its reserve, or the
+	/// virtual reserve of the liquidity in range of a concentrated liquidity pool. Sources
+	/// without depth, such as price feeds, fail with [`OracleError::UnsupportedPair`].
+	fn parse_depth(&self, _data: &[Vec<u8>], _pool: &PoolMetadata) -> Result<u128, OracleError> {
+		Err(OracleError::UnsupportedPair)
+	}
+
+	/// Storage slot of a pool holding the state [`Self::call_data`] returns, so its price can be
+	/// proven against the storage of the pool, see [`crate::proofs`]. Protocols keeping that state
+	/// across slots, or in accounts, have none.
+	fn price_slot(&self) -> Option<U256> {
+		None
+	}
+
+	/// The return data of [`Self::call_data`] of a pool whose [`Self::price_slot`] holds `value`.
+	fn slot_return_data(&self, _value: U256) -> Vec<u8> {
+		Vec::new()
+	}
+
+	/// Calldata of the view calls of a pool returning its token0, its token1 and, for protocols
+	/// with fee tiers, its fee.
+	fn token_calls(&self) -> &'static [&'static str] {
+		&[TOKEN0, TOKEN1]
+	}
+
+	/// Calldata of the call of the venue's router quoting the raw amount of `token_out` bought
+	/// with `amount_in` raw units of `token_in` in a pool with `fee`.
+	fn quote_call_data(
+		&self,
+		token_in: &EvmAddress,
+		token_out: &EvmAddress,
+		fee: u32,
+		amount_in: u128,
+	) -> String;
+
+	/// Turn the return data of [`Self::quote_call_data`] into the quoted output amount.
+	fn parse_quote(&self, data: &[u8]) -> Result<u128, OracleError>;
+}
+
+/// Calldata of `token0()`.
+pub(crate) const TOKEN0: &str = "0x0dfe1681";
+
+/// Calldata of `token1()`.
+pub(crate) const TOKEN1: &str = "0xd21220a7";
+
+/// An address as a hex encoded 32 byte ABI word, without `0x`.
+pub(crate) fn address_hex(address: &EvmAddress) -> String {
+	alloc::format!("{:024x}{}", 0, sp_core::hexdisplay::HexDisplay::from(address))
+}
+
+/// The `bits` bits of the storage slot `value` from bit `offset`, as Solidity packs the variables
+/// sharing a slot from its lowest bits.
+pub(crate) fn slot_field(value: U256, offset: usize, bits: usize) -> U256 {
+	(value >> offset) & ((U256::one() << bits) - 1)
+}
+
+/// `fields` as ABI encoded return data.
+pub(crate) fn words(fields: &[U256]) -> Vec<u8> {
+	fields.iter().flat_map(U256::to_big_endian).collect()
+}
+
+/// The protocols an exchange can speak, dispatching to their [`DexProtocol`] implementation.
+#[derive(
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	Debug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+	serde::Serialize,
+	serde::Deserialize,
+)]
+pub enum ProtocolKind {
+	/// Uniswap V2 and its constant product forks.
+	UniswapV2,
+	/// Uniswap V3 concentrated liquidity pools.
+	UniswapV3,
+	/// Trader Joe V1.
+	TraderJoe,
+	/// Orca Whirlpools on Solana.
+	OrcaWhirlpool,
+	/// Chainlink price feeds, a reference for the DEX prices rather than a venue, see
+	/// [`ProtocolKind::is_reference`].
+	Chainlink,
+}
+
+impl ProtocolKind {
+	/// Whether prices of the protocol are a reference the DEX prices of a pair are checked
+	/// against instead of a venue they are aggregated with.
+	pub fn is_reference(&self) -> bool {
+		matches!(self, ProtocolKind::Chainlink)
+	}
+}
+
+impl DexProtocol for ProtocolKind {
+	fn name(&self) -> &'static str {
+		match self {
+			ProtocolKind::UniswapV2 => UniswapV2Protocol.name(),
+			ProtocolKind::UniswapV3 => UniswapV3Protocol.name(),
+			ProtocolKind::TraderJoe => TraderJoeProtocol.name(),
+			ProtocolKind::OrcaWhirlpool => OrcaWhirlpoolProtocol.name(),
+			ProtocolKind::Chainlink => ChainlinkProtocol.name(),
+		}
+	}
+
+	fn call_data(&self) -> &'static str {
+		match self {
+			ProtocolKind::UniswapV2 => UniswapV2Protocol.call_data(),
+			ProtocolKind::UniswapV3 => UniswapV3Protocol.call_data(),
+			ProtocolKind::TraderJoe => TraderJoeProtocol.call_data(),
+			ProtocolKind::OrcaWhirlpool => OrcaWhirlpoolProtocol.call_data(),
+			ProtocolKind::Chainlink => ChainlinkProtocol.call_data(),
+		}
+	}
+
+	fn parse_price(&self, data: &[u8], pool: &PoolMetadata) -> Result<Price, OracleError> {
+		match self {
+			ProtocolKind::UniswapV2 => UniswapV2Protocol.parse_price(data, pool),
+			ProtocolKind::UniswapV3 => UniswapV3Protocol.parse_price(data, pool),
+			ProtocolKind::TraderJoe => TraderJoeProtocol.parse_price(data, pool),
+			ProtocolKind::OrcaWhirlpool => OrcaWhirlpoolProtocol.parse_price(data, pool),
+			ProtocolKind::Chainlink => ChainlinkProtocol.parse_price(data, pool),
+		}
+	}
+
+	fn depth_calls(&self) -> &'static [&'static str] {
+		match self {
+			ProtocolKind::UniswapV2 => UniswapV2Protocol.depth_calls(),
+			ProtocolKind::UniswapV3 => UniswapV3Protocol.depth_calls(),
+			ProtocolKind::TraderJoe => TraderJoeProtocol.depth_calls(),
+			ProtocolKind::OrcaWhirlpool => OrcaWhirlpoolProtocol.depth_calls(),
+			ProtocolKind::Chainlink => ChainlinkProtocol.depth_calls(),
+		}
+	}
+
+	fn parse_depth(&self, data: &[Vec<u8>], pool: &PoolMetadata) -> Result<u128, OracleError> {
+		match self {
+			ProtocolKind::UniswapV2 => UniswapV2Protocol.parse_depth(data, pool),
+			ProtocolKind::UniswapV3 => UniswapV3Protocol.parse_depth(data, pool),
+			ProtocolKind::TraderJoe => TraderJoeProtocol.parse_depth(data, pool),
+			ProtocolKind::OrcaWhirlpool => OrcaWhirlpoolProtocol.parse_depth(data, pool),
+			ProtocolKind::Chainlink => ChainlinkProtocol.parse_depth(data, pool),
+		}
+	}
+
+	fn price_slot(&self) -> Option<U256> {
+		match self {
+			ProtocolKind::UniswapV2 => UniswapV2Protocol.price_slot(),
+			ProtocolKind::UniswapV3 => UniswapV3Protocol.price_slot(),
+			ProtocolKind::TraderJoe => TraderJoeProtocol.price_slot(),
+			ProtocolKind::OrcaWhirlpool => OrcaWhirlpoolProtocol.price_slot(),
+			ProtocolKind::Chainlink => ChainlinkProtocol.price_slot(),
+		}
+	}
+
+	fn slot_return_data(&self, value: U256) -> Vec<u8> {
+		match self {
+			ProtocolKind::UniswapV2 => UniswapV2Protocol.slot_return_data(value),
+			ProtocolKind::UniswapV3 => UniswapV3Protocol.slot_return_data(value),
+			ProtocolKind::TraderJoe => TraderJoeProtocol.slot_return_data(value),
+			ProtocolKind::OrcaWhirlpool => OrcaWhirlpoolProtocol.slot_return_data(value),
+			ProtocolKind::Chainlink => ChainlinkProtocol.slot_return_data(value),
+		}
+	}
+
+	fn token_calls(&self) -> &'static [&'static str] {
+		match self {
+			ProtocolKind::UniswapV2 => UniswapV2Protocol.token_calls(),
+			ProtocolKind::UniswapV3 => UniswapV3Protocol.token_calls(),
+			ProtocolKind::TraderJoe => TraderJoeProtocol.token_calls(),
+			ProtocolKind::OrcaWhirlpool => OrcaWhirlpoolProtocol.token_calls(),
+			ProtocolKind::Chainlink => ChainlinkProtocol.token_calls(),
+		}
+	}
+
+	fn quote_call_data(
+		&self,
+		token_in: &EvmAddress,
+		token_out: &EvmAddress,
+		fee: u32,
+		amount_in: u128,
+	) -> String {
+		match self {
+			ProtocolKind::UniswapV2 =>
+				UniswapV2Protocol.quote_call_data(token_in, token_out, fee, amount_in),
+			ProtocolKind::UniswapV3 =>
+				UniswapV3Protocol.quote_call_data(token_in, token_out, fee, amount_in),
+			ProtocolKind::TraderJoe =>
+				TraderJoeProtocol.quote_call_data(token_in, token_out, fee, amount_in),
+			ProtocolKind::OrcaWhirlpool =>
+				OrcaWhirlpoolProtocol.quote_call_data(token_in, token_out, fee, amount_in),
+			ProtocolKind::Chainlink =>
+				ChainlinkProtocol.quote_call_data(token_in, token_out, fee, amount_in),
+		}
+	}
+
+	fn parse_quote(&self, data: &[u8]) -> Result<u128, OracleError> {
+		match self {
+			ProtocolKind::UniswapV2 => UniswapV2Protocol.parse_quote(data),
+			ProtocolKind::UniswapV3 => UniswapV3Protocol.parse_quote(data),
+			ProtocolKind::TraderJoe => TraderJoeProtocol.parse_quote(data),
+			ProtocolKind::OrcaWhirlpool => OrcaWhirlpoolProtocol.parse_quote(data),
+			ProtocolKind::Chainlink => ChainlinkProtocol.parse_quote(data),
+		}
+	}
+}
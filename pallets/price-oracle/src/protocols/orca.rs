@@ -0,0 +1,124 @@
+//! Orca Whirlpools, the concentrated liquidity pools of Solana.
+
+use super::DexProtocol;
+use crate::{
+	error::OracleError,
+	fixed::{self, Price},
+	rpc::AccountFilter,
+	types::{EvmAddress, PoolMetadata, SolanaAddress},
+};
+use alloc::{string::String, vec::Vec};
+use sp_core::U256;
+
+/// Program owning the Whirlpool accounts, `whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc`.
+pub const PROGRAM: SolanaAddress = [
+	0x0e, 0x03, 0x68, 0x5f, 0x8e, 0x90, 0x90, 0x53, 0xe4, 0x58, 0x12, 0x1c, 0x66, 0xf5, 0xa7, 0x6a,
+	0xed, 0xc7, 0x70, 0x6a, 0xa1, 0x1c, 0x82, 0xf8, 0xaa, 0x95, 0x2a, 0x8f, 0x2b, 0x78, 0x79, 0xa9,
+];
+
+/// Length of a Whirlpool account.
+pub const ACCOUNT_LEN: usize = 653;
+
+/// Anchor discriminator the data of a Whirlpool account starts with, the first bytes of
+/// `sha256("account:Whirlpool")`.
+pub const DISCRIMINATOR: [u8; 8] = [0x3f, 0x95, 0xd1, 0x0c, 0xe1, 0x80, 0x63, 0x09];
+
+/// Offset of the in-range liquidity, a little endian `u128`.
+pub const LIQUIDITY: usize = 49;
+
+/// Offset of the square root of the price of token A in token B, a little endian Q64.64 `u128`.
+pub const SQRT_PRICE: usize = 65;
+
+/// Offset of the mint of token A.
+pub const TOKEN_MINT_A: usize = 101;
+
+/// Offset of the mint of token B.
+pub const TOKEN_MINT_B: usize = 181;
+
+/// Orca Whirlpools, priced from the `sqrt_price` of the pool account. Token A of a pool is its
+/// token0.
+///
+/// Whirlpools are read as accounts and take no calldata, and swaps are quoted by simulating
+/// transactions rather than through a view call, so quotes aren't supported.
+pub struct OrcaWhirlpoolProtocol;
+
+impl OrcaWhirlpoolProtocol {
+	/// Filters of `getProgramAccounts` on [`PROGRAM`] returning the Whirlpools of token A
+	/// `mint_a` and token B `mint_b`, one per fee tier.
+	pub fn pool_filters<'a>(
+		&self,
+		mint_a: &'a SolanaAddress,
+		mint_b: &'a SolanaAddress,
+	) -> [AccountFilter<'a>; 3] {
+		[
+			AccountFilter::DataSize(ACCOUNT_LEN as u64),
+			AccountFilter::Memcmp { offset: TOKEN_MINT_A as u64, bytes: mint_a },
+			AccountFilter::Memcmp { offset: TOKEN_MINT_B as u64, bytes: mint_b },
+		]
+	}
+
+	/// The in-range liquidity of the Whirlpool account `data`, telling the fee tiers of a pair
+	/// apart.
+	pub fn parse_liquidity(&self, data: &[u8]) -> Result<u128, OracleError> {
+		read_u128(whirlpool(data)?, LIQUIDITY)
+	}
+}
+
+impl DexProtocol for OrcaWhirlpoolProtocol {
+	fn name(&self) -> &'static str {
+		"Orca Whirlpool"
+	}
+
+	fn call_data(&self) -> &'static str {
+		""
+	}
+
+	fn parse_price(&self, data: &[u8], pool: &PoolMetadata) -> Result<Price, OracleError> {
+		let sqrt_price_x64 = read_u128(whirlpool(data)?, SQRT_PRICE)?;
+		if sqrt_price_x64 == 0 {
+			return Err(OracleError::DecodeError)
+		}
+		// Q64.64 to the Q64.96 of Uniswap V3.
+		let sqrt_price_x96 = U256::from(sqrt_price_x64) << 32;
+		fixed::price_from_sqrt_x96(sqrt_price_x96, pool.decimals_delta(), !pool.base_is_token0)
+	}
+
+	fn parse_depth(&self, data: &[Vec<u8>], pool: &PoolMetadata) -> Result<u128, OracleError> {
+		let [account] = data else { return Err(OracleError::DecodeError) };
+		let account = whirlpool(account)?;
+		let liquidity = read_u128(account, LIQUIDITY)?;
+		let sqrt_price_x96 = U256::from(read_u128(account, SQRT_PRICE)?) << 32;
+		fixed::virtual_reserve(liquidity, sqrt_price_x96, !pool.base_is_token0)
+	}
+
+	fn token_calls(&self) -> &'static [&'static str] {
+		&[]
+	}
+
+	fn quote_call_data(
+		&self,
+		_token_in: &EvmAddress,
+		_token_out: &EvmAddress,
+		_fee: u32,
+		_amount_in: u128,
+	) -> String {
+		String::new()
+	}
+
+	fn parse_quote(&self, _data: &[u8]) -> Result<u128, OracleError> {
+		Err(OracleError::UnsupportedPair)
+	}
+}
+
+/// `data` if it is a Whirlpool account.
+fn whirlpool(data: &[u8]) -> Result<&[u8], OracleError> {
+	match data.len() == ACCOUNT_LEN && data.starts_with(&DISCRIMINATOR) {
+		true => Ok(data),
+		false => Err(OracleError::DecodeError),
+	}
+}
+
+fn read_u128(data: &[u8], offset: usize) -> Result<u128, OracleError> {
+	let bytes = data.get(offset..offset + 16).ok_or(OracleError::DecodeError)?;
+	Ok(u128::from_le_bytes(bytes.try_into().map_err(|_| OracleError::DecodeError)?))
+}
@@ -0,0 +1,63 @@
+//! Trader Joe on Avalanche.
+
+use super::{
+	uniswap_v2::{
+		amounts_out_call_data, parse_amounts_out, price_from_reserves, quote_reserve,
+		reserves_return_data, GET_RESERVES, RESERVES_SLOT,
+	},
+	DexProtocol,
+};
+use crate::{
+	error::OracleError,
+	fixed::Price,
+	types::{EvmAddress, PoolMetadata},
+};
+use alloc::{string::String, vec::Vec};
+use sp_core::U256;
+
+/// Trader Joe V1 pools, which share the Uniswap V2 interface.
+pub struct TraderJoeProtocol;
+
+impl DexProtocol for TraderJoeProtocol {
+	fn name(&self) -> &'static str {
+		"Trader Joe"
+	}
+
+	fn call_data(&self) -> &'static str {
+		GET_RESERVES
+	}
+
+	fn parse_price(&self, data: &[u8], pool: &PoolMetadata) -> Result<Price, OracleError> {
+		price_from_reserves(data, pool)
+	}
+
+	fn depth_calls(&self) -> &'static [&'static str] {
+		&[GET_RESERVES]
+	}
+
+	fn parse_depth(&self, data: &[Vec<u8>], pool: &PoolMetadata) -> Result<u128, OracleError> {
+		quote_reserve(data, pool)
+	}
+
+	fn price_slot(&self) -> Option<U256> {
+		Some(RESERVES_SLOT.into())
+	}
+
+	fn slot_return_data(&self, value: U256) -> Vec<u8> {
+		reserves_return_data(value)
+	}
+
+	fn quote_call_data(
+		&self,
+		token_in: &EvmAddress,
+		token_out: &EvmAddress,
+		_fee: u32,
+		amount_in: u128,
+	) -> String {
+		amounts_out_call_data(token_in, token_out, amount_in)
+	}
+
+	fn parse_quote(&self, data: &[u8]) -> Result<u128, OracleError> {
+		parse_amounts_out(data)
+	}
+}
@@ -0,0 +1,118 @@
+//! Uniswap V2 style constant product pools and their forks.
+
+use super::{address_hex, slot_field, words, DexProtocol};
+use crate::{
+	error::OracleError,
+	fixed::{self, Price},
+	types::{EvmAddress, PoolMetadata},
+};
+use alloc::{format, string::String, vec::Vec};
+use sp_core::{U256, U512};
+
+/// Calldata of `getReserves()`.
+pub(crate) const GET_RESERVES: &str = "0x0902f1ac";
+
+/// Storage slot of the reserves of a pool, after `totalSupply`, `balanceOf`, `allowance`,
+/// `DOMAIN_SEPARATOR`, `nonces`, `factory`, `token0` and `token1`.
+pub(crate) const RESERVES_SLOT: u64 = 8;
+
+/// The return data of `getReserves()` of a pool whose [`RESERVES_SLOT`] holds `value`: `reserve0`,
+/// `reserve1` and `blockTimestampLast`.
+pub(crate) fn reserves_return_data(value: U256) -> Vec<u8> {
+	words(&[(0, 112), (112, 112), (224, 32)].map(|(offset, bits)| slot_field(value, offset, bits)))
+}
+
+/// Price implied by the reserves returned by `getReserves()`: the quote reserve over the base
+/// reserve, adjusted for the tokens' decimals.
+pub(crate) fn price_from_reserves(data: &[u8], pool: &PoolMetadata) -> Result<Price, OracleError> {
+	let reserve0 = U512::from(fixed::word(data, 0)?);
+	let reserve1 = U512::from(fixed::word(data, 1)?);
+	if reserve0.is_zero() || reserve1.is_zero() {
+		return Err(OracleError::DecodeError)
+	}
+
+	let (base, quote) =
+		if pool.base_is_token0 { (reserve0, reserve1) } else { (reserve1, reserve0) };
+	fixed::ratio(quote, base, pool.decimals_delta())
+}
+
+/// Raw reserve of the quote token in the return data of `getReserves()`.
+pub(crate) fn quote_reserve(data: &[Vec<u8>], pool: &PoolMetadata) -> Result<u128, OracleError> {
+	let [reserves] = data else { return Err(OracleError::DecodeError) };
+	let reserve = fixed::word(reserves, if pool.base_is_token0 { 1 } else { 0 })?;
+	reserve.try_into().map_err(|_| OracleError::DecodeError)
+}
+
+/// Calldata of the router's `getAmountsOut(amount_in, [token_in, token_out])`.
+pub(crate) fn amounts_out_call_data(
+	token_in: &EvmAddress,
+	token_out: &EvmAddress,
+	amount_in: u128,
+) -> String {
+	// getAmountsOut(uint256,address[]): the amount, the offset of the path, its length and the
+	// two tokens.
+	format!(
+		"0xd06ca61f{:064x}{:064x}{:064x}{}{}",
+		amount_in,
+		0x40,
+		2,
+		address_hex(token_in),
+		address_hex(token_out)
+	)
+}
+
+/// Output amount of the return data of [`amounts_out_call_data`]: the last of the amounts.
+pub(crate) fn parse_amounts_out(data: &[u8]) -> Result<u128, OracleError> {
+	// uint256[]: its offset, its length and the amount in and out.
+	if fixed::word(data, 1)? != 2u8.into() {
+		return Err(OracleError::DecodeError)
+	}
+	fixed::word(data, 3)?.try_into().map_err(|_| OracleError::DecodeError)
+}
+
+/// Uniswap V2 and forks sharing its pair interface, such as SushiSwap, PancakeSwap and QuickSwap.
+pub struct UniswapV2Protocol;
+
+impl DexProtocol for UniswapV2Protocol {
+	fn name(&self) -> &'static str {
+		"Uniswap V2"
+	}
+
+	fn call_data(&self) -> &'static str {
+		GET_RESERVES
+	}
+
+	fn parse_price(&self, data: &[u8], pool: &PoolMetadata) -> Result<Price, OracleError> {
+		price_from_reserves(data, pool)
+	}
+
+	fn depth_calls(&self) -> &'static [&'static str] {
+		&[GET_RESERVES]
+	}
+
+	fn parse_depth(&self, data: &[Vec<u8>], pool: &PoolMetadata) -> Result<u128, OracleError> {
+		quote_reserve(data, pool)
+	}
+
+	fn price_slot(&self) -> Option<U256> {
+		Some(RESERVES_SLOT.into())
+	}
+
+	fn slot_return_data(&self, value: U256) -> Vec<u8> {
+		reserves_return_data(value)
+	}
+
+	fn quote_call_data(
+		&self,
+		token_in: &EvmAddress,
+		token_out: &EvmAddress,
+		_fee: u32,
+		amount_in: u128,
+	) -> String {
+		amounts_out_call_data(token_in, token_out, amount_in)
+	}
+
+	fn parse_quote(&self, data: &[u8]) -> Result<u128, OracleError> {
+		parse_amounts_out(data)
+	}
+}
@@ -0,0 +1,138 @@
+//! Uniswap V3 concentrated liquidity pools.
+
+use super::{address_hex, slot_field, words, DexProtocol, TOKEN0, TOKEN1};
+use crate::{
+	error::OracleError,
+	fixed::{self, Price},
+	types::{EvmAddress, PoolMetadata},
+};
+use alloc::{format, string::String, vec::Vec};
+use sp_core::U256;
+
+/// Uniswap V3, priced from the pool's `slot0()`.
+pub struct UniswapV3Protocol;
+
+impl UniswapV3Protocol {
+	/// Calldata of `observe([window, 0])`, returning the tick accumulators of the pool `window`
+	/// seconds ago and now.
+	pub fn observe_call_data(&self, window: u32) -> String {
+		// observe(uint32[]): offset of the array, its length and the two `secondsAgos`.
+		format!("0x883bdbfd{:064x}{:064x}{:064x}{:064x}", 0x20, 2, window, 0)
+	}
+
+	/// Calldata of `liquidity()`, returning the liquidity in range of the current tick.
+	pub fn liquidity_call_data(&self) -> &'static str {
+		"0x1a686502"
+	}
+
+	/// Turn the return data of [`Self::liquidity_call_data`] into the pool's in-range liquidity.
+	pub fn parse_liquidity(&self, data: &[u8]) -> Result<u128, OracleError> {
+		fixed::word(data, 0)?.try_into().map_err(|_| OracleError::DecodeError)
+	}
+
+	/// Turn the return data of [`Self::observe_call_data`] for `window` into the price at the
+	/// pool's time-weighted average tick over the window.
+	pub fn parse_twap(
+		&self,
+		data: &[u8],
+		window: u32,
+		pool: &PoolMetadata,
+	) -> Result<Price, OracleError> {
+		if window == 0 {
+			return Err(OracleError::InvalidConfig)
+		}
+		// `(int56[] tickCumulatives, uint160[] secondsPerLiquidityCumulativeX128s)`: the first
+		// array holds its length followed by the accumulators, oldest first.
+		let offset = fixed::word(data, 0)?;
+		let start = usize::try_from(offset).ok().filter(|offset| offset % 32 == 0);
+		let start = start.ok_or(OracleError::DecodeError)? / 32;
+		if fixed::word(data, start)? != 2u8.into() {
+			return Err(OracleError::DecodeError)
+		}
+		let elapsed = fixed::signed_word(data, start + 2)? - fixed::signed_word(data, start + 1)?;
+		// Round towards negative infinity, like Uniswap's `OracleLibrary.consult`.
+		let window = i128::from(window);
+		let mut tick = elapsed / window;
+		if elapsed < 0 && elapsed % window != 0 {
+			tick -= 1;
+		}
+		let tick = i32::try_from(tick).map_err(|_| OracleError::DecodeError)?;
+		let sqrt_price_x96 = fixed::sqrt_ratio_at_tick(tick)?;
+		fixed::price_from_sqrt_x96(sqrt_price_x96, pool.decimals_delta(), !pool.base_is_token0)
+	}
+}
+
+impl DexProtocol for UniswapV3Protocol {
+	fn name(&self) -> &'static str {
+		"Uniswap V3"
+	}
+
+	fn call_data(&self) -> &'static str {
+		// slot0()
+		"0x3850c7bd"
+	}
+
+	fn parse_price(&self, data: &[u8], pool: &PoolMetadata) -> Result<Price, OracleError> {
+		let sqrt_price_x96 = fixed::word(data, 0)?;
+		fixed::price_from_sqrt_x96(sqrt_price_x96, pool.decimals_delta(), !pool.base_is_token0)
+	}
+
+	fn depth_calls(&self) -> &'static [&'static str] {
+		// slot0() and liquidity()
+		&["0x3850c7bd", "0x1a686502"]
+	}
+
+	fn parse_depth(&self, data: &[Vec<u8>], pool: &PoolMetadata) -> Result<u128, OracleError> {
+		let [slot0, liquidity] = data else { return Err(OracleError::DecodeError) };
+		let sqrt_price_x96 = fixed::word(slot0, 0)?;
+		let liquidity = self.parse_liquidity(liquidity)?;
+		fixed::virtual_reserve(liquidity, sqrt_price_x96, !pool.base_is_token0)
+	}
+
+	fn price_slot(&self) -> Option<U256> {
+		// slot0 is the first slot of the pool.
+		Some(U256::zero())
+	}
+
+	fn slot_return_data(&self, value: U256) -> Vec<u8> {
+		// sqrtPriceX96, tick, observationIndex, observationCardinality,
+		// observationCardinalityNext, feeProtocol and unlocked.
+		let mut fields = [(0, 160), (160, 24), (184, 16), (200, 16), (216, 16), (232, 8), (240, 8)]
+			.map(|(offset, bits)| slot_field(value, offset, bits));
+		// The tick is an int24, sign extended to a word.
+		if fields[1].bit(23) {
+			fields[1] |= U256::MAX << 24;
+		}
+		words(&fields)
+	}
+
+	fn token_calls(&self) -> &'static [&'static str] {
+		// token0(), token1() and fee()
+		&[TOKEN0, TOKEN1, "0xddca3f43"]
+	}
+
+	/// Calldata of `QuoterV2.quoteExactInputSingle`, quoting without a price limit.
+	fn quote_call_data(
+		&self,
+		token_in: &EvmAddress,
+		token_out: &EvmAddress,
+		fee: u32,
+		amount_in: u128,
+	) -> String {
+		// quoteExactInputSingle((address,address,uint256,uint24,uint160)): the static tuple is
+		// encoded in place.
+		format!(
+			"0xc6a5026a{}{}{:064x}{:064x}{:064x}",
+			address_hex(token_in),
+			address_hex(token_out),
+			amount_in,
+			fee,
+			0
+		)
+	}
+
+	fn parse_quote(&self, data: &[u8]) -> Result<u128, OracleError> {
+		// (amountOut, sqrtPriceX96After, initializedTicksCrossed, gasEstimate)
+		fixed::word(data, 0)?.try_into().map_err(|_| OracleError::DecodeError)
+	}
+}
@@ -0,0 +1,77 @@
+//! Outbound HTTP proxy of the offchain worker.
+//!
+//! Validators that can't reach JSON-RPC endpoints directly can route the requests of the offchain
+//! worker through a forwarding proxy, configured in the node's persistent offchain storage, e.g.
+//! with the `offchain_localStorageSet` RPC:
+//!
+//! - [`PROXY_KEY`]: `host:port` of the proxy, as UTF-8.
+//! - [`PROXY_AUTH_KEY`]: optional `user:password`, sent base64 encoded in a `Proxy-Authorization:
+//!   Basic` header.
+//!
+//! The offchain HTTP client can't tunnel through `CONNECT`, so requests are sent to
+//! `http://host:port/<target URL>` instead. That isn't the HTTP proxy protocol: stock forward
+//! proxies won't understand it, and the proxy has to be a custom forwarder that takes the target
+//! URL from the path and sends the request on to it.
+//!
+//! Requests reach the proxy in plaintext, those to `https://` targets and the `Proxy-Authorization`
+//! credentials included, so it must run on the validator's host or a network trusted as much.
+
+use crate::error::OracleError;
+use alloc::{format, string::String, vec::Vec};
+use base64::Engine;
+use sp_core::offchain::StorageKind;
+
+/// Key of the `host:port` of the proxy in the persistent offchain storage.
+pub const PROXY_KEY: &[u8] = b"price-oracle/proxy";
+
+/// Key of the `user:password` credentials of the proxy in the persistent offchain storage.
+pub const PROXY_AUTH_KEY: &[u8] = b"price-oracle/proxy-auth";
+
+/// A forwarding HTTP proxy.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Proxy {
+	/// Host name or address of the proxy.
+	pub host: String,
+	/// Port of the proxy.
+	pub port: u16,
+	/// `user:password` credentials, if the proxy requires them.
+	pub auth: Option<Vec<u8>>,
+}
+
+impl Proxy {
+	/// The proxy configured in the persistent offchain storage, if any.
+	///
+	/// Fails with [`OracleError::InvalidConfig`] if the configured address can't be parsed, so
+	/// requests are not sent around a proxy the operator relies on.
+	pub fn configured() -> Result<Option<Self>, OracleError> {
+		let Some(address) = sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, PROXY_KEY)
+		else {
+			return Ok(None)
+		};
+		let auth = sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, PROXY_AUTH_KEY);
+		Self::parse(&address, auth).map(Some)
+	}
+
+	/// Parse a `host:port` proxy address.
+	pub fn parse(address: &[u8], auth: Option<Vec<u8>>) -> Result<Self, OracleError> {
+		let address = core::str::from_utf8(address).map_err(|_| OracleError::InvalidConfig)?;
+		let (host, port) = address.rsplit_once(':').ok_or(OracleError::InvalidConfig)?;
+		let port = port.parse().map_err(|_| OracleError::InvalidConfig)?;
+		if host.is_empty() || host.contains('/') {
+			return Err(OracleError::InvalidConfig)
+		}
+		Ok(Proxy { host: host.into(), port, auth })
+	}
+
+	/// URL a request to `target` is sent to, in plaintext whatever the scheme of `target`.
+	pub fn url(&self, target: &str) -> String {
+		format!("http://{}:{}/{}", self.host, self.port, target)
+	}
+
+	/// Value of the `Proxy-Authorization` header, if the proxy requires credentials.
+	pub fn authorization(&self) -> Option<String> {
+		self.auth
+			.as_deref()
+			.map(|auth| format!("Basic {}", base64::engine::general_purpose::STANDARD.encode(auth)))
+	}
+}
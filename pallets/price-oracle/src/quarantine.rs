@@ -0,0 +1,53 @@
+//! Quarantine of the exchanges failing or deviating too often.
+//!
+//! Every read of an exchange and every price it contributes is counted into its
+//! [`ExchangeStats`], as a success or as a failure: a read that fails, or a price left out of
+//! the aggregation as an outlier. Once the share of failures crosses [`Config::QuarantineBps`],
+//! the exchange is quarantined in [`QuarantinedExchanges`] for [`Config::QuarantinePeriod`]
+//! blocks: it is neither read nor aggregated until then, or until
+//! [`Pallet::release_exchange`] ends the quarantine early.
+
+use crate::{
+	exchanges::ExchangeId, Config, Event, ExchangeStats, Pallet, QuarantinedExchanges,
+	ReliabilityStats,
+};
+use frame_support::traits::Get;
+use sp_runtime::traits::Saturating;
+
+impl<T: Config> Pallet<T> {
+	/// Count an outcome of `exchange_id` into its [`ExchangeStats`] with `note`, and
+	/// quarantine it if it turned unreliable, see [`Config::QuarantineBps`]. Outcomes during a
+	/// quarantine aren't counted; the first one after it ends the quarantine.
+	pub(crate) fn note_reliability(
+		exchange_id: ExchangeId,
+		note: impl FnOnce(&mut ReliabilityStats),
+	) {
+		let now = frame_system::Pallet::<T>::block_number();
+		match QuarantinedExchanges::<T>::get(exchange_id) {
+			Some(until) if now < until => return,
+			Some(_) => {
+				QuarantinedExchanges::<T>::remove(exchange_id);
+				Self::deposit_event(Event::ExchangeReleased { exchange_id });
+			},
+			None => {},
+		}
+		let min_samples = T::QuarantineMinSamples::get();
+		let mut stats = ExchangeStats::<T>::get(exchange_id);
+		note(&mut stats);
+		if stats.is_unreliable(min_samples, T::QuarantineBps::get()) {
+			let until = now.saturating_add(T::QuarantinePeriod::get().into());
+			QuarantinedExchanges::<T>::insert(exchange_id, until);
+			ExchangeStats::<T>::remove(exchange_id);
+			Self::deposit_event(Event::ExchangeQuarantined { exchange_id, until });
+			return
+		}
+		stats.decay(min_samples);
+		ExchangeStats::<T>::insert(exchange_id, stats);
+	}
+
+	/// Whether `exchange_id` is in quarantine, see [`QuarantinedExchanges`].
+	pub fn is_quarantined(exchange_id: ExchangeId) -> bool {
+		let now = frame_system::Pallet::<T>::block_number();
+		QuarantinedExchanges::<T>::get(exchange_id).is_some_and(|until| now < until)
+	}
+}
@@ -0,0 +1,255 @@
+//! The exchanges, endpoints and pairs a chain starts out with, and [`RegistrySnapshot`]s that
+//! carry a tuned registry over to another chain.
+
+use crate::{
+	chains::{solana, ChainKind},
+	exchanges::{ExchangeConfig, ExchangeId, PoolConfig},
+	fixed::PRICE_UNIT,
+	protocols::ProtocolKind,
+	types::{
+		pair_id, ChainId, CrossChainCheck, EvmAddress, FailureMode, FastPath, FreshnessSla, PairId,
+		PairInfo, PoolMetadata, RpcUrls, TwapCheck,
+	},
+};
+use alloc::{vec, vec::Vec};
+use codec::{Decode, Encode};
+use frame_support::{traits::Get, BoundedVec};
+use sp_runtime::RuntimeDebug;
+
+/// Maximum nesting depth of a SCALE encoded [`RegistrySnapshot`].
+pub const MAX_SNAPSHOT_DEPTH: u32 = 16;
+
+/// The exchanges and pairs of an oracle with their pools and settings, exported with
+/// [`Pallet::export_registry`](crate::Pallet::export_registry) and restored with
+/// [`Call::import_registry`](crate::Call::import_registry).
+///
+/// Endpoints, operators and the pause state belong to a deployment and aren't part of it.
+#[derive(Clone, Default, PartialEq, Eq, RuntimeDebug, Encode, Decode)]
+pub struct RegistrySnapshot {
+	/// The registered exchanges.
+	pub exchanges: Vec<(ExchangeId, ExchangeConfig)>,
+	/// Router or quoter of each exchange that has one.
+	pub routers: Vec<(ExchangeId, EvmAddress)>,
+	/// The tracked pairs.
+	pub pairs: Vec<PairSnapshot>,
+}
+
+/// A tracked pair of a [`RegistrySnapshot`] with its settings.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode)]
+pub struct PairSnapshot {
+	/// The pair and its pools.
+	pub info: PairInfo,
+	/// See [`TwapChecks`](crate::TwapChecks).
+	pub twap_check: Option<TwapCheck>,
+	/// See [`FastPaths`](crate::FastPaths).
+	pub fast_path: Option<FastPath>,
+	/// See [`FailureModes`](crate::FailureModes).
+	pub failure_mode: FailureMode,
+	/// See [`CrossChainChecks`](crate::CrossChainChecks).
+	pub cross_chain_check: Option<CrossChainCheck>,
+	/// See [`PairSlas`](crate::PairSlas).
+	pub sla: Option<FreshnessSla>,
+	/// See [`LongTailPairs`](crate::LongTailPairs).
+	pub min_notional: Option<u128>,
+	/// See [`PoolHops`](crate::PoolHops).
+	pub hops: Vec<(ExchangeId, PoolConfig)>,
+}
+
+fn bounded<S: Get<u32>>(value: &str) -> BoundedVec<u8, S> {
+	BoundedVec::truncate_from(value.as_bytes().to_vec())
+}
+
+fn exchange(name: &str, chain_id: ChainId, protocol: ProtocolKind) -> ExchangeConfig {
+	ExchangeConfig {
+		name: bounded(name),
+		chain: ChainKind::Evm,
+		chain_id,
+		protocol,
+		enabled: true,
+		twap_window: None,
+		max_price_age: None,
+	}
+}
+
+fn pool(
+	address: &str,
+	token0_decimals: u8,
+	token1_decimals: u8,
+	base_is_token0: bool,
+) -> PoolConfig {
+	PoolConfig {
+		address: price_oracle_eip55::parse(address).expect("registry addresses are checksummed"),
+		metadata: PoolMetadata { token0_decimals, token1_decimals, base_is_token0 },
+		account: None,
+	}
+}
+
+fn solana_pool(
+	account: &str,
+	token0_decimals: u8,
+	token1_decimals: u8,
+	base_is_token0: bool,
+) -> PoolConfig {
+	PoolConfig {
+		address: EvmAddress::default(),
+		metadata: PoolMetadata { token0_decimals, token1_decimals, base_is_token0 },
+		account: Some(solana::parse_address(account).expect("registry accounts are base58")),
+	}
+}
+
+/// The exchanges known to the oracle, in the order the offchain worker queries them.
+pub fn default_exchanges() -> Vec<(ExchangeId, ExchangeConfig)> {
+	vec![
+		(0, exchange("Uniswap V3", 1, ProtocolKind::UniswapV3)),
+		(1, exchange("SushiSwap", 1, ProtocolKind::UniswapV2)),
+		(2, exchange("PancakeSwap", 56, ProtocolKind::UniswapV2)),
+		(3, exchange("QuickSwap", 137, ProtocolKind::UniswapV2)),
+		(4, exchange("Trader Joe", 43114, ProtocolKind::TraderJoe)),
+		(
+			5,
+			ExchangeConfig {
+				chain: ChainKind::Solana,
+				..exchange("Orca", solana::MAINNET, ProtocolKind::OrcaWhirlpool)
+			},
+		),
+		(
+			6,
+			// The feeds are updated on deviation or after an hour.
+			ExchangeConfig {
+				max_price_age: Some(60 * 60_000),
+				..exchange("Chainlink", 1, ProtocolKind::Chainlink)
+			},
+		),
+		// Rollups, read at their head like the other chains, see `ChainConfirmations`.
+		(7, exchange("Uniswap V3 Arbitrum", 42161, ProtocolKind::UniswapV3)),
+		(8, exchange("Uniswap V3 Optimism", 10, ProtocolKind::UniswapV3)),
+		(9, exchange("Uniswap V3 Base", 8453, ProtocolKind::UniswapV3)),
+	]
+}
+
+fn endpoints(urls: &[&str]) -> RpcUrls {
+	BoundedVec::truncate_from(urls.iter().map(|url| bounded(url)).collect())
+}
+
+/// Public JSON-RPC endpoints of the chains of [`default_exchanges`].
+pub fn default_rpc_endpoints() -> Vec<(ChainId, RpcUrls)> {
+	vec![
+		(1, endpoints(&["https://eth.llamarpc.com", "https://ethereum-rpc.publicnode.com"])),
+		(56, endpoints(&["https://bsc-dataseed.binance.org", "https://bsc-rpc.publicnode.com"])),
+		(137, endpoints(&["https://polygon-rpc.com", "https://polygon-bor-rpc.publicnode.com"])),
+		(
+			43114,
+			endpoints(&[
+				"https://api.avax.network/ext/bc/C/rpc",
+				"https://avalanche-c-chain-rpc.publicnode.com",
+			]),
+		),
+		(
+			42161,
+			endpoints(&["https://arb1.arbitrum.io/rpc", "https://arbitrum-one-rpc.publicnode.com"]),
+		),
+		(10, endpoints(&["https://mainnet.optimism.io", "https://optimism-rpc.publicnode.com"])),
+		(8453, endpoints(&["https://mainnet.base.org", "https://base-rpc.publicnode.com"])),
+		(
+			solana::MAINNET,
+			endpoints(&[
+				"https://api.mainnet-beta.solana.com",
+				"https://solana-rpc.publicnode.com",
+			]),
+		),
+	]
+}
+
+/// The pairs tracked on a fresh chain.
+pub fn default_pairs() -> Vec<PairInfo> {
+	vec![
+		PairInfo {
+			base: bounded("ETH"),
+			quote: bounded("USD"),
+			decimals: 8,
+			min_price: 1_000 * PRICE_UNIT,
+			max_price: 20_000 * PRICE_UNIT,
+			dynamic_bounds: None,
+			pools: BoundedVec::truncate_from(vec![
+				// Uniswap V3 USDC/WETH 0.05%
+				(0, pool("0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640", 6, 18, false)),
+				// SushiSwap USDC/WETH
+				(1, pool("0x397FF1542f962076d0BFE58eA045FfA2d347ACa0", 6, 18, false)),
+				// PancakeSwap ETH/USDC, both Binance-Peg tokens with 18 decimals.
+				(2, pool("0xEa26B78255Df2bBC31C1eBf60010D78670185bD0", 18, 18, true)),
+				// QuickSwap USDC/WETH
+				(3, pool("0x853Ee4b2A13f8a742d64C8F088bE7bA2131f670d", 6, 18, false)),
+				// Trader Joe WETH.e/WAVAX, routed through WAVAX/USDC, see `default_pool_hops`.
+				(4, pool("0xFE15c2695F1F920da45C30AAE47d11dE51007AF9", 18, 18, true)),
+				// Chainlink ETH/USD, answering with 8 decimals, see `ChainlinkProtocol`.
+				(6, pool("0x5f4eC3Df9cbd43714FE2740f5E3616155c5b8419", 0, 8, true)),
+				// Uniswap V3 WETH/USDC 0.05% on Arbitrum
+				(7, pool("0xC6962004f452bE9203591991D15f6b388e09E8D0", 18, 6, true)),
+				// Uniswap V3 WETH/USDC.e 0.05% on Optimism
+				(8, pool("0x1fb3cf6e48F1E7B10213E7b6d87D4c073C7Fdb7b", 18, 6, true)),
+				// Uniswap V3 WETH/USDC 0.05% on Base
+				(9, pool("0xd0b53D9277642d899DF5C87A3966A349A798F224", 18, 6, true)),
+			]),
+		},
+		PairInfo {
+			base: bounded("SOL"),
+			quote: bounded("USD"),
+			decimals: 8,
+			min_price: 10 * PRICE_UNIT,
+			max_price: 2_000 * PRICE_UNIT,
+			dynamic_bounds: None,
+			pools: BoundedVec::truncate_from(vec![
+				// Orca SOL/USDC Whirlpool, wrapped SOL with 9 decimals.
+				(5, solana_pool("HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ", 9, 6, true)),
+			]),
+		},
+		PairInfo {
+			base: bounded("BTC"),
+			quote: bounded("USD"),
+			decimals: 8,
+			min_price: 10_000 * PRICE_UNIT,
+			max_price: 500_000 * PRICE_UNIT,
+			dynamic_bounds: None,
+			pools: BoundedVec::truncate_from(vec![
+				// Uniswap V3 WBTC/USDC 0.3%
+				(0, pool("0x99ac8cA7087fA4A2A1FB6357269965A2014ABc35", 8, 6, true)),
+				// SushiSwap WBTC/WETH, routed through USDC/WETH, see `default_pool_hops`.
+				(1, pool("0xCEfF51756c56CeFFCA006cD410B03FFC46dd3a58", 8, 18, true)),
+				// PancakeSwap BTCB/WBNB, routed through WBNB/BUSD.
+				(2, pool("0x61EB789d75A95CAa3fF50ed7E47b96c132fEc082", 18, 18, true)),
+				// QuickSwap WBTC/USDC
+				(3, pool("0xF6a637525402643B0654a54bEAd2Cb9A83C8B498", 8, 6, true)),
+				// Chainlink BTC/USD
+				(6, pool("0xF4030086522a5bEEa4988F8cA5B36dbC97BeE88c", 0, 8, true)),
+			]),
+		},
+		PairInfo {
+			base: bounded("AVAX"),
+			quote: bounded("USD"),
+			decimals: 8,
+			min_price: PRICE_UNIT,
+			max_price: 1_000 * PRICE_UNIT,
+			dynamic_bounds: None,
+			pools: BoundedVec::truncate_from(vec![
+				// Trader Joe WAVAX/USDC
+				(4, pool("0xf4003F4efBE8691B60249E6afbD307aBE7758adb", 18, 6, true)),
+				// Chainlink AVAX/USD
+				(6, pool("0xFF3EEb22B5E3dE6e705b44749C2559d704923FD7", 0, 8, true)),
+			]),
+		},
+	]
+}
+
+/// The pools the pairs of [`default_pairs`] are routed through on exchanges without a USD pool
+/// of their own, by the ids of the pairs under the [`Blake2PairHasher`](crate::Blake2PairHasher).
+pub fn default_pool_hops() -> Vec<(PairId, ExchangeId, PoolConfig)> {
+	let (eth, btc) = (pair_id(b"ETH", b"USD"), pair_id(b"BTC", b"USD"));
+	vec![
+		// Trader Joe WAVAX/USDC
+		(eth, 4, pool("0xf4003F4efBE8691B60249E6afbD307aBE7758adb", 18, 6, true)),
+		// SushiSwap USDC/WETH
+		(btc, 1, pool("0x397FF1542f962076d0BFE58eA045FfA2d347ACa0", 6, 18, false)),
+		// PancakeSwap WBNB/BUSD
+		(btc, 2, pool("0x58F876857a02D6762E0101bb5C46A8c1ED44Dc16", 18, 18, true)),
+	]
+}
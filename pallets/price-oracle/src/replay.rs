@@ -0,0 +1,80 @@
+//! Replay of submitted prices from archived RPC responses.
+//!
+//! A price is only as trustworthy as the pool state it was read from. Given the raw JSON-RPC
+//! response an operator received for the price call of a pool, or the read of its account on
+//! Solana, and the configuration of the exchange and pair at that block, [`replay_price`]
+//! recomputes the price exactly as the offchain worker did, down to the precision it was
+//! submitted with, and [`verify`] checks it against the price stored in
+//! [`PriceData`](crate::PriceData). Disputes and external audits are built on it.
+//!
+//! Only available with `std`: replays run on the auditor's machine, not in the runtime.
+
+use crate::{
+	error::OracleError,
+	exchanges::{ExchangeConfig, PoolConfig},
+	fixed::{self, Price},
+	json::{self, RpcResponse},
+	protocols::DexProtocol,
+	rounding::RoundingPolicy,
+	types::PairInfo,
+};
+
+/// Why a submitted price doesn't replay.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ReplayError {
+	/// The archived response yields no price, as it failed the offchain worker.
+	Fetch(OracleError),
+	/// The archived response yields another price than the stored one.
+	Mismatch {
+		/// Price recomputed from the response.
+		replayed: Price,
+		/// Price stored on-chain.
+		stored: Price,
+	},
+}
+
+/// Price of `pair` on `exchange` read from `pool` in `response`, the archived response to the
+/// price call of the pool's protocol or to the `getAccountInfo` of its account, as it is stored
+/// once submitted with the pair's precision under the runtime's `policy`.
+///
+/// The request id of the response isn't checked: archives keep responses to requests made long
+/// ago, whose ids no longer mean anything.
+pub fn replay_price(
+	exchange: &ExchangeConfig,
+	pair: &PairInfo,
+	pool: &PoolConfig,
+	response: &[u8],
+	policy: RoundingPolicy,
+) -> Result<Price, OracleError> {
+	let result = RpcResponse::parse(response)?.into_result()?;
+	let data = match pool.account {
+		Some(_) => json::account_info_data(&result)?,
+		None => json::hex_bytes(&result)?,
+	};
+	let price = exchange.protocol.parse_price(&data, &pool.metadata)?;
+	if !pair.in_bounds(price) {
+		return Err(OracleError::PriceOutOfBounds { value: price })
+	}
+	// Cut to the submitted precision, like the offchain worker does.
+	let exponent = pair.decimals.min(fixed::PRICE_DECIMALS);
+	let submitted = fixed::to_precision(price, exponent, policy.rounding);
+	fixed::normalize(submitted, exponent).ok_or(OracleError::DecodeError)
+}
+
+/// Check that `stored`, a price in [`PriceData`](crate::PriceData), is the one [`replay_price`]
+/// recomputes from `response`.
+pub fn verify(
+	exchange: &ExchangeConfig,
+	pair: &PairInfo,
+	pool: &PoolConfig,
+	response: &[u8],
+	policy: RoundingPolicy,
+	stored: Price,
+) -> Result<(), ReplayError> {
+	let replayed =
+		replay_price(exchange, pair, pool, response, policy).map_err(ReplayError::Fetch)?;
+	match replayed == stored {
+		true => Ok(()),
+		false => Err(ReplayError::Mismatch { replayed, stored }),
+	}
+}
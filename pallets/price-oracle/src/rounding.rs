@@ -0,0 +1,81 @@
+//! Rounding of the divisions prices go through.
+//!
+//! Pool state is converted into a [`Price`](crate::Price) at the full
+//! [`PRICE_DECIMALS`](crate::fixed::PRICE_DECIMALS) precision, where the truncated remainder is
+//! below any pair's precision. Precision is lost further down: when a price is cut to the
+//! decimals of its pair for submission, and when prices are averaged into means, TWAPs and the
+//! median of an even number of sources. Those steps round by the runtime's [`RoundingPolicy`],
+//! see [`Config::Rounding`](crate::Config::Rounding).
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+
+/// How a quotient is rounded.
+#[derive(
+	Clone, Copy, Default, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen,
+)]
+pub enum Rounding {
+	/// Towards zero.
+	#[default]
+	Floor,
+	/// To the nearest value, halves away from zero.
+	HalfUp,
+	/// To the nearest value, halves to the even one, so ties don't drift in one direction.
+	HalfEven,
+}
+
+impl Rounding {
+	/// `quotient` rounded by the `remainder` it left when divided by `divisor`, saturating.
+	pub fn round(self, quotient: u128, remainder: u128, divisor: u128) -> u128 {
+		// `2 * remainder` compared with `divisor`, without overflowing.
+		let half = remainder.cmp(&(divisor - remainder));
+		let up = match self {
+			Rounding::Floor => false,
+			Rounding::HalfUp => half.is_ge(),
+			Rounding::HalfEven => half.is_gt() || (half.is_eq() && quotient % 2 == 1),
+		};
+		match up && remainder > 0 {
+			true => quotient.saturating_add(1),
+			false => quotient,
+		}
+	}
+
+	/// `numerator / divisor`, rounded. `divisor` must be non-zero.
+	pub fn divide(self, numerator: u128, divisor: u128) -> u128 {
+		self.round(numerator / divisor, numerator % divisor, divisor)
+	}
+}
+
+/// Which price the median of an even number of prices is.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum MedianTie {
+	/// The mean of the two middle prices.
+	Mean,
+	/// The lower of the two middle prices.
+	Lower,
+	/// The higher of the two middle prices.
+	Upper,
+}
+
+/// The rounding rules of a runtime.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct RoundingPolicy {
+	/// Rounding of quotients: prices cut to the precision of their pair, means, TWAPs and the
+	/// mean of the middle prices of a median.
+	pub rounding: Rounding,
+	/// The median of an even number of prices.
+	pub median_tie: MedianTie,
+}
+
+impl RoundingPolicy {
+	/// Truncate everything and take the mean of the middle prices, the pallet's rules before
+	/// they were configurable.
+	pub const FLOOR: Self = Self { rounding: Rounding::Floor, median_tie: MedianTie::Mean };
+}
+
+impl Default for RoundingPolicy {
+	fn default() -> Self {
+		Self::FLOOR
+	}
+}
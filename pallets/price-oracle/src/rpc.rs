@@ -0,0 +1,250 @@
+//! Construction of JSON-RPC request bodies.
+//!
+//! Every request the offchain worker sends to an EVM or Solana endpoint is built here and
+//! serialized with [`lite_json`], which takes care of escaping, instead of being assembled from
+//! string templates.
+
+use crate::types::{CallOptions, Confirmation, EvmAddress, SolanaAddress};
+use alloc::{format, string::String, vec, vec::Vec};
+use lite_json::{JsonValue, NumberValue, Serialize};
+
+/// Block a read is executed against.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlockTag {
+	Latest,
+	Safe,
+	Finalized,
+	Number(u64),
+}
+
+impl BlockTag {
+	fn to_json(self) -> JsonValue {
+		match self {
+			BlockTag::Latest => string("latest"),
+			BlockTag::Safe => string("safe"),
+			BlockTag::Finalized => string("finalized"),
+			BlockTag::Number(n) => string(&quantity(n)),
+		}
+	}
+}
+
+impl From<Confirmation> for BlockTag {
+	fn from(confirmation: Confirmation) -> Self {
+		match confirmation {
+			Confirmation::Latest => BlockTag::Latest,
+			Confirmation::Safe => BlockTag::Safe,
+			Confirmation::Finalized => BlockTag::Finalized,
+		}
+	}
+}
+
+/// A filter of the accounts returned by `getProgramAccounts`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AccountFilter<'a> {
+	/// Accounts whose data is this many bytes long.
+	DataSize(u64),
+	/// Accounts whose data holds `bytes` at `offset`.
+	Memcmp { offset: u64, bytes: &'a [u8] },
+}
+
+impl AccountFilter<'_> {
+	fn to_json(self) -> JsonValue {
+		match self {
+			AccountFilter::DataSize(len) =>
+				JsonValue::Object(vec![(chars("dataSize"), number(len))]),
+			AccountFilter::Memcmp { offset, bytes } => {
+				let memcmp = vec![
+					(chars("offset"), number(offset)),
+					(chars("bytes"), string(&bs58::encode(bytes).into_string())),
+				];
+				JsonValue::Object(vec![(chars("memcmp"), JsonValue::Object(memcmp))])
+			},
+		}
+	}
+}
+
+/// A single JSON-RPC 2.0 request.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RequestBuilder {
+	id: u64,
+	method: &'static str,
+	params: Vec<JsonValue>,
+}
+
+impl RequestBuilder {
+	/// A request calling `method` without parameters.
+	pub fn new(method: &'static str) -> Self {
+		RequestBuilder { id: 1, method, params: Vec::new() }
+	}
+
+	/// Set the id the response will echo back. Defaults to 1.
+	pub fn id(mut self, id: u64) -> Self {
+		self.id = id;
+		self
+	}
+
+	/// Append a positional parameter.
+	pub fn param(mut self, param: JsonValue) -> Self {
+		self.params.push(param);
+		self
+	}
+
+	/// `eth_call` of `data` against the contract at `to`, which is sent EIP-55 checksummed.
+	pub fn eth_call(to: &EvmAddress, data: &str, block: BlockTag) -> Self {
+		Self::eth_call_with(to, data, block, &CallOptions::default())
+	}
+
+	/// [`Self::eth_call`] with the gas limit and the state overrides of `options`, which are
+	/// left out where they aren't set.
+	pub fn eth_call_with(
+		to: &EvmAddress,
+		data: &str,
+		block: BlockTag,
+		options: &CallOptions,
+	) -> Self {
+		let to = price_oracle_eip55::encode(to);
+		let mut call = vec![(chars("to"), string(to.as_str())), (chars("data"), string(data))];
+		if let Some(gas) = options.gas {
+			call.push((chars("gas"), string(&quantity(gas))));
+		}
+		let request = Self::new("eth_call").param(JsonValue::Object(call)).param(block.to_json());
+		if options.state_overrides.is_empty() {
+			return request
+		}
+		let overrides = options
+			.state_overrides
+			.iter()
+			.map(|state| {
+				let mut fields = Vec::new();
+				if let Some(code) = &state.code {
+					fields.push((chars("code"), string(&hex(code))));
+				}
+				if !state.state_diff.is_empty() {
+					let slots = state
+						.state_diff
+						.iter()
+						.map(|(slot, value)| (chars(&hex(slot)), string(&hex(value))))
+						.collect();
+					fields.push((chars("stateDiff"), JsonValue::Object(slots)));
+				}
+				(
+					chars(price_oracle_eip55::encode(&state.address).as_str()),
+					JsonValue::Object(fields),
+				)
+			})
+			.collect();
+		request.param(JsonValue::Object(overrides))
+	}
+
+	/// `eth_blockNumber`.
+	pub fn eth_block_number() -> Self {
+		Self::new("eth_blockNumber")
+	}
+
+	/// `eth_gasPrice`.
+	pub fn eth_gas_price() -> Self {
+		Self::new("eth_gasPrice")
+	}
+
+	/// `eth_feeHistory` over the `block_count` blocks up to `newest`, sampling the given priority
+	/// fee percentiles.
+	pub fn eth_fee_history(block_count: u64, newest: BlockTag, percentiles: &[u8]) -> Self {
+		let percentiles = percentiles.iter().map(|p| number(u64::from(*p))).collect();
+		Self::new("eth_feeHistory")
+			.param(string(&quantity(block_count)))
+			.param(newest.to_json())
+			.param(JsonValue::Array(percentiles))
+	}
+
+	/// `eth_getBlockByNumber`, with full transactions if `full`.
+	pub fn eth_get_block_by_number(block: BlockTag, full: bool) -> Self {
+		Self::new("eth_getBlockByNumber")
+			.param(block.to_json())
+			.param(JsonValue::Boolean(full))
+	}
+
+	/// Solana `getAccountInfo` of `account`, with its data base64 encoded.
+	pub fn get_account_info(account: &SolanaAddress) -> Self {
+		Self::new("getAccountInfo")
+			.param(string(&bs58::encode(account).into_string()))
+			.param(account_config(&[]))
+	}
+
+	/// Solana `getProgramAccounts` of the accounts owned by `program` passing all of `filters`,
+	/// with their data base64 encoded.
+	pub fn get_program_accounts(program: &SolanaAddress, filters: &[AccountFilter]) -> Self {
+		Self::new("getProgramAccounts")
+			.param(string(&bs58::encode(program).into_string()))
+			.param(account_config(filters))
+	}
+
+	/// Solana `getSlot`.
+	pub fn get_slot() -> Self {
+		Self::new("getSlot")
+	}
+
+	/// Id of the request.
+	pub fn request_id(&self) -> u64 {
+		self.id
+	}
+
+	/// The request as a JSON object.
+	pub fn to_json(&self) -> JsonValue {
+		JsonValue::Object(vec![
+			(chars("jsonrpc"), string("2.0")),
+			(chars("method"), string(self.method)),
+			(chars("params"), JsonValue::Array(self.params.clone())),
+			(chars("id"), number(self.id)),
+		])
+	}
+
+	/// Serialized request body.
+	pub fn build(&self) -> Vec<u8> {
+		self.to_json().serialize()
+	}
+
+	/// Serialized body of a batch sending all `requests` in one HTTP request.
+	pub fn batch(requests: &[RequestBuilder]) -> Vec<u8> {
+		JsonValue::Array(requests.iter().map(Self::to_json).collect()).serialize()
+	}
+}
+
+/// `0x` prefixed hex encoding of a quantity, as expected by Ethereum JSON-RPC.
+pub fn quantity(n: u64) -> String {
+	format!("{:#x}", n)
+}
+
+/// Configuration of a Solana account read: base64 encoded data, at the `confirmed` commitment the
+/// way EVM reads are made against the latest block, of the accounts passing `filters`.
+fn account_config(filters: &[AccountFilter]) -> JsonValue {
+	let mut config =
+		vec![(chars("encoding"), string("base64")), (chars("commitment"), string("confirmed"))];
+	if !filters.is_empty() {
+		let filters = filters.iter().map(|filter| filter.to_json()).collect();
+		config.push((chars("filters"), JsonValue::Array(filters)));
+	}
+	JsonValue::Object(config)
+}
+
+/// `0x` prefixed hex encoding of bytes.
+fn hex(bytes: &[u8]) -> String {
+	array_bytes::bytes2hex("0x", bytes)
+}
+
+fn chars(s: &str) -> Vec<char> {
+	s.chars().collect()
+}
+
+fn string(s: &str) -> JsonValue {
+	JsonValue::String(chars(s))
+}
+
+fn number(n: u64) -> JsonValue {
+	JsonValue::Number(NumberValue {
+		integer: n,
+		fraction: 0,
+		fraction_length: 0,
+		exponent: 0,
+		negative: false,
+	})
+}
@@ -0,0 +1,107 @@
+//! Price pushes to consumers on other consensus systems.
+//!
+//! Consumers such as sibling parachains `Transact`ing over XCM either request a price once with
+//! [`Pallet::request_price_xcm`] or subscribe to a pair with [`Pallet::subscribe_price`]. A
+//! subscription is pushed the new aggregated price of its pair whenever the median moves at
+//! least its [`PriceSubscription::threshold_bps`] away from the last price pushed to it. Prices
+//! travel through the [`PriceMessenger`] of the runtime, see [`Config::PriceMessenger`].
+
+use crate::{
+	aggregation, Bootstrapping, Config, Event, PairId, Pallet, PriceAggregate, PriceSubscription,
+	PriceSubscriptions, ProvidedPrice, LOG_TARGET,
+};
+use frame_support::BoundedVec;
+
+/// Sends prices of the oracle to consumers on other consensus systems, e.g. to sibling
+/// parachains in XCM `QueryResponse`s, for [`Pallet::request_price_xcm`] and the
+/// [`PriceSubscriptions`] of each pair.
+pub trait PriceMessenger<Subscriber> {
+	/// Send `price`, the [`PriceProvider::price`](crate::PriceProvider::price) of `pair_id`, to
+	/// `dest` in response to its query `query_id`.
+	fn send_price(
+		dest: &Subscriber,
+		query_id: u64,
+		pair_id: PairId,
+		price: ProvidedPrice,
+	) -> sp_runtime::DispatchResult;
+
+	/// Worst case weight of [`Self::send_price`].
+	fn send_price_weight() -> frame_support::weights::Weight;
+}
+
+/// No messaging: every price sent fails, for runtimes without cross-chain consumers.
+impl<Subscriber> PriceMessenger<Subscriber> for () {
+	fn send_price(
+		_dest: &Subscriber,
+		_query_id: u64,
+		_pair_id: PairId,
+		_price: ProvidedPrice,
+	) -> sp_runtime::DispatchResult {
+		Err(sp_runtime::DispatchError::Unavailable)
+	}
+
+	fn send_price_weight() -> frame_support::weights::Weight {
+		frame_support::weights::Weight::zero()
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Push the price of `pair_id` at `aggregate`, its new aggregated price, to the
+	/// subscriptions its median moved far enough from their last pushed price. A
+	/// subscription whose push fails is tried again after the next aggregation.
+	///
+	/// Reads the price from `aggregate` rather than through
+	/// [`PriceProvider::price`](crate::PriceProvider::price), so pushes don't count in
+	/// [`PairReads`](crate::PairReads).
+	pub(crate) fn push_subscriptions(pair_id: PairId, aggregate: &PriceAggregate) {
+		let mut subscriptions = PriceSubscriptions::<T>::get(pair_id);
+		if subscriptions.is_empty() || Bootstrapping::<T>::contains_key(pair_id) {
+			return
+		}
+		let price = match Self::provided_price(pair_id, aggregate) {
+			Ok(price) => price,
+			Err(e) => {
+				log::warn!(target: LOG_TARGET, "Failed to push the price of a pair: {e:?}");
+				return
+			},
+		};
+		let median = aggregate.median;
+		let mut pushed = false;
+		for subscription in subscriptions.iter_mut() {
+			let due = subscription.last.is_none_or(|last| {
+				aggregation::deviation_bps(median, last) >= subscription.threshold_bps
+			});
+			if !due {
+				continue
+			}
+			let PriceSubscription { subscriber, query_id, .. } = &*subscription;
+			let sent = T::PriceMessenger::send_price(subscriber, *query_id, pair_id, price);
+			if let Err(e) = sent {
+				log::warn!(target: LOG_TARGET, "Failed to push the price of a pair: {e:?}");
+				continue
+			}
+			subscription.last = Some(median);
+			pushed = true;
+			Self::deposit_event(Event::PricePushed {
+				subscriber: subscription.subscriber.clone(),
+				pair_id,
+				price: median,
+			});
+		}
+		if pushed {
+			PriceSubscriptions::<T>::insert(pair_id, subscriptions);
+		}
+	}
+
+	/// Store the subscriptions to `pair_id`, removing the entry once none are left.
+	pub(crate) fn store_subscriptions(
+		pair_id: PairId,
+		subscriptions: BoundedVec<PriceSubscription<T::Subscriber>, T::MaxSubscriptionsPerPair>,
+	) {
+		if subscriptions.is_empty() {
+			PriceSubscriptions::<T>::remove(pair_id);
+		} else {
+			PriceSubscriptions::<T>::insert(pair_id, subscriptions);
+		}
+	}
+}
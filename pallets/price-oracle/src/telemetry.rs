@@ -0,0 +1,97 @@
+//! Counters of the offchain worker in the node's persistent offchain storage.
+//!
+//! Every run adds its fetches and their failures per exchange, its submitted transactions and the
+//! latency of each fetch to the [`OcwTelemetry`] under [`TELEMETRY_KEY`]. The counters only grow,
+//! so a node-side exporter can read them whenever it likes and turn them into Prometheus
+//! counters; the latencies are kept as the last [`MAX_LATENCIES`] samples together with the number
+//! of samples ever taken, so the exporter observes each sample once.
+
+use crate::exchanges::ExchangeId;
+use alloc::{collections::BTreeMap, vec::Vec};
+use codec::{Decode, Encode};
+use sp_runtime::{offchain::storage::StorageValueRef, traits::Saturating, RuntimeDebug};
+
+/// Key of the [`OcwTelemetry`] of the node in the persistent offchain storage.
+///
+/// The value is the SCALE encoded [`OcwTelemetry`].
+pub const TELEMETRY_KEY: &[u8] = b"price-oracle/telemetry";
+
+/// Number of fetch latencies kept in [`OcwTelemetry::latencies`], enough to cover the fetches of
+/// an offchain worker run.
+pub const MAX_LATENCIES: usize = 64;
+
+/// Fetches of the prices of one exchange.
+#[derive(Clone, Copy, Default, PartialEq, Eq, RuntimeDebug, Encode, Decode)]
+pub struct VenueCounters {
+	/// Fetches started, whatever their outcome.
+	pub fetches: u64,
+	/// Fetches that returned no usable price.
+	pub failures: u64,
+}
+
+/// What the offchain worker of the node did since its offchain storage was created.
+#[derive(Clone, Default, PartialEq, Eq, RuntimeDebug, Encode, Decode)]
+pub struct OcwTelemetry {
+	/// Fetches of each exchange.
+	pub venues: BTreeMap<ExchangeId, VenueCounters>,
+	/// Transactions handed to the transaction pool.
+	pub submissions: u64,
+	/// Transactions that couldn't be signed or were refused by the transaction pool.
+	pub failed_submissions: u64,
+	/// The latest fetch latencies as `(exchange, milliseconds)`, oldest first.
+	pub latencies: Vec<(ExchangeId, u64)>,
+	/// Number of latencies ever recorded, of which the last ones are in [`Self::latencies`].
+	pub latency_samples: u64,
+}
+
+impl OcwTelemetry {
+	/// The latencies recorded after the first `seen` samples, as far as they are still kept.
+	pub fn latencies_since(&self, seen: u64) -> &[(ExchangeId, u64)] {
+		let new = self.latency_samples.saturating_sub(seen).min(self.latencies.len() as u64);
+		&self.latencies[self.latencies.len() - new as usize..]
+	}
+}
+
+/// The telemetry recorded by the offchain worker of this node.
+pub fn read() -> OcwTelemetry {
+	StorageValueRef::persistent(TELEMETRY_KEY)
+		.get::<OcwTelemetry>()
+		.ok()
+		.flatten()
+		.unwrap_or_default()
+}
+
+/// Record a fetch of `exchange_id` that took `millis` milliseconds and returned a usable price
+/// or not.
+pub fn record_fetch(exchange_id: ExchangeId, millis: u64, succeeded: bool) {
+	update(|telemetry| {
+		let venue = telemetry.venues.entry(exchange_id).or_default();
+		venue.fetches.saturating_inc();
+		if !succeeded {
+			venue.failures.saturating_inc();
+		}
+		if telemetry.latencies.len() >= MAX_LATENCIES {
+			telemetry.latencies.remove(0);
+		}
+		telemetry.latencies.push((exchange_id, millis));
+		telemetry.latency_samples.saturating_inc();
+	})
+}
+
+/// Record a transaction handed to the transaction pool, or that failed to be.
+pub fn record_submission(submitted: bool) {
+	update(|telemetry| match submitted {
+		true => telemetry.submissions.saturating_inc(),
+		false => telemetry.failed_submissions.saturating_inc(),
+	})
+}
+
+fn update(f: impl FnOnce(&mut OcwTelemetry)) {
+	let _ = StorageValueRef::persistent(TELEMETRY_KEY).mutate(
+		|telemetry: Result<Option<OcwTelemetry>, _>| {
+			let mut telemetry = telemetry.ok().flatten().unwrap_or_default();
+			f(&mut telemetry);
+			Ok::<_, ()>(telemetry)
+		},
+	);
+}
@@ -0,0 +1,5864 @@
+use crate::{
+	aggregation, arbitrage, budget,
+	chains::multicall,
+	endpoints,
+	exchanges::{ExchangeConfig, ExchangeId, PoolConfig},
+	fixed::{self, PRICE_UNIT},
+	gas,
+	json::{self, RpcError, RpcResponse},
+	mock::*,
+	pair_id, pair_lock_key, pool_cache, proofs,
+	protocols::{
+		DexProtocol, OrcaWhirlpoolProtocol, ProtocolKind, TraderJoeProtocol, UniswapV2Protocol,
+		UniswapV3Protocol,
+	},
+	proxy::{self, Proxy},
+	registry,
+	rounding::{MedianTie, Rounding, RoundingPolicy},
+	rpc::{BlockTag, RequestBuilder},
+	rpc_override_key,
+	telemetry::{self, VenueCounters},
+	types::{CallOptions, PairInfo, PoolMetadata, StateOverride},
+	AggregatedPrice, AggregationDeferrals, AlertDeposits, Alerts, ArbitrageOpportunities,
+	ArbitrageOpportunity, BestVenues, BootstrapProgress, Bootstrapping, BreachedSlas,
+	BuilderTipChains, Call, ChainAggregates, ChainConfirmations, Confirmation, CrossChainCheck,
+	CrossChainChecks, DeltaHistory, DeprecatedPairs, DynamicBounds, Error, Event, ExchangeKeys,
+	ExchangeStats, Exchanges, FailureMode, FailureModes, FastPath, FastPathUntil, FastPaths,
+	FreshnessSla, GasData, GasFees, LatestAggregates, LongTailPairs, LowQualityPairs,
+	MulticallChains, NativeTokens, OperatorBonds, OracleError, OracleStatus, OutlierCounts,
+	PairHasher, PairId, PairReads, PairSlas, PendingAggregation, PoolHops, PoolNotionals,
+	PriceAggregate, PriceCommitments, PriceData, PriceGuard, PriceGuards, PriceHistory,
+	PriceProvider, PriceSubscriptions, ProtocolCallOptions, ProvidedPrice, QuarantinedExchanges,
+	ReadCounter, ReliabilityStats, RepeatedPrices, Routers, RpcEndpoints, RpcUrls,
+	SignedSubmissions, SlaBreach, SourceBlocks, SubmissionSummaries, SubmissionSummary,
+	TrackedPairs, TwapCheck, TwapChecks, VenueQuote, VerifiedPrice, VerifiedPrices,
+	INVALID_EXPONENT, PRICE_OUT_OF_BOUNDS, UNKNOWN_EXCHANGE, UNTRACKED_PAIR,
+};
+use codec::{Decode, Encode};
+use frame_support::{assert_noop, assert_ok, traits::Hooks, BoundedVec};
+use sp_core::{
+	offchain::{
+		testing::{PendingRequest, TestOffchainExt, TestTransactionPoolExt},
+		OffchainDbExt, OffchainWorkerExt, Timestamp, TransactionPoolExt,
+	},
+	H256, U256,
+};
+use sp_runtime::{
+	generic::Preamble,
+	offchain::{
+		storage_lock::{BlockAndTime, StorageLock},
+		Duration,
+	},
+	testing::UintAuthorityId,
+	traits::ValidateUnsigned,
+	transaction_validity::{InvalidTransaction, TransactionSource},
+	DispatchError, Permill,
+};
+
+/// `slot0()` return data of the USDC/WETH pool with ETH at exactly 2500 USD.
+const SLOT0_ETH_2500: &str = "0x0000000000000000000000000000000000004e20000000000000000000000000";
+
+/// `getReserves()` return data of a USDC/WETH pool with ETH at exactly 2000 USD.
+const RESERVES_ETH_2000: &str = concat!(
+	"0x000000000000000000000000000000000000000000000000000001d1a94a2000",
+	"00000000000000000000000000000000000000000000003635c9adc5dea00000",
+);
+
+/// Square root of the price of SOL in USDC of a Whirlpool with SOL at 150 USD, as Q64.64.
+const SQRT_PRICE_SOL_150: u128 = 7_144_393_258_922_745_605;
+
+/// Data of a Whirlpool account at `sqrt_price` with `liquidity` in range.
+fn whirlpool_account(sqrt_price: u128, liquidity: u128) -> Vec<u8> {
+	use crate::protocols::orca;
+
+	let mut data = vec![0; orca::ACCOUNT_LEN];
+	data[..8].copy_from_slice(&orca::DISCRIMINATOR);
+	data[orca::LIQUIDITY..][..16].copy_from_slice(&liquidity.to_le_bytes());
+	data[orca::SQRT_PRICE..][..16].copy_from_slice(&sqrt_price.to_le_bytes());
+	data
+}
+
+/// Base64 encoding of `data`, as Solana endpoints return account data.
+fn base64(data: &[u8]) -> String {
+	use base64::Engine;
+	base64::engine::general_purpose::STANDARD.encode(data)
+}
+
+fn eth_usd() -> PairId {
+	pair_id(b"ETH", b"USD")
+}
+
+fn sol_usd() -> PairId {
+	pair_id(b"SOL", b"USD")
+}
+
+/// Block of the `round`-th interval the offchain worker fetches ETH/USD in.
+fn fetch_block(round: u32) -> u64 {
+	(round * UPDATE_INTERVAL + PriceOracle::fetch_phase(eth_usd())).into()
+}
+
+fn rpc_response(result: &str) -> Vec<u8> {
+	format!(r#"{{"jsonrpc":"2.0","id":1,"result":"{}"}}"#, result).into_bytes()
+}
+
+#[test]
+fn submit_price_unsigned_stores_price() {
+	new_test_ext().execute_with(|| {
+		let pair_hash = eth_usd();
+		assert_ok!(PriceOracle::submit_price_unsigned(
+			RuntimeOrigin::none(),
+			pair_hash,
+			0,
+			2_500_000_000,
+			6,
+			42,
+			None,
+			None,
+		));
+
+		let price = 2500 * PRICE_UNIT;
+		System::assert_last_event(
+			Event::PriceSubmitted { pair_id: pair_hash, exchange_id: 0, price, timestamp: 42 }
+				.into(),
+		);
+		assert_eq!(PriceData::<Test>::get(pair_hash, 0), Some((price, 42)));
+		assert_eq!(PriceOracle::get_price(eth_usd(), 0), Some((price, 42)));
+		assert_eq!(PriceOracle::get_all_prices(eth_usd()), vec![(0, price, 42)]);
+	});
+}
+
+#[test]
+fn prices_are_aggregated_at_the_end_of_the_block() {
+	new_test_ext().execute_with(|| {
+		for (exchange_id, price, timestamp) in [(0, 2_000, 10), (1, 2_600, 30), (2, 2_100, 20)] {
+			assert_ok!(PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				exchange_id,
+				price,
+				0,
+				timestamp,
+				None,
+				None,
+			));
+		}
+		assert_eq!(PriceOracle::get_aggregated_price(eth_usd()), None);
+
+		PriceOracle::on_finalize(1);
+		// Exchanges 0 and 1 are on chain 1, exchange 2 on chain 56: the chains are aggregated
+		// first, then their medians.
+		let chain_1 = PriceAggregate {
+			median: 2_300 * PRICE_UNIT,
+			mean: 2_300 * PRICE_UNIT,
+			sources: 2,
+			timestamp: 30,
+		};
+		let chain_56 = PriceAggregate {
+			median: 2_100 * PRICE_UNIT,
+			mean: 2_100 * PRICE_UNIT,
+			sources: 1,
+			timestamp: 20,
+		};
+		assert_eq!(PriceOracle::chain_prices(eth_usd()), vec![(1, chain_1), (56, chain_56)]);
+		let aggregate = PriceAggregate {
+			median: 2_200 * PRICE_UNIT,
+			mean: 2_200 * PRICE_UNIT,
+			sources: 3,
+			timestamp: 30,
+		};
+		assert_eq!(PriceOracle::get_aggregated_price(eth_usd()), Some(aggregate));
+		System::assert_last_event(
+			Event::PriceAggregated {
+				pair_id: eth_usd(),
+				median: aggregate.median,
+				mean: aggregate.mean,
+				sources: 3,
+			}
+			.into(),
+		);
+		assert_eq!(PendingAggregation::<Test>::iter().count(), 0);
+		assert_eq!(
+			PriceOracle::best_venues(eth_usd()),
+			Some(BestVenues {
+				ask: VenueQuote { exchange_id: 0, price: 2_000 * PRICE_UNIT, timestamp: 10 },
+				bid: VenueQuote { exchange_id: 1, price: 2_600 * PRICE_UNIT, timestamp: 30 },
+				spread_bps: 3_000,
+			})
+		);
+		set_now(MAX_PRICE_AGE + 11);
+		assert_eq!(PriceOracle::best_venues(eth_usd()), None);
+		set_now(0);
+
+		// Bounded reads stop after `limit` exchanges.
+		assert_eq!(PriceOracle::get_all_prices(eth_usd()).len(), 3);
+		assert_eq!(PriceOracle::get_prices(eth_usd(), 2).len(), 2);
+		assert_eq!(PriceOracle::get_prices(eth_usd(), 0), vec![]);
+
+		// Only pairs with new prices are aggregated again.
+		System::reset_events();
+		PriceOracle::on_finalize(2);
+		assert!(System::events().is_empty());
+	});
+}
+
+#[test]
+fn chainlink_feeds_flag_deviating_exchange_prices() {
+	use crate::protocols::ChainlinkProtocol;
+
+	// latestRoundData() of round 7, answered in `answered_in_round`, with 8 decimals.
+	let round_data = |answer: i128, answered_in_round: u8| {
+		let mut data = [0u8; 160];
+		data[31] = 7;
+		data[32..64].copy_from_slice(&U256::from(answer.unsigned_abs()).to_big_endian());
+		if answer < 0 {
+			let word = !U256::from_big_endian(&data[32..64]) + U256::one();
+			data[32..64].copy_from_slice(&word.to_big_endian());
+		}
+		data[159] = answered_in_round;
+		data
+	};
+	let feed = registry::default_pairs()[0].pool(6).unwrap().metadata;
+	let chainlink = ChainlinkProtocol;
+	assert_eq!(
+		chainlink.parse_price(&round_data(250_000_000_000, 7), &feed),
+		Ok(2_500 * PRICE_UNIT)
+	);
+	assert_eq!(
+		chainlink.parse_price(&round_data(250_000_000_000, 6), &feed),
+		Err(OracleError::StaleData)
+	);
+	assert_eq!(chainlink.parse_price(&round_data(-1, 7), &feed), Err(OracleError::DecodeError));
+	assert_eq!(chainlink.parse_price(&round_data(0, 7), &feed), Err(OracleError::DecodeError));
+
+	new_test_ext().execute_with(|| {
+		PriceData::<Test>::insert(eth_usd(), 0, (1_900 * PRICE_UNIT, 10));
+		PriceData::<Test>::insert(eth_usd(), 1, (2_050 * PRICE_UNIT, 10));
+		PriceData::<Test>::insert(eth_usd(), 6, (2_100 * PRICE_UNIT, 10));
+		let aggregate = PriceOracle::aggregate_prices(eth_usd()).unwrap();
+
+		// The feed is a reference, not a source.
+		assert_eq!(aggregate.sources, 2);
+		assert_eq!(aggregate.median, 1_975 * PRICE_UNIT);
+		assert_eq!(PriceOracle::best_venues(eth_usd()).unwrap().bid.exchange_id, 1);
+		// 9.5% off the feed is flagged, 2.4% isn't.
+		System::assert_has_event(
+			Event::ReferenceDeviation {
+				pair_id: eth_usd(),
+				exchange_id: 0,
+				price: 1_900 * PRICE_UNIT,
+				reference: 2_100 * PRICE_UNIT,
+				deviation_bps: 952,
+			}
+			.into(),
+		);
+		let flagged = System::events()
+			.iter()
+			.filter(|record| {
+				matches!(record.event, RuntimeEvent::PriceOracle(Event::ReferenceDeviation { .. }))
+			})
+			.count();
+		assert_eq!(flagged, 1);
+	});
+}
+
+#[test]
+fn arbitrage_opportunities_are_detected_across_exchanges() {
+	new_test_ext().execute_with(|| {
+		let aggregate = |prices: [(u8, u128, u64); 2]| {
+			for (exchange_id, price, timestamp) in prices {
+				PriceData::<Test>::insert(eth_usd(), exchange_id, (price * PRICE_UNIT, timestamp));
+			}
+			PriceOracle::aggregate_prices(eth_usd());
+		};
+
+		// 15% apart, over the 10% of the mock runtime.
+		aggregate([(0, 2_000, 10), (1, 2_300, 20)]);
+		let opportunity = ArbitrageOpportunity {
+			buy_exchange: 0,
+			sell_exchange: 1,
+			spread_bps: 1_500,
+			timestamp: 10,
+		};
+		assert_eq!(ArbitrageOpportunities::<Test>::get(eth_usd()), Some(opportunity));
+		assert_eq!(PriceOracle::arbitrage_opportunity(eth_usd()), Some(opportunity));
+		System::assert_has_event(
+			Event::ArbitrageOpportunity {
+				pair_id: eth_usd(),
+				buy_exchange: 0,
+				sell_exchange: 1,
+				spread_bps: 1_500,
+				timestamp: 10,
+			}
+			.into(),
+		);
+		set_now(MAX_PRICE_AGE + 11);
+		assert_eq!(PriceOracle::arbitrage_opportunity(eth_usd()), None);
+		set_now(0);
+
+		// The other way round.
+		aggregate([(0, 2_400, 30), (1, 2_000, 30)]);
+		assert_eq!(
+			ArbitrageOpportunities::<Test>::get(eth_usd()),
+			Some(ArbitrageOpportunity {
+				buy_exchange: 1,
+				sell_exchange: 0,
+				spread_bps: 2_000,
+				timestamp: 30
+			})
+		);
+
+		// A spread of 5% closes the opportunity.
+		System::reset_events();
+		aggregate([(0, 2_000, 40), (1, 2_100, 40)]);
+		assert_eq!(ArbitrageOpportunities::<Test>::get(eth_usd()), None);
+		assert!(System::events().iter().all(|record| !matches!(
+			record.event,
+			RuntimeEvent::PriceOracle(Event::ArbitrageOpportunity { .. })
+		)));
+	});
+}
+
+#[test]
+fn arbitrage_spreads_must_pay_for_the_gas_of_both_swaps() {
+	new_test_ext().execute_with(|| {
+		let aggregate = |fees: Option<GasFees>| {
+			if let Some(fees) = fees {
+				GasData::<Test>::insert(1, fees);
+			}
+			// The gas of both swaps is paid in ETH at 2,000 USD.
+			let eth = PriceAggregate {
+				median: 2_000 * PRICE_UNIT,
+				mean: 2_000 * PRICE_UNIT,
+				sources: 2,
+				timestamp: 0,
+			};
+			AggregatedPrice::<Test>::insert(eth_usd(), eth);
+			PriceData::<Test>::insert(eth_usd(), 0, (2_000 * PRICE_UNIT, 0));
+			PriceData::<Test>::insert(eth_usd(), 1, (2_300 * PRICE_UNIT, 0));
+			PriceOracle::aggregate_prices(eth_usd());
+			ArbitrageOpportunities::<Test>::get(eth_usd()).is_some()
+		};
+		let fees = |base_fee| GasFees {
+			base_fee,
+			priority_fee: 10_000_000_000,
+			builder_tip: None,
+			block: 1,
+			timestamp: 0,
+		};
+		let eth = BoundedVec::truncate_from(b"ETH".to_vec());
+
+		// 15% of the 10,000 USD trade of the mock runtime leave 1,500 USD for 4M gas per swap.
+		ArbitrageGasUnits::set(4_000_000);
+		assert_noop!(
+			PriceOracle::set_native_token(RuntimeOrigin::root(), 1, Some(eth.clone())),
+			Error::<Test>::GasNotTracked
+		);
+		assert_ok!(PriceOracle::set_gas_tracking(RuntimeOrigin::root(), 1, true));
+		// Without the gas token of the chain the gas can't be priced.
+		assert!(aggregate(Some(fees(90_000_000_000))));
+
+		assert_ok!(PriceOracle::set_native_token(RuntimeOrigin::root(), 1, Some(eth.clone())));
+		System::assert_last_event(Event::NativeTokenSet { chain_id: 1, token: Some(eth) }.into());
+		// At 100 gwei both swaps cost 0.8 ETH, 1,600 USD.
+		assert!(!aggregate(None));
+		// At 50 gwei they cost 800 USD.
+		assert!(aggregate(Some(fees(40_000_000_000))));
+
+		// Untracking the fees of the chain forgets its gas token.
+		assert_ok!(PriceOracle::set_gas_tracking(RuntimeOrigin::root(), 1, false));
+		assert_eq!(NativeTokens::<Test>::get(1), None);
+	});
+}
+
+#[test]
+fn stale_prices_are_ignored_and_pruned() {
+	new_test_ext().execute_with(|| {
+		let submit = |exchange_id, price, timestamp| {
+			assert_ok!(PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				exchange_id,
+				price,
+				0,
+				timestamp,
+				None,
+				None,
+			));
+		};
+		set_now(1_000_000);
+		submit(0, 2_000, 1_000_000);
+		set_now(1_000_000 + MAX_PRICE_AGE / 2);
+		submit(1, 2_100, 1_000_000 + MAX_PRICE_AGE / 2);
+
+		set_now(1_000_001 + MAX_PRICE_AGE);
+		assert_eq!(PriceOracle::get_price(eth_usd(), 0), None);
+		assert_eq!(
+			PriceOracle::get_all_prices(eth_usd()),
+			vec![(1, 2_100 * PRICE_UNIT, 1_000_000 + MAX_PRICE_AGE / 2)]
+		);
+		// Stale prices don't count towards the aggregate.
+		assert_eq!(PriceOracle::aggregate_prices(eth_usd()).unwrap().sources, 1);
+
+		// Pruning only runs at update intervals.
+		PriceOracle::on_initialize((UPDATE_INTERVAL + 1).into());
+		assert_eq!(PriceData::<Test>::iter().count(), 2);
+		PriceOracle::on_initialize(UPDATE_INTERVAL.into());
+		assert_eq!(PriceData::<Test>::iter_keys().collect::<Vec<_>>(), vec![(eth_usd(), 1)]);
+	});
+}
+
+#[test]
+fn exchanges_can_keep_their_prices_fresh_for_longer() {
+	new_test_ext().execute_with(|| {
+		let submit = |exchange_id, price| {
+			assert_ok!(PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				exchange_id,
+				price,
+				0,
+				1_000_000,
+				None,
+				None,
+			));
+		};
+		Exchanges::<Test>::mutate(1, |exchange| {
+			exchange.as_mut().unwrap().max_price_age = Some(2 * MAX_PRICE_AGE);
+		});
+		set_now(1_000_000);
+		submit(0, 2_000);
+		submit(1, 2_100);
+
+		set_now(1_000_001 + MAX_PRICE_AGE);
+		assert_eq!(PriceOracle::get_price(eth_usd(), 0), None);
+		assert_eq!(PriceOracle::get_price(eth_usd(), 1), Some((2_100 * PRICE_UNIT, 1_000_000)));
+		assert_eq!(PriceOracle::aggregate_prices(eth_usd()).unwrap().sources, 1);
+		PriceOracle::on_initialize(UPDATE_INTERVAL.into());
+		assert_eq!(PriceData::<Test>::iter_keys().collect::<Vec<_>>(), vec![(eth_usd(), 1)]);
+
+		set_now(1_000_001 + 2 * MAX_PRICE_AGE);
+		assert_eq!(PriceOracle::get_all_prices(eth_usd()), vec![]);
+		PriceOracle::on_initialize(UPDATE_INTERVAL.into());
+		assert_eq!(PriceData::<Test>::iter().count(), 0);
+
+		let uniswap = ExchangeConfig {
+			name: BoundedVec::truncate_from(b"Uniswap V3 Polygon".to_vec()),
+			max_price_age: Some(0),
+			..registry::default_exchanges().remove(0).1
+		};
+		assert_noop!(
+			PriceOracle::add_exchange(RuntimeOrigin::root(), 10, uniswap),
+			Error::<Test>::InvalidMaxPriceAge
+		);
+	});
+}
+
+#[test]
+fn aggregated_prices_are_kept_in_a_bounded_history() {
+	new_test_ext().execute_with(|| {
+		for (block, price) in [(1u64, 2_000u128), (2, 2_100), (4, 2_400), (5, 2_500), (6, 2_600)] {
+			System::set_block_number(block);
+			assert_ok!(PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				0,
+				price,
+				0,
+				block,
+				None,
+				None,
+			));
+			PriceOracle::on_finalize(block);
+		}
+
+		// The oldest entry was dropped to make room.
+		let history: Vec<_> = PriceHistory::<Test>::get(eth_usd()).iter().collect();
+		assert_eq!(
+			history,
+			[2_100, 2_400, 2_500, 2_600]
+				.map(|p| p * PRICE_UNIT)
+				.into_iter()
+				.zip([2, 4, 5, 6])
+				.collect::<Vec<_>>()
+		);
+
+		System::set_block_number(8);
+		// 2_500 for one block, 2_600 for two.
+		assert_eq!(PriceOracle::twap(eth_usd(), 3), Some(2_566 * PRICE_UNIT + PRICE_UNIT * 2 / 3));
+		// The window is cut at the oldest entry: 2_100 for two blocks, 2_400, 2_500 for one,
+		// 2_600 for two.
+		assert_eq!(PriceOracle::twap(eth_usd(), 100), Some(2_383 * PRICE_UNIT + PRICE_UNIT / 3));
+		assert_eq!(PriceOracle::twap(pair_id(b"BTC", b"USD"), 100), None);
+	});
+}
+
+#[test]
+fn dynamic_bounds_follow_the_twap() {
+	new_test_ext().execute_with(|| {
+		let mut pair = registry::default_pairs().remove(0);
+		assert_eq!(PriceOracle::price_bounds(&pair), pair.min_price..=pair.max_price);
+
+		pair.dynamic_bounds =
+			Some(DynamicBounds { deviation: Permill::from_percent(10), window: 10 });
+		assert_ok!(PriceOracle::update_pair(RuntimeOrigin::root(), pair.clone()));
+		// Without a history only the static bounds apply.
+		assert_eq!(PriceOracle::price_bounds(&pair), pair.min_price..=pair.max_price);
+
+		assert_ok!(PriceOracle::submit_price_unsigned(
+			RuntimeOrigin::none(),
+			eth_usd(),
+			0,
+			2_000,
+			0,
+			1,
+			None,
+			None
+		));
+		PriceOracle::on_finalize(1);
+		System::set_block_number(2);
+		assert_eq!(PriceOracle::price_bounds(&pair), 1_800 * PRICE_UNIT..=2_200 * PRICE_UNIT);
+
+		assert_ok!(PriceOracle::submit_price_unsigned(
+			RuntimeOrigin::none(),
+			eth_usd(),
+			1,
+			2_300,
+			0,
+			2,
+			None,
+			None
+		));
+		System::assert_last_event(
+			Event::PriceRejected {
+				pair_id: eth_usd(),
+				exchange_id: 1,
+				reason: OracleError::PriceOutOfBounds { value: 2_300 * PRICE_UNIT },
+			}
+			.into(),
+		);
+		assert_ok!(PriceOracle::submit_price_unsigned(
+			RuntimeOrigin::none(),
+			eth_usd(),
+			1,
+			2_200,
+			0,
+			2,
+			None,
+			None
+		));
+		assert_eq!(PriceData::<Test>::get(eth_usd(), 1), Some((2_200 * PRICE_UNIT, 2)));
+
+		// The static bounds still cap the band.
+		pair.min_price = 1_900 * PRICE_UNIT;
+		assert_eq!(PriceOracle::price_bounds(&pair), 1_900 * PRICE_UNIT..=2_200 * PRICE_UNIT);
+
+		// Long-tail pairs get at least the wider band of `LongTailDeviation`.
+		assert_ok!(PriceOracle::set_long_tail(RuntimeOrigin::root(), eth_usd(), Some(1)));
+		assert_eq!(PriceOracle::price_bounds(&pair), 1_900 * PRICE_UNIT..=2_400 * PRICE_UNIT);
+	});
+}
+
+#[test]
+fn long_tail_pairs_aggregating_thin_pools_are_served_as_low_quality() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PriceOracle::set_long_tail(RuntimeOrigin::signed(1), eth_usd(), Some(100_000)),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			PriceOracle::set_long_tail(RuntimeOrigin::root(), eth_usd(), Some(0)),
+			Error::<Test>::InvalidMinNotional
+		);
+		assert_noop!(
+			PriceOracle::set_long_tail(RuntimeOrigin::root(), pair_id(b"LINK", b"USD"), Some(1)),
+			Error::<Test>::PairNotTracked
+		);
+		assert_ok!(PriceOracle::set_long_tail(RuntimeOrigin::root(), eth_usd(), Some(100_000)));
+		System::assert_last_event(
+			Event::LongTailSet { pair_id: eth_usd(), min_notional: Some(100_000) }.into(),
+		);
+
+		let submit = |exchange_id, price, timestamp, notional| {
+			assert_ok!(PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				exchange_id,
+				price,
+				0,
+				timestamp,
+				None,
+				notional,
+			));
+			PriceOracle::on_finalize(1);
+		};
+		let low_quality =
+			|| <PriceOracle as PriceProvider>::price(eth_usd()).map(|price| price.low_quality);
+		submit(0, 2_000, 1, Some(250_000));
+		assert_eq!(low_quality(), Ok(false));
+
+		// A pool of unknown depth is as bad as a thin one.
+		submit(1, 2_010, 1, None);
+		System::assert_has_event(Event::PairLowQuality { pair_id: eth_usd() }.into());
+		assert_eq!(low_quality(), Ok(true));
+
+		submit(1, 2_020, 2, Some(100_000));
+		System::assert_has_event(Event::PairQualityRestored { pair_id: eth_usd() }.into());
+		assert_eq!(PoolNotionals::<Test>::get(eth_usd(), 1), Some(100_000));
+		assert_eq!(low_quality(), Ok(false));
+
+		submit(1, 2_030, 3, Some(99_999));
+		assert_eq!(low_quality(), Ok(true));
+
+		// As a blue-chip pair again it is served like any other.
+		assert_ok!(PriceOracle::set_long_tail(RuntimeOrigin::root(), eth_usd(), None));
+		assert_eq!(low_quality(), Ok(false));
+		submit(1, 2_040, 4, Some(1));
+		assert!(!LowQualityPairs::<Test>::contains_key(eth_usd()));
+
+		assert_ok!(PriceOracle::set_long_tail(RuntimeOrigin::root(), eth_usd(), Some(1)));
+		assert_ok!(PriceOracle::remove_pair(RuntimeOrigin::root(), eth_usd()));
+		assert!(!LongTailPairs::<Test>::contains_key(eth_usd()));
+		assert_eq!(PoolNotionals::<Test>::iter_prefix(eth_usd()).count(), 0);
+	});
+}
+
+#[test]
+fn twap_weights_prices_by_duration() {
+	let history = [(10, 0), (20, 10), (40, 15)];
+	assert_eq!(
+		aggregation::twap(&history, 0, 20, Rounding::Floor),
+		Some((10 * 10 + 20 * 5 + 40 * 5) / 20)
+	);
+	assert_eq!(aggregation::twap(&history, 12, 20, Rounding::Floor), Some((20 * 3 + 40 * 5) / 8));
+	assert_eq!(aggregation::twap(&history, 15, 15, Rounding::Floor), Some(40));
+	assert_eq!(aggregation::twap(&[], 0, 20, Rounding::Floor), None);
+}
+
+#[test]
+fn median_and_mean_of_prices() {
+	assert_eq!(aggregation::median(&mut [], RoundingPolicy::FLOOR), None);
+	assert_eq!(aggregation::median(&mut [3], RoundingPolicy::FLOOR), Some(3));
+	assert_eq!(aggregation::median(&mut [4, 1, 3, 2], RoundingPolicy::FLOOR), Some(2));
+	assert_eq!(
+		aggregation::median(&mut [u128::MAX, u128::MAX], RoundingPolicy::FLOOR),
+		Some(u128::MAX)
+	);
+	assert_eq!(aggregation::mean(&[], Rounding::Floor), None);
+	assert_eq!(aggregation::mean(&[1, 2, 4], Rounding::Floor), Some(2));
+	assert_eq!(aggregation::mean(&[u128::MAX, u128::MAX], Rounding::Floor), Some(u128::MAX));
+}
+
+#[test]
+fn rounding_follows_the_policy() {
+	use Rounding::*;
+	for (numerator, floor, half_up, half_even) in
+		[(14, 1, 1, 1), (15, 1, 2, 2), (25, 2, 3, 2), (26, 2, 3, 3), (30, 3, 3, 3)]
+	{
+		assert_eq!(
+			[Floor, HalfUp, HalfEven].map(|rounding| rounding.divide(numerator, 10)),
+			[floor, half_up, half_even],
+		);
+	}
+	assert_eq!(HalfUp.divide(u128::MAX, 1), u128::MAX);
+	assert_eq!(HalfUp.round(u128::MAX, 1, 2), u128::MAX);
+
+	let policy = |rounding, median_tie| RoundingPolicy { rounding, median_tie };
+	let median = |prices: [u128; 4], policy| aggregation::median(&mut prices.clone(), policy);
+	assert_eq!(median([4, 1, 3, 2], policy(Floor, MedianTie::Mean)), Some(2));
+	assert_eq!(median([4, 1, 3, 2], policy(HalfUp, MedianTie::Mean)), Some(3));
+	assert_eq!(median([4, 1, 3, 2], policy(HalfEven, MedianTie::Mean)), Some(2));
+	assert_eq!(median([5, 1, 6, 2], policy(HalfEven, MedianTie::Mean)), Some(4));
+	assert_eq!(median([4, 1, 3, 2], policy(HalfUp, MedianTie::Lower)), Some(2));
+	assert_eq!(median([4, 1, 3, 2], policy(Floor, MedianTie::Upper)), Some(3));
+	assert_eq!(aggregation::mean(&[1, 2], HalfUp), Some(2));
+	assert_eq!(aggregation::mean(&[u128::MAX, u128::MAX - 1], HalfEven), Some(u128::MAX - 1));
+	assert_eq!(aggregation::twap(&[(10, 0), (11, 5)], 0, 8, HalfUp), Some(10));
+	assert_eq!(aggregation::twap(&[(10, 0), (11, 4)], 0, 8, HalfUp), Some(11));
+
+	assert_eq!(fixed::to_precision(1_005 * PRICE_UNIT / 1_000, 2, HalfUp), 101);
+	assert_eq!(fixed::to_precision(1_005 * PRICE_UNIT / 1_000, 2, HalfEven), 100);
+	assert_eq!(fixed::to_precision(1_005 * PRICE_UNIT / 1_000, 2, Floor), 100);
+}
+
+#[test]
+fn aggregation_follows_the_rounding_policy_of_the_runtime() {
+	new_test_ext().execute_with(|| {
+		crate::mock::Rounding::set(RoundingPolicy {
+			rounding: Rounding::Floor,
+			median_tie: MedianTie::Upper,
+		});
+		for (exchange_id, price) in [(0, 2_000), (1, 2_001)] {
+			assert_ok!(PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				exchange_id,
+				price,
+				0,
+				10,
+				None,
+				None
+			));
+		}
+		PriceOracle::on_finalize(1);
+
+		let chain = ChainAggregates::<Test>::get(eth_usd(), 1).unwrap();
+		assert_eq!(chain.median, 2_001 * PRICE_UNIT);
+		assert_eq!(chain.mean, 2_000_500 * PRICE_UNIT / 1_000);
+	});
+}
+
+#[test]
+fn submit_price_unsigned_scales_by_exponent() {
+	new_test_ext().execute_with(|| {
+		// A long-tail token worth 0.000000000123 USD, below one micro-USD.
+		let mut pair = registry::default_pairs().remove(0);
+		pair.base = b"TAIL".to_vec().try_into().unwrap();
+		pair.decimals = 12;
+		(pair.min_price, pair.max_price) = (1, u128::MAX);
+		let pair_hash = pair.id();
+		assert_ok!(PriceOracle::add_pair(RuntimeOrigin::root(), pair));
+
+		assert_ok!(PriceOracle::submit_price_unsigned(
+			RuntimeOrigin::none(),
+			pair_hash,
+			0,
+			123,
+			12,
+			1,
+			None,
+			None
+		));
+		assert_eq!(PriceData::<Test>::get(pair_hash, 0), Some((123_000_000, 1)));
+
+		assert_noop!(
+			PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				pair_hash,
+				0,
+				1,
+				19,
+				1,
+				None,
+				None
+			),
+			Error::<Test>::InvalidExponent,
+		);
+		assert_noop!(
+			PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				pair_hash,
+				0,
+				u128::MAX,
+				0,
+				1,
+				None,
+				None
+			),
+			Error::<Test>::PriceOverflow,
+		);
+	});
+}
+
+#[test]
+fn validate_unsigned_rejects_invalid_exponent() {
+	new_test_ext().execute_with(|| {
+		let call = |exponent| Call::submit_price_unsigned {
+			pair_hash: eth_usd(),
+			exchange_id: 0,
+			price: 2500 * PRICE_UNIT,
+			exponent,
+			timestamp: 1,
+			source_block: None,
+			notional: None,
+		};
+		assert_ok!(PriceOracle::validate_unsigned(TransactionSource::Local, &call(18)));
+		assert_eq!(
+			PriceOracle::validate_unsigned(TransactionSource::Local, &call(19)),
+			InvalidTransaction::Custom(INVALID_EXPONENT).into()
+		);
+	});
+}
+
+#[test]
+fn submissions_for_unknown_exchanges_are_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				10,
+				1,
+				6,
+				1,
+				None,
+				None
+			),
+			Error::<Test>::UnknownExchange,
+		);
+		let call = Call::submit_price_unsigned {
+			pair_hash: eth_usd(),
+			exchange_id: 10,
+			price: 1,
+			exponent: 6,
+			timestamp: 1,
+			source_block: None,
+			notional: None,
+		};
+		assert_eq!(
+			PriceOracle::validate_unsigned(TransactionSource::Local, &call),
+			InvalidTransaction::Custom(UNKNOWN_EXCHANGE).into()
+		);
+		assert_eq!(PriceData::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn source_events_are_summarized_per_block() {
+	new_test_ext().execute_with(|| {
+		SummarizeSourceEvents::set(true);
+		for (exchange_id, price) in [(0, 2_000), (1, 2_600), (2, 999), (2, 2_100)] {
+			assert_ok!(PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				exchange_id,
+				price,
+				0,
+				1,
+				None,
+				None,
+			));
+		}
+		assert!(System::events().is_empty());
+		let summary = SubmissionSummary {
+			submitted: 3,
+			rejected: 1,
+			lowest: Some((0, 2_000 * PRICE_UNIT)),
+			highest: Some((1, 2_600 * PRICE_UNIT)),
+		};
+		assert_eq!(SubmissionSummaries::<Test>::get(eth_usd()), summary);
+
+		PriceOracle::on_finalize(1);
+		System::assert_has_event(Event::PricesSummarized { pair_id: eth_usd(), summary }.into());
+		assert_eq!(SubmissionSummaries::<Test>::iter().count(), 0);
+		assert!(System::events().iter().all(|record| !matches!(
+			record.event,
+			RuntimeEvent::PriceOracle(Event::PriceSubmitted { .. } | Event::PriceRejected { .. })
+		)));
+	});
+}
+
+#[test]
+fn submissions_outside_of_bounds_are_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(PriceOracle::submit_price_unsigned(
+			RuntimeOrigin::none(),
+			eth_usd(),
+			0,
+			999,
+			0,
+			1,
+			None,
+			None
+		));
+		System::assert_last_event(
+			Event::PriceRejected {
+				pair_id: eth_usd(),
+				exchange_id: 0,
+				reason: OracleError::PriceOutOfBounds { value: 999 * PRICE_UNIT },
+			}
+			.into(),
+		);
+		assert_eq!(PriceData::<Test>::get(eth_usd(), 0), None);
+		assert_eq!(PendingAggregation::<Test>::iter().count(), 0);
+
+		assert_noop!(
+			PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				pair_id(b"BTC", b"USD"),
+				0,
+				1,
+				0,
+				1,
+				None,
+				None
+			),
+			Error::<Test>::PairNotTracked,
+		);
+	});
+}
+
+#[test]
+fn freshness_slas_report_breaches() {
+	new_test_ext().execute_with(|| {
+		let sla = FreshnessSla { max_staleness: 60_000, min_sources: 2, max_spread_bps: 1_000 };
+		assert_noop!(
+			PriceOracle::set_sla(RuntimeOrigin::signed(1), eth_usd(), Some(sla)),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			PriceOracle::set_sla(RuntimeOrigin::root(), pair_id(b"BTC", b"USD"), Some(sla)),
+			Error::<Test>::PairNotTracked
+		);
+		assert_noop!(
+			PriceOracle::set_sla(
+				RuntimeOrigin::root(),
+				eth_usd(),
+				Some(FreshnessSla { min_sources: 0, ..sla })
+			),
+			Error::<Test>::InvalidSla
+		);
+		assert!(!PriceOracle::meets_sla(eth_usd()));
+		assert_ok!(PriceOracle::set_sla(RuntimeOrigin::root(), eth_usd(), Some(sla)));
+		System::assert_last_event(Event::SlaSet { pair_id: eth_usd(), sla: Some(sla) }.into());
+
+		let interval = UPDATE_INTERVAL.into();
+		PriceOracle::on_initialize(interval);
+		System::assert_last_event(
+			Event::SlaBreached { pair_id: eth_usd(), breach: SlaBreach::NoPrice }.into(),
+		);
+		assert_eq!(BreachedSlas::<Test>::get(eth_usd()), Some(SlaBreach::NoPrice));
+
+		let submit = |exchange_id, price, timestamp| {
+			assert_ok!(PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				exchange_id,
+				price,
+				0,
+				timestamp,
+				None,
+				None,
+			));
+			PriceOracle::on_finalize(1);
+		};
+		set_now(1_000_000);
+		submit(0, 2_000, 1_000_000);
+		assert!(!PriceOracle::meets_sla(eth_usd()));
+		PriceOracle::on_initialize(interval);
+		System::assert_last_event(
+			Event::SlaBreached { pair_id: eth_usd(), breach: SlaBreach::TooFewSources }.into(),
+		);
+		// 5% apart, within the SLA.
+		submit(1, 2_100, 1_000_000);
+		assert!(PriceOracle::meets_sla(eth_usd()));
+		PriceOracle::on_initialize(interval);
+		System::assert_last_event(Event::SlaRestored { pair_id: eth_usd() }.into());
+		assert_eq!(BreachedSlas::<Test>::get(eth_usd()), None);
+
+		// Breaches are only reported when they change.
+		System::reset_events();
+		PriceOracle::on_initialize(interval);
+		assert!(System::events().is_empty());
+
+		set_now(1_060_001);
+		assert!(!PriceOracle::meets_sla(eth_usd()));
+		PriceOracle::on_initialize(interval);
+		System::assert_last_event(
+			Event::SlaBreached { pair_id: eth_usd(), breach: SlaBreach::Stale }.into(),
+		);
+
+		// 20% apart.
+		submit(0, 2_000, 1_060_001);
+		submit(1, 2_400, 1_060_001);
+		PriceOracle::on_initialize(interval);
+		System::assert_last_event(
+			Event::SlaBreached { pair_id: eth_usd(), breach: SlaBreach::SpreadTooWide }.into(),
+		);
+
+		assert_ok!(PriceOracle::remove_pair(RuntimeOrigin::root(), eth_usd()));
+		assert_eq!(PairSlas::<Test>::get(eth_usd()), None);
+		assert_eq!(BreachedSlas::<Test>::get(eth_usd()), None);
+	});
+}
+
+#[test]
+fn new_pairs_bootstrap_before_their_price_is_served() {
+	new_test_ext().execute_with(|| {
+		BootstrapIntervals::set(2);
+		let mut btc = registry::default_pairs().remove(0);
+		btc.base = b"BTC".to_vec().try_into().unwrap();
+		let btc_usd = btc.id();
+		assert_ok!(PriceOracle::add_pair(RuntimeOrigin::root(), btc));
+		assert_eq!(Bootstrapping::<Test>::get(btc_usd), Some(BootstrapProgress::default()));
+		// Pairs from genesis are served at once.
+		assert!(!Bootstrapping::<Test>::contains_key(eth_usd()));
+
+		set_now(1_000_000);
+		let submit = |price, timestamp| {
+			for exchange_id in [0, 1] {
+				assert_ok!(PriceOracle::submit_price_unsigned(
+					RuntimeOrigin::none(),
+					btc_usd,
+					exchange_id,
+					price,
+					0,
+					timestamp,
+					None,
+					None,
+				));
+			}
+			PriceOracle::on_finalize(1);
+		};
+		let interval = UPDATE_INTERVAL.into();
+		let intervals = || Bootstrapping::<Test>::get(btc_usd).map(|progress| progress.intervals);
+
+		// Collected and aggregated, but not served.
+		submit(2_000, 1_000_000);
+		assert!(PriceOracle::get_aggregated_price(btc_usd).is_some());
+		assert_eq!(
+			<PriceOracle as PriceProvider>::price(btc_usd),
+			Err(Error::<Test>::Bootstrapping.into())
+		);
+		assert_eq!(PriceOracle::latest_price(btc_usd), None);
+		assert_eq!(PriceOracle::read_many(&[btc_usd]), vec![None]);
+		PriceOracle::on_initialize(interval);
+		assert_eq!(intervals(), Some(1));
+
+		// An interval without new data doesn't count.
+		PriceOracle::on_initialize(interval);
+		assert_eq!(intervals(), Some(1));
+
+		// A move of 20% starts the count over.
+		submit(2_400, 1_000_001);
+		PriceOracle::on_initialize(interval);
+		assert_eq!(intervals(), Some(0));
+
+		submit(2_410, 1_000_002);
+		PriceOracle::on_initialize(interval);
+		assert_eq!(intervals(), Some(1));
+		submit(2_420, 1_000_003);
+		PriceOracle::on_initialize(interval);
+		System::assert_last_event(Event::BootstrapCompleted { pair_id: btc_usd }.into());
+		assert_eq!(intervals(), None);
+		assert_eq!(PriceOracle::latest_price(btc_usd), Some((2_420 * PRICE_UNIT, 1_000_003)));
+		assert_eq!(
+			PriceOracle::read_many(&[btc_usd])[0].map(|aggregate| aggregate.median),
+			Some(2_420 * PRICE_UNIT)
+		);
+	});
+}
+
+#[test]
+fn cross_chain_discrepancies_need_aligned_samples() {
+	new_test_ext().execute_with(|| {
+		let submit = |exchange_id, price, timestamp| {
+			assert_ok!(PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				exchange_id,
+				price,
+				0,
+				timestamp,
+				Some(1),
+				None,
+			));
+			PriceOracle::on_finalize(1);
+		};
+		let check = CrossChainCheck { threshold_bps: 1_000, max_skew: Some(5) };
+		assert_noop!(
+			PriceOracle::set_cross_chain_check(
+				RuntimeOrigin::root(),
+				eth_usd(),
+				Some(CrossChainCheck { threshold_bps: 0, ..check })
+			),
+			Error::<Test>::InvalidCrossChainCheck,
+		);
+		assert_ok!(PriceOracle::set_cross_chain_check(
+			RuntimeOrigin::root(),
+			eth_usd(),
+			Some(check)
+		));
+
+		// Uniswap V3 on chain 1 and PancakeSwap on chain 56, 15% apart but sampled 10 ms apart.
+		submit(0, 2_000, 10);
+		assert_eq!(PriceOracle::sample_skew(eth_usd()), None);
+		submit(2, 2_300, 20);
+		assert_eq!(PriceOracle::sample_skew(eth_usd()), Some(10));
+		let discrepancies = || {
+			System::events()
+				.into_iter()
+				.filter(|record| {
+					matches!(
+						record.event,
+						RuntimeEvent::PriceOracle(Event::CrossChainDiscrepancy { .. })
+					)
+				})
+				.count()
+		};
+		assert_eq!(discrepancies(), 0);
+
+		// A fresh sample of chain 1 brings them within the skew.
+		submit(0, 2_000, 18);
+		System::assert_has_event(
+			Event::CrossChainDiscrepancy {
+				pair_id: eth_usd(),
+				low: 1,
+				high: 56,
+				spread_bps: 1_500,
+				skew: 2,
+			}
+			.into(),
+		);
+		assert_eq!(discrepancies(), 1);
+
+		assert_ok!(PriceOracle::remove_pair(RuntimeOrigin::root(), eth_usd()));
+		assert_eq!(CrossChainChecks::<Test>::get(eth_usd()), None);
+		assert_eq!(SourceBlocks::<Test>::iter_prefix(eth_usd()).count(), 0);
+	});
+}
+
+#[test]
+fn offchain_worker_reports_fetch_failures() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: br#"{"jsonrpc":"2.0","method":"eth_call","params":[{"to":"0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640","data":"0x3850c7bd"},"latest"],"id":1}"#.to_vec(),
+		response: Some(rpc_response("0x01")),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		PriceOracle::offchain_worker(fetch_block(1));
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		let call = Call::report_fetch_failure {
+			pair_id: eth_usd(),
+			exchange_id: 0,
+			error: OracleError::DecodeError,
+		};
+		assert_eq!(tx.function, RuntimeCall::PriceOracle(call.clone()));
+		assert_ok!(PriceOracle::validate_unsigned(TransactionSource::Local, &call));
+
+		assert_ok!(PriceOracle::report_fetch_failure(
+			RuntimeOrigin::none(),
+			eth_usd(),
+			0,
+			OracleError::DecodeError
+		));
+		System::assert_last_event(
+			Event::ExchangeFetchFailed {
+				pair_id: eth_usd(),
+				exchange_id: 0,
+				error: OracleError::DecodeError,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn offchain_worker_records_telemetry() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, _pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: br#"{"jsonrpc":"2.0","method":"eth_call","params":[{"to":"0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640","data":"0x3850c7bd"},"latest"],"id":1}"#.to_vec(),
+		response: Some(rpc_response("0x01")),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		PriceOracle::offchain_worker(fetch_block(1));
+
+		let recorded = telemetry::read();
+		assert_eq!(recorded.venues[&0], VenueCounters { fetches: 1, failures: 1 });
+		assert_eq!((recorded.submissions, recorded.failed_submissions), (1, 0));
+		assert_eq!(recorded.latencies, vec![(0, 0)]);
+
+		for sample in 0..telemetry::MAX_LATENCIES as u64 {
+			telemetry::record_fetch(1, sample, true);
+		}
+		let recorded = telemetry::read();
+		assert_eq!(recorded.venues[&1], VenueCounters { fetches: 64, failures: 0 });
+		assert_eq!(recorded.latency_samples, 65);
+		assert_eq!(recorded.latencies_since(1).len(), telemetry::MAX_LATENCIES);
+		assert_eq!(recorded.latencies_since(0).len(), telemetry::MAX_LATENCIES);
+		assert_eq!(recorded.latencies_since(63), &[(1, 62), (1, 63)]);
+	});
+}
+
+#[test]
+fn offchain_worker_stops_fetching_when_out_of_time() {
+	let (offchain, _state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	MaxOcwMillis::set(0);
+
+	ext.execute_with(|| {
+		let overruns = || {
+			sp_io::offchain::local_storage_get(
+				sp_core::offchain::StorageKind::PERSISTENT,
+				crate::OCW_OVERRUNS_KEY,
+			)
+			.map(|value| u32::decode(&mut &value[..]).unwrap())
+		};
+		// No request is expected: the run is over before the first fetch.
+		PriceOracle::offchain_worker(fetch_block(1));
+		assert!(pool_state.read().transactions.is_empty());
+		assert_eq!(overruns(), Some(1));
+	});
+}
+
+#[test]
+fn orca_whirlpools_price_sol_from_their_solana_accounts() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	let account = base64(&whirlpool_account(SQRT_PRICE_SOL_150, 1));
+	let response = format!(
+		r#"{{"jsonrpc":"2.0","id":1,"result":{{"context":{{"slot":7}},"value":{{"data":["{account}","base64"],"executable":false,"lamports":1,"owner":"whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc","rentEpoch":0}}}}}}"#
+	)
+	.into_bytes();
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://api.mainnet-beta.solana.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: br#"{"jsonrpc":"2.0","method":"getAccountInfo","params":["HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ",{"encoding":"base64","commitment":"confirmed"}],"id":1}"#.to_vec(),
+		response: Some(response.clone()),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		// Only SOL/USD is fetched.
+		TrackedPairs::<Test>::remove(eth_usd());
+		PriceOracle::offchain_worker(
+			(UPDATE_INTERVAL + PriceOracle::fetch_phase(sol_usd())).into(),
+		);
+		assert_eq!(pool_state.read().transactions.len(), 1);
+	});
+
+	let (_, orca) = registry::default_exchanges().remove(5);
+	let sol = registry::default_pairs().remove(1);
+	let (_, whirlpool) = &sol.pools[0];
+	assert_eq!(
+		crate::replay::replay_price(&orca, &sol, whirlpool, &response, RoundingPolicy::FLOOR),
+		Ok(150 * PRICE_UNIT)
+	);
+	let data = whirlpool_account(SQRT_PRICE_SOL_150, 1);
+	assert_eq!(orca.protocol.parse_price(&data, &whirlpool.metadata), Ok(150 * PRICE_UNIT + 11));
+	// Accounts of other programs, or other accounts of the Whirlpool program, aren't priced.
+	assert_eq!(
+		orca.protocol.parse_price(&data[..data.len() - 1], &whirlpool.metadata),
+		Err(OracleError::DecodeError)
+	);
+	let mut position = data;
+	position[0] ^= 1;
+	assert_eq!(
+		orca.protocol.parse_price(&position, &whirlpool.metadata),
+		Err(OracleError::DecodeError)
+	);
+	// A missing account has no data.
+	let missing = br#"{"jsonrpc":"2.0","id":1,"result":{"context":{"slot":7},"value":null}}"#;
+	assert_eq!(json::account_data(missing, 1), Err(OracleError::DecodeError));
+}
+
+#[test]
+fn whirlpools_of_a_pair_are_found_by_their_mints() {
+	use crate::{
+		chains::{solana, SolanaChain},
+		protocols::{orca, OrcaWhirlpoolProtocol},
+	};
+
+	let (offchain, state) = TestOffchainExt::new();
+	let mut ext = sp_io::TestExternalities::default();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+
+	let sol = solana::parse_address("So11111111111111111111111111111111111111112").unwrap();
+	let usdc = solana::parse_address("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").unwrap();
+	let tiers = [
+		("HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ", 5_000),
+		("Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE", 9_000),
+	];
+	let accounts: Vec<_> = tiers
+		.iter()
+		.map(|(key, liquidity)| {
+			let data = base64(&whirlpool_account(SQRT_PRICE_SOL_150, *liquidity));
+			format!(r#"{{"pubkey":"{key}","account":{{"data":["{data}","base64"],"owner":"whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc"}}}}"#)
+		})
+		.collect();
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://api.mainnet-beta.solana.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: br#"{"jsonrpc":"2.0","method":"getProgramAccounts","params":["whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc",{"encoding":"base64","commitment":"confirmed","filters":[{"dataSize":653},{"memcmp":{"offset":101,"bytes":"So11111111111111111111111111111111111111112"}},{"memcmp":{"offset":181,"bytes":"EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"}}]}],"id":1}"#.to_vec(),
+		response: Some(
+			format!(r#"{{"jsonrpc":"2.0","id":1,"result":[{}]}}"#, accounts.join(",")).into_bytes(),
+		),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		let pools = SolanaChain
+			.program_accounts(
+				"https://api.mainnet-beta.solana.com",
+				&orca::PROGRAM,
+				&OrcaWhirlpoolProtocol.pool_filters(&sol, &usdc),
+				1_000,
+			)
+			.unwrap();
+		let found: Vec<_> = pools
+			.iter()
+			.map(|(key, data)| (*key, OrcaWhirlpoolProtocol.parse_liquidity(data).unwrap()))
+			.collect();
+		let expected: Vec<_> = tiers
+			.iter()
+			.map(|(key, liquidity)| (solana::parse_address(key).unwrap(), *liquidity))
+			.collect();
+		assert_eq!(found, expected);
+	});
+}
+
+#[test]
+fn pools_have_accounts_exactly_on_chains_of_accounts() {
+	new_test_ext().execute_with(|| {
+		let sol = registry::default_pairs().remove(1);
+		let mut no_account = sol.clone();
+		no_account.pools[0].1.account = None;
+		assert_noop!(
+			PriceOracle::update_pair(RuntimeOrigin::root(), no_account.clone()),
+			Error::<Test>::ZeroAddress
+		);
+		no_account.pools[0].1.address = registry::default_pairs()[0].pools[0].1.address;
+		assert_noop!(
+			PriceOracle::update_pair(RuntimeOrigin::root(), no_account),
+			Error::<Test>::InvalidPoolAccount
+		);
+		let mut zero_account = sol.clone();
+		zero_account.pools[0].1.account = Some([0; 32]);
+		assert_noop!(
+			PriceOracle::update_pair(RuntimeOrigin::root(), zero_account),
+			Error::<Test>::ZeroAddress
+		);
+
+		let mut eth = registry::default_pairs().remove(0);
+		eth.pools[0].1.account = sol.pools[0].1.account;
+		assert_noop!(
+			PriceOracle::update_pair(RuntimeOrigin::root(), eth),
+			Error::<Test>::InvalidPoolAccount
+		);
+
+		// Whirlpools can only be read on Solana.
+		let (_, orca) = registry::default_exchanges().remove(5);
+		let evm_orca = ExchangeConfig { chain: crate::chains::ChainKind::Evm, ..orca };
+		assert_noop!(
+			PriceOracle::add_exchange(RuntimeOrigin::root(), 10, evm_orca),
+			Error::<Test>::UnsupportedProtocol
+		);
+	});
+}
+
+#[test]
+fn submitted_prices_replay_from_archived_responses() {
+	use crate::replay::{self, ReplayError};
+
+	let (_, exchange) = registry::default_exchanges().remove(0);
+	let pair = registry::default_pairs().remove(0);
+	let (_, pool) = &pair.pools[0];
+	let response = rpc_response(SLOT0_ETH_2500);
+	let policy = RoundingPolicy::FLOOR;
+
+	assert_eq!(
+		replay::replay_price(&exchange, &pair, pool, &response, policy),
+		Ok(2_500 * PRICE_UNIT)
+	);
+	assert_eq!(
+		replay::verify(&exchange, &pair, pool, &response, policy, 2_500 * PRICE_UNIT),
+		Ok(())
+	);
+	assert_eq!(
+		replay::verify(&exchange, &pair, pool, &response, policy, 2_400 * PRICE_UNIT),
+		Err(ReplayError::Mismatch { replayed: 2_500 * PRICE_UNIT, stored: 2_400 * PRICE_UNIT })
+	);
+	// Replays keep to the precision the pair is submitted with.
+	let response = rpc_response(&SLOT0_ETH_2500.replace("4e20", "4e21"));
+	let exact = PairInfo { decimals: 18, ..pair.clone() };
+	let exact = replay::replay_price(&exchange, &exact, pool, &response, policy).unwrap();
+	let cents = PairInfo { decimals: 2, ..pair.clone() };
+	let cents = replay::replay_price(&exchange, &cents, pool, &response, policy).unwrap();
+	assert_ne!(exact % (PRICE_UNIT / 100), 0);
+	assert_eq!(cents, exact - exact % (PRICE_UNIT / 100));
+
+	let failed =
+		br#"{"jsonrpc":"2.0","id":7,"error":{"code":-32000,"message":"header not found"}}"#;
+	assert_eq!(
+		replay::verify(&exchange, &pair, pool, failed, policy, 2_500 * PRICE_UNIT),
+		Err(ReplayError::Fetch(OracleError::RpcError { code: -32000 }))
+	);
+}
+
+#[test]
+fn stale_and_replayed_submissions_are_rejected() {
+	new_test_ext().execute_with(|| {
+		set_now(1_000_000);
+		let call = |price: u128, timestamp| Call::submit_price_unsigned {
+			pair_hash: eth_usd(),
+			exchange_id: 0,
+			price,
+			exponent: 0,
+			timestamp,
+			source_block: None,
+			notional: None,
+		};
+		let validate = |call| PriceOracle::validate_unsigned(TransactionSource::External, &call);
+		let submit = |price, timestamp| {
+			PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				0,
+				price,
+				0,
+				timestamp,
+				None,
+				None,
+			)
+		};
+
+		for timestamp in
+			[1_000_000 - MAX_SUBMISSION_DELAY - 1, 1_000_000 + MAX_SUBMISSION_DELAY + 1]
+		{
+			assert_eq!(validate(call(2_500, timestamp)), InvalidTransaction::Stale.into());
+			assert_noop!(submit(2_500, timestamp), Error::<Test>::StaleSubmission);
+		}
+
+		assert_ok!(validate(call(2_500, 1_000_000)));
+		assert_ok!(submit(2_500, 1_000_000));
+		// The same or an older observation can't be submitted again.
+		assert_eq!(validate(call(2_500, 1_000_000)), InvalidTransaction::Stale.into());
+		assert_noop!(submit(2_600, 999_999), Error::<Test>::OutdatedSubmission);
+		assert_ok!(submit(2_600, 1_000_001));
+
+		assert_eq!(
+			validate(call(999, 1_000_002)),
+			InvalidTransaction::Custom(PRICE_OUT_OF_BOUNDS).into()
+		);
+		assert_eq!(
+			PriceOracle::validate_unsigned(
+				TransactionSource::External,
+				&Call::submit_price_unsigned {
+					pair_hash: pair_id(b"BTC", b"USD"),
+					exchange_id: 0,
+					price: 1,
+					exponent: 0,
+					timestamp: 1_000_002,
+					source_block: None,
+					notional: None,
+				}
+			),
+			InvalidTransaction::Custom(UNTRACKED_PAIR).into()
+		);
+	});
+}
+
+#[test]
+fn submission_tags_are_unique_per_block() {
+	new_test_ext().execute_with(|| {
+		let call = |timestamp| Call::submit_price_unsigned {
+			pair_hash: eth_usd(),
+			exchange_id: 0,
+			price: 2_500,
+			exponent: 0,
+			timestamp,
+			source_block: None,
+			notional: None,
+		};
+		let provides = |call| {
+			PriceOracle::validate_unsigned(TransactionSource::Local, &call)
+				.unwrap()
+				.provides
+		};
+		let first_block = provides(call(1));
+		assert_eq!(provides(call(2)), first_block);
+		System::set_block_number(2);
+		assert_ne!(provides(call(2)), first_block);
+	});
+}
+
+#[test]
+fn submit_price_unsigned_rejects_signed_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::signed(1),
+				eth_usd(),
+				0,
+				1,
+				6,
+				1,
+				None,
+				None
+			),
+			DispatchError::BadOrigin,
+		);
+	});
+}
+
+#[test]
+fn hex_result_decodes_response() {
+	assert_eq!(json::hex_result(&rpc_response("0x0102ff"), 1), Ok(vec![1, 2, 255]));
+	// Whitespace, member order and escaped quotes elsewhere in the body don't matter.
+	let body = br#"{ "id" : 7, "note": "say \"result\":\"0xdead\"", "result" : "0x01", "jsonrpc": "2.0" }"#;
+	assert_eq!(json::hex_result(body, 7), Ok(vec![1]));
+	assert_eq!(RpcResponse::parse(body).unwrap().id, Some(7));
+
+	assert_eq!(
+		json::hex_result(br#"{"jsonrpc":"2.0","id":1,"result":"0102"}"#, 1),
+		Err(OracleError::DecodeError)
+	);
+	assert_eq!(json::hex_result(br#"{"jsonrpc":"2.0","id":1}"#, 1), Err(OracleError::DecodeError));
+	assert_eq!(json::hex_result(b"<html>502 Bad Gateway</html>", 1), Err(OracleError::DecodeError));
+}
+
+#[test]
+fn responses_must_echo_the_request_id() {
+	assert_eq!(json::hex_result(&rpc_response("0x01"), 2), Err(OracleError::ResponseIdMismatch));
+	assert_eq!(
+		json::hex_result(br#"{"jsonrpc":"2.0","result":"0x01"}"#, 1),
+		Err(OracleError::ResponseIdMismatch)
+	);
+	assert!(OracleError::ResponseIdMismatch.is_endpoint_failure());
+
+	let batch =
+		br#"[{"jsonrpc":"2.0","id":4,"result":"0x02"},{"jsonrpc":"2.0","id":3,"result":"0x01"}]"#;
+	assert_eq!(json::hex_results(batch, 3..5), Ok(vec![vec![1], vec![2]]));
+	assert_eq!(json::hex_results(batch, 1..3), Err(OracleError::ResponseIdMismatch));
+}
+
+#[test]
+fn request_ids_increase_across_requests() {
+	let (offchain, _) = TestOffchainExt::new();
+	let mut t = sp_io::TestExternalities::default();
+	t.register_extension(OffchainDbExt::new(offchain.clone()));
+	t.register_extension(OffchainWorkerExt::new(offchain));
+	t.execute_with(|| {
+		assert_eq!(endpoints::next_request_ids(1), 1);
+		assert_eq!(endpoints::next_request_ids(3), 2);
+		assert_eq!(endpoints::next_request_ids(1), 5);
+	});
+}
+
+#[test]
+fn identical_responses_from_different_endpoints_are_rejected() {
+	let (offchain, _) = TestOffchainExt::new();
+	let mut t = sp_io::TestExternalities::default();
+	t.register_extension(OffchainDbExt::new(offchain.clone()));
+	t.register_extension(OffchainWorkerExt::new(offchain));
+	t.execute_with(|| {
+		let body = rpc_response("0x01");
+		assert_eq!(endpoints::check_unique_response(b"https://a.example", &body), Ok(()));
+		assert_eq!(endpoints::check_unique_response(b"https://a.example", &body), Ok(()));
+		assert_eq!(
+			endpoints::check_unique_response(b"https://b.example", &body),
+			Err(OracleError::DuplicateResponse)
+		);
+		assert_eq!(
+			endpoints::check_unique_response(b"https://b.example", &rpc_response("0x02")),
+			Ok(())
+		);
+
+		// Only the latest responses are remembered.
+		for i in 0..endpoints::RECENT_RESPONSES {
+			let other = rpc_response(&format!("0x{:02x}", i + 3));
+			assert_eq!(endpoints::check_unique_response(b"https://a.example", &other), Ok(()));
+		}
+		assert_eq!(endpoints::check_unique_response(b"https://c.example", &body), Ok(()));
+	});
+}
+
+#[test]
+fn rpc_errors_are_surfaced() {
+	let body = br#"{"jsonrpc":"2.0","id":1,"error":{"code":-32005,"message":"rate limited"}}"#;
+	assert_eq!(
+		RpcResponse::parse(body).unwrap().result,
+		Err(RpcError { code: -32005, message: "rate limited".into() })
+	);
+	assert_eq!(json::hex_result(body, 1), Err(OracleError::RpcError { code: -32005 }));
+}
+
+#[test]
+fn uniswap_v3_parses_slot0() {
+	let data = array_bytes::hex2bytes(SLOT0_ETH_2500).unwrap();
+	let pool = registry::default_pairs()[0].pool(0).unwrap().metadata;
+	assert_eq!(UniswapV3Protocol.parse_price(&data, &pool), Ok(2500 * PRICE_UNIT));
+	assert_eq!(UniswapV3Protocol.parse_price(&data[..16], &pool), Err(OracleError::DecodeError));
+	assert_eq!(ProtocolKind::UniswapV3.parse_price(&data, &pool), Ok(2500 * PRICE_UNIT));
+
+	// The same pool state read the other way round: the price of USDC in WETH.
+	let inverse = PoolMetadata { base_is_token0: true, ..pool };
+	assert_eq!(UniswapV3Protocol.parse_price(&data, &inverse), Ok(PRICE_UNIT / 2500));
+}
+
+#[test]
+fn uniswap_v2_fork_parses_reserves() {
+	let data = array_bytes::hex2bytes(RESERVES_ETH_2000).unwrap();
+	let pool = registry::default_pairs()[0].pool(1).unwrap().metadata;
+	assert_eq!(UniswapV2Protocol.parse_price(&data, &pool), Ok(2000 * PRICE_UNIT));
+	assert_eq!(ProtocolKind::UniswapV2.parse_price(&data, &pool), Ok(2000 * PRICE_UNIT));
+
+	// Decimals are taken from the metadata rather than guessed: a pool of two 18 decimal tokens
+	// holding the same raw reserves prices the base token 1e12 times lower.
+	let same_decimals = PoolMetadata { token0_decimals: 18, ..pool };
+	assert_eq!(
+		UniswapV2Protocol.parse_price(&data, &same_decimals),
+		Ok(2000 * PRICE_UNIT / 1_000_000_000_000)
+	);
+	let inverse = PoolMetadata { base_is_token0: true, ..pool };
+	assert_eq!(UniswapV2Protocol.parse_price(&data, &inverse), Ok(PRICE_UNIT / 2000));
+}
+
+#[test]
+fn trader_joe_prices_avax_and_eth_from_their_pools() {
+	let reserves = |reserve0: u128, reserve1: u128| {
+		array_bytes::hex2bytes(format!("0x{reserve0:064x}{reserve1:064x}")).unwrap()
+	};
+	let pairs = registry::default_pairs();
+	let (eth, avax) = (pairs[0].pool(4).unwrap(), pairs[3].pool(4).unwrap());
+
+	// 1000 WAVAX against 25000 USDC.
+	let wavax_usdc = reserves(1_000 * 10u128.pow(18), 25_000 * 10u128.pow(6));
+	let avax_price = TraderJoeProtocol.parse_price(&wavax_usdc, &avax.metadata);
+	assert_eq!(avax_price, Ok(25 * PRICE_UNIT));
+	assert_eq!(ProtocolKind::TraderJoe.parse_price(&wavax_usdc, &avax.metadata), avax_price);
+
+	// 10 WETH.e against 1000 WAVAX, routed through the same WAVAX/USDC pool.
+	let weth_wavax = reserves(10 * 10u128.pow(18), 1_000 * 10u128.pow(18));
+	let eth_in_avax = TraderJoeProtocol.parse_price(&weth_wavax, &eth.metadata).unwrap();
+	assert_eq!(eth_in_avax, 100 * PRICE_UNIT);
+	let (_, _, hop) = registry::default_pool_hops().remove(0);
+	assert_eq!(hop, *avax);
+	assert_eq!(fixed::product(eth_in_avax, avax_price.unwrap()), Ok(2500 * PRICE_UNIT));
+}
+
+#[test]
+fn offchain_worker_submits_fetched_price() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: br#"{"jsonrpc":"2.0","method":"eth_call","params":[{"to":"0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640","data":"0x3850c7bd"},"latest"],"id":1}"#.to_vec(),
+		response: Some(rpc_response(SLOT0_ETH_2500)),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		PriceOracle::offchain_worker(fetch_block(1));
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		assert!(pool_state.read().transactions.is_empty());
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(
+			tx.function,
+			RuntimeCall::PriceOracle(Call::submit_price_unsigned {
+				pair_hash: eth_usd(),
+				exchange_id: 0,
+				price: 2500 * 100_000_000,
+				exponent: 8,
+				timestamp: 0,
+				source_block: None,
+				notional: None,
+			})
+		);
+	});
+}
+
+#[test]
+fn reads_left_out_by_the_budget_are_carried_over() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	// Uniswap V3 in the interval of the pair, SushiSwap in the block after.
+	for (id, pool, data, result) in [
+		(1, "0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640", "0x3850c7bd", SLOT0_ETH_2500),
+		(2, "0x397FF1542f962076d0BFE58eA045FfA2d347ACa0", "0x0902f1ac", RESERVES_ETH_2000),
+	] {
+		state.write().expect_request(PendingRequest {
+			method: "POST".into(),
+			uri: "https://eth.llamarpc.com".into(),
+			headers: vec![("Content-Type".into(), "application/json".into())],
+			body: format!(
+				r#"{{"jsonrpc":"2.0","method":"eth_call","params":[{{"to":"{pool}","data":"{data}"}},"latest"],"id":{id}}}"#
+			)
+			.into_bytes(),
+			response: Some(format!(r#"{{"jsonrpc":"2.0","id":{id},"result":"{result}"}}"#).into_bytes()),
+			sent: true,
+			..Default::default()
+		});
+	}
+
+	ext.execute_with(|| {
+		let carried = || {
+			let value = sp_io::offchain::local_storage_get(
+				sp_core::offchain::StorageKind::PERSISTENT,
+				crate::CARRIED_READS_KEY,
+			)
+			.unwrap();
+			Vec::<(PairId, ExchangeId, u32)>::decode(&mut &value[..]).unwrap()
+		};
+		let submitted = || {
+			let tx = pool_state.write().transactions.pop().unwrap();
+			match Extrinsic::decode(&mut &*tx).unwrap().function {
+				RuntimeCall::PriceOracle(Call::submit_price_unsigned {
+					exchange_id,
+					price,
+					..
+				}) => (exchange_id, price),
+				call => panic!("unexpected call {call:?}"),
+			}
+		};
+		MaxReadDelay::set(1);
+		let block = fetch_block(1);
+		PriceOracle::offchain_worker(block);
+		assert_eq!(submitted(), (0, 2500 * 100_000_000));
+		let exchanges: Vec<_> = carried().iter().map(|(_, exchange_id, _)| *exchange_id).collect();
+		assert_eq!(exchanges, [1, 2, 3, 4, 6, 7, 8, 9]);
+		assert_eq!(carried()[0], (eth_usd(), 1, block as u32));
+
+		// The carried reads are due in the next block, read first once they waited long enough.
+		PriceOracle::offchain_worker(block + 1);
+		assert_eq!(submitted(), (1, 2000 * 100_000_000));
+		assert!(pool_state.read().transactions.is_empty());
+		assert!(!carried().iter().any(|(_, exchange_id, _)| *exchange_id == 1));
+		assert!(carried().contains(&(eth_usd(), 2, block as u32)));
+	});
+}
+
+#[test]
+fn pairs_are_read_by_one_run_per_block() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: br#"{"jsonrpc":"2.0","method":"eth_call","params":[{"to":"0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640","data":"0x3850c7bd"},"latest"],"id":1}"#.to_vec(),
+		response: Some(rpc_response(SLOT0_ETH_2500)),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		// A second run of the block, e.g. on a sibling fork, leaves the pair to the first.
+		PriceOracle::offchain_worker(fetch_block(1));
+		PriceOracle::offchain_worker(fetch_block(1));
+		assert_eq!(pool_state.write().transactions.drain(..).count(), 1);
+
+		// Nor does a run read a pair another run holds the lock of.
+		let key = pair_lock_key(&eth_usd());
+		let mut lock = StorageLock::<BlockAndTime<System>>::with_block_and_time_deadline(
+			&key,
+			0,
+			Duration::from_millis(MaxOcwMillis::get()),
+		);
+		let _guard = lock.try_lock().unwrap();
+		PriceOracle::offchain_worker(fetch_block(2));
+		assert!(pool_state.read().transactions.is_empty());
+	});
+}
+
+#[test]
+fn flat_prices_are_only_submitted_as_heartbeats() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	for id in 1..=4 {
+		state.write().expect_request(PendingRequest {
+			method: "POST".into(),
+			uri: "https://eth.llamarpc.com".into(),
+			headers: vec![("Content-Type".into(), "application/json".into())],
+			body: format!(r#"{{"jsonrpc":"2.0","method":"eth_call","params":[{{"to":"0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640","data":"0x3850c7bd"}},"latest"],"id":{id}}}"#).into_bytes(),
+			response: Some(format!(r#"{{"jsonrpc":"2.0","id":{id},"result":"{SLOT0_ETH_2500}"}}"#).into_bytes()),
+			sent: true,
+			..Default::default()
+		});
+	}
+
+	ext.execute_with(|| {
+		SubmissionDeviationBps::set(100);
+		let run = |round, stored| {
+			PriceData::<Test>::insert(eth_usd(), 0, (stored, 0));
+			PriceOracle::offchain_worker(fetch_block(round));
+			pool_state.write().transactions.drain(..).count()
+		};
+		// 2500 is 0.4% off 2490 and 4% off 2400.
+		assert_eq!(run(1, 2_490 * PRICE_UNIT), 0);
+		assert_eq!(run(2, 2_400 * PRICE_UNIT), 1);
+		// The same price again, for the frozen price watchdog to count.
+		assert_eq!(run(3, 2_500 * PRICE_UNIT), 1);
+		state.write().timestamp = Timestamp::from_unix_millis(MAX_PRICE_AGE / 2);
+		assert_eq!(run(4, 2_490 * PRICE_UNIT), 1);
+	});
+}
+
+#[test]
+fn pairs_are_priced_through_their_pool_hops() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	// ETH at 2500 USDC, routed through a USDC/USDT pool at par.
+	const SLOT0_PAR: &str = "0x0000000000000000000000000000000000000001000000000000000000000000";
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: concat!(
+			r#"[{"jsonrpc":"2.0","method":"eth_call","params":[{"to":"0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640","data":"0x3850c7bd"},"latest"],"id":1},"#,
+			r#"{"jsonrpc":"2.0","method":"eth_call","params":[{"to":"0x3416cF6C708Da44DB2624D63ea0AAef7113527C6","data":"0x3850c7bd"},"latest"],"id":2}]"#,
+		)
+		.into(),
+		response: Some(
+			format!(
+				r#"[{{"jsonrpc":"2.0","id":1,"result":"{SLOT0_ETH_2500}"}},{{"jsonrpc":"2.0","id":2,"result":"{SLOT0_PAR}"}}]"#
+			)
+			.into_bytes(),
+		),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		let hop = PoolConfig {
+			address: price_oracle_eip55::parse("0x3416cF6C708Da44DB2624D63ea0AAef7113527C6")
+				.unwrap(),
+			metadata: PoolMetadata { token0_decimals: 6, token1_decimals: 6, base_is_token0: true },
+			account: None,
+		};
+		assert_ok!(PriceOracle::set_pool_hop(RuntimeOrigin::root(), eth_usd(), 0, Some(hop)));
+		PriceOracle::offchain_worker(fetch_block(1));
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		assert_eq!(
+			Extrinsic::decode(&mut &*tx).unwrap().function,
+			RuntimeCall::PriceOracle(Call::submit_price_unsigned {
+				pair_hash: eth_usd(),
+				exchange_id: 0,
+				price: 2500 * 100_000_000,
+				exponent: 8,
+				timestamp: 0,
+				source_block: None,
+				notional: None,
+			})
+		);
+	});
+}
+
+#[test]
+fn pool_hops_route_pools_of_the_pair_on_contract_chains() {
+	new_test_ext().execute_with(|| {
+		let (_, _, hop) = registry::default_pool_hops().remove(0);
+		let set = |pair_id, exchange_id, hop: &PoolConfig| {
+			let hop = Some(hop.clone());
+			PriceOracle::set_pool_hop(RuntimeOrigin::root(), pair_id, exchange_id, hop)
+		};
+		assert_noop!(
+			PriceOracle::set_pool_hop(RuntimeOrigin::signed(OPERATOR), eth_usd(), 1, None),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(set(pair_id(b"BTC", b"USD"), 1, &hop), Error::<Test>::PairNotTracked);
+		assert_noop!(set(eth_usd(), 10, &hop), Error::<Test>::UnknownExchange);
+		assert_noop!(set(sol_usd(), 1, &hop), Error::<Test>::HopWithoutPool);
+		assert_noop!(set(sol_usd(), 5, &hop), Error::<Test>::InvalidPoolAccount);
+		let zero = PoolConfig { address: Default::default(), ..hop.clone() };
+		assert_noop!(set(eth_usd(), 1, &zero), Error::<Test>::ZeroAddress);
+
+		assert_ok!(set(eth_usd(), 1, &hop));
+		System::assert_last_event(
+			Event::PoolHopSet { pair_id: eth_usd(), exchange_id: 1, hop: Some(hop.clone()) }.into(),
+		);
+		assert_eq!(PoolHops::<Test>::get(eth_usd(), 1), Some(hop));
+
+		// Hops follow their exchange and go with their pair.
+		let key = Exchanges::<Test>::get(1).unwrap().key();
+		assert_ok!(PriceOracle::remap_exchange(RuntimeOrigin::root(), key, 10, 2, 1));
+		assert!(PoolHops::<Test>::contains_key(eth_usd(), 10));
+		assert_ok!(PriceOracle::remove_pair(RuntimeOrigin::root(), eth_usd()));
+		assert_eq!(PoolHops::<Test>::iter_prefix(eth_usd()).count(), 0);
+	});
+}
+
+#[test]
+fn prices_from_lagging_endpoints_are_discarded() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	let head = |id: u64, timestamp: u64| {
+		(
+			format!(
+				r#"{{"jsonrpc":"2.0","method":"eth_getBlockByNumber","params":["latest",false],"id":{id}}}"#
+			),
+			format!(
+				r#"{{"jsonrpc":"2.0","id":{id},"result":{{"number":"0x1406f40","timestamp":"{timestamp:#x}"}}}}"#
+			),
+		)
+	};
+	// Uniswap V3 read at a head of 100 s, SushiSwap at one 90 s older.
+	let requests = [
+		(
+			r#"{"jsonrpc":"2.0","method":"eth_call","params":[{"to":"0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640","data":"0x3850c7bd"},"latest"],"id":1}"#.to_string(),
+			String::from_utf8(rpc_response(SLOT0_ETH_2500)).unwrap(),
+		),
+		head(2, 100),
+		(
+			r#"{"jsonrpc":"2.0","method":"eth_call","params":[{"to":"0x397FF1542f962076d0BFE58eA045FfA2d347ACa0","data":"0x0902f1ac"},"latest"],"id":3}"#.to_string(),
+			format!(r#"{{"jsonrpc":"2.0","id":3,"result":"{RESERVES_ETH_2000}"}}"#),
+		),
+		head(4, 10),
+	];
+	for (body, response) in requests {
+		state.write().expect_request(PendingRequest {
+			method: "POST".into(),
+			uri: "https://eth.llamarpc.com".into(),
+			headers: vec![("Content-Type".into(), "application/json".into())],
+			body: body.into_bytes(),
+			response: Some(response.into_bytes()),
+			sent: true,
+			..Default::default()
+		});
+	}
+
+	ext.execute_with(|| {
+		MaxExchangesPerBlock::set(2);
+		ChainTimeTolerance::set(60);
+		PriceOracle::offchain_worker(fetch_block(1));
+
+		let calls: Vec<_> = pool_state
+			.write()
+			.transactions
+			.drain(..)
+			.map(|tx| Extrinsic::decode(&mut &*tx).unwrap().function)
+			.collect();
+		// Failures are reported as they are found, prices once every read is in.
+		assert_eq!(
+			calls,
+			vec![
+				RuntimeCall::PriceOracle(Call::report_fetch_failure {
+					pair_id: eth_usd(),
+					exchange_id: 1,
+					error: OracleError::LaggingEndpoint,
+				}),
+				RuntimeCall::PriceOracle(Call::submit_price_unsigned {
+					pair_hash: eth_usd(),
+					exchange_id: 0,
+					price: 2500 * 100_000_000,
+					exponent: 8,
+					timestamp: 0,
+					source_block: None,
+					notional: None,
+				}),
+			]
+		);
+		assert_eq!(endpoints::failures(b"https://eth.llamarpc.com"), 1);
+	});
+
+	assert_eq!(
+		endpoints::lagging_heads(&[(1, 100), (56, 10), (1, 40), (1, 39)], 60),
+		[false, false, false, true]
+	);
+}
+
+/// `aggregate3` return data of the `slot0()` of the USDC/WETH pool with ETH at 2500 USD and the
+/// `getReserves()` of a USDC/WETH pool with ETH at 2000 USD.
+const AGGREGATE3_ETH_2500_2000: &str = concat!(
+	"0x0000000000000000000000000000000000000000000000000000000000000020",
+	"0000000000000000000000000000000000000000000000000000000000000002",
+	"0000000000000000000000000000000000000000000000000000000000000040",
+	"00000000000000000000000000000000000000000000000000000000000000c0",
+	"0000000000000000000000000000000000000000000000000000000000000001",
+	"0000000000000000000000000000000000000000000000000000000000000040",
+	"0000000000000000000000000000000000000000000000000000000000000020",
+	"0000000000000000000000000000000000004e20000000000000000000000000",
+	"0000000000000000000000000000000000000000000000000000000000000001",
+	"0000000000000000000000000000000000000000000000000000000000000040",
+	"0000000000000000000000000000000000000000000000000000000000000040",
+	"000000000000000000000000000000000000000000000000000001d1a94a2000",
+	"00000000000000000000000000000000000000000000003635c9adc5dea00000",
+);
+
+#[test]
+fn multicall_encodes_calls_and_splits_their_results() {
+	let pool = price_oracle_eip55::parse("0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640").unwrap();
+	assert_eq!(
+		multicall::aggregate3_call_data(&[(&pool, "0x3850c7bd")]).unwrap(),
+		concat!(
+			"0x82ad56cb",
+			"0000000000000000000000000000000000000000000000000000000000000020",
+			"0000000000000000000000000000000000000000000000000000000000000001",
+			"0000000000000000000000000000000000000000000000000000000000000020",
+			"00000000000000000000000088e6a0c2ddd26feeb64f039a2c41296fcb3f5640",
+			"0000000000000000000000000000000000000000000000000000000000000001",
+			"0000000000000000000000000000000000000000000000000000000000000060",
+			"0000000000000000000000000000000000000000000000000000000000000004",
+			"3850c7bd00000000000000000000000000000000000000000000000000000000",
+		)
+	);
+	assert_eq!(
+		multicall::aggregate3_call_data(&[(&pool, "0x3850c7b")]),
+		Err(OracleError::InvalidConfig)
+	);
+
+	let data = array_bytes::hex2bytes(AGGREGATE3_ETH_2500_2000).unwrap();
+	let results = multicall::parse_aggregate3(&data, 2).unwrap();
+	assert_eq!(results[0], Ok(array_bytes::hex2bytes(SLOT0_ETH_2500).unwrap()));
+	assert_eq!(results[1], Ok(array_bytes::hex2bytes(RESERVES_ETH_2000).unwrap()));
+	assert_eq!(multicall::parse_aggregate3(&data, 3), Err(OracleError::DecodeError));
+
+	// The second call reverted with no data.
+	let reverted = array_bytes::hex2bytes(concat!(
+		"0x0000000000000000000000000000000000000000000000000000000000000020",
+		"0000000000000000000000000000000000000000000000000000000000000002",
+		"0000000000000000000000000000000000000000000000000000000000000040",
+		"00000000000000000000000000000000000000000000000000000000000000c0",
+		"0000000000000000000000000000000000000000000000000000000000000001",
+		"0000000000000000000000000000000000000000000000000000000000000040",
+		"0000000000000000000000000000000000000000000000000000000000000020",
+		"0000000000000000000000000000000000004e20000000000000000000000000",
+		"0000000000000000000000000000000000000000000000000000000000000000",
+		"0000000000000000000000000000000000000000000000000000000000000040",
+		"0000000000000000000000000000000000000000000000000000000000000000",
+	))
+	.unwrap();
+	let results = multicall::parse_aggregate3(&reverted, 2).unwrap();
+	assert!(results[0].is_ok());
+	assert_eq!(results[1], Err(OracleError::Reverted));
+}
+
+#[test]
+fn pools_of_multicall_chains_are_read_in_one_call() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	let uniswap = price_oracle_eip55::parse("0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640").unwrap();
+	let sushi = price_oracle_eip55::parse("0x397FF1542f962076d0BFE58eA045FfA2d347ACa0").unwrap();
+	let data = multicall::aggregate3_call_data(&[(&uniswap, "0x3850c7bd"), (&sushi, "0x0902f1ac")])
+		.unwrap();
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: format!(
+			r#"{{"jsonrpc":"2.0","method":"eth_call","params":[{{"to":"0xcA11bde05977b3631167028862bE2a173976CA11","data":"{data}"}},"latest"],"id":1}}"#
+		)
+		.into_bytes(),
+		response: Some(rpc_response(AGGREGATE3_ETH_2500_2000)),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		MaxExchangesPerBlock::set(2);
+		assert_noop!(
+			PriceOracle::set_multicall(RuntimeOrigin::root(), 7, true),
+			Error::<Test>::UnknownChain
+		);
+		assert_ok!(PriceOracle::set_multicall(RuntimeOrigin::root(), 1, true));
+		assert!(MulticallChains::<Test>::contains_key(1));
+		PriceOracle::offchain_worker(fetch_block(1));
+
+		let calls: Vec<_> = pool_state
+			.write()
+			.transactions
+			.drain(..)
+			.map(|tx| Extrinsic::decode(&mut &*tx).unwrap().function)
+			.collect();
+		let submission = |exchange_id, price| {
+			RuntimeCall::PriceOracle(Call::submit_price_unsigned {
+				pair_hash: eth_usd(),
+				exchange_id,
+				price,
+				exponent: 8,
+				timestamp: 0,
+				source_block: None,
+				notional: None,
+			})
+		};
+		assert_eq!(
+			calls,
+			vec![submission(0, 2500 * 100_000_000), submission(1, 2000 * 100_000_000)]
+		);
+	});
+}
+
+#[test]
+fn pools_of_other_chains_are_read_in_one_batch() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	// Answered out of order, and mapped back by id.
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: concat!(
+			r#"[{"jsonrpc":"2.0","method":"eth_call","params":[{"to":"0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640","data":"0x3850c7bd"},"latest"],"id":1},"#,
+			r#"{"jsonrpc":"2.0","method":"eth_call","params":[{"to":"0x397FF1542f962076d0BFE58eA045FfA2d347ACa0","data":"0x0902f1ac"},"latest"],"id":2}]"#,
+		)
+		.into(),
+		response: Some(
+			format!(
+				r#"[{{"jsonrpc":"2.0","id":2,"result":"{RESERVES_ETH_2000}"}},{{"jsonrpc":"2.0","id":1,"result":"{SLOT0_ETH_2500}"}}]"#
+			)
+			.into_bytes(),
+		),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		MaxExchangesPerBlock::set(2);
+		BatchRpcCalls::set(true);
+		PriceOracle::offchain_worker(fetch_block(1));
+
+		let calls: Vec<_> = pool_state
+			.write()
+			.transactions
+			.drain(..)
+			.map(|tx| Extrinsic::decode(&mut &*tx).unwrap().function)
+			.collect();
+		let submission = |exchange_id, price| {
+			RuntimeCall::PriceOracle(Call::submit_price_unsigned {
+				pair_hash: eth_usd(),
+				exchange_id,
+				price,
+				exponent: 8,
+				timestamp: 0,
+				source_block: None,
+				notional: None,
+			})
+		};
+		assert_eq!(
+			calls,
+			vec![submission(0, 2500 * 100_000_000), submission(1, 2000 * 100_000_000)]
+		);
+	});
+}
+
+#[test]
+fn congested_transaction_pools_get_the_median_price_only() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	let uniswap = price_oracle_eip55::parse("0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640").unwrap();
+	let sushi = price_oracle_eip55::parse("0x397FF1542f962076d0BFE58eA045FfA2d347ACa0").unwrap();
+	let data = multicall::aggregate3_call_data(&[(&uniswap, "0x3850c7bd"), (&sushi, "0x0902f1ac")])
+		.unwrap();
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: format!(
+			r#"{{"jsonrpc":"2.0","method":"eth_call","params":[{{"to":"0xcA11bde05977b3631167028862bE2a173976CA11","data":"{data}"}},"latest"],"id":1}}"#
+		)
+		.into_bytes(),
+		response: Some(rpc_response(AGGREGATE3_ETH_2500_2000)),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		let refused = || {
+			sp_io::offchain::local_storage_get(
+				sp_core::offchain::StorageKind::PERSISTENT,
+				crate::REFUSED_SUBMISSIONS_KEY,
+			)
+			.map(|value| u32::decode(&mut &value[..]).unwrap())
+		};
+		MaxExchangesPerBlock::set(2);
+		assert_ok!(PriceOracle::set_multicall(RuntimeOrigin::root(), 1, true));
+		// The pool refused a transaction of the last run.
+		sp_io::offchain::local_storage_set(
+			sp_core::offchain::StorageKind::PERSISTENT,
+			crate::REFUSED_SUBMISSIONS_KEY,
+			&1u32.encode(),
+		);
+		PriceOracle::offchain_worker(fetch_block(1));
+
+		let calls: Vec<_> = pool_state
+			.write()
+			.transactions
+			.drain(..)
+			.map(|tx| Extrinsic::decode(&mut &*tx).unwrap().function)
+			.collect();
+		// The lower of the two prices, the median of an even number.
+		assert_eq!(
+			calls,
+			vec![RuntimeCall::PriceOracle(Call::submit_price_unsigned {
+				pair_hash: eth_usd(),
+				exchange_id: 1,
+				price: 2000 * 100_000_000,
+				exponent: 8,
+				timestamp: 0,
+				source_block: None,
+				notional: None,
+			})]
+		);
+		assert_eq!(refused(), Some(0));
+	});
+}
+
+#[test]
+fn the_exchange_budget_is_shared_by_contribution() {
+	// Exchange 0 weighs three times exchange 1, whose reads are due in between.
+	let candidates = [(0u8, 3), (1, 1), (0, 3), (0, 3), (1, 1)];
+	assert_eq!(budget::allocate(4, &candidates), [true, true, true, true, false]);
+	assert_eq!(budget::allocate(1, &candidates), [true, false, false, false, false]);
+	// Equal weights share evenly, each exchange reading its pools in the order they are due.
+	assert_eq!(budget::allocate(2, &[(0u8, 1), (0, 1), (1, 1)]), [true, false, true]);
+	// The share of an exchange out of reads goes to the others.
+	assert_eq!(budget::allocate(4, &[(0u8, 5), (1, 1), (1, 1)]), [true, true, true]);
+
+	new_test_ext().execute_with(|| {
+		set_now(MAX_PRICE_AGE + 11);
+		let median = 2_000 * PRICE_UNIT;
+		let aggregate = PriceAggregate { median, mean: median, sources: 3, timestamp: 20 };
+		AggregatedPrice::<Test>::insert(eth_usd(), aggregate);
+		// Within 1% of the median, 5% off it, and stale.
+		PriceData::<Test>::insert(eth_usd(), 0, (2_010 * PRICE_UNIT, 20));
+		PriceData::<Test>::insert(eth_usd(), 1, (2_100 * PRICE_UNIT, 20));
+		PriceData::<Test>::insert(eth_usd(), 2, (2_000 * PRICE_UNIT, 10));
+		assert_eq!(PriceOracle::contribution_scores(), [(0, 1)].into());
+	});
+}
+
+#[test]
+fn calls_carry_the_options_of_their_protocol() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	let (slot, value) = (format!("0x{:064x}", 0), format!("0x{:064x}", 1));
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: format!(
+			r#"{{"jsonrpc":"2.0","method":"eth_call","params":[{{"to":"0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640","data":"0x3850c7bd","gas":"0x1e8480"}},"latest",{{"0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640":{{"stateDiff":{{"{slot}":"{value}"}}}}}}],"id":1}}"#
+		)
+		.into_bytes(),
+		response: Some(rpc_response(SLOT0_ETH_2500)),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		let pool = registry::default_pairs()[0].pools[0].1.address;
+		let options = CallOptions {
+			gas: Some(2_000_000),
+			state_overrides: vec![StateOverride {
+				address: pool,
+				code: None,
+				state_diff: vec![([0; 32], U256::one().to_big_endian())].try_into().unwrap(),
+			}]
+			.try_into()
+			.unwrap(),
+			..Default::default()
+		};
+		assert_noop!(
+			PriceOracle::set_call_options(
+				RuntimeOrigin::signed(1),
+				ProtocolKind::UniswapV3,
+				Some(options.clone())
+			),
+			DispatchError::BadOrigin,
+		);
+		assert_ok!(PriceOracle::set_call_options(
+			RuntimeOrigin::root(),
+			ProtocolKind::UniswapV3,
+			Some(options.clone())
+		));
+		System::assert_last_event(
+			Event::CallOptionsSet { protocol: ProtocolKind::UniswapV3, options: Some(options) }
+				.into(),
+		);
+
+		PriceOracle::offchain_worker(fetch_block(1));
+		assert_eq!(pool_state.read().transactions.len(), 1);
+
+		assert_ok!(PriceOracle::set_call_options(
+			RuntimeOrigin::root(),
+			ProtocolKind::UniswapV3,
+			None
+		));
+		assert_eq!(
+			ProtocolCallOptions::<Test>::get(ProtocolKind::UniswapV3),
+			CallOptions::default()
+		);
+	});
+}
+
+#[test]
+fn calls_are_executed_at_the_confirmation_of_their_chain() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	let call = |id: u64, block: &str| {
+		format!(
+			r#"{{"jsonrpc":"2.0","method":"eth_call","params":[{{"to":"0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640","data":"0x3850c7bd"}},"{block}"],"id":{id}}}"#
+		)
+	};
+	let result =
+		|id: u64, result: &str| format!(r#"{{"jsonrpc":"2.0","id":{id},"result":"{result}"}}"#);
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: call(1, "safe").into_bytes(),
+		response: Some(rpc_response(SLOT0_ETH_2500)),
+		sent: true,
+		..Default::default()
+	});
+	ext.execute_with(|| {
+		assert_noop!(
+			PriceOracle::set_confirmation(RuntimeOrigin::signed(1), 1, Confirmation::Safe),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			PriceOracle::set_confirmation(RuntimeOrigin::root(), 7, Confirmation::Safe),
+			Error::<Test>::UnknownChain
+		);
+		assert_ok!(PriceOracle::set_confirmation(RuntimeOrigin::root(), 1, Confirmation::Safe));
+		System::assert_last_event(
+			Event::ConfirmationSet { chain_id: 1, confirmation: Confirmation::Safe }.into(),
+		);
+
+		PriceOracle::offchain_worker(fetch_block(1));
+		assert_eq!(pool_state.read().transactions.len(), 1);
+	});
+
+	// The more confirmed of the chain's and the protocol's blocks wins.
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: call(2, "finalized").into_bytes(),
+		response: Some(result(2, SLOT0_ETH_2500).into_bytes()),
+		sent: true,
+		..Default::default()
+	});
+	ext.execute_with(|| {
+		let options = CallOptions { confirmation: Confirmation::Finalized, ..Default::default() };
+		assert_ok!(PriceOracle::set_call_options(
+			RuntimeOrigin::root(),
+			ProtocolKind::UniswapV3,
+			Some(options)
+		));
+
+		PriceOracle::offchain_worker(fetch_block(2));
+		assert_eq!(pool_state.read().transactions.len(), 2);
+
+		assert_ok!(PriceOracle::set_confirmation(RuntimeOrigin::root(), 1, Confirmation::Latest));
+		assert!(!ChainConfirmations::<Test>::contains_key(1));
+	});
+}
+
+#[test]
+fn deprecated_pairs_are_read_only_until_they_are_removed() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PriceOracle::deprecate_pair(RuntimeOrigin::signed(1), eth_usd(), 6),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			PriceOracle::deprecate_pair(RuntimeOrigin::root(), pair_id(b"LINK", b"USD"), 6),
+			Error::<Test>::PairNotTracked
+		);
+		assert_noop!(
+			PriceOracle::deprecate_pair(RuntimeOrigin::root(), eth_usd(), 1),
+			Error::<Test>::InvalidDeprecation
+		);
+		assert_ok!(PriceOracle::set_alert(RuntimeOrigin::signed(1), eth_usd(), 500, PRICE_UNIT));
+		assert_ok!(PriceOracle::deprecate_pair(RuntimeOrigin::root(), eth_usd(), 6));
+		System::assert_last_event(
+			Event::PairDeprecated { pair_id: eth_usd(), after_block: 6 }.into(),
+		);
+
+		let pair = TrackedPairs::<Test>::get(eth_usd()).unwrap();
+		assert_noop!(
+			PriceOracle::update_pair(RuntimeOrigin::root(), pair),
+			Error::<Test>::PairDeprecated
+		);
+		assert_noop!(
+			PriceOracle::set_long_tail(RuntimeOrigin::root(), eth_usd(), Some(1)),
+			Error::<Test>::PairDeprecated
+		);
+		assert_noop!(
+			PriceOracle::deprecate_pair(RuntimeOrigin::root(), eth_usd(), 10),
+			Error::<Test>::PairDeprecated
+		);
+		// Consumers can drop their alerts, but not add new ones.
+		assert_noop!(
+			PriceOracle::set_alert(RuntimeOrigin::signed(2), eth_usd(), 500, PRICE_UNIT),
+			Error::<Test>::PairDeprecated
+		);
+		assert_ok!(PriceOracle::set_alert(RuntimeOrigin::signed(1), eth_usd(), 0, PRICE_UNIT));
+
+		// The pair is still priced until it is removed.
+		assert_ok!(PriceOracle::submit_price_unsigned(
+			RuntimeOrigin::none(),
+			eth_usd(),
+			0,
+			2_000,
+			0,
+			1,
+			None,
+			None,
+		));
+		for block in [3, 6] {
+			PriceOracle::on_initialize(block);
+			System::assert_last_event(
+				Event::PairDeprecationPending { pair_id: eth_usd(), after_block: 6 }.into(),
+			);
+		}
+		PriceOracle::on_initialize(9);
+		System::assert_last_event(Event::PairRemoved { pair_id: eth_usd() }.into());
+		assert!(!TrackedPairs::<Test>::contains_key(eth_usd()));
+		assert!(!DeprecatedPairs::<Test>::contains_key(eth_usd()));
+	});
+}
+
+#[test]
+fn offchain_worker_submits_the_pool_notional_of_long_tail_pairs() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	let pool = "0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640";
+	let call = |id: u64, data: &str| {
+		format!(
+			r#"{{"jsonrpc":"2.0","method":"eth_call","params":[{{"to":"{pool}","data":"{data}"}},"latest"],"id":{id}}}"#
+		)
+	};
+	let result =
+		|id: u64, result: &str| format!(r#"{{"jsonrpc":"2.0","id":{id},"result":"{result}"}}"#);
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: call(1, "0x3850c7bd").into_bytes(),
+		response: Some(rpc_response(SLOT0_ETH_2500)),
+		sent: true,
+		..Default::default()
+	});
+	// At 2500 USD per ETH a liquidity of 10^15 backs 50,000 USDC in range.
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: format!("[{},{}]", call(2, "0x3850c7bd"), call(3, "0x1a686502")).into_bytes(),
+		response: Some(
+			format!(
+				"[{},{}]",
+				result(3, &format!("0x{:064x}", 10u128.pow(15))),
+				result(2, SLOT0_ETH_2500)
+			)
+			.into_bytes(),
+		),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		TrackedPairs::<Test>::remove(sol_usd());
+		assert_ok!(PriceOracle::set_long_tail(RuntimeOrigin::root(), eth_usd(), Some(1_000)));
+		PriceOracle::offchain_worker(fetch_block(1));
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(
+			tx.function,
+			RuntimeCall::PriceOracle(Call::submit_price_unsigned {
+				pair_hash: eth_usd(),
+				exchange_id: 0,
+				price: 2500 * 100_000_000,
+				exponent: 8,
+				timestamp: 0,
+				source_block: None,
+				notional: Some(100_000),
+			})
+		);
+	});
+}
+
+#[test]
+fn offchain_worker_reports_the_pool_notional_of_every_pair_with_report_liquidity() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	let pool = "0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640";
+	let call = |id: u64, data: &str| {
+		format!(
+			r#"{{"jsonrpc":"2.0","method":"eth_call","params":[{{"to":"{pool}","data":"{data}"}},"latest"],"id":{id}}}"#
+		)
+	};
+	let result =
+		|id: u64, result: &str| format!(r#"{{"jsonrpc":"2.0","id":{id},"result":"{result}"}}"#);
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: call(1, "0x3850c7bd").into_bytes(),
+		response: Some(rpc_response(SLOT0_ETH_2500)),
+		sent: true,
+		..Default::default()
+	});
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: format!("[{},{}]", call(2, "0x3850c7bd"), call(3, "0x1a686502")).into_bytes(),
+		response: Some(
+			format!(
+				"[{},{}]",
+				result(2, SLOT0_ETH_2500),
+				result(3, &format!("0x{:064x}", 10u128.pow(15)))
+			)
+			.into_bytes(),
+		),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		ReportLiquidity::set(true);
+		TrackedPairs::<Test>::remove(sol_usd());
+		PriceOracle::offchain_worker(fetch_block(1));
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		let RuntimeCall::PriceOracle(Call::submit_price_unsigned { price, notional, .. }) =
+			tx.function
+		else {
+			panic!("unexpected call {:?}", tx.function)
+		};
+		assert_eq!(notional, Some(100_000));
+		assert_ok!(PriceOracle::submit_price_unsigned(
+			RuntimeOrigin::none(),
+			eth_usd(),
+			0,
+			price,
+			8,
+			0,
+			None,
+			notional,
+		));
+
+		// A pool of unknown notional only counts without a minimum.
+		PriceData::<Test>::insert(eth_usd(), 1, (2_501 * 100_000_000, 0));
+		let liquid = |min_liquidity| {
+			let mut liquid: Vec<_> = PriceOracle::liquid_prices(eth_usd(), min_liquidity, 8)
+				.into_iter()
+				.map(|(exchange_id, quote)| (exchange_id, quote.liquidity))
+				.collect();
+			liquid.sort();
+			liquid
+		};
+		assert_eq!(liquid(0), vec![(0, Some(100_000)), (1, None)]);
+		assert_eq!(liquid(100_000), vec![(0, Some(100_000))]);
+		assert_eq!(liquid(100_001), vec![]);
+	});
+}
+
+#[test]
+fn pool_depth_is_the_quote_token_a_pool_holds() {
+	// USDC/WETH: the quote token is token0.
+	let pool = PoolMetadata { token0_decimals: 6, token1_decimals: 18, base_is_token0: false };
+	let word = |value: u128| format!("{value:064x}");
+	let reserves = array_bytes::hex2bytes(format!("{}{}{}", word(7), word(3), word(0))).unwrap();
+	assert_eq!(UniswapV2Protocol.parse_depth(core::slice::from_ref(&reserves), &pool), Ok(7));
+	let weth_usdc = PoolMetadata { base_is_token0: true, ..pool };
+	assert_eq!(UniswapV2Protocol.parse_depth(&[reserves], &weth_usdc), Ok(3));
+
+	// Concentrated liquidity holds `L / sqrtP` of token0 and `L * sqrtP` of token1.
+	let sqrt_price_x96 = U256::from(4) << 96;
+	assert_eq!(fixed::virtual_reserve(1_000, sqrt_price_x96, true), Ok(250));
+	assert_eq!(fixed::virtual_reserve(1_000, sqrt_price_x96, false), Ok(4_000));
+	assert_eq!(fixed::virtual_reserve(1_000, U256::zero(), true), Err(OracleError::DecodeError));
+
+	let account = whirlpool_account(SQRT_PRICE_SOL_150, 1_000_000_000_000);
+	let sol_usdc = PoolMetadata { token0_decimals: 9, token1_decimals: 6, base_is_token0: true };
+	let usdc = OrcaWhirlpoolProtocol.parse_depth(&[account], &sol_usdc).unwrap();
+	// sqrt(150 * 10^6 / 10^9) * 10^12 raw USDC.
+	assert_eq!(usdc / 10_000_000_000, 38);
+	assert_eq!(
+		ProtocolKind::Chainlink.parse_depth(&[], &sol_usdc),
+		Err(OracleError::UnsupportedPair)
+	);
+}
+
+#[test]
+fn offchain_worker_submits_the_source_block_of_cross_checked_pairs() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: br#"{"jsonrpc":"2.0","method":"eth_call","params":[{"to":"0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640","data":"0x3850c7bd"},"latest"],"id":1}"#.to_vec(),
+		response: Some(rpc_response(SLOT0_ETH_2500)),
+		sent: true,
+		..Default::default()
+	});
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: br#"{"jsonrpc":"2.0","method":"eth_blockNumber","params":[],"id":2}"#.to_vec(),
+		response: Some(br#"{"jsonrpc":"2.0","id":2,"result":"0x1406f40"}"#.to_vec()),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		let check = CrossChainCheck { threshold_bps: 100, max_skew: None };
+		assert_ok!(PriceOracle::set_cross_chain_check(
+			RuntimeOrigin::root(),
+			eth_usd(),
+			Some(check)
+		));
+		PriceOracle::offchain_worker(fetch_block(1));
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(
+			tx.function,
+			RuntimeCall::PriceOracle(Call::submit_price_unsigned {
+				pair_hash: eth_usd(),
+				exchange_id: 0,
+				price: 2500 * 100_000_000,
+				exponent: 8,
+				timestamp: 0,
+				source_block: Some(21_000_000),
+				notional: None,
+			})
+		);
+		assert_ok!(PriceOracle::submit_price_unsigned(
+			RuntimeOrigin::none(),
+			eth_usd(),
+			0,
+			2500 * 100_000_000,
+			8,
+			0,
+			Some(21_000_000),
+			None,
+		));
+		assert_eq!(SourceBlocks::<Test>::get(eth_usd(), 0), Some(21_000_000));
+	});
+}
+
+#[test]
+fn offchain_worker_skips_pairs_outside_of_their_phase() {
+	let (offchain, _state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	ext.execute_with(|| {
+		PriceOracle::offchain_worker(fetch_block(1) + 1);
+		assert!(pool_state.read().transactions.is_empty());
+	});
+}
+
+#[test]
+fn fetch_price_reports_failing_layer() {
+	let (offchain, state) = TestOffchainExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: br#"{"jsonrpc":"2.0","method":"eth_call","params":[{"to":"0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640","data":"0x3850c7bd"},"latest"],"id":1}"#.to_vec(),
+		response: Some(br#"{"jsonrpc":"2.0","id":1,"error":{"code":-32005,"message":"limit"}}"#.to_vec()),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		let (id, uniswap) = registry::default_exchanges().remove(0);
+		let pair = registry::default_pairs().remove(0);
+		let pool = pair.pool(id).unwrap();
+		assert_eq!(
+			uniswap.fetch_price(
+				b"https://eth.llamarpc.com",
+				&pair,
+				pool,
+				&CallOptions::default(),
+				1_000
+			),
+			Err(OracleError::RpcError { code: -32005 })
+		);
+		assert_eq!(
+			uniswap.fetch_price(&[0xff], &pair, pool, &CallOptions::default(), 1_000),
+			Err(OracleError::InvalidConfig)
+		);
+	});
+}
+
+#[test]
+fn request_builder_serializes_requests() {
+	let body = |request: RequestBuilder| String::from_utf8(request.build()).unwrap();
+
+	assert_eq!(
+		body(RequestBuilder::eth_call(&[0xaa; 20], "0x02", BlockTag::Number(255)).id(3)),
+		r#"{"jsonrpc":"2.0","method":"eth_call","params":[{"to":"0xaAaAaAaaAaAaAaaAaAAAAAAAAaaaAaAaAaaAaaAa","data":"0x02"},"0xff"],"id":3}"#
+	);
+	assert_eq!(
+		body(RequestBuilder::eth_gas_price()),
+		r#"{"jsonrpc":"2.0","method":"eth_gasPrice","params":[],"id":1}"#
+	);
+	assert_eq!(
+		body(RequestBuilder::eth_fee_history(4, BlockTag::Latest, &[25, 75])),
+		r#"{"jsonrpc":"2.0","method":"eth_feeHistory","params":["0x4","latest",[25,75]],"id":1}"#
+	);
+	assert_eq!(
+		body(RequestBuilder::eth_get_block_by_number(BlockTag::Finalized, false)),
+		r#"{"jsonrpc":"2.0","method":"eth_getBlockByNumber","params":["finalized",false],"id":1}"#
+	);
+	// Parameters are escaped rather than spliced into the body.
+	assert_eq!(
+		body(RequestBuilder::eth_call(&[0; 20], "0x01\",\"x", BlockTag::Safe)),
+		r#"{"jsonrpc":"2.0","method":"eth_call","params":[{"to":"0x0000000000000000000000000000000000000000","data":"0x01\",\"x"},"safe"],"id":1}"#
+	);
+}
+
+#[test]
+fn request_builder_serializes_batches() {
+	let batch = RequestBuilder::batch(&[
+		RequestBuilder::eth_gas_price().id(1),
+		RequestBuilder::eth_get_block_by_number(BlockTag::Latest, false).id(2),
+	]);
+	assert_eq!(
+		String::from_utf8(batch).unwrap(),
+		concat!(
+			r#"[{"jsonrpc":"2.0","method":"eth_gasPrice","params":[],"id":1},"#,
+			r#"{"jsonrpc":"2.0","method":"eth_getBlockByNumber","params":["latest",false],"id":2}]"#,
+		)
+	);
+}
+
+#[test]
+fn fixed_point_conversions_are_exact() {
+	// sqrtPriceX96 of exactly 1: one raw token1 per raw token0.
+	let one = U256::one() << 96;
+	assert_eq!(fixed::price_from_sqrt_x96(one, 0, false), Ok(PRICE_UNIT));
+	assert_eq!(fixed::price_from_sqrt_x96(one, -6, true), Ok(PRICE_UNIT / 1_000_000));
+	assert_eq!(fixed::price_from_sqrt_x96(U256::zero(), 0, true), Err(OracleError::DecodeError));
+	// Results that don't fit a price are rejected rather than wrapped.
+	assert_eq!(fixed::price_from_sqrt_x96(U256::MAX, 0, false), Err(OracleError::DecodeError));
+
+	assert_eq!(fixed::to_micro(2500 * PRICE_UNIT + 999_999_999_999), 2_500_000_000);
+	assert_eq!(fixed::from_micro(2_500_000_000), 2500 * PRICE_UNIT);
+}
+
+#[test]
+fn registry_origin_manages_pairs() {
+	new_test_ext().execute_with(|| {
+		let mut btc = registry::default_pairs().remove(0);
+		btc.base = b"BTC".to_vec().try_into().unwrap();
+		btc.pools.clear();
+		let btc_usd = pair_id(b"BTC", b"USD");
+		assert_eq!(btc.id(), btc_usd);
+
+		assert_noop!(
+			PriceOracle::add_pair(RuntimeOrigin::signed(1), btc.clone()),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(PriceOracle::add_pair(RuntimeOrigin::root(), btc.clone()));
+		System::assert_last_event(Event::PairAdded { pair_id: btc_usd }.into());
+		assert_noop!(
+			PriceOracle::add_pair(RuntimeOrigin::root(), btc.clone()),
+			Error::<Test>::PairAlreadyTracked
+		);
+
+		btc.max_price = 200_000 * PRICE_UNIT;
+		assert_ok!(PriceOracle::update_pair(RuntimeOrigin::root(), btc.clone()));
+		System::assert_last_event(Event::PairUpdated { pair_id: btc_usd }.into());
+		assert_eq!(TrackedPairs::<Test>::get(btc_usd), Some(btc.clone()));
+
+		assert_ok!(PriceOracle::remove_pair(RuntimeOrigin::root(), btc_usd));
+		System::assert_last_event(Event::PairRemoved { pair_id: btc_usd }.into());
+		assert!(!TrackedPairs::<Test>::contains_key(btc_usd));
+		assert_noop!(
+			PriceOracle::remove_pair(RuntimeOrigin::root(), btc_usd),
+			Error::<Test>::PairNotTracked
+		);
+		assert_noop!(
+			PriceOracle::update_pair(RuntimeOrigin::root(), btc),
+			Error::<Test>::PairNotTracked
+		);
+	});
+}
+
+#[test]
+fn at_most_max_pairs_are_tracked() {
+	new_test_ext().execute_with(|| {
+		let tracked = TrackedPairs::<Test>::count();
+		let pair = |i: u32| {
+			let mut pair = registry::default_pairs().remove(0);
+			pair.base = format!("T{i}").into_bytes().try_into().unwrap();
+			pair
+		};
+		for i in tracked..MAX_PAIRS {
+			assert_ok!(PriceOracle::add_pair(RuntimeOrigin::root(), pair(i)));
+		}
+		assert_noop!(
+			PriceOracle::add_pair(RuntimeOrigin::root(), pair(MAX_PAIRS)),
+			Error::<Test>::PairLimitReached
+		);
+		assert_ok!(PriceOracle::remove_pair(RuntimeOrigin::root(), pair(tracked).id()));
+		assert_ok!(PriceOracle::add_pair(RuntimeOrigin::root(), pair(MAX_PAIRS)));
+	});
+}
+
+#[test]
+fn oracle_status_sums_up_pairs_and_exchanges() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			PriceOracle::oracle_status(),
+			OracleStatus {
+				pairs_total: 2,
+				pairs_stale: 2,
+				exchanges_unhealthy: 10,
+				..Default::default()
+			}
+		);
+
+		set_now(MAX_PRICE_AGE + 11);
+		let aggregate =
+			|median, timestamp| PriceAggregate { median, mean: median, sources: 1, timestamp };
+		AggregatedPrice::<Test>::insert(eth_usd(), aggregate(2_000 * PRICE_UNIT, 20));
+		AggregatedPrice::<Test>::insert(sol_usd(), aggregate(150 * PRICE_UNIT, 10));
+		PriceData::<Test>::insert(eth_usd(), 0, (2_000 * PRICE_UNIT, 20));
+		PriceData::<Test>::insert(eth_usd(), 1, (2_000 * PRICE_UNIT, 10));
+		// Chainlink prices stay fresh for an hour.
+		PriceData::<Test>::insert(eth_usd(), 6, (2_000 * PRICE_UNIT, 10));
+		let opportunity = ArbitrageOpportunity {
+			buy_exchange: 0,
+			sell_exchange: 1,
+			spread_bps: 1_500,
+			timestamp: 20,
+		};
+		ArbitrageOpportunities::<Test>::insert(eth_usd(), opportunity);
+		assert_ok!(PriceOracle::set_exchange_enabled(RuntimeOrigin::root(), 5, false));
+		assert_ok!(PriceOracle::set_pair_paused(RuntimeOrigin::root(), sol_usd(), true));
+
+		assert_eq!(
+			PriceOracle::oracle_status(),
+			OracleStatus {
+				pairs_total: 2,
+				pairs_fresh: 1,
+				pairs_stale: 0,
+				pairs_paused: 1,
+				exchanges_healthy: 2,
+				exchanges_unhealthy: 7,
+				last_update: Some(20),
+				open_opportunities: 1,
+			}
+		);
+	});
+}
+
+#[test]
+fn latest_aggregates_of_all_pairs_are_kept_in_one_value() {
+	new_test_ext().execute_with(|| {
+		let btc_usd = pair_id(b"BTC", b"USD");
+		let mut btc = registry::default_pairs().remove(0);
+		btc.base = b"BTC".to_vec().try_into().unwrap();
+		btc.max_price = 200_000 * PRICE_UNIT;
+		assert_ok!(PriceOracle::add_pair(RuntimeOrigin::root(), btc));
+
+		for (pair_id, price) in [(eth_usd(), 2_000), (btc_usd, 60_000)] {
+			assert_ok!(PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				pair_id,
+				0,
+				price,
+				0,
+				10,
+				None,
+				None
+			));
+		}
+		PriceOracle::on_finalize(1);
+
+		let latest = PriceOracle::latest_aggregates();
+		assert_eq!(latest.len(), 2);
+		for pair_id in [eth_usd(), btc_usd] {
+			assert_eq!(latest.get(&pair_id).copied(), AggregatedPrice::<Test>::get(pair_id));
+		}
+
+		let unknown = pair_id(b"SOL", b"USD");
+		assert_eq!(
+			PriceOracle::read_many(&[btc_usd, unknown, eth_usd()]),
+			vec![
+				AggregatedPrice::<Test>::get(btc_usd),
+				None,
+				AggregatedPrice::<Test>::get(eth_usd())
+			]
+		);
+
+		assert_ok!(PriceOracle::remove_pair(RuntimeOrigin::root(), btc_usd));
+		let latest = PriceOracle::latest_aggregates();
+		assert_eq!(latest.keys().collect::<Vec<_>>(), vec![&eth_usd()]);
+		assert_eq!(PriceOracle::read_many(&[btc_usd]), vec![None]);
+	});
+}
+
+#[test]
+fn pairs_removed_in_the_block_of_their_submissions_are_not_aggregated() {
+	new_test_ext().execute_with(|| {
+		let submit = |price, timestamp| {
+			assert_ok!(PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				0,
+				price,
+				0,
+				timestamp,
+				None,
+				None
+			));
+		};
+		set_now(1_000_000);
+		submit(2_000, 1_000_000);
+		PriceOracle::on_finalize(1);
+		assert_ok!(<PriceOracle as PriceGuard>::guarded_price(eth_usd()));
+		submit(2_100, 1_000_001);
+
+		assert_ok!(PriceOracle::remove_pair(RuntimeOrigin::root(), eth_usd()));
+		assert!(!PendingAggregation::<Test>::contains_key(eth_usd()));
+		assert!(!PriceGuards::<Test>::contains_key(eth_usd()));
+		PriceOracle::on_finalize(1);
+		PriceOracle::on_finalize(2);
+		assert!(!PriceOracle::latest_aggregates().contains_key(&eth_usd()));
+	});
+}
+
+#[test]
+fn full_latest_aggregates_evict_the_stalest() {
+	new_test_ext().execute_with(|| {
+		// Left over from a higher bound, the odd ones a bit staler.
+		LatestAggregates::<Test>::mutate(|latest| {
+			for i in 0..MAX_PAIRS as u8 {
+				let timestamp = 5 - u64::from(i % 2);
+				let stale = PriceAggregate { mean: 1, median: 1, sources: 1, timestamp };
+				latest.try_insert([i; 32], stale).unwrap();
+			}
+		});
+
+		assert_ok!(PriceOracle::submit_price_unsigned(
+			RuntimeOrigin::none(),
+			eth_usd(),
+			0,
+			2_000,
+			0,
+			10,
+			None,
+			None
+		));
+		PriceOracle::on_finalize(1);
+		System::assert_has_event(Event::LatestAggregateEvicted { pair_id: [1; 32] }.into());
+		let latest = PriceOracle::latest_aggregates();
+		assert_eq!(latest.len(), MAX_PAIRS as usize);
+		assert_eq!(latest.get(&eth_usd()).copied(), AggregatedPrice::<Test>::get(eth_usd()));
+	});
+}
+
+#[test]
+fn invalid_pairs_are_rejected() {
+	new_test_ext().execute_with(|| {
+		let mut link = registry::default_pairs().remove(0);
+		link.base = b"LINK".to_vec().try_into().unwrap();
+
+		let inverted = PairInfo { min_price: link.max_price, ..link.clone() };
+		assert_noop!(
+			PriceOracle::add_pair(RuntimeOrigin::root(), inverted),
+			Error::<Test>::InvalidBounds
+		);
+		let too_precise = PairInfo { decimals: 19, ..link.clone() };
+		assert_noop!(
+			PriceOracle::add_pair(RuntimeOrigin::root(), too_precise),
+			Error::<Test>::InvalidExponent
+		);
+		let no_window = PairInfo {
+			dynamic_bounds: Some(DynamicBounds { deviation: Permill::from_percent(5), window: 0 }),
+			..link.clone()
+		};
+		assert_noop!(
+			PriceOracle::add_pair(RuntimeOrigin::root(), no_window),
+			Error::<Test>::InvalidBounds
+		);
+
+		let with_pool = |exchange_id, pool: &PoolConfig| {
+			let mut pair = link.clone();
+			pair.pools.try_push((exchange_id, pool.clone())).unwrap();
+			pair
+		};
+		let (_, pool) = link.pools[0].clone();
+		assert_noop!(
+			PriceOracle::add_pair(
+				RuntimeOrigin::root(),
+				with_pool(0, &PoolConfig { address: [0; 20], ..pool.clone() })
+			),
+			Error::<Test>::ZeroAddress
+		);
+		let mut many_decimals = pool.clone();
+		many_decimals.metadata.token1_decimals = 77;
+		assert_noop!(
+			PriceOracle::add_pair(RuntimeOrigin::root(), with_pool(1, &many_decimals)),
+			Error::<Test>::InvalidPoolDecimals
+		);
+		assert_noop!(
+			PriceOracle::add_pair(RuntimeOrigin::root(), with_pool(link.pools[0].0, &pool)),
+			Error::<Test>::DuplicatePool
+		);
+		assert_noop!(
+			PriceOracle::add_pair(RuntimeOrigin::root(), with_pool(10, &pool)),
+			Error::<Test>::UnknownExchange
+		);
+		assert_noop!(
+			PriceOracle::update_pair(RuntimeOrigin::root(), with_pool(10, &pool)),
+			Error::<Test>::UnknownExchange
+		);
+		// The same pool address on another exchange is a different pool.
+		assert_ok!(PriceOracle::add_pair(RuntimeOrigin::root(), with_pool(1, &pool)));
+	});
+}
+
+#[test]
+fn offchain_worker_skips_untracked_pairs() {
+	let (offchain, _state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	ext.execute_with(|| {
+		assert_ok!(PriceOracle::remove_pair(RuntimeOrigin::root(), eth_usd()));
+		// No request is expected, so any HTTP call would panic.
+		PriceOracle::offchain_worker(fetch_block(1));
+		assert!(pool_state.read().transactions.is_empty());
+	});
+}
+
+#[test]
+fn registry_origin_manages_exchanges() {
+	new_test_ext().execute_with(|| {
+		let (_, uniswap) = registry::default_exchanges().remove(0);
+		assert_noop!(
+			PriceOracle::add_exchange(RuntimeOrigin::signed(1), 10, uniswap.clone()),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			PriceOracle::add_exchange(RuntimeOrigin::root(), 0, uniswap.clone()),
+			Error::<Test>::ExchangeAlreadyRegistered
+		);
+		// Exchange names are unique.
+		assert_noop!(
+			PriceOracle::add_exchange(RuntimeOrigin::root(), 10, uniswap.clone()),
+			Error::<Test>::ExchangeAlreadyRegistered
+		);
+		let uniswap = ExchangeConfig {
+			name: BoundedVec::truncate_from(b"Uniswap V3 Polygon".to_vec()),
+			..uniswap
+		};
+		assert_ok!(PriceOracle::add_exchange(RuntimeOrigin::root(), 10, uniswap.clone()));
+		System::assert_last_event(Event::ExchangeAdded { exchange_id: 10 }.into());
+
+		assert_ok!(PriceOracle::set_exchange_enabled(RuntimeOrigin::root(), 10, false));
+		System::assert_last_event(
+			Event::ExchangeEnabled { exchange_id: 10, enabled: false }.into(),
+		);
+		assert!(!Exchanges::<Test>::get(10).unwrap().enabled);
+		assert_noop!(
+			PriceOracle::set_exchange_enabled(RuntimeOrigin::root(), 11, true),
+			Error::<Test>::UnknownExchange
+		);
+	});
+}
+
+#[test]
+fn misconfigured_exchanges_are_rejected() {
+	new_test_ext().execute_with(|| {
+		let (_, uniswap) = registry::default_exchanges().remove(0);
+		let add = |config| PriceOracle::add_exchange(RuntimeOrigin::root(), 10, config);
+
+		let unnamed = ExchangeConfig { name: Default::default(), ..uniswap.clone() };
+		assert_noop!(add(unnamed), Error::<Test>::InvalidExchangeName);
+		let garbled =
+			ExchangeConfig { name: BoundedVec::truncate_from(vec![0xff, 0xfe]), ..uniswap.clone() };
+		assert_noop!(add(garbled), Error::<Test>::InvalidExchangeName);
+		let no_chain = ExchangeConfig {
+			name: BoundedVec::truncate_from(b"Uniswap V3 Polygon".to_vec()),
+			chain_id: 0,
+			..uniswap
+		};
+		assert_noop!(add(no_chain), Error::<Test>::InvalidChainId);
+
+		assert_noop!(
+			PriceOracle::set_router(RuntimeOrigin::root(), 0, Some([0; 20])),
+			Error::<Test>::ZeroAddress
+		);
+		let mut snapshot = PriceOracle::export_registry();
+		snapshot.exchanges[0].1.chain_id = 0;
+		assert_noop!(
+			PriceOracle::import_registry(RuntimeOrigin::root(), snapshot.encode()),
+			Error::<Test>::InvalidChainId
+		);
+	});
+}
+
+#[test]
+fn offchain_worker_skips_disabled_exchanges() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	// With Uniswap V3 disabled the single request of the run goes to SushiSwap.
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: br#"{"jsonrpc":"2.0","method":"eth_call","params":[{"to":"0x397FF1542f962076d0BFE58eA045FfA2d347ACa0","data":"0x0902f1ac"},"latest"],"id":1}"#.to_vec(),
+		response: Some(rpc_response(RESERVES_ETH_2000)),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		assert_ok!(PriceOracle::set_exchange_enabled(RuntimeOrigin::root(), 0, false));
+		PriceOracle::offchain_worker(fetch_block(1));
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert!(matches!(
+			tx.function,
+			RuntimeCall::PriceOracle(Call::submit_price_unsigned { exchange_id: 1, .. })
+		));
+	});
+}
+
+#[test]
+fn registry_origin_manages_rpc_endpoints() {
+	new_test_ext().execute_with(|| {
+		let urls: RpcUrls =
+			vec![b"http://localhost:8545".to_vec().try_into().unwrap()].try_into().unwrap();
+		assert_noop!(
+			PriceOracle::set_rpc_endpoints(RuntimeOrigin::signed(1), 1, urls.clone()),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			PriceOracle::set_rpc_endpoints(
+				RuntimeOrigin::root(),
+				1,
+				vec![vec![0xff].try_into().unwrap()].try_into().unwrap()
+			),
+			Error::<Test>::InvalidUrl
+		);
+
+		assert_ok!(PriceOracle::set_rpc_endpoints(RuntimeOrigin::root(), 1, urls.clone()));
+		System::assert_last_event(
+			Event::RpcEndpointsSet { chain_id: 1, urls: urls.clone() }.into(),
+		);
+		assert_eq!(RpcEndpoints::<Test>::get(1), Some(urls));
+
+		assert_ok!(PriceOracle::set_rpc_endpoints(RuntimeOrigin::root(), 1, Default::default()));
+		System::assert_last_event(
+			Event::RpcEndpointsSet { chain_id: 1, urls: Default::default() }.into(),
+		);
+		assert_eq!(RpcEndpoints::<Test>::get(1), None);
+	});
+}
+
+#[test]
+fn offchain_worker_prefers_local_rpc_override() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "http://localhost:8545".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: br#"{"jsonrpc":"2.0","method":"eth_call","params":[{"to":"0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640","data":"0x3850c7bd"},"latest"],"id":1}"#.to_vec(),
+		response: Some(rpc_response(SLOT0_ETH_2500)),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		sp_io::offchain::local_storage_set(
+			sp_core::offchain::StorageKind::PERSISTENT,
+			&rpc_override_key(1),
+			b"http://localhost:8545",
+		);
+		PriceOracle::offchain_worker(fetch_block(1));
+		assert_eq!(pool_state.read().transactions.len(), 1);
+	});
+}
+
+#[test]
+fn offchain_worker_fails_over_to_next_endpoint() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	// Every request carries the next id and the responses echo it.
+	let slot0 = |uri: &str, id: u64, response: &str| {
+		PendingRequest {
+		method: "POST".into(),
+		uri: uri.into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: format!(r#"{{"jsonrpc":"2.0","method":"eth_call","params":[{{"to":"0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640","data":"0x3850c7bd"}},"latest"],"id":{id}}}"#).into_bytes(),
+		response: Some(response.replace(r#""id":1"#, &format!(r#""id":{id}"#)).into_bytes()),
+		sent: true,
+		..Default::default()
+	}
+	};
+	let rate_limited = r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32005,"message":"limit"}}"#;
+	let answer = String::from_utf8(rpc_response(SLOT0_ETH_2500)).unwrap();
+	let primary = "https://eth.llamarpc.com";
+	let fallback = "https://ethereum-rpc.publicnode.com";
+
+	// The first endpoint is rate limited, the second one answers.
+	state.write().expect_request(slot0(primary, 1, rate_limited));
+	state.write().expect_request(slot0(fallback, 2, &answer));
+	ext.execute_with(|| {
+		PriceOracle::offchain_worker(fetch_block(1));
+		assert_eq!(pool_state.read().transactions.len(), 1);
+		assert_eq!(endpoints::failures(primary.as_bytes()), 1);
+		assert_eq!(endpoints::failures(fallback.as_bytes()), 0);
+	});
+
+	// The failing endpoint is deprioritized, so the next run asks the fallback first.
+	state.write().expect_request(slot0(fallback, 3, &answer));
+	ext.execute_with(|| {
+		PriceOracle::offchain_worker(fetch_block(2));
+		assert_eq!(pool_state.read().transactions.len(), 2);
+		assert_eq!(endpoints::failures(primary.as_bytes()), 1);
+	});
+}
+
+#[test]
+fn offchain_worker_skips_chains_without_endpoint() {
+	let (offchain, _state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext_with(crate::GenesisConfig {
+		exchanges: registry::default_exchanges(),
+		pairs: registry::default_pairs(),
+		..Default::default()
+	});
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	ext.execute_with(|| {
+		// No request is expected, so any HTTP call would panic.
+		PriceOracle::offchain_worker(fetch_block(1));
+		assert!(pool_state.read().transactions.is_empty());
+	});
+}
+
+#[test]
+fn genesis_registers_exchanges_and_pairs() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Exchanges::<Test>::iter().count(), registry::default_exchanges().len());
+		assert_eq!(
+			RpcEndpoints::<Test>::get(1).unwrap()[0].clone().into_inner(),
+			b"https://eth.llamarpc.com".to_vec()
+		);
+		assert_eq!(TrackedPairs::<Test>::get(eth_usd()), Some(registry::default_pairs().remove(0)));
+	});
+
+	new_test_ext_with(Default::default()).execute_with(|| {
+		assert_eq!(Exchanges::<Test>::iter().count(), 0);
+		assert_eq!(TrackedPairs::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+#[should_panic(expected = "genesis pairs must be valid")]
+fn genesis_rejects_invalid_pairs() {
+	let mut pair = registry::default_pairs().remove(0);
+	pair.min_price = pair.max_price;
+	new_test_ext_with(crate::GenesisConfig { pairs: vec![pair], ..Default::default() });
+}
+
+#[test]
+fn genesis_config_round_trips_through_json() {
+	let genesis = crate::GenesisConfig::<Test> {
+		exchanges: registry::default_exchanges(),
+		pairs: registry::default_pairs(),
+		pool_hops: registry::default_pool_hops(),
+		rpc_endpoints: registry::default_rpc_endpoints(),
+		..Default::default()
+	};
+	let json = serde_json::to_string(&genesis).unwrap();
+	let decoded: crate::GenesisConfig<Test> = serde_json::from_str(&json).unwrap();
+	assert_eq!(decoded.exchanges, genesis.exchanges);
+	assert_eq!(decoded.pairs, genesis.pairs);
+	assert_eq!(decoded.pool_hops, genesis.pool_hops);
+	assert_eq!(decoded.rpc_endpoints, genesis.rpc_endpoints);
+}
+
+#[test]
+fn registry_origin_manages_operators() {
+	new_test_ext().execute_with(|| {
+		assert!(PriceOracle::is_operator(&OPERATOR));
+		assert_noop!(
+			PriceOracle::register_operator(RuntimeOrigin::signed(OPERATOR), 8),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			PriceOracle::register_operator(RuntimeOrigin::root(), OPERATOR),
+			Error::<Test>::AlreadyOperator
+		);
+
+		assert_ok!(PriceOracle::register_operator(RuntimeOrigin::root(), 8));
+		System::assert_last_event(Event::OperatorRegistered { who: 8 }.into());
+		assert_noop!(
+			PriceOracle::register_operator(RuntimeOrigin::root(), 9),
+			Error::<Test>::TooManyOperators
+		);
+
+		assert_ok!(PriceOracle::deregister_operator(RuntimeOrigin::root(), 8));
+		System::assert_last_event(Event::OperatorDeregistered { who: 8 }.into());
+		assert!(!PriceOracle::is_operator(&8));
+		assert_noop!(
+			PriceOracle::deregister_operator(RuntimeOrigin::root(), 8),
+			Error::<Test>::NotOperator
+		);
+	});
+}
+
+#[test]
+fn only_operators_submit_signed_prices() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PriceOracle::submit_price_signed(
+				RuntimeOrigin::signed(8),
+				eth_usd(),
+				0,
+				2_500,
+				0,
+				42,
+				None,
+				None
+			),
+			Error::<Test>::NotOperator
+		);
+		assert_noop!(
+			PriceOracle::report_fetch_failure(
+				RuntimeOrigin::signed(8),
+				eth_usd(),
+				0,
+				OracleError::Timeout
+			),
+			Error::<Test>::NotOperator
+		);
+
+		assert_ok!(PriceOracle::submit_price_signed(
+			RuntimeOrigin::signed(OPERATOR),
+			eth_usd(),
+			0,
+			2_500,
+			0,
+			42,
+			None,
+			None
+		));
+		let price = 2500 * PRICE_UNIT;
+		System::assert_last_event(
+			Event::PriceSubmitted { pair_id: eth_usd(), exchange_id: 0, price, timestamp: 42 }
+				.into(),
+		);
+		assert_eq!(PriceData::<Test>::get(eth_usd(), 0), Some((price, 42)));
+		assert_eq!(Contributions::get(), vec![(OPERATOR, 1)]);
+		assert_eq!(Submissions::get(), vec![OPERATOR]);
+
+		// A rejected price earns nothing.
+		assert_ok!(PriceOracle::submit_price_signed(
+			RuntimeOrigin::signed(OPERATOR),
+			eth_usd(),
+			0,
+			1,
+			0,
+			43,
+			None,
+			None
+		));
+		System::assert_last_event(
+			Event::PriceRejected {
+				pair_id: eth_usd(),
+				exchange_id: 0,
+				reason: OracleError::PriceOutOfBounds { value: PRICE_UNIT },
+			}
+			.into(),
+		);
+		assert_eq!(Contributions::get(), vec![(OPERATOR, 1)]);
+		assert_eq!(Submissions::get(), vec![OPERATOR]);
+	});
+}
+
+#[test]
+fn deviant_signed_prices_slash_the_bond_of_their_operator() {
+	new_test_ext().execute_with(|| {
+		OperatorBond::set(1_000);
+		assert_noop!(
+			PriceOracle::register_operator(RuntimeOrigin::root(), 8),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+		assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), 8, 10_000));
+		assert_ok!(PriceOracle::register_operator(RuntimeOrigin::root(), 8));
+		assert_eq!(Balances::reserved_balance(8), 1_000);
+		assert_eq!(OperatorBonds::<Test>::get(8), 1_000);
+
+		let submit = |who, exchange_id, price, timestamp| {
+			PriceOracle::submit_price_signed(
+				RuntimeOrigin::signed(who),
+				eth_usd(),
+				exchange_id,
+				price,
+				0,
+				timestamp,
+				None,
+				None,
+			)
+		};
+		assert_noop!(
+			PriceOracle::report_misbehavior(RuntimeOrigin::signed(9), 8, eth_usd(), 1),
+			Error::<Test>::NoSubmission
+		);
+		assert_ok!(submit(OPERATOR, 0, 2_500, 42));
+		assert_ok!(submit(8, 1, 2_520, 42));
+		assert_noop!(
+			PriceOracle::report_misbehavior(RuntimeOrigin::signed(9), 8, eth_usd(), 1),
+			Error::<Test>::NoMedian
+		);
+		PriceOracle::on_finalize(1);
+		// 2,520 lies 39 basis points from the median of 2,510.
+		assert_noop!(
+			PriceOracle::report_misbehavior(RuntimeOrigin::signed(9), 8, eth_usd(), 1),
+			Error::<Test>::NotDeviant
+		);
+
+		System::set_block_number(4);
+		assert_ok!(submit(8, 1, 4_000, 43));
+		PriceOracle::on_finalize(4);
+		// 4,000 lies 2,307 basis points from the median of 3,250, and the median of block 1 is
+		// no longer within an update interval of it.
+		assert_ok!(PriceOracle::report_misbehavior(RuntimeOrigin::signed(9), 8, eth_usd(), 1));
+		System::assert_last_event(
+			Event::OperatorSlashed {
+				who: 8,
+				reporter: 9,
+				pair_id: eth_usd(),
+				exchange_id: 1,
+				amount: 500,
+				reward: 50,
+			}
+			.into(),
+		);
+		assert_eq!(Balances::free_balance(9), 50);
+		assert_eq!(Balances::reserved_balance(8), 500);
+		assert_eq!(OperatorBonds::<Test>::get(8), 500);
+		assert_noop!(
+			PriceOracle::report_misbehavior(RuntimeOrigin::signed(9), 8, eth_usd(), 1),
+			Error::<Test>::NoSubmission
+		);
+
+		// Operators registered at genesis have no bond to slash.
+		assert_ok!(submit(OPERATOR, 0, 2_000, 43));
+		PriceOracle::on_finalize(4);
+		assert_noop!(
+			PriceOracle::report_misbehavior(RuntimeOrigin::signed(9), OPERATOR, eth_usd(), 0),
+			Error::<Test>::NotBonded
+		);
+
+		// Deregistering returns what is left of the bond.
+		assert_ok!(PriceOracle::deregister_operator(RuntimeOrigin::root(), 8));
+		assert_eq!(Balances::reserved_balance(8), 0);
+		assert_eq!(Balances::free_balance(8), 9_500);
+	});
+}
+
+#[test]
+fn committed_prices_are_revealed_within_the_window() {
+	new_test_ext().execute_with(|| {
+		RevealWindow::set(2);
+		let (salt, observation) = ([7; 32], (42, None, None));
+		let commitment = PriceOracle::price_commitment(2_500, 0, observation, &salt);
+
+		// Prices are no longer submitted in the clear.
+		assert_noop!(
+			PriceOracle::submit_price_signed(
+				RuntimeOrigin::signed(OPERATOR),
+				eth_usd(),
+				0,
+				2_500,
+				0,
+				42,
+				None,
+				None
+			),
+			Error::<Test>::CommitRequired
+		);
+		let call = Call::submit_price_unsigned {
+			pair_hash: eth_usd(),
+			exchange_id: 0,
+			price: 2_500,
+			exponent: 0,
+			timestamp: 42,
+			source_block: None,
+			notional: None,
+		};
+		assert_eq!(
+			PriceOracle::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Call.into()
+		);
+		assert_noop!(
+			PriceOracle::commit_price(RuntimeOrigin::signed(8), eth_usd(), 0, commitment),
+			Error::<Test>::NotOperator
+		);
+
+		assert_ok!(PriceOracle::commit_price(
+			RuntimeOrigin::signed(OPERATOR),
+			eth_usd(),
+			0,
+			commitment
+		));
+		System::assert_last_event(
+			Event::PriceCommitted { who: OPERATOR, pair_id: eth_usd(), exchange_id: 0 }.into(),
+		);
+		let reveal = |price: u128, salt: [u8; 32]| {
+			PriceOracle::reveal_price(
+				RuntimeOrigin::signed(OPERATOR),
+				eth_usd(),
+				0,
+				price,
+				0,
+				42,
+				None,
+				None,
+				salt,
+			)
+		};
+		assert_noop!(reveal(2_500, salt), Error::<Test>::RevealTooEarly);
+
+		System::set_block_number(2);
+		assert_noop!(reveal(2_600, salt), Error::<Test>::CommitmentMismatch);
+		assert_noop!(reveal(2_500, [8; 32]), Error::<Test>::CommitmentMismatch);
+		assert_ok!(reveal(2_500, salt));
+		assert_eq!(PriceData::<Test>::get(eth_usd(), 0), Some((2_500 * PRICE_UNIT, 42)));
+		assert_eq!(Contributions::get(), vec![(OPERATOR, 1)]);
+		assert_noop!(reveal(2_500, salt), Error::<Test>::NoCommitment);
+
+		// A commitment not revealed in time can't be revealed anymore.
+		assert_ok!(PriceOracle::commit_price(
+			RuntimeOrigin::signed(OPERATOR),
+			eth_usd(),
+			0,
+			commitment
+		));
+		System::set_block_number(5);
+		assert_noop!(reveal(2_500, salt), Error::<Test>::CommitmentExpired);
+	});
+}
+
+#[test]
+fn offchain_worker_reveals_its_commitments_in_its_next_run() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	UintAuthorityId::set_all_keys([OPERATOR]);
+
+	let call = |id: u64| {
+		format!(
+			r#"{{"jsonrpc":"2.0","method":"eth_call","params":[{{"to":"0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640","data":"0x3850c7bd"}},"latest"],"id":{id}}}"#
+		)
+	};
+	let result =
+		|id: u64, result: &str| format!(r#"{{"jsonrpc":"2.0","id":{id},"result":"{result}"}}"#);
+	let function = |tx: Vec<u8>| match Extrinsic::decode(&mut &*tx).unwrap().function {
+		RuntimeCall::PriceOracle(call) => call,
+		call => panic!("unexpected call {call:?}"),
+	};
+
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: call(1).into_bytes(),
+		response: Some(rpc_response(SLOT0_ETH_2500)),
+		sent: true,
+		..Default::default()
+	});
+	ext.execute_with(|| {
+		AcceptUnsigned::set(false);
+		RevealWindow::set(UPDATE_INTERVAL);
+		PriceOracle::offchain_worker(fetch_block(1));
+
+		let Call::commit_price { pair_hash, exchange_id, commitment } =
+			function(pool_state.write().transactions.pop().unwrap())
+		else {
+			panic!("expected a commitment")
+		};
+		System::set_block_number(fetch_block(1) + 1);
+		let origin = RuntimeOrigin::signed(OPERATOR);
+		assert_ok!(PriceOracle::commit_price(origin, pair_hash, exchange_id, commitment));
+	});
+
+	// The next run reveals the price before it commits to the next one.
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: call(2).into_bytes(),
+		response: Some(result(2, SLOT0_ETH_2500).into_bytes()),
+		sent: true,
+		..Default::default()
+	});
+	ext.execute_with(|| {
+		PriceOracle::offchain_worker(fetch_block(2));
+
+		let mut calls = pool_state.write().transactions.drain(..).map(function).collect::<Vec<_>>();
+		assert!(matches!(calls.pop(), Some(Call::commit_price { exchange_id: 0, .. })));
+		let Some(Call::reveal_price {
+			pair_hash,
+			exchange_id,
+			price,
+			exponent,
+			timestamp,
+			source_block,
+			notional,
+			salt,
+		}) = calls.pop()
+		else {
+			panic!("expected a reveal")
+		};
+		System::set_block_number(fetch_block(2) + 1);
+		assert_ok!(PriceOracle::reveal_price(
+			RuntimeOrigin::signed(OPERATOR),
+			pair_hash,
+			exchange_id,
+			price,
+			exponent,
+			timestamp,
+			source_block,
+			notional,
+			salt
+		));
+		assert_eq!(PriceData::<Test>::get(eth_usd(), 0), Some((2_500 * PRICE_UNIT, timestamp)));
+	});
+}
+
+#[test]
+fn unsigned_submissions_can_be_disabled() {
+	new_test_ext().execute_with(|| {
+		AcceptUnsigned::set(false);
+		let call = Call::submit_price_unsigned {
+			pair_hash: eth_usd(),
+			exchange_id: 0,
+			price: 2_500,
+			exponent: 0,
+			timestamp: 42,
+			source_block: None,
+			notional: None,
+		};
+		assert_eq!(
+			PriceOracle::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Call.into()
+		);
+		assert_noop!(
+			PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				0,
+				2_500,
+				0,
+				42,
+				None,
+				None
+			),
+			Error::<Test>::UnsignedDisabled
+		);
+		assert_noop!(
+			PriceOracle::report_fetch_failure(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				0,
+				OracleError::Timeout
+			),
+			Error::<Test>::UnsignedDisabled
+		);
+	});
+}
+
+#[test]
+fn offchain_worker_signs_with_operator_key() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	UintAuthorityId::set_all_keys([OPERATOR]);
+
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: br#"{"jsonrpc":"2.0","method":"eth_call","params":[{"to":"0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640","data":"0x3850c7bd"},"latest"],"id":1}"#.to_vec(),
+		response: Some(rpc_response(SLOT0_ETH_2500)),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		AcceptUnsigned::set(false);
+		PriceOracle::offchain_worker(fetch_block(1));
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert!(matches!(tx.preamble, Preamble::Signed(OPERATOR, ..)));
+		assert!(matches!(
+			tx.function,
+			RuntimeCall::PriceOracle(Call::submit_price_signed { exchange_id: 0, .. })
+		));
+	});
+}
+
+#[test]
+fn offchain_worker_without_key_or_unsigned_submits_nothing() {
+	let (offchain, _state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	ext.execute_with(|| {
+		AcceptUnsigned::set(false);
+		PriceOracle::offchain_worker(fetch_block(1));
+		assert!(pool_state.read().transactions.is_empty());
+	});
+}
+
+#[test]
+fn committee_members_submit_signed_prices() {
+	new_test_ext().execute_with(|| {
+		assert!(!PriceOracle::is_operator(&8));
+		CommitteeMembers::set(vec![8]);
+		assert!(PriceOracle::is_operator(&8));
+
+		assert_ok!(PriceOracle::submit_price_signed(
+			RuntimeOrigin::signed(8),
+			eth_usd(),
+			0,
+			2_500,
+			0,
+			42,
+			None,
+			None
+		));
+		assert_eq!(PriceData::<Test>::get(eth_usd(), 0), Some((2500 * PRICE_UNIT, 42)));
+	});
+}
+
+#[test]
+fn latest_price_is_the_fresh_median() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(PriceOracle::latest_price(eth_usd()), None);
+		for (exchange_id, price) in [(0, 2_000), (1, 2_600), (2, 2_100)] {
+			assert_ok!(PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				exchange_id,
+				price,
+				0,
+				10,
+				None,
+				None,
+			));
+		}
+		PriceOracle::on_finalize(1);
+		assert_eq!(PriceOracle::latest_price(eth_usd()), Some((2_200 * PRICE_UNIT, 10)));
+
+		set_now(10 + MAX_PRICE_AGE + 1);
+		assert_eq!(PriceOracle::latest_price(eth_usd()), None);
+		assert!(PriceOracle::get_aggregated_price(eth_usd()).is_some());
+	});
+}
+
+#[test]
+fn outliers_are_left_out_of_the_aggregate() {
+	new_test_ext().execute_with(|| {
+		let submit = |prices: &[(ExchangeId, u128)], timestamp| {
+			for &(exchange_id, price) in prices {
+				assert_ok!(PriceOracle::submit_price_unsigned(
+					RuntimeOrigin::none(),
+					eth_usd(),
+					exchange_id,
+					price,
+					0,
+					timestamp,
+					None,
+					None,
+				));
+			}
+		};
+		OutlierMads::set(3);
+		submit(&[(0, 2_000), (1, 2_010), (2, 1_990), (3, 3_000), (4, 2_020)], 10);
+		let aggregate = PriceOracle::aggregate_prices(eth_usd()).unwrap();
+		assert_eq!(aggregate.sources, 4);
+		assert!(aggregate.median < 2_100 * PRICE_UNIT);
+		System::assert_has_event(
+			Event::PriceOutlierRejected {
+				pair_id: eth_usd(),
+				exchange_id: 3,
+				price: 3_000 * PRICE_UNIT,
+				median: 2_010 * PRICE_UNIT,
+			}
+			.into(),
+		);
+		assert_eq!(OutlierCounts::<Test>::get(3), 1);
+		assert_eq!(OutlierCounts::<Test>::get(0), 0);
+
+		// Prices within ConsensusBps of the median are kept, however closely the others agree.
+		submit(&[(1, 2_000), (2, 2_000), (3, 2_015), (4, 2_000)], 11);
+		assert_eq!(PriceOracle::aggregate_prices(eth_usd()).unwrap().sources, 5);
+		assert_eq!(OutlierCounts::<Test>::get(3), 1);
+
+		OutlierMads::set(0);
+		submit(&[(3, 3_000)], 12);
+		assert_eq!(PriceOracle::aggregate_prices(eth_usd()).unwrap().sources, 5);
+	});
+}
+
+#[test]
+fn unreliable_exchanges_are_quarantined() {
+	new_test_ext().execute_with(|| {
+		let submit = |exchange_id, price, timestamp| {
+			assert_ok!(PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				exchange_id,
+				price,
+				0,
+				timestamp,
+				None,
+				None,
+			));
+		};
+		let fail = || {
+			assert_ok!(PriceOracle::report_fetch_failure(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				2,
+				OracleError::Timeout
+			));
+		};
+		QuarantineBps::set(5_000);
+		submit(2, 2_000, 10);
+		fail();
+		assert_eq!(
+			ExchangeStats::<Test>::get(2),
+			ReliabilityStats { successes: 1, failures: 1, ..Default::default() }
+		);
+		// Judged once four reads are in, on more than half of them failing.
+		fail();
+		assert!(!PriceOracle::is_quarantined(2));
+		fail();
+		System::assert_last_event(Event::ExchangeQuarantined { exchange_id: 2, until: 11 }.into());
+		assert!(PriceOracle::is_quarantined(2));
+		assert_eq!(ExchangeStats::<Test>::get(2), ReliabilityStats::default());
+
+		// Its prices are left out of the aggregates, and its outcomes aren't counted.
+		submit(0, 2_100, 10);
+		submit(2, 2_000, 11);
+		assert_eq!(PriceOracle::aggregate_prices(eth_usd()).unwrap().sources, 1);
+		assert_eq!(ExchangeStats::<Test>::get(2), ReliabilityStats::default());
+		assert_eq!(PriceOracle::oracle_status().exchanges_healthy, 1);
+
+		assert_noop!(
+			PriceOracle::release_exchange(RuntimeOrigin::signed(1), 2),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(PriceOracle::release_exchange(RuntimeOrigin::root(), 2));
+		System::assert_last_event(Event::ExchangeReleased { exchange_id: 2 }.into());
+		assert_eq!(PriceOracle::aggregate_prices(eth_usd()).unwrap().sources, 2);
+		assert_noop!(
+			PriceOracle::release_exchange(RuntimeOrigin::root(), 2),
+			Error::<Test>::NotQuarantined
+		);
+
+		// The quarantine ends by itself after QuarantinePeriod blocks, with the next read.
+		for _ in 0..4 {
+			fail();
+		}
+		assert!(PriceOracle::is_quarantined(2));
+		System::set_block_number(11);
+		assert!(!PriceOracle::is_quarantined(2));
+		submit(2, 2_050, 12);
+		System::assert_has_event(Event::ExchangeReleased { exchange_id: 2 }.into());
+		assert!(!QuarantinedExchanges::<Test>::contains_key(2));
+		assert_eq!(ExchangeStats::<Test>::get(2).successes, 1);
+	});
+}
+
+#[test]
+fn tracked_pairs_lists_the_genesis_pairs() {
+	new_test_ext().execute_with(|| {
+		let mut pairs: Vec<_> = genesis_pairs().into_iter().map(|p| (p.id(), p)).collect();
+		let mut tracked = PriceOracle::tracked_pairs();
+		pairs.sort_by_key(|(pair_id, _)| *pair_id);
+		tracked.sort_by_key(|(pair_id, _)| *pair_id);
+		assert_eq!(tracked, pairs);
+	});
+}
+
+#[test]
+fn price_reads_are_counted_per_period() {
+	new_test_ext().execute_with(|| {
+		PriceOracle::get_price(eth_usd(), 0);
+		PriceOracle::get_aggregated_price(eth_usd());
+		PriceOracle::latest_price(eth_usd());
+		PriceOracle::get_all_prices(eth_usd());
+		assert_eq!(
+			PriceOracle::pair_reads(eth_usd()),
+			ReadCounter { period: 0, count: 4, previous: 0 }
+		);
+
+		System::set_block_number(READ_PERIOD.into());
+		assert_eq!(
+			PriceOracle::pair_reads(eth_usd()),
+			ReadCounter { period: 1, count: 0, previous: 4 }
+		);
+		PriceOracle::latest_price(eth_usd());
+		assert_eq!(
+			PairReads::<Test>::get(eth_usd()),
+			ReadCounter { period: 1, count: 1, previous: 4 }
+		);
+
+		// Periods without reads in between leave nothing to carry over.
+		System::set_block_number((3 * READ_PERIOD).into());
+		assert_eq!(
+			PriceOracle::pair_reads(eth_usd()),
+			ReadCounter { period: 3, count: 0, previous: 0 }
+		);
+
+		assert_ok!(PriceOracle::remove_pair(RuntimeOrigin::root(), eth_usd()));
+		assert!(!PairReads::<Test>::contains_key(eth_usd()));
+	});
+}
+
+#[test]
+fn alerts_trigger_once_the_median_moves_past_the_threshold() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let target = 2_000 * PRICE_UNIT;
+		assert_ok!(PriceOracle::set_alert(RuntimeOrigin::signed(1), eth_usd(), 500, target));
+		assert_ok!(PriceOracle::set_alert(RuntimeOrigin::signed(2), eth_usd(), 1_000, target));
+		assert_noop!(
+			PriceOracle::set_alert(RuntimeOrigin::signed(3), eth_usd(), 1_000, target),
+			Error::<Test>::TooManyAlerts
+		);
+		assert_noop!(
+			PriceOracle::set_alert(RuntimeOrigin::signed(3), [0; 32], 1_000, target),
+			Error::<Test>::PairNotTracked
+		);
+		assert_noop!(
+			PriceOracle::set_alert(RuntimeOrigin::signed(1), eth_usd(), 1_000, 0),
+			Error::<Test>::InvalidTarget
+		);
+
+		// 4% below the target triggers neither alert.
+		assert_ok!(PriceOracle::submit_price_unsigned(
+			RuntimeOrigin::none(),
+			eth_usd(),
+			0,
+			1_920,
+			0,
+			10,
+			None,
+			None
+		));
+		PriceOracle::on_finalize(1);
+		assert_eq!(Alerts::<Test>::get(eth_usd()).len(), 2);
+
+		// 6% above it triggers the 5% alert only, which is removed.
+		assert_ok!(PriceOracle::submit_price_unsigned(
+			RuntimeOrigin::none(),
+			eth_usd(),
+			0,
+			2_120,
+			0,
+			11,
+			None,
+			None
+		));
+		PriceOracle::on_finalize(1);
+		System::assert_has_event(
+			Event::AlertTriggered { who: 1, pair_id: eth_usd(), move_bps: 600 }.into(),
+		);
+		let alerts = Alerts::<Test>::get(eth_usd());
+		assert_eq!(alerts.iter().map(|alert| alert.who).collect::<Vec<_>>(), vec![2]);
+
+		// A zero threshold removes the caller's alert.
+		assert_ok!(PriceOracle::set_alert(RuntimeOrigin::signed(2), eth_usd(), 0, target));
+		assert!(!Alerts::<Test>::contains_key(eth_usd()));
+	});
+}
+
+#[test]
+fn alerts_reserve_a_deposit_until_they_are_gone() {
+	new_test_ext().execute_with(|| {
+		AlertDeposit::set(100);
+		let target = 2_000 * PRICE_UNIT;
+		assert_noop!(
+			PriceOracle::set_alert(RuntimeOrigin::signed(1), eth_usd(), 500, target),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+		for who in [1, 2, 3] {
+			assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), who, 1_000));
+		}
+		assert_ok!(PriceOracle::set_alert(RuntimeOrigin::signed(1), eth_usd(), 500, target));
+		assert_ok!(PriceOracle::set_alert(RuntimeOrigin::signed(2), eth_usd(), 1_000, target));
+		assert_eq!(Balances::reserved_balance(1), 100);
+		assert_eq!(AlertDeposits::<Test>::get(eth_usd(), 1), 100);
+
+		// Replacing an alert keeps one deposit, removing it releases the deposit.
+		assert_ok!(PriceOracle::set_alert(RuntimeOrigin::signed(1), eth_usd(), 600, target));
+		assert_eq!(Balances::reserved_balance(1), 100);
+		assert_ok!(PriceOracle::set_alert(RuntimeOrigin::signed(1), eth_usd(), 0, target));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert!(!AlertDeposits::<Test>::contains_key(eth_usd(), 1));
+
+		// Triggered alerts and those of removed pairs are refunded.
+		assert_ok!(PriceOracle::set_alert(RuntimeOrigin::signed(3), sol_usd(), 500, target));
+		assert_ok!(PriceOracle::submit_price_unsigned(
+			RuntimeOrigin::none(),
+			eth_usd(),
+			0,
+			2_400,
+			0,
+			10,
+			None,
+			None
+		));
+		PriceOracle::on_finalize(1);
+		assert!(!Alerts::<Test>::contains_key(eth_usd()));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_ok!(PriceOracle::remove_pair(RuntimeOrigin::root(), sol_usd()));
+		assert_eq!(Balances::reserved_balance(3), 0);
+		assert_eq!(Balances::free_balance(3), 1_000);
+	});
+}
+
+#[test]
+fn consumers_are_served_prices_and_pushed_their_moves() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PriceOracle::request_price_xcm(RuntimeOrigin::none(), eth_usd(), 1),
+			DispatchError::BadOrigin
+		);
+		assert_eq!(
+			PriceOracle::request_price_xcm(RuntimeOrigin::signed(5), eth_usd(), 1),
+			Err(Error::<Test>::NoPrice.into())
+		);
+		assert_ok!(PriceOracle::subscribe_price(RuntimeOrigin::signed(5), eth_usd(), 2, 500));
+		assert_ok!(PriceOracle::subscribe_price(RuntimeOrigin::signed(6), eth_usd(), 3, 1_000));
+		System::assert_last_event(
+			Event::PriceSubscribed {
+				subscriber: 6,
+				pair_id: eth_usd(),
+				query_id: 3,
+				threshold_bps: 1_000,
+			}
+			.into(),
+		);
+		assert_noop!(
+			PriceOracle::subscribe_price(RuntimeOrigin::signed(7), eth_usd(), 4, 1_000),
+			Error::<Test>::TooManySubscriptions
+		);
+		assert_noop!(
+			PriceOracle::subscribe_price(RuntimeOrigin::signed(7), [0; 32], 4, 1_000),
+			Error::<Test>::PairNotTracked
+		);
+
+		// The first aggregation pushes the price to every subscription.
+		let submit = |price, timestamp| {
+			assert_ok!(PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				0,
+				price,
+				0,
+				timestamp,
+				None,
+				None
+			));
+			PriceOracle::on_finalize(1);
+		};
+		submit(2_000, 10);
+		let price = 2_000 * PRICE_UNIT;
+		assert_eq!(SentPrices::get(), vec![(5, 2, eth_usd(), price), (6, 3, eth_usd(), price)]);
+		System::assert_has_event(
+			Event::PricePushed { subscriber: 5, pair_id: eth_usd(), price }.into(),
+		);
+
+		// A move of 6% is pushed to the 5% subscription only, again after the next aggregation
+		// if the push fails.
+		MessengerDown::set(true);
+		submit(2_120, 11);
+		assert_eq!(SentPrices::get().len(), 2);
+		MessengerDown::set(false);
+		submit(2_120, 12);
+		let price = 2_120 * PRICE_UNIT;
+		assert_eq!(SentPrices::get()[2..], [(5, 2, eth_usd(), price)]);
+		// Pushes aren't reads of the pair.
+		assert_eq!(PriceOracle::pair_reads(eth_usd()).count, 0);
+
+		// Requests are served the price at once.
+		assert_ok!(PriceOracle::request_price_xcm(RuntimeOrigin::signed(6), eth_usd(), 4));
+		System::assert_last_event(
+			Event::PriceServed { subscriber: 6, pair_id: eth_usd(), query_id: 4 }.into(),
+		);
+		assert_eq!(SentPrices::get().last(), Some(&(6, 4, eth_usd(), price)));
+
+		// A zero threshold removes the consumer's subscription.
+		assert_ok!(PriceOracle::subscribe_price(RuntimeOrigin::signed(5), eth_usd(), 2, 0));
+		assert_ok!(PriceOracle::subscribe_price(RuntimeOrigin::signed(6), eth_usd(), 3, 0));
+		assert!(!PriceSubscriptions::<Test>::contains_key(eth_usd()));
+	});
+}
+
+#[cfg(feature = "xcm")]
+#[test]
+fn prices_are_sent_over_xcm_as_transacts_of_the_consumer_call() {
+	use crate::{xcm_messenger::XcmPriceMessenger, PriceMessenger};
+	use frame_support::weights::Weight;
+	use xcm::latest::prelude::*;
+
+	frame_support::parameter_types! {
+		static SentXcm: Vec<(Location, Xcm<()>)> = vec![];
+		const ReceivePrice: [u8; 2] = [42, 0];
+		const ReceivePriceWeight: Weight = Weight::from_parts(1_000_000, 1_000);
+		const SendWeight: Weight = Weight::from_parts(10_000, 100);
+	}
+	struct TestRouter;
+	impl SendXcm for TestRouter {
+		type Ticket = (Location, Xcm<()>);
+		fn validate(
+			dest: &mut Option<Location>,
+			message: &mut Option<Xcm<()>>,
+		) -> SendResult<Self::Ticket> {
+			let ticket = (dest.take().unwrap(), message.take().unwrap());
+			Ok((ticket, Assets::new()))
+		}
+		fn deliver(ticket: Self::Ticket) -> Result<XcmHash, SendError> {
+			SentXcm::mutate(|sent| sent.push(ticket));
+			Ok([0; 32])
+		}
+	}
+	type Messenger<Router> =
+		XcmPriceMessenger<Router, ReceivePrice, ReceivePriceWeight, SendWeight>;
+
+	let sibling = Location::new(1, [Parachain(2_000)]);
+	let price = ProvidedPrice {
+		price: 2_000 * PRICE_UNIT,
+		timestamp: 10,
+		stale: false,
+		low_quality: false,
+	};
+	assert_ok!(Messenger::<TestRouter>::send_price(&sibling, 7, eth_usd(), price));
+	let call = ([42u8, 0], 7u64, eth_usd(), price).encode();
+	assert_eq!(
+		SentXcm::get(),
+		vec![(
+			sibling.clone(),
+			Xcm(vec![
+				UnpaidExecution { weight_limit: Unlimited, check_origin: None },
+				Transact {
+					origin_kind: OriginKind::Xcm,
+					fallback_max_weight: Some(ReceivePriceWeight::get()),
+					call: call.into(),
+				},
+			])
+		)]
+	);
+	assert_eq!(Messenger::<TestRouter>::send_price_weight(), SendWeight::get());
+
+	// Consumers no router reaches aren't served.
+	assert_eq!(
+		Messenger::<()>::send_price(&sibling, 7, eth_usd(), price),
+		Err(DispatchError::Unavailable)
+	);
+}
+
+#[test]
+fn deviation_is_measured_in_basis_points_of_the_reference() {
+	assert_eq!(aggregation::deviation_bps(106, 100), 600);
+	assert_eq!(aggregation::deviation_bps(94, 100), 600);
+	assert_eq!(aggregation::deviation_bps(100, 100), 0);
+	assert_eq!(aggregation::deviation_bps(u128::MAX, 1), u32::MAX);
+	assert_eq!(aggregation::deviation_bps(1, 0), u32::MAX);
+}
+
+#[test]
+fn history_is_paged_by_block_range_and_step() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		for (block, price) in [(1u64, 2_000u128), (2, 2_100), (4, 2_400), (5, 2_500), (6, 2_600)] {
+			System::set_block_number(block);
+			assert_ok!(PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				0,
+				price,
+				0,
+				block,
+				None,
+				None,
+			));
+			PriceOracle::on_finalize(block);
+		}
+
+		let history = |from, to, step| -> Vec<_> {
+			PriceOracle::history(eth_usd(), from, to, step)
+				.into_iter()
+				.map(|(price, block)| (price / PRICE_UNIT, block))
+				.collect()
+		};
+		// The first entry no longer fits the on-chain history.
+		assert_eq!(history(0, 10, 0), [(2_100, 2), (2_400, 4), (2_500, 5), (2_600, 6)]);
+		assert_eq!(history(3, 5, 0), [(2_400, 4), (2_500, 5)]);
+		assert_eq!(history(0, 10, 2), [(2_100, 2), (2_400, 4), (2_600, 6)]);
+		assert_eq!(history(7, 10, 1), []);
+	});
+
+	// Every aggregate is indexed offchain, including the pruned one.
+	ext.persist_offchain_overlay();
+	let indexed = ext
+		.offchain_db()
+		.get(&crate::history_key(&eth_usd(), 1u64))
+		.map(|value| <(u128, u64)>::decode(&mut &value[..]).unwrap());
+	assert_eq!(indexed, Some((2_000 * PRICE_UNIT, 1)));
+}
+
+#[test]
+fn accepted_prices_are_indexed_offchain_per_exchange() {
+	let mut ext = new_test_ext();
+	let keys = ext.execute_with(|| {
+		System::set_block_number(2);
+		for (exchange_id, price, timestamp) in [(0, 2_000, 10), (1, 2_100, 10), (0, 2_050, 11)] {
+			assert_ok!(PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				exchange_id,
+				price,
+				0,
+				timestamp,
+				None,
+				None
+			));
+		}
+
+		// Every exchange of the pair in every block of the range, oldest first.
+		let keys = PriceOracle::indexed_price_keys(eth_usd(), 1, 2);
+		let exchanges = [0, 1, 2, 3, 4, 6, 7, 8, 9];
+		let expected: Vec<_> = [1u64, 2]
+			.into_iter()
+			.flat_map(|block| exchanges.map(|id| (block, crate::price_key(&eth_usd(), block, id))))
+			.collect();
+		assert_eq!(keys, expected);
+		assert_eq!(PriceOracle::indexed_price_keys(eth_usd(), 3, 2), vec![]);
+		assert_eq!(PriceOracle::indexed_price_keys([0; 32], 1, 2), vec![]);
+
+		// Pages hold whole blocks.
+		let page = PriceOracle::indexed_price_keys(eth_usd(), 1, 1_000);
+		assert_eq!(page.len(), (crate::MAX_HISTORY_PAGE as usize / 9) * 9);
+		assert_eq!(page.last().map(|(block, _)| *block), Some(28));
+		keys
+	});
+
+	ext.persist_offchain_overlay();
+	let indexed: Vec<_> = keys
+		.iter()
+		.filter_map(|(block, key)| {
+			let value = ext.offchain_db().get(key)?;
+			Some((*block, key[key.len() - 1], <(u128, u64)>::decode(&mut &value[..]).unwrap()))
+		})
+		.collect();
+	assert_eq!(indexed, vec![(2, 0, (2_050 * PRICE_UNIT, 11)), (2, 1, (2_100 * PRICE_UNIT, 10))]);
+}
+
+#[test]
+fn pair_ids_follow_the_configured_scheme() {
+	struct Concat;
+	impl PairHasher for Concat {
+		fn pair_id(base: &[u8], quote: &[u8]) -> PairId {
+			let mut id = [0; 32];
+			let symbol = [base, b"-", quote].concat();
+			id[..symbol.len()].copy_from_slice(&symbol);
+			id
+		}
+	}
+
+	let pair = registry::default_pairs().remove(0);
+	let mut expected = [0; 32];
+	expected[..7].copy_from_slice(b"ETH-USD");
+	assert_eq!(pair.id_with::<Concat>(), expected);
+	assert_eq!(pair.id(), eth_usd());
+	assert_eq!(PriceOracle::pair_id_of(&pair), pair.id());
+}
+
+#[test]
+fn remapped_exchanges_keep_their_quarantine_and_commitments() {
+	new_test_ext().execute_with(|| {
+		RevealWindow::set(2);
+		let key = Exchanges::<Test>::get(0).unwrap().key();
+		let (salt, observation) = ([7; 32], (42, None, None));
+		let commitment = PriceOracle::price_commitment(2_500, 0, observation, &salt);
+		let origin = RuntimeOrigin::signed(OPERATOR);
+		assert_ok!(PriceOracle::commit_price(origin.clone(), eth_usd(), 0, commitment));
+		QuarantinedExchanges::<Test>::insert(0, 100);
+		OutlierCounts::<Test>::insert(0, 3);
+		CommitteeMembers::set(vec![8]);
+
+		assert_ok!(PriceOracle::remap_exchange(RuntimeOrigin::root(), key, 200, 2, 2));
+		assert!(PriceOracle::is_quarantined(200) && !PriceOracle::is_quarantined(0));
+		assert_eq!(OutlierCounts::<Test>::get(200), 3);
+		assert!(!PriceCommitments::<Test>::contains_key(OPERATOR, (eth_usd(), 0)));
+
+		// The commitment is revealed under the new id, leaving evidence under it.
+		System::set_block_number(2);
+		assert_ok!(PriceOracle::reveal_price(
+			origin,
+			eth_usd(),
+			200,
+			2_500,
+			0,
+			42,
+			None,
+			None,
+			salt
+		));
+		assert_eq!(PriceOracle::get_price(eth_usd(), 200), Some((2_500 * PRICE_UNIT, 42)));
+		assert!(SignedSubmissions::<Test>::contains_key(OPERATOR, (eth_usd(), 200)));
+	});
+}
+
+#[test]
+fn remapped_exchanges_keep_their_pools_and_prices() {
+	new_test_ext().execute_with(|| {
+		let key = Exchanges::<Test>::get(0).unwrap().key();
+		assert_eq!(ExchangeKeys::<Test>::get(key), Some(0));
+		assert_ok!(PriceOracle::submit_price_unsigned(
+			RuntimeOrigin::none(),
+			eth_usd(),
+			0,
+			2_000,
+			0,
+			10,
+			None,
+			None
+		));
+
+		assert_noop!(
+			PriceOracle::remap_exchange(RuntimeOrigin::root(), key, 1, u32::MAX, u32::MAX),
+			Error::<Test>::ExchangeAlreadyRegistered
+		);
+		assert_noop!(
+			PriceOracle::remap_exchange(RuntimeOrigin::root(), key, 200, 0, u32::MAX),
+			Error::<Test>::TooManyPairs
+		);
+		assert_noop!(
+			PriceOracle::remap_exchange(RuntimeOrigin::root(), key, 200, u32::MAX, 0),
+			Error::<Test>::TooManyOperators
+		);
+		assert_noop!(
+			PriceOracle::remap_exchange(RuntimeOrigin::root(), [0; 32], 200, u32::MAX, u32::MAX),
+			Error::<Test>::UnknownExchange
+		);
+		assert_ok!(PriceOracle::remap_exchange(RuntimeOrigin::root(), key, 200, u32::MAX, 1));
+
+		assert_eq!(ExchangeKeys::<Test>::get(key), Some(200));
+		assert!(!Exchanges::<Test>::contains_key(0));
+		assert_eq!(PriceOracle::get_price(eth_usd(), 200), Some((2_000 * PRICE_UNIT, 10)));
+		assert_eq!(PriceOracle::get_price(eth_usd(), 0), None);
+		let pair = TrackedPairs::<Test>::get(eth_usd()).unwrap();
+		assert!(pair.pool(200).is_some() && pair.pool(0).is_none());
+
+		// Names stay unique, so keys keep pointing at one exchange.
+		let config = Exchanges::<Test>::get(200).unwrap();
+		assert_noop!(
+			PriceOracle::add_exchange(RuntimeOrigin::root(), 0, config),
+			Error::<Test>::ExchangeAlreadyRegistered
+		);
+	});
+}
+
+#[test]
+fn price_history_is_delta_encoded() {
+	type History = DeltaHistory<u32, frame_support::traits::ConstU32<4>>;
+	let unit = PRICE_UNIT;
+	let entries =
+		[(2_500 * unit, 10), (2_501 * unit, 11), (2_499 * unit, 12), (u128::MAX, 20), (0, 21)];
+
+	let history: History = entries.into_iter().collect();
+	// The oldest entry was dropped to make room.
+	assert_eq!(history.iter().collect::<Vec<_>>(), entries[1..]);
+	assert_eq!(History::decode(&mut &history.encode()[..]).unwrap(), history);
+
+	// A price moving by a few dollars every block takes about half the space.
+	let moves: Vec<_> = (0..64).map(|block| ((2_500 + block % 7) * unit, block as u32)).collect();
+	let mut history: DeltaHistory<u32, frame_support::traits::ConstU32<64>> =
+		moves.iter().copied().collect();
+	let (delta, full) = (history.encode().len(), moves.encode().len());
+	assert!(delta * 2 <= full + full / 10, "{delta} of {full} bytes");
+
+	// Recording in the same block replaces the block's entry.
+	history.record(2_600 * unit, 63);
+	assert_eq!(history.last(), Some((2_600 * unit, 63)));
+	assert_eq!(history.len(), 64);
+}
+
+#[test]
+fn pool_types_of_a_venue_are_separate_exchanges() {
+	new_test_ext().execute_with(|| {
+		// SushiSwap's concentrated liquidity pools next to its classic ones.
+		let classic = Exchanges::<Test>::get(1).unwrap();
+		let concentrated = ExchangeConfig { protocol: ProtocolKind::UniswapV3, ..classic.clone() };
+		assert_ne!(classic.key(), concentrated.key());
+		assert_eq!(concentrated.label(), "SushiSwap (Uniswap V3)");
+		assert_noop!(
+			PriceOracle::add_exchange(RuntimeOrigin::root(), 10, classic),
+			Error::<Test>::ExchangeAlreadyRegistered
+		);
+		assert_ok!(PriceOracle::add_exchange(RuntimeOrigin::root(), 10, concentrated.clone()));
+		assert_eq!(ExchangeKeys::<Test>::get(concentrated.key()), Some(10));
+
+		for (exchange_id, price, timestamp) in [(1, 2_000, 10), (10, 2_010, 11)] {
+			assert_ok!(PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				exchange_id,
+				price,
+				0,
+				timestamp,
+				None,
+				None
+			));
+		}
+		assert_eq!(PriceOracle::get_price(eth_usd(), 1), Some((2_000 * PRICE_UNIT, 10)));
+		assert_eq!(PriceOracle::get_price(eth_usd(), 10), Some((2_010 * PRICE_UNIT, 11)));
+	});
+}
+
+/// `observe([window, 0])` return data of a pool whose tick accumulators were `tick_cumulatives`
+/// `window` seconds ago and now.
+fn observe_result(tick_cumulatives: [i64; 2]) -> String {
+	let word = |value: i64| match value {
+		value if value < 0 => U256::MAX - U256::from(value.unsigned_abs() - 1),
+		value => U256::from(value),
+	};
+	let words = [
+		U256::from(0x40),
+		U256::from(0xa0),
+		U256::from(2),
+		word(tick_cumulatives[0]),
+		word(tick_cumulatives[1]),
+		U256::from(2),
+		U256::zero(),
+		U256::zero(),
+	];
+	words.iter().fold(String::from("0x"), |hex, word| hex + &format!("{word:064x}"))
+}
+
+#[test]
+fn sqrt_ratios_match_uniswap_tick_math() {
+	assert_eq!(fixed::sqrt_ratio_at_tick(0), Ok(U256::one() << 96));
+	assert_eq!(fixed::sqrt_ratio_at_tick(fixed::MIN_TICK), Ok(U256::from(4_295_128_739u64)));
+	assert_eq!(
+		fixed::sqrt_ratio_at_tick(fixed::MAX_TICK),
+		Ok(U256::from_dec_str("1461446703485210103287273052203988822378723970342").unwrap())
+	);
+	assert_eq!(fixed::sqrt_ratio_at_tick(fixed::MAX_TICK + 1), Err(OracleError::DecodeError));
+}
+
+#[test]
+fn uniswap_v3_parses_observations_into_a_twap() {
+	let pool = registry::default_pairs()[0].pool(0).unwrap().metadata;
+	let at_tick = |tick| {
+		let sqrt_price_x96 = fixed::sqrt_ratio_at_tick(tick).unwrap();
+		fixed::price_from_sqrt_x96(sqrt_price_x96, pool.decimals_delta(), !pool.base_is_token0)
+	};
+	let twap = |tick_cumulatives, window| {
+		let data = array_bytes::hex2bytes(observe_result(tick_cumulatives)).unwrap();
+		UniswapV3Protocol.parse_twap(&data, window, &pool)
+	};
+
+	// Tick 198080 is ETH at just below 2500 USD.
+	let price = twap([1_000, 1_000 + 198_080 * 1_800], 1_800).unwrap();
+	assert_eq!(Ok(price), at_tick(198_080));
+	assert_eq!(price / PRICE_UNIT, 2_499);
+	// Negative average ticks round down.
+	assert_eq!(twap([0, -3], 2), at_tick(-2));
+	assert_eq!(twap([0, -4], 2), at_tick(-2));
+	assert_eq!(twap([0, 0], 0), Err(OracleError::InvalidConfig));
+	assert_eq!(
+		UniswapV3Protocol.observe_call_data(1_800),
+		format!("0x883bdbfd{:064x}{:064x}{:064x}{:064x}", 0x20, 2, 1_800, 0)
+	);
+}
+
+#[test]
+fn the_fee_tier_with_the_most_liquidity_is_priced() {
+	let (offchain, state) = TestOffchainExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+
+	let low_fee = "0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640";
+	let high_fee = "0x1111111111111111111111111111111111111111";
+	let slot0_eth_2000 = format!("0x{:064x}", fixed::sqrt_ratio_at_tick(200_311).unwrap());
+	let liquidity = |amount: u128| format!("0x{amount:064x}");
+	// The liquidity moves from the low to the high fee tier between the two runs.
+	for (first, low_liquidity, high_liquidity) in [(1, 5_000, 100), (5, 100, 5_000)] {
+		let call = |id: u64, to: &str, data: &str| {
+			format!(
+				r#"{{"jsonrpc":"2.0","method":"eth_call","params":[{{"to":"{to}","data":"{data}"}},"latest"],"id":{id}}}"#
+			)
+		};
+		let result =
+			|id: u64, result: &str| format!(r#"{{"jsonrpc":"2.0","id":{id},"result":"{result}"}}"#);
+		let request = [
+			call(first, low_fee, "0x3850c7bd"),
+			call(first + 1, low_fee, "0x1a686502"),
+			call(first + 2, high_fee, "0x3850c7bd"),
+			call(first + 3, high_fee, "0x1a686502"),
+		];
+		let response = [
+			result(first + 3, &liquidity(high_liquidity)),
+			result(first + 2, &slot0_eth_2000),
+			result(first + 1, &liquidity(low_liquidity)),
+			result(first, SLOT0_ETH_2500),
+		];
+		state.write().expect_request(PendingRequest {
+			method: "POST".into(),
+			uri: "https://eth.llamarpc.com".into(),
+			headers: vec![("Content-Type".into(), "application/json".into())],
+			body: format!("[{}]", request.join(",")).into_bytes(),
+			response: Some(format!("[{}]", response.join(",")).into_bytes()),
+			sent: true,
+			..Default::default()
+		});
+	}
+
+	ext.execute_with(|| {
+		let (id, uniswap) = registry::default_exchanges().remove(0);
+		let mut pair = registry::default_pairs().remove(0);
+		let low = pair.pool(id).unwrap().clone();
+		let high = PoolConfig { address: [0x11; 20], ..low.clone() };
+		pair.pools.try_push((id, high)).unwrap();
+		let tiers: Vec<_> = pair.pools_of(id).collect();
+		assert_eq!(tiers.len(), 2);
+		let url = b"https://eth.llamarpc.com";
+
+		assert_eq!(
+			uniswap.fetch_tiered_price(url, &pair, &tiers, None, &CallOptions::default(), 1_000),
+			Ok(2_500 * PRICE_UNIT)
+		);
+		let price = uniswap
+			.fetch_tiered_price(url, &pair, &tiers, None, &CallOptions::default(), 1_000)
+			.unwrap();
+		assert_eq!(price / PRICE_UNIT, 2_000);
+	});
+}
+
+#[test]
+fn buy_legs_are_simulated_through_the_router() {
+	let (offchain, state) = TestOffchainExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+
+	let pool = "0x397FF1542f962076d0BFE58eA045FfA2d347ACa0";
+	let router = "0xd9e1cE17f2641f24aE83637ab66a2cca9C378B9F";
+	let usdc = "000000000000000000000000a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+	let weth = "000000000000000000000000c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2";
+	let amount_in = 10_000_000_000u128;
+	let call = |id: u64, to: &str, data: &str| {
+		format!(
+			r#"{{"jsonrpc":"2.0","method":"eth_call","params":[{{"to":"{to}","data":"{data}"}},"latest"],"id":{id}}}"#
+		)
+	};
+	let result =
+		|id: u64, result: &str| format!(r#"{{"jsonrpc":"2.0","id":{id},"result":"{result}"}}"#);
+	// 10k USDC buy 4.9 ETH in the first run and 4.7 ETH in the second, which reuses the cached
+	// tokens of the pool, and the third, which reads them again once the cache expired.
+	let mut id = 0;
+	for (read_tokens, amount_out) in [
+		(true, 4_900_000_000_000_000_000u128),
+		(false, 4_700_000_000_000_000_000),
+		(true, 4_700_000_000_000_000_000),
+	] {
+		let mut requests = Vec::new();
+		if read_tokens {
+			let tokens = [call(id + 1, pool, "0x0dfe1681"), call(id + 2, pool, "0xd21220a7")];
+			let token_results =
+				[result(id + 2, &format!("0x{weth}")), result(id + 1, &format!("0x{usdc}"))];
+			requests.push((
+				format!("[{}]", tokens.join(",")),
+				format!("[{}]", token_results.join(",")),
+			));
+			id += 2;
+		}
+		id += 1;
+		requests.push((
+			call(
+				id,
+				router,
+				&format!("0xd06ca61f{amount_in:064x}{:064x}{:064x}{usdc}{weth}", 0x40, 2),
+			),
+			result(id, &format!("0x{:064x}{:064x}{amount_in:064x}{amount_out:064x}", 0x20, 2)),
+		));
+		for (body, response) in requests {
+			state.write().expect_request(PendingRequest {
+				method: "POST".into(),
+				uri: "https://eth.llamarpc.com".into(),
+				headers: vec![("Content-Type".into(), "application/json".into())],
+				body: body.into_bytes(),
+				response: Some(response.into_bytes()),
+				sent: true,
+				..Default::default()
+			});
+		}
+	}
+
+	ext.execute_with(|| {
+		let sushi = Exchanges::<Test>::get(1).unwrap();
+		let router = price_oracle_eip55::parse(router).unwrap();
+		assert_noop!(
+			PriceOracle::set_router(RuntimeOrigin::root(), 10, Some(router)),
+			Error::<Test>::UnknownExchange
+		);
+		assert_ok!(PriceOracle::set_router(RuntimeOrigin::root(), 1, Some(router)));
+		assert_eq!(Routers::<Test>::get(sushi.key()), Some(router));
+
+		let pair = registry::default_pairs().remove(0);
+		let pool = pair.pool(1).unwrap();
+		let url = b"https://eth.llamarpc.com";
+		let sell_price = 2_100 * PRICE_UNIT;
+		for (run, (bought, confirmed)) in [
+			(4_900_000_000_000_000_000, true),
+			(4_700_000_000_000_000_000, false),
+			(4_700_000_000_000_000_000, false),
+		]
+		.into_iter()
+		.enumerate()
+		{
+			state.write().timestamp =
+				Timestamp::from_unix_millis(run as u64 * (pool_cache::POOL_CACHE_TTL / 2 + 1));
+			let simulated =
+				sushi.simulate_buy(url, &router, pool, amount_in, &CallOptions::default(), 1_000);
+			assert_eq!(simulated, Ok(bought));
+			// Buying at 2040.8 leaves a 2.9% spread; buying at 2127.7 none.
+			assert_eq!(
+				arbitrage::confirms_spread(sell_price, amount_in, bought, &pool.metadata, 100),
+				confirmed
+			);
+		}
+		assert!(!arbitrage::confirms_spread(sell_price, amount_in, 0, &pool.metadata, 0));
+	});
+}
+
+#[test]
+fn gas_fees_of_tracked_chains_are_read_from_the_fee_history() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	// Three blocks of history: the base fee of the next block comes last and the median tip of
+	// 1, 3 and 2 gwei is 2 gwei.
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: br#"{"jsonrpc":"2.0","method":"eth_feeHistory","params":["0xa","latest",[50]],"id":1}"#.to_vec(),
+		response: Some(
+			br#"{"jsonrpc":"2.0","id":1,"result":{"oldestBlock":"0x10","baseFeePerGas":["0x1","0x2","0x3","0x4"],"gasUsedRatio":[0.5,0.5,0.5],"reward":[["0x3b9aca00"],["0xb2d05e00"],["0x77359400"]]}}"#
+				.to_vec(),
+		),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		let _ = TrackedPairs::<Test>::clear(u32::MAX, None);
+		assert_noop!(
+			PriceOracle::set_gas_tracking(RuntimeOrigin::signed(OPERATOR), 1, true),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			PriceOracle::set_gas_tracking(RuntimeOrigin::root(), 7, true),
+			Error::<Test>::UnknownChain
+		);
+		assert_ok!(PriceOracle::set_gas_tracking(RuntimeOrigin::root(), 1, true));
+		System::assert_last_event(Event::GasTrackingSet { chain_id: 1, tracked: true }.into());
+
+		// Untracked chains aren't read, so only chain 1 is.
+		PriceOracle::offchain_worker(UPDATE_INTERVAL.into());
+		let tx = pool_state.write().transactions.pop().unwrap();
+		assert!(pool_state.read().transactions.is_empty());
+		let fees = GasFees {
+			base_fee: 4,
+			priority_fee: 2_000_000_000,
+			builder_tip: None,
+			block: 19,
+			timestamp: 0,
+		};
+		let call = Call::submit_gas_fees { chain_id: 1, fees };
+		assert_eq!(
+			Extrinsic::decode(&mut &*tx).unwrap().function,
+			RuntimeCall::PriceOracle(call.clone())
+		);
+
+		assert_ok!(PriceOracle::validate_unsigned(TransactionSource::External, &call));
+		assert_ok!(PriceOracle::submit_gas_fees(RuntimeOrigin::none(), 1, fees));
+		assert_eq!(GasData::<Test>::get(1), Some(fees));
+		System::assert_last_event(Event::GasFeesSubmitted { chain_id: 1, fees }.into());
+
+		// Fees of the same or an older block are stale, those of untracked chains invalid.
+		assert_eq!(
+			PriceOracle::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Stale.into()
+		);
+		assert_noop!(
+			PriceOracle::submit_gas_fees(RuntimeOrigin::none(), 1, fees),
+			Error::<Test>::StaleSubmission
+		);
+		assert_noop!(
+			PriceOracle::submit_gas_fees(RuntimeOrigin::signed(1), 1, fees),
+			Error::<Test>::NotOperator
+		);
+		let call = Call::submit_gas_fees { chain_id: 56, fees };
+		assert_eq!(
+			PriceOracle::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Call.into()
+		);
+
+		assert_ok!(PriceOracle::set_gas_tracking(RuntimeOrigin::root(), 1, false));
+		assert_eq!(GasData::<Test>::get(1), None);
+		assert_noop!(
+			PriceOracle::submit_gas_fees(RuntimeOrigin::none(), 1, fees),
+			Error::<Test>::GasNotTracked
+		);
+	});
+}
+
+#[test]
+fn builder_tips_are_sampled_for_opted_in_chains() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	// Tips at the median and the 90th percentile of two blocks.
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: br#"{"jsonrpc":"2.0","method":"eth_feeHistory","params":["0xa","latest",[50,90]],"id":1}"#.to_vec(),
+		response: Some(
+			br#"{"jsonrpc":"2.0","id":1,"result":{"oldestBlock":"0x10","baseFeePerGas":["0x1","0x2","0x3"],"gasUsedRatio":[0.5,0.5],"reward":[["0x1","0x64"],["0x3","0xc8"]]}}"#
+				.to_vec(),
+		),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		let _ = TrackedPairs::<Test>::clear(u32::MAX, None);
+		assert_noop!(
+			PriceOracle::set_builder_tips(RuntimeOrigin::root(), 1, true),
+			Error::<Test>::GasNotTracked
+		);
+		assert_ok!(PriceOracle::set_gas_tracking(RuntimeOrigin::root(), 1, true));
+		assert_ok!(PriceOracle::set_builder_tips(RuntimeOrigin::root(), 1, true));
+		System::assert_last_event(Event::BuilderTipsSet { chain_id: 1, enabled: true }.into());
+
+		PriceOracle::offchain_worker(UPDATE_INTERVAL.into());
+		let tx = pool_state.write().transactions.pop().unwrap();
+		let fees = GasFees {
+			base_fee: 3,
+			priority_fee: 2,
+			builder_tip: Some(150),
+			block: 18,
+			timestamp: 0,
+		};
+		assert_eq!(
+			Extrinsic::decode(&mut &*tx).unwrap().function,
+			RuntimeCall::PriceOracle(Call::submit_gas_fees { chain_id: 1, fees })
+		);
+		assert_eq!(fees.competitive_tip(), 150);
+		assert_eq!(GasFees { builder_tip: None, ..fees }.competitive_tip(), 2);
+
+		// Untracking the fees of a chain stops sampling its builder tips.
+		assert_ok!(PriceOracle::set_gas_tracking(RuntimeOrigin::root(), 1, false));
+		assert!(!BuilderTipChains::<Test>::contains_key(1));
+	});
+}
+
+#[test]
+fn registry_snapshots_replicate_the_configuration() {
+	let twap_check = TwapCheck { window: 600, max_deviation: Permill::from_percent(2) };
+	let fast_path = FastPath { threshold_bps: 200, blocks: 5 };
+	let sla = FreshnessSla { max_staleness: 60_000, min_sources: 2, max_spread_bps: 500 };
+	let router = [9; 20];
+	let (_, _, hop) = registry::default_pool_hops().remove(2);
+	let blob = new_test_ext().execute_with(|| {
+		assert_ok!(PriceOracle::set_twap_check(RuntimeOrigin::root(), eth_usd(), Some(twap_check)));
+		assert_ok!(PriceOracle::set_fast_path(RuntimeOrigin::root(), eth_usd(), Some(fast_path)));
+		assert_ok!(PriceOracle::set_sla(RuntimeOrigin::root(), eth_usd(), Some(sla)));
+		assert_ok!(PriceOracle::set_failure_mode(
+			RuntimeOrigin::root(),
+			eth_usd(),
+			FailureMode::FailOpen
+		));
+		assert_ok!(PriceOracle::set_router(RuntimeOrigin::root(), 1, Some(router)));
+		assert_ok!(PriceOracle::set_pool_hop(
+			RuntimeOrigin::root(),
+			eth_usd(),
+			2,
+			Some(hop.clone())
+		));
+		PriceOracle::export_registry().encode()
+	});
+
+	// A chain with one pair of its own and no exchanges.
+	let mut pair = registry::default_pairs().remove(0);
+	pair.base = BoundedVec::truncate_from(b"WBTC".to_vec());
+	pair.pools = Default::default();
+	new_test_ext_with(crate::GenesisConfig { pairs: vec![pair.clone()], ..Default::default() })
+		.execute_with(|| {
+			let own = PriceOracle::pair_id_of(&pair);
+			assert_ok!(PriceOracle::set_failure_mode(
+				RuntimeOrigin::root(),
+				own,
+				FailureMode::FailOpen
+			));
+			assert_noop!(
+				PriceOracle::import_registry(RuntimeOrigin::signed(OPERATOR), blob.clone()),
+				DispatchError::BadOrigin
+			);
+			assert_noop!(
+				PriceOracle::import_registry(RuntimeOrigin::root(), blob[1..].to_vec()),
+				Error::<Test>::InvalidSnapshot
+			);
+
+			assert_ok!(PriceOracle::import_registry(RuntimeOrigin::root(), blob.clone()));
+			let exchanges = registry::default_exchanges();
+			let pairs = genesis_pairs();
+			System::assert_last_event(
+				Event::RegistryImported {
+					exchanges: exchanges.len() as u32,
+					pairs: pairs.len() as u32,
+				}
+				.into(),
+			);
+			assert_eq!(PriceOracle::export_registry().encode(), blob);
+			assert!(!TrackedPairs::<Test>::contains_key(own));
+			assert_eq!(FailureModes::<Test>::get(own), FailureMode::FailClosed);
+			assert_eq!(TwapChecks::<Test>::get(eth_usd()), Some(twap_check));
+			assert_eq!(FastPaths::<Test>::get(eth_usd()), Some(fast_path));
+			assert_eq!(PairSlas::<Test>::get(eth_usd()), Some(sla));
+			assert_eq!(FailureModes::<Test>::get(eth_usd()), FailureMode::FailOpen);
+			assert_eq!(Routers::<Test>::get(exchanges[1].1.key()), Some(router));
+			assert_eq!(PoolHops::<Test>::get(eth_usd(), 2), Some(hop));
+			for (exchange_id, config) in exchanges {
+				assert_eq!(ExchangeKeys::<Test>::get(config.key()), Some(exchange_id));
+			}
+		});
+}
+
+#[test]
+fn inconsistent_registry_snapshots_are_rejected() {
+	new_test_ext().execute_with(|| {
+		let snapshot = PriceOracle::export_registry();
+		let import = |snapshot: &registry::RegistrySnapshot| {
+			PriceOracle::import_registry(RuntimeOrigin::root(), snapshot.encode())
+		};
+
+		let mut duplicate = snapshot.clone();
+		duplicate.exchanges.push(duplicate.exchanges[0].clone());
+		assert_noop!(import(&duplicate), Error::<Test>::ExchangeAlreadyRegistered);
+
+		let mut unknown = snapshot.clone();
+		unknown.exchanges.remove(0);
+		assert_noop!(import(&unknown), Error::<Test>::UnknownExchange);
+		let mut unknown = snapshot.clone();
+		unknown.routers.push((200, [1; 20]));
+		assert_noop!(import(&unknown), Error::<Test>::UnknownExchange);
+
+		let mut invalid = snapshot.clone();
+		invalid.pairs[0].info.max_price = 0;
+		assert_noop!(import(&invalid), Error::<Test>::InvalidBounds);
+		let mut invalid = snapshot.clone();
+		invalid.pairs[0].sla =
+			Some(FreshnessSla { max_staleness: 0, min_sources: 1, max_spread_bps: 100 });
+		assert_noop!(import(&invalid), Error::<Test>::InvalidSla);
+
+		let mut twice = snapshot.clone();
+		twice.pairs.push(twice.pairs[0].clone());
+		assert_noop!(import(&twice), Error::<Test>::PairAlreadyTracked);
+
+		let mut too_many = snapshot;
+		too_many.pairs = (0..=MAX_PAIRS)
+			.map(|i| {
+				let mut pair = too_many.pairs[0].clone();
+				pair.info.base = BoundedVec::truncate_from(i.to_le_bytes().to_vec());
+				pair
+			})
+			.collect();
+		assert_noop!(import(&too_many), Error::<Test>::PairLimitReached);
+	});
+}
+
+#[cfg(feature = "synthetic-prices")]
+#[test]
+fn synthetic_exchanges_walk_from_the_last_aggregate() {
+	use crate::chains::{synthetic, ChainKind};
+
+	let mut exchange = registry::default_exchanges().remove(0).1;
+	exchange.chain = ChainKind::Synthetic;
+	let mut pair = registry::default_pairs().remove(0);
+	pair.pools.truncate(1);
+	let address = pair.pools[0].1.address;
+	let (offchain, _state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext_with(crate::GenesisConfig {
+		exchanges: vec![(0, exchange)],
+		pairs: vec![pair.clone()],
+		..Default::default()
+	});
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	ext.execute_with(|| {
+		let scale = 10u128.pow(u32::from(fixed::PRICE_DECIMALS - pair.decimals));
+		let fetch = |round| {
+			PriceOracle::offchain_worker(fetch_block(round));
+			let tx = pool_state.write().transactions.pop().unwrap();
+			match Extrinsic::decode(&mut &*tx).unwrap().function {
+				RuntimeCall::PriceOracle(Call::submit_price_unsigned { price, .. }) =>
+					price * scale,
+				call => panic!("unexpected call {call:?}"),
+			}
+		};
+
+		// Without an aggregate the walk starts in the middle of the bounds, without requests.
+		let bounds = PriceOracle::price_bounds(&pair);
+		let middle = bounds.start() + (bounds.end() - bounds.start()) / 2;
+		let parent = System::parent_hash();
+		let first = synthetic::next_price(middle, parent.as_ref(), &address, bounds.clone());
+		assert_eq!(fetch(1), first);
+		assert_eq!(fetch(2), first);
+
+		// Later steps move from the last aggregate, drawn from the parent hash.
+		AggregatedPrice::<Test>::insert(
+			eth_usd(),
+			PriceAggregate { median: first, mean: first, sources: 1, timestamp: 0 },
+		);
+		System::set_parent_hash([1; 32].into());
+		let second = fetch(3);
+		assert_eq!(second, synthetic::next_price(first, &[1; 32], &address, bounds));
+		let max_move = first * synthetic::MAX_STEP_BPS as u128 / 10_000;
+		assert!(second.abs_diff(first) <= max_move);
+	});
+
+	let steps: Vec<_> = (0u8..=255).map(|seed| synthetic::step_bps(&[seed], &address)).collect();
+	let max_step = synthetic::MAX_STEP_BPS as i64;
+	assert!(steps.iter().all(|step| (-max_step..=max_step).contains(step)));
+	assert!(steps.iter().any(|step| *step < 0) && steps.iter().any(|step| *step > 0));
+	assert_eq!(synthetic::next_price(100, &[0], &address, 100..=100), 100);
+}
+
+#[test]
+fn fee_history_without_base_fees_is_rejected() {
+	let body = br#"{"jsonrpc":"2.0","id":1,"result":{"oldestBlock":"0x10","baseFeePerGas":[]}}"#;
+	let history = json::fee_history(body, 1).unwrap();
+	assert!(history.rewards.is_empty());
+	assert_eq!(
+		gas::gas_fees(&history, false, 0, RoundingPolicy::FLOOR),
+		Err(OracleError::DecodeError)
+	);
+	let body = br#"{"jsonrpc":"2.0","id":1,"result":{"oldestBlock":16,"baseFeePerGas":[]}}"#;
+	assert_eq!(json::fee_history(body, 1), Err(OracleError::DecodeError));
+}
+
+#[test]
+fn failed_pool_lookups_are_not_cached() {
+	let (offchain, _state) = TestOffchainExt::new();
+	let mut ext = sp_io::TestExternalities::default();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+
+	ext.execute_with(|| {
+		let pool = [7; 20];
+		let lookup = |value| pool_cache::cached("test", 1, &pool, || value);
+		assert_eq!(lookup(Err(OracleError::Timeout)), Err(OracleError::Timeout));
+		assert_eq!(lookup(Ok(1u32)), Ok(1));
+		assert_eq!(lookup(Ok(2)), Ok(1));
+		// Lookups are cached per chain.
+		assert_eq!(pool_cache::cached("test", 56, &pool, || Ok(3u32)), Ok(3));
+		assert_eq!(
+			pool_cache::pool_cache_key("tokens", 1, &pool),
+			b"price-oracle/pools/tokens/1/0x0707070707070707070707070707070707070707".to_vec()
+		);
+	});
+}
+
+#[test]
+fn spot_prices_are_checked_against_the_pool_twap() {
+	let (offchain, state) = TestOffchainExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+
+	let observe = UniswapV3Protocol.observe_call_data(1_800);
+	// Answers out of order, with a TWAP at 2499.9 and then at about 2000 USD.
+	for (first, tick) in [(1, 198_080), (3, 200_311)] {
+		let request = format!(
+			concat!(
+				r#"[{{"jsonrpc":"2.0","method":"eth_call","params":[{{"to":"0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640","data":"0x3850c7bd"}},"latest"],"id":{}}},"#,
+				r#"{{"jsonrpc":"2.0","method":"eth_call","params":[{{"to":"0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640","data":"{}"}},"latest"],"id":{}}}]"#,
+			),
+			first,
+			observe,
+			first + 1,
+		);
+		let response = format!(
+			r#"[{{"jsonrpc":"2.0","id":{},"result":"{}"}},{{"jsonrpc":"2.0","id":{},"result":"{}"}}]"#,
+			first + 1,
+			observe_result([0, tick * 1_800]),
+			first,
+			SLOT0_ETH_2500,
+		);
+		state.write().expect_request(PendingRequest {
+			method: "POST".into(),
+			uri: "https://eth.llamarpc.com".into(),
+			headers: vec![("Content-Type".into(), "application/json".into())],
+			body: request.clone().into_bytes(),
+			response: Some(response.into_bytes()),
+			sent: true,
+			..Default::default()
+		});
+	}
+
+	ext.execute_with(|| {
+		let (id, uniswap) = registry::default_exchanges().remove(0);
+		let pair = registry::default_pairs().remove(0);
+		let pool = pair.pool(id).unwrap();
+		let check = TwapCheck { window: 1_800, max_deviation: Permill::from_percent(1) };
+		let url = b"https://eth.llamarpc.com";
+
+		assert_eq!(
+			uniswap.fetch_checked_price(url, &pair, pool, &check, &CallOptions::default(), 1_000),
+			Ok(2_500 * PRICE_UNIT)
+		);
+		assert!(matches!(
+			uniswap.fetch_checked_price(url, &pair, pool, &check, &CallOptions::default(), 1_000),
+			Err(OracleError::SpotDeviatesFromTwap { spot, twap })
+				if spot == 2_500 * PRICE_UNIT && twap / PRICE_UNIT == 2_000
+		));
+	});
+}
+
+#[test]
+fn exchanges_priced_at_the_twap_read_observe_instead_of_slot0() {
+	let (offchain, state) = TestOffchainExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+
+	let observe = UniswapV3Protocol.observe_call_data(1_800);
+	for id in 1..=2 {
+		state.write().expect_request(PendingRequest {
+			method: "POST".into(),
+			uri: "https://eth.llamarpc.com".into(),
+			headers: vec![("Content-Type".into(), "application/json".into())],
+			body: format!(
+				r#"{{"jsonrpc":"2.0","method":"eth_call","params":[{{"to":"0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640","data":"{observe}"}},"latest"],"id":{id}}}"#
+			)
+			.into_bytes(),
+			response: Some(
+				format!(
+					r#"{{"jsonrpc":"2.0","id":{id},"result":"{}"}}"#,
+					observe_result([0, 198_080 * 1_800])
+				)
+				.into_bytes(),
+			),
+			sent: true,
+			..Default::default()
+		});
+	}
+
+	ext.execute_with(|| {
+		let (id, uniswap) = registry::default_exchanges().remove(0);
+		let uniswap = ExchangeConfig { twap_window: Some(1_800), ..uniswap };
+		let pair = registry::default_pairs().remove(0);
+		let pool = pair.pool(id).unwrap();
+		let url = b"https://eth.llamarpc.com";
+		let twap = 2_499 * PRICE_UNIT..2_500 * PRICE_UNIT;
+
+		let price = uniswap.fetch_price(url, &pair, pool, &CallOptions::default(), 1_000);
+		assert!(twap.contains(&price.unwrap()));
+		// There is no spot price to check against the TWAP.
+		let check = TwapCheck { window: 60, max_deviation: Permill::from_percent(1) };
+		let price =
+			uniswap.fetch_checked_price(url, &pair, pool, &check, &CallOptions::default(), 1_000);
+		assert!(twap.contains(&price.unwrap()));
+	});
+}
+
+#[test]
+fn only_uniswap_v3_exchanges_are_priced_at_the_twap() {
+	new_test_ext().execute_with(|| {
+		let uniswap = ExchangeConfig {
+			name: BoundedVec::truncate_from(b"Uniswap V3 TWAP".to_vec()),
+			twap_window: Some(1_800),
+			..registry::default_exchanges().remove(0).1
+		};
+		let v2 = ExchangeConfig { protocol: ProtocolKind::UniswapV2, ..uniswap.clone() };
+		assert_noop!(
+			PriceOracle::add_exchange(RuntimeOrigin::root(), 10, v2),
+			Error::<Test>::InvalidTwapWindow
+		);
+		let no_window = ExchangeConfig { twap_window: Some(0), ..uniswap.clone() };
+		assert_noop!(
+			PriceOracle::add_exchange(RuntimeOrigin::root(), 10, no_window),
+			Error::<Test>::InvalidTwapWindow
+		);
+		assert_ok!(PriceOracle::add_exchange(RuntimeOrigin::root(), 10, uniswap.clone()));
+		assert_eq!(Exchanges::<Test>::get(10), Some(uniswap));
+	});
+}
+
+#[test]
+fn registry_origin_sets_twap_checks() {
+	new_test_ext().execute_with(|| {
+		let check = TwapCheck { window: 1_800, max_deviation: Permill::from_percent(1) };
+		assert_noop!(
+			PriceOracle::set_twap_check(RuntimeOrigin::signed(1), eth_usd(), Some(check)),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			PriceOracle::set_twap_check(RuntimeOrigin::root(), [0; 32], Some(check)),
+			Error::<Test>::PairNotTracked
+		);
+		assert_noop!(
+			PriceOracle::set_twap_check(
+				RuntimeOrigin::root(),
+				eth_usd(),
+				Some(TwapCheck { window: 0, ..check })
+			),
+			Error::<Test>::InvalidTwapWindow
+		);
+
+		assert_ok!(PriceOracle::set_twap_check(RuntimeOrigin::root(), eth_usd(), Some(check)));
+		assert_eq!(TwapChecks::<Test>::get(eth_usd()), Some(check));
+		assert_ok!(PriceOracle::set_twap_check(RuntimeOrigin::root(), eth_usd(), None));
+		assert!(!TwapChecks::<Test>::contains_key(eth_usd()));
+	});
+}
+
+#[test]
+fn stale_and_paused_prices_are_served_by_failure_mode() {
+	new_test_ext().execute_with(|| {
+		let price = |timestamp, stale| ProvidedPrice {
+			price: 2_000 * PRICE_UNIT,
+			timestamp,
+			stale,
+			low_quality: false,
+		};
+		assert_eq!(
+			<PriceOracle as PriceProvider>::price(eth_usd()),
+			Err(Error::<Test>::NoPrice.into())
+		);
+		set_now(1_000_000);
+		assert_ok!(PriceOracle::submit_price_unsigned(
+			RuntimeOrigin::none(),
+			eth_usd(),
+			0,
+			2_000,
+			0,
+			1_000_000,
+			None,
+			None
+		));
+		PriceOracle::aggregate_prices(eth_usd());
+		assert_eq!(<PriceOracle as PriceProvider>::price(eth_usd()), Ok(price(1_000_000, false)));
+
+		// Pairs fail closed by default.
+		assert_ok!(PriceOracle::set_pair_paused(RuntimeOrigin::root(), eth_usd(), true));
+		System::assert_last_event(Event::PairPaused { pair_id: eth_usd() }.into());
+		assert_eq!(
+			<PriceOracle as PriceProvider>::price(eth_usd()),
+			Err(Error::<Test>::PriceUnavailable.into())
+		);
+		assert_ok!(PriceOracle::set_pair_paused(RuntimeOrigin::root(), eth_usd(), false));
+		set_now(1_000_001 + MAX_PRICE_AGE);
+		assert_eq!(
+			<PriceOracle as PriceProvider>::price(eth_usd()),
+			Err(Error::<Test>::PriceUnavailable.into())
+		);
+
+		assert_noop!(
+			PriceOracle::set_failure_mode(
+				RuntimeOrigin::signed(1),
+				eth_usd(),
+				FailureMode::FailOpen
+			),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			PriceOracle::set_failure_mode(RuntimeOrigin::root(), [0; 32], FailureMode::FailOpen),
+			Error::<Test>::PairNotTracked
+		);
+		assert_ok!(PriceOracle::set_failure_mode(
+			RuntimeOrigin::root(),
+			eth_usd(),
+			FailureMode::FailOpen
+		));
+		assert_eq!(<PriceOracle as PriceProvider>::price(eth_usd()), Ok(price(1_000_000, true)));
+
+		assert_ok!(PriceOracle::remove_pair(RuntimeOrigin::root(), eth_usd()));
+		assert_eq!(FailureModes::<Test>::get(eth_usd()), FailureMode::FailClosed);
+	});
+}
+
+#[test]
+fn prices_are_provided_by_symbol_and_convert_amounts() {
+	new_test_ext().execute_with(|| {
+		set_now(1_000_000);
+		assert_ok!(PriceOracle::submit_price_unsigned(
+			RuntimeOrigin::none(),
+			eth_usd(),
+			0,
+			2_000,
+			0,
+			1_000_000,
+			None,
+			None
+		));
+		PriceOracle::aggregate_prices(eth_usd());
+		assert_eq!(
+			<PriceOracle as PriceProvider>::price_of(b"ETH", b"USD"),
+			<PriceOracle as PriceProvider>::price(eth_usd())
+		);
+		assert_eq!(
+			<PriceOracle as PriceProvider>::price_of(b"USD", b"ETH"),
+			Err(Error::<Test>::NoPrice.into())
+		);
+
+		// 1.5 ETH of 18 decimals in USDC of 6.
+		let amount = 1_500_000_000_000_000_000;
+		assert_eq!(
+			<PriceOracle as PriceProvider>::convert(eth_usd(), amount, 18, 6),
+			Ok(3_000_000_000)
+		);
+		assert_eq!(<PriceOracle as PriceProvider>::convert(eth_usd(), 1, 18, 6), Ok(0));
+		assert_eq!(
+			<PriceOracle as PriceProvider>::convert(eth_usd(), u128::MAX, 6, 18),
+			Err(Error::<Test>::ConversionOverflow.into())
+		);
+		// Scales beyond 512 bits fail rather than panic.
+		for (base_decimals, quote_decimals) in [(0, 200), (200, 0)] {
+			assert_eq!(
+				<PriceOracle as PriceProvider>::convert(
+					eth_usd(),
+					amount,
+					base_decimals,
+					quote_decimals
+				),
+				Err(Error::<Test>::ConversionOverflow.into())
+			);
+		}
+	});
+}
+
+#[test]
+fn inverse_and_cross_prices_are_derived_from_tracked_pairs() {
+	new_test_ext().execute_with(|| {
+		set_now(MAX_PRICE_AGE + 10);
+		let aggregate =
+			|median, timestamp| PriceAggregate { median, mean: median, sources: 1, timestamp };
+		AggregatedPrice::<Test>::insert(eth_usd(), aggregate(2_000 * PRICE_UNIT, 20));
+		AggregatedPrice::<Test>::insert(sol_usd(), aggregate(160 * PRICE_UNIT, 10));
+
+		assert_eq!(PriceOracle::get_derived_price(b"ETH", b"USD"), Some((2_000 * PRICE_UNIT, 20)));
+		assert_eq!(PriceOracle::get_derived_price(b"USD", b"ETH"), Some((PRICE_UNIT / 2_000, 20)));
+		// Crosses are as old as their oldest leg.
+		assert_eq!(
+			PriceOracle::get_derived_price(b"ETH", b"SOL"),
+			Some((12_500_000_000_000_000_000, 10))
+		);
+		assert_eq!(
+			PriceOracle::get_derived_price(b"SOL", b"ETH"),
+			Some((80_000_000_000_000_000, 10))
+		);
+		assert_eq!(PriceOracle::get_derived_price(b"BTC", b"USD"), None);
+
+		// A stale leg leaves the cross without a price.
+		set_now(MAX_PRICE_AGE + 11);
+		assert_eq!(PriceOracle::get_derived_price(b"ETH", b"SOL"), None);
+		assert_eq!(PriceOracle::get_derived_price(b"USD", b"ETH"), Some((PRICE_UNIT / 2_000, 20)));
+
+		// Derived prices aren't reads of the pairs they are derived from.
+		assert_eq!(PairReads::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn guarded_prices_hold_until_the_end_of_the_block() {
+	new_test_ext().execute_with(|| {
+		let submit = |price, timestamp| {
+			assert_ok!(PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				0,
+				price,
+				0,
+				timestamp,
+				None,
+				None
+			));
+		};
+		let price = || <PriceOracle as PriceProvider>::price(eth_usd()).unwrap().price;
+		set_now(1_000_000);
+		submit(2_000, 1_000_000);
+		PriceOracle::on_finalize(1);
+		let guarded = <PriceOracle as PriceGuard>::guarded_price(eth_usd()).unwrap();
+		assert_eq!(guarded.price, 2_000 * PRICE_UNIT);
+
+		// A price submitted after the guard was taken is aggregated a block late.
+		submit(2_100, 1_000_001);
+		PriceOracle::on_finalize(1);
+		System::assert_last_event(Event::AggregationDeferred { pair_id: eth_usd() }.into());
+		assert_eq!(price(), 2_000 * PRICE_UNIT);
+		assert_eq!(PriceGuards::<Test>::iter().count(), 0);
+		PriceOracle::on_finalize(2);
+		assert_eq!(price(), 2_100 * PRICE_UNIT);
+		assert_eq!(PendingAggregation::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn pairs_guarded_in_every_block_are_still_aggregated() {
+	new_test_ext().execute_with(|| {
+		let price = || <PriceOracle as PriceProvider>::price(eth_usd()).unwrap().price;
+		let guard = || {
+			assert_ok!(<PriceOracle as PriceGuard>::guarded_price(eth_usd()));
+		};
+		set_now(1_000_000);
+		assert_ok!(PriceOracle::submit_price_unsigned(
+			RuntimeOrigin::none(),
+			eth_usd(),
+			0,
+			2_000,
+			0,
+			1_000_000,
+			None,
+			None
+		));
+		PriceOracle::on_finalize(1);
+		guard();
+		assert_ok!(PriceOracle::submit_price_unsigned(
+			RuntimeOrigin::none(),
+			eth_usd(),
+			0,
+			2_100,
+			0,
+			1_000_001,
+			None,
+			None
+		));
+
+		// Deferred for `MaxAggregationDeferrals` blocks, then aggregated despite the guard.
+		for block in 1..=2 {
+			PriceOracle::on_finalize(block);
+			System::assert_last_event(Event::AggregationDeferred { pair_id: eth_usd() }.into());
+			assert_eq!(price(), 2_000 * PRICE_UNIT);
+			guard();
+		}
+		PriceOracle::on_finalize(3);
+		assert_eq!(price(), 2_100 * PRICE_UNIT);
+		assert!(!PendingAggregation::<Test>::contains_key(eth_usd()));
+		assert!(!AggregationDeferrals::<Test>::contains_key(eth_usd()));
+	});
+}
+
+#[test]
+fn interleaved_submissions_converge_regardless_of_order() {
+	// Two observations of exchange 0, one of them tied in time with a third, and one of
+	// exchange 1.
+	let submissions = [(0, 2_500, 10), (0, 2_510, 11), (0, 2_490, 11), (1, 2_530, 10)];
+	let mut outcomes = Vec::new();
+	for order in permutations(submissions.len()) {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			for &(exchange_id, price, timestamp) in order.iter().map(|i| &submissions[*i]) {
+				let _ = PriceOracle::submit_price_unsigned(
+					RuntimeOrigin::none(),
+					eth_usd(),
+					exchange_id,
+					price,
+					0,
+					timestamp,
+					None,
+					None,
+				);
+			}
+			PriceOracle::on_finalize(1);
+			let aggregate = AggregatedPrice::<Test>::get(eth_usd()).unwrap();
+
+			// Aggregating again in the same block changes nothing.
+			assert_eq!(PriceOracle::aggregate_prices(eth_usd()), Some(aggregate));
+			assert_eq!(PriceHistory::<Test>::get(eth_usd()).len(), 1);
+
+			outcomes.push((
+				aggregate,
+				PriceData::<Test>::get(eth_usd(), 0),
+				PriceData::<Test>::get(eth_usd(), 1),
+			));
+		});
+	}
+
+	assert_eq!(outcomes.len(), 24);
+	assert!(outcomes.iter().all(|outcome| *outcome == outcomes[0]));
+	let (aggregate, first, second) = outcomes[0];
+	assert_eq!(first, Some((2_510 * PRICE_UNIT, 11)));
+	assert_eq!(second, Some((2_530 * PRICE_UNIT, 10)));
+	assert_eq!(aggregate.median, 2_520 * PRICE_UNIT);
+}
+
+/// Every ordering of `0..n`.
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+	if n == 0 {
+		return vec![Vec::new()]
+	}
+	permutations(n - 1)
+		.into_iter()
+		.flat_map(|order| {
+			(0..n).map(move |at| {
+				let mut order = order.clone();
+				order.insert(at, n - 1);
+				order
+			})
+		})
+		.collect()
+}
+
+#[test]
+fn large_moves_open_the_fast_path() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let fast_path = FastPath { threshold_bps: 300, blocks: 5 };
+		assert_noop!(
+			PriceOracle::set_fast_path(RuntimeOrigin::signed(1), eth_usd(), Some(fast_path)),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			PriceOracle::set_fast_path(
+				RuntimeOrigin::root(),
+				eth_usd(),
+				Some(FastPath { blocks: 0, ..fast_path })
+			),
+			Error::<Test>::InvalidFastPath
+		);
+		assert_ok!(PriceOracle::set_fast_path(RuntimeOrigin::root(), eth_usd(), Some(fast_path)));
+
+		let submit = |price, timestamp| {
+			assert_ok!(PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				0,
+				price,
+				0,
+				timestamp,
+				None,
+				None
+			));
+			PriceOracle::on_finalize(System::block_number());
+		};
+
+		// The first aggregate and a 2% move keep the pair on its interval.
+		submit(2_000, 10);
+		submit(2_040, 11);
+		assert!(!PriceOracle::on_fast_path(eth_usd(), 1));
+
+		// A 5% move opens the fast path for the next five blocks.
+		submit(2_142, 12);
+		System::assert_last_event(
+			Event::FastPathOpened { pair_id: eth_usd(), move_bps: 500, until: 6 }.into(),
+		);
+		assert!(PriceOracle::on_fast_path(eth_usd(), 6));
+		assert!(!PriceOracle::on_fast_path(eth_usd(), 7));
+
+		// A calm aggregation after it expired drops it.
+		System::set_block_number(7);
+		submit(2_143, 13);
+		assert_eq!(FastPathUntil::<Test>::get(eth_usd()), None);
+
+		submit(2_400, 14);
+		assert_ok!(PriceOracle::set_fast_path(RuntimeOrigin::root(), eth_usd(), None));
+		assert!(!FastPaths::<Test>::contains_key(eth_usd()));
+		assert_eq!(FastPathUntil::<Test>::get(eth_usd()), None);
+	});
+}
+
+#[test]
+fn offchain_worker_fetches_pairs_on_the_fast_path_every_block() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "https://eth.llamarpc.com".into(),
+		headers: vec![("Content-Type".into(), "application/json".into())],
+		body: br#"{"jsonrpc":"2.0","method":"eth_call","params":[{"to":"0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640","data":"0x3850c7bd"},"latest"],"id":1}"#.to_vec(),
+		response: Some(rpc_response(SLOT0_ETH_2500)),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		// Only ETH/USD is fetched off its phase.
+		TrackedPairs::<Test>::remove(sol_usd());
+		let block = fetch_block(1) + 1;
+		FastPathUntil::<Test>::insert(eth_usd(), block);
+		PriceOracle::offchain_worker(block);
+		assert_eq!(pool_state.read().transactions.len(), 1);
+
+		// Once the fast path is closed the pair waits for its phase again.
+		PriceOracle::offchain_worker(block + 1);
+		assert_eq!(pool_state.read().transactions.len(), 1);
+	});
+}
+
+#[test]
+fn frozen_prices_are_left_out_until_they_change() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let submit = |exchange_id, price, timestamp| {
+			assert_ok!(PriceOracle::submit_price_unsigned(
+				RuntimeOrigin::none(),
+				eth_usd(),
+				exchange_id,
+				price,
+				0,
+				timestamp,
+				None,
+				None,
+			));
+			PriceOracle::on_finalize(1);
+		};
+
+		submit(1, 2_100, 1);
+		// The third submission of the same price in a row freezes it.
+		for timestamp in 1..=3 {
+			submit(0, 2_000, timestamp);
+		}
+		System::assert_has_event(Event::PriceFrozen { pair_id: eth_usd(), exchange_id: 0 }.into());
+		assert!(PriceOracle::is_frozen(eth_usd(), 0));
+		let aggregate = PriceOracle::get_aggregated_price(eth_usd()).unwrap();
+		assert_eq!((aggregate.median, aggregate.sources), (2_100 * PRICE_UNIT, 1));
+
+		submit(0, 2_001, 4);
+		System::assert_has_event(
+			Event::PriceUnfrozen { pair_id: eth_usd(), exchange_id: 0 }.into(),
+		);
+		assert!(!RepeatedPrices::<Test>::contains_key(eth_usd(), 0));
+		assert_eq!(PriceOracle::get_aggregated_price(eth_usd()).unwrap().sources, 2);
+	});
+}
+
+#[test]
+fn pairs_are_fetched_in_their_phase_of_the_interval() {
+	let phases: Vec<_> = [b"ETH", b"BTC", b"SOL", b"AVA"]
+		.map(|base| PriceOracle::fetch_phase(pair_id(base, b"USD")))
+		.into();
+	assert!(phases.iter().all(|phase| *phase < UPDATE_INTERVAL));
+	// Different pairs land in different blocks of the interval.
+	assert!(phases.iter().any(|phase| *phase != phases[0]));
+	let [a, b, c, d, ..] = eth_usd();
+	assert_eq!(
+		PriceOracle::fetch_phase(eth_usd()),
+		u32::from_le_bytes([a, b, c, d]) % UPDATE_INTERVAL
+	);
+}
+
+#[test]
+fn exchanges_are_sampled_up_to_the_jitter_after_their_pair() {
+	new_test_ext().execute_with(|| {
+		let phase = PriceOracle::fetch_phase(eth_usd());
+		let sample = |exchange_id, seed| PriceOracle::sample_phase(eth_usd(), exchange_id, &seed);
+		// Without jitter every exchange is sampled in the phase of its pair.
+		assert!((0..5).all(|exchange_id| sample(exchange_id, H256::repeat_byte(1)) == phase));
+
+		SamplingJitter::set(1);
+		let phases: Vec<_> = (0..16)
+			.flat_map(|byte| {
+				(0..5).map(move |exchange_id| sample(exchange_id, H256::repeat_byte(byte)))
+			})
+			.collect();
+		assert!(phases
+			.iter()
+			.all(|sample| [phase, (phase + 1) % UPDATE_INTERVAL].contains(sample)));
+		// The exchanges of a pair are spread by the seed.
+		assert!(phases.contains(&phase) && phases.contains(&((phase + 1) % UPDATE_INTERVAL)));
+
+		// Every block of an interval is seeded by the hash of the block before it.
+		let seed = H256::repeat_byte(7);
+		frame_system::BlockHash::<Test>::insert(u64::from(UPDATE_INTERVAL) - 1, seed);
+		let interval = u64::from(UPDATE_INTERVAL)..u64::from(2 * UPDATE_INTERVAL);
+		assert!(interval.clone().all(|block| PriceOracle::sampling_seed(block) == seed));
+		assert_ne!(PriceOracle::sampling_seed(interval.end), seed);
+	});
+}
+
+#[test]
+fn proxy_addresses_are_parsed() {
+	assert_eq!(
+		Proxy::parse(b"proxy.internal:3128", None),
+		Ok(Proxy { host: "proxy.internal".into(), port: 3128, auth: None })
+	);
+	assert_eq!(Proxy::parse(b"[::1]:8080", None).map(|proxy| proxy.host), Ok("[::1]".into()));
+	for invalid in
+		[&b"proxy.internal"[..], b":3128", b"proxy:99999", b"http://proxy:3128", b"\xff:1"]
+	{
+		assert_eq!(Proxy::parse(invalid, None), Err(OracleError::InvalidConfig));
+	}
+
+	let proxy = Proxy::parse(b"proxy:3128", Some(b"Aladdin:open sesame".to_vec())).unwrap();
+	assert_eq!(proxy.url("https://eth.llamarpc.com"), "http://proxy:3128/https://eth.llamarpc.com");
+	assert_eq!(proxy.authorization().as_deref(), Some("Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ=="));
+	let proxy = Proxy { auth: Some(b"ab".to_vec()), ..proxy };
+	assert_eq!(proxy.authorization().as_deref(), Some("Basic YWI="));
+}
+
+#[test]
+fn offchain_worker_routes_requests_through_the_proxy() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(OffchainDbExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	state.write().expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: "http://proxy.internal:3128/https://eth.llamarpc.com".into(),
+		headers: vec![
+			("Content-Type".into(), "application/json".into()),
+			("Proxy-Authorization".into(), "Basic b3JhY2xlOnNlY3JldA==".into()),
+		],
+		body: br#"{"jsonrpc":"2.0","method":"eth_call","params":[{"to":"0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640","data":"0x3850c7bd"},"latest"],"id":1}"#.to_vec(),
+		response: Some(rpc_response(SLOT0_ETH_2500)),
+		sent: true,
+		..Default::default()
+	});
+
+	ext.execute_with(|| {
+		let set = |key, value| {
+			sp_io::offchain::local_storage_set(
+				sp_core::offchain::StorageKind::PERSISTENT,
+				key,
+				value,
+			)
+		};
+		set(proxy::PROXY_KEY, b"proxy.internal:3128");
+		set(proxy::PROXY_AUTH_KEY, b"oracle:secret");
+		PriceOracle::offchain_worker(fetch_block(1));
+		assert_eq!(pool_state.read().transactions.len(), 1);
+	});
+}
+
+#[test]
+fn slots_of_pools_unpack_to_the_return_data_of_their_price_call() {
+	// sqrtPriceX96 with ETH at 2500 USD, tick -1, observation index 2 and unlocked.
+	let slot0 = (U256::from(0x4e20) << 96) |
+		(U256::from(0xff_ffff) << 160) |
+		(U256::from(2) << 184) |
+		(U256::one() << 240);
+	let data = UniswapV3Protocol.slot_return_data(slot0);
+	assert_eq!(data.len(), 7 * 32);
+	assert_eq!(fixed::word(&data, 0).unwrap(), U256::from(0x4e20) << 96);
+	assert_eq!(fixed::word(&data, 1).unwrap(), U256::MAX);
+	assert_eq!(fixed::word(&data, 2).unwrap(), U256::from(2));
+	assert_eq!(fixed::word(&data, 6).unwrap(), U256::one());
+
+	let reserves = array_bytes::hex2bytes(RESERVES_ETH_2000).unwrap();
+	let slot = fixed::word(&reserves, 0).unwrap() | (fixed::word(&reserves, 1).unwrap() << 112);
+	assert_eq!(UniswapV2Protocol.price_slot(), Some(U256::from(8)));
+	assert_eq!(&UniswapV2Protocol.slot_return_data(slot)[..64], &reserves[..]);
+	assert_eq!(ProtocolKind::Chainlink.price_slot(), None);
+}
+
+#[test]
+fn verified_prices_are_proven_against_the_light_client() {
+	new_test_ext().execute_with(|| {
+		set_now(1_000);
+		let pool = price_oracle_eip55::parse("0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640").unwrap();
+		let slot0 = (U256::from(0x4e20) << 96) | (U256::one() << 240);
+		let (state_root, account_proof, storage_proof) =
+			proofs::build_storage_proofs(&pool, U256::zero(), slot0, 3, 2);
+		StateRoots::set(vec![(1, 100, state_root)]);
+		let submit = |exchange_id, block, storage_proof: &[proofs::ProofNode]| {
+			PriceOracle::submit_verified_price(
+				RuntimeOrigin::signed(1),
+				eth_usd(),
+				exchange_id,
+				block,
+				BoundedVec::truncate_from(account_proof.clone()),
+				BoundedVec::truncate_from(storage_proof.to_vec()),
+			)
+		};
+
+		assert_noop!(submit(6, 100, &storage_proof), Error::<Test>::UnverifiableSource);
+		assert_noop!(submit(0, 101, &storage_proof), Error::<Test>::UnknownHeader);
+		let mut tampered = storage_proof.clone();
+		let mut node = tampered[1].to_vec();
+		node[40] ^= 1;
+		tampered[1] = BoundedVec::truncate_from(node);
+		assert_noop!(submit(0, 100, &tampered), Error::<Test>::InvalidProof);
+
+		assert_ok!(submit(0, 100, &storage_proof));
+		let price = 2500 * PRICE_UNIT;
+		assert_eq!(
+			VerifiedPrices::<Test>::get(eth_usd(), 0),
+			Some(VerifiedPrice { price, block: 100, timestamp: 1_000 })
+		);
+		System::assert_last_event(
+			Event::VerifiedPriceSubmitted { pair_id: eth_usd(), exchange_id: 0, price, block: 100 }
+				.into(),
+		);
+		// Verified prices are kept apart from the reported ones.
+		assert_eq!(PriceData::<Test>::get(eth_usd(), 0), None);
+		assert_noop!(submit(0, 100, &storage_proof), Error::<Test>::OutdatedSubmission);
+	});
+}
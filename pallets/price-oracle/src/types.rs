@@ -0,0 +1,977 @@
+//! Types shared by the oracle storage, the offchain worker and the exchange adapters.
+
+use crate::{
+	exchanges::{ExchangeId, PoolConfig},
+	fixed::Price,
+};
+use alloc::{format, string::String};
+use codec::{Compact, Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use frame_support::{
+	traits::{ConstU32, Get},
+	BoundedVec, CloneNoBound, DefaultNoBound, EqNoBound, PartialEqNoBound, RuntimeDebugNoBound,
+};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{AtLeast32BitUnsigned, Saturating},
+	Permill, RuntimeDebug,
+};
+
+/// Maximum length of an exchange name, in bytes.
+pub const MAX_NAME_LEN: u32 = 32;
+
+/// Maximum length of an RPC endpoint URL, in bytes.
+pub const MAX_URL_LEN: u32 = 256;
+
+/// Maximum length of a token ticker, in bytes.
+pub const MAX_SYMBOL_LEN: u32 = 16;
+
+/// Maximum number of RPC endpoints of a chain.
+pub const MAX_ENDPOINTS_PER_CHAIN: u32 = 4;
+
+/// Maximum number of pools a pair can be priced from.
+pub const MAX_POOLS_PER_PAIR: u32 = 16;
+
+/// Maximum number of decimals of a pool token. ERC-20 tokens use up to 18; more only comes from a
+/// misconfigured pool, and would overflow the scaling of its prices.
+pub const MAX_TOKEN_DECIMALS: u8 = 36;
+
+/// Maximum number of contracts whose state an `eth_call` overrides.
+pub const MAX_STATE_OVERRIDES: u32 = 4;
+
+/// Maximum number of storage slots overridden per contract.
+pub const MAX_OVERRIDE_SLOTS: u32 = 8;
+
+/// Maximum length of the code an `eth_call` replaces a contract's with, the EIP-170 limit.
+pub const MAX_OVERRIDE_CODE_LEN: u32 = 24_576;
+
+/// Human readable name.
+pub type Name = BoundedVec<u8, ConstU32<MAX_NAME_LEN>>;
+
+/// Identifier of a chain, e.g. the EIP-155 chain id of an EVM chain.
+pub type ChainId = u64;
+
+/// URL of a JSON-RPC endpoint.
+pub type RpcUrl = BoundedVec<u8, ConstU32<MAX_URL_LEN>>;
+
+/// The RPC endpoints of a chain, in order of preference.
+pub type RpcUrls = BoundedVec<RpcUrl, ConstU32<MAX_ENDPOINTS_PER_CHAIN>>;
+
+/// Raw 20 byte address of a contract on an EVM chain.
+pub use price_oracle_eip55::Address as EvmAddress;
+
+/// Raw 32 byte public key of an account on Solana, base58 encoded on the wire.
+pub type SolanaAddress = [u8; 32];
+
+/// Identifier of a pair, derived from its symbols by
+/// [`Config::PairHasher`](crate::Config::PairHasher).
+pub type PairId = [u8; 32];
+
+/// Ticker of a token, e.g. `ETH`.
+pub type Symbol = BoundedVec<u8, ConstU32<MAX_SYMBOL_LEN>>;
+
+/// Storage key of the pair of `base` priced in `quote` under the [`Blake2PairHasher`] scheme:
+/// `blake2_256` of its `BASE/QUOTE` symbol.
+pub fn pair_id(base: &[u8], quote: &[u8]) -> PairId {
+	sp_io::hashing::blake2_256(&[base, b"/", quote].concat())
+}
+
+/// Scheme deriving the [`PairId`] of a pair from its symbols.
+///
+/// Pair ids are storage keys, so a chain can't change its scheme without migrating the storage
+/// of the pallet. Chains whose pairs are keyed by an external system, e.g. by Chainlink or Pyth
+/// feed ids, can derive those instead.
+pub trait PairHasher {
+	/// Id of the pair of `base` priced in `quote`.
+	fn pair_id(base: &[u8], quote: &[u8]) -> PairId;
+}
+
+/// `blake2_256` of the `BASE/QUOTE` symbol of a pair, see [`pair_id`].
+pub struct Blake2PairHasher;
+
+impl PairHasher for Blake2PairHasher {
+	fn pair_id(base: &[u8], quote: &[u8]) -> PairId {
+		pair_id(base, quote)
+	}
+}
+
+/// A pair tracked by the oracle and the pools it is priced from.
+#[derive(
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+	serde::Serialize,
+	serde::Deserialize,
+)]
+pub struct PairInfo {
+	/// Ticker of the priced token.
+	pub base: Symbol,
+	/// Ticker of the token the price is quoted in.
+	pub quote: Symbol,
+	/// Number of decimals prices of the pair are submitted with.
+	pub decimals: u8,
+	/// Lowest plausible price. Observations below it are discarded as mis-parsed or manipulated.
+	pub min_price: Price,
+	/// Highest plausible price.
+	pub max_price: Price,
+	/// Narrower bounds around the recent price, within `min_price` and `max_price`.
+	pub dynamic_bounds: Option<DynamicBounds>,
+	/// The pools pricing the pair on each exchange. An exchange with several pools, like the
+	/// fee tiers of a Uniswap V3 pair, is priced from the one with the most liquidity.
+	pub pools: BoundedVec<(ExchangeId, PoolConfig), ConstU32<MAX_POOLS_PER_PAIR>>,
+}
+
+impl PairInfo {
+	/// Storage key of the pair under the [`PairHasher`] scheme `H`.
+	pub fn id_with<H: PairHasher>(&self) -> PairId {
+		H::pair_id(&self.base, &self.quote)
+	}
+
+	/// Storage key of the pair under the [`Blake2PairHasher`] scheme.
+	pub fn id(&self) -> PairId {
+		self.id_with::<Blake2PairHasher>()
+	}
+
+	/// `BASE/QUOTE`, for logging.
+	pub fn symbol(&self) -> String {
+		format!(
+			"{}/{}",
+			core::str::from_utf8(&self.base).unwrap_or("?"),
+			core::str::from_utf8(&self.quote).unwrap_or("?")
+		)
+	}
+
+	/// Whether `price` lies within the pair's bounds.
+	pub fn in_bounds(&self, price: Price) -> bool {
+		(self.min_price..=self.max_price).contains(&price)
+	}
+
+	/// The first pool of `exchange_id`, if the pair is priced there.
+	pub fn pool(&self, exchange_id: ExchangeId) -> Option<&PoolConfig> {
+		self.pools_of(exchange_id).next()
+	}
+
+	/// The pools of `exchange_id`, e.g. the fee tiers of a Uniswap V3 pair, in order.
+	pub fn pools_of(&self, exchange_id: ExchangeId) -> impl Iterator<Item = &PoolConfig> {
+		self.pools
+			.iter()
+			.filter(move |(id, _)| *id == exchange_id)
+			.map(|(_, pool)| pool)
+	}
+}
+
+/// Bounds of a pair relative to its time-weighted average price.
+#[derive(
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+	serde::Serialize,
+	serde::Deserialize,
+)]
+pub struct DynamicBounds {
+	/// Largest accepted deviation from the TWAP, in either direction.
+	pub deviation: Permill,
+	/// Number of blocks the TWAP is taken over.
+	pub window: u32,
+}
+
+/// Cross-check of the spot price of a pair's Uniswap V3 pools against their own TWAP, see
+/// [`crate::TwapChecks`].
+#[derive(
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct TwapCheck {
+	/// Number of seconds the pool's TWAP is taken over.
+	pub window: u32,
+	/// Largest accepted deviation of the spot price from the TWAP, in either direction.
+	pub max_deviation: Permill,
+}
+
+/// Check of the spread between the chains a pair is priced on, see [`crate::CrossChainChecks`].
+///
+/// Once the medians of two chains are at least `threshold_bps` basis points apart, the
+/// aggregation emits [`Event::CrossChainDiscrepancy`](crate::Event::CrossChainDiscrepancy),
+/// unless their newest observations are more than `max_skew` milliseconds apart and the spread
+/// may just be the market moving between the samples.
+#[derive(
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct CrossChainCheck {
+	/// Spread between the lowest and the highest chain median that counts as a discrepancy, in
+	/// basis points.
+	pub threshold_bps: u32,
+	/// Largest time between the newest observations of the two chains, in milliseconds, or
+	/// `None` to report discrepancies however misaligned the samples are.
+	pub max_skew: Option<u64>,
+}
+
+/// Freshness and quality of the aggregated price a pair promises its consumers, see
+/// [`crate::PairSlas`].
+#[derive(
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct FreshnessSla {
+	/// Largest age of the aggregated price, in milliseconds.
+	pub max_staleness: u64,
+	/// Fewest exchanges the aggregated price is combined from.
+	pub min_sources: u32,
+	/// Widest spread between the cheapest and the dearest exchange of the pair, in basis points,
+	/// see [`crate::PairVenues`].
+	pub max_spread_bps: u32,
+}
+
+impl FreshnessSla {
+	/// Whether the SLA can be met at all: a price of age zero from no exchange can't.
+	pub fn is_valid(&self) -> bool {
+		self.max_staleness > 0 && self.min_sources > 0
+	}
+}
+
+/// How a pair falls short of its [`FreshnessSla`].
+#[derive(
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub enum SlaBreach {
+	/// The pair has no aggregated price.
+	NoPrice,
+	/// The aggregated price is older than the SLA allows.
+	Stale,
+	/// The aggregated price is combined from fewer exchanges than the SLA requires.
+	TooFewSources,
+	/// The exchanges of the pair are further apart than the SLA allows.
+	SpreadTooWide,
+}
+
+/// State of a contract replaced for the duration of an `eth_call`.
+#[derive(
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct StateOverride {
+	/// The contract.
+	pub address: EvmAddress,
+	/// Code to run instead of the deployed one, if any.
+	pub code: Option<BoundedVec<u8, ConstU32<MAX_OVERRIDE_CODE_LEN>>>,
+	/// Storage slots to read as the given values, other slots keeping theirs.
+	pub state_diff: BoundedVec<([u8; 32], [u8; 32]), ConstU32<MAX_OVERRIDE_SLOTS>>,
+}
+
+/// Block an `eth_call` is executed against, from the head of the chain to the blocks it can no
+/// longer reorg. Ordered from the least to the most confirmed.
+#[derive(
+	Clone,
+	Copy,
+	Default,
+	PartialEq,
+	Eq,
+	PartialOrd,
+	Ord,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub enum Confirmation {
+	/// The head of the chain, which can still be reorged away.
+	#[default]
+	Latest,
+	/// The newest block unlikely to be reorged. On rollups, the newest block posted to L1.
+	Safe,
+	/// The newest block that can't be reorged. On rollups, the newest block posted in a
+	/// finalized L1 block.
+	Finalized,
+}
+
+/// Extra fields of the `eth_call`s of a protocol, see [`crate::CallOptions`].
+///
+/// Some providers cap the gas of calls without an explicit limit below what heavy views need,
+/// and state overrides read pools behind proxies or with uninitialized slots.
+#[derive(
+	Clone,
+	Default,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct CallOptions {
+	/// Gas the call may use, or the provider's default.
+	pub gas: Option<u64>,
+	/// State overrides sent with the call.
+	pub state_overrides: BoundedVec<StateOverride, ConstU32<MAX_STATE_OVERRIDES>>,
+	/// Block the call is executed against, or the [`crate::ChainConfirmations`] of the chain if
+	/// that is more confirmed.
+	pub confirmation: Confirmation,
+}
+
+/// Fast path of a critical pair, see [`crate::FastPaths`].
+///
+/// Once an aggregation moves the median of the pair at least `threshold_bps` basis points away
+/// from the previous one, the offchain worker fetches the pair in every block of the next
+/// `blocks` blocks instead of once per update interval.
+#[derive(
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct FastPath {
+	/// Move of the median between two aggregations that opens the fast path, in basis points.
+	pub threshold_bps: u32,
+	/// Number of blocks the fast path stays open after the last such move.
+	pub blocks: u32,
+}
+
+/// The prices of a pair across exchanges, combined.
+#[derive(
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct PriceAggregate {
+	/// Median of the exchange prices.
+	pub median: Price,
+	/// Mean of the exchange prices.
+	pub mean: Price,
+	/// Number of exchange prices combined.
+	pub sources: u32,
+	/// Observation time of the newest exchange price, in unix milliseconds.
+	pub timestamp: u64,
+}
+
+/// Latest price of a pair on an exchange.
+#[derive(
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct VenueQuote {
+	pub exchange_id: ExchangeId,
+	pub price: Price,
+	/// Observation time of the price, in unix milliseconds.
+	pub timestamp: u64,
+}
+
+/// Latest price of a pair on an exchange with the depth of the pool it was read from, see
+/// [`crate::Pallet::liquid_prices`].
+#[derive(
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct LiquidPrice {
+	pub price: Price,
+	/// Notional in whole USD of the pool, if the offchain worker read it.
+	pub liquidity: Option<u128>,
+	/// Observation time of the price, in unix milliseconds.
+	pub timestamp: u64,
+}
+
+/// A spread between two exchanges of a pair wide enough to trade on, see
+/// [`crate::ArbitrageOpportunities`].
+#[derive(
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct ArbitrageOpportunity {
+	/// Exchange with the lowest price, where the base token is bought.
+	pub buy_exchange: ExchangeId,
+	/// Exchange with the highest price, where the base token is sold.
+	pub sell_exchange: ExchangeId,
+	/// Spread of the sell price over the buy price, in basis points of the buy price.
+	pub spread_bps: u32,
+	/// Observation time of the older of the two prices, in unix milliseconds.
+	pub timestamp: u64,
+}
+
+/// Progress of a newly added pair towards having its price served, see [`crate::Bootstrapping`].
+#[derive(
+	Clone, Copy, Default, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen,
+)]
+pub struct BootstrapProgress {
+	/// Consecutive update intervals of consistent data from several exchanges.
+	pub intervals: u32,
+	/// Median and timestamp of the aggregated price the last interval was judged on.
+	pub last: Option<(Price, u64)>,
+}
+
+/// The prices of a pair submitted in one block, reported in a single event in place of one per
+/// submission, see [`crate::Config::SummarizeSourceEvents`].
+#[derive(
+	Clone,
+	Copy,
+	Default,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct SubmissionSummary {
+	/// Number of prices stored.
+	pub submitted: u32,
+	/// Number of prices discarded for being outside of the pair's bounds.
+	pub rejected: u32,
+	/// The lowest stored price, with its exchange.
+	pub lowest: Option<(ExchangeId, Price)>,
+	/// The highest stored price, with its exchange.
+	pub highest: Option<(ExchangeId, Price)>,
+}
+
+impl SubmissionSummary {
+	/// Count a price of `exchange_id`, `None` if it was discarded.
+	pub fn record(&mut self, exchange_id: ExchangeId, price: Option<Price>) {
+		let Some(price) = price else {
+			self.rejected.saturating_inc();
+			return
+		};
+		self.submitted.saturating_inc();
+		if self.lowest.is_none_or(|(_, lowest)| price < lowest) {
+			self.lowest = Some((exchange_id, price));
+		}
+		if self.highest.is_none_or(|(_, highest)| price > highest) {
+			self.highest = Some((exchange_id, price));
+		}
+	}
+}
+
+/// Outcomes of the reads of an exchange and of the aggregations judging its prices, see
+/// [`crate::ExchangeStats`].
+#[derive(
+	Clone,
+	Copy,
+	Default,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct ReliabilityStats {
+	/// Number of prices stored.
+	pub successes: u32,
+	/// Number of reported fetch failures and of prices discarded for being outside of the
+	/// pair's bounds.
+	pub failures: u32,
+	/// Number of aggregations with an outlier filter that a fresh price of the exchange took
+	/// part in.
+	pub judged: u32,
+	/// Number of those aggregations that left the price out as an outlier.
+	pub outliers: u32,
+}
+
+impl ReliabilityStats {
+	/// Whether the share of failed reads or the share of outlying prices, each over at least
+	/// `min_samples`, exceeds `max_bps` basis points.
+	pub fn is_unreliable(&self, min_samples: u32, max_bps: u32) -> bool {
+		let exceeds = |faults: u32, samples: u32| {
+			samples >= min_samples.max(1) &&
+				u64::from(faults) * 10_000 > u64::from(max_bps) * u64::from(samples)
+		};
+		exceeds(self.failures, self.successes.saturating_add(self.failures)) ||
+			exceeds(self.outliers, self.judged)
+	}
+
+	/// Halve the counts of each share over at least twice `min_samples`, so recent outcomes
+	/// weigh the most.
+	pub fn decay(&mut self, min_samples: u32) {
+		let full = min_samples.max(1).saturating_mul(2);
+		if self.successes.saturating_add(self.failures) >= full {
+			self.successes /= 2;
+			self.failures /= 2;
+		}
+		if self.judged >= full {
+			self.judged /= 2;
+			self.outliers /= 2;
+		}
+	}
+}
+
+/// The cheapest and the dearest exchange of a pair, see [`crate::PairVenues`].
+#[derive(
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct BestVenues {
+	/// Exchange with the lowest price, where the base token is bought cheapest.
+	pub ask: VenueQuote,
+	/// Exchange with the highest price, where the base token is sold dearest.
+	pub bid: VenueQuote,
+	/// Spread of the bid over the ask, in basis points of the ask.
+	pub spread_bps: u32,
+}
+
+/// How often the prices of a pair were read by other pallets, see [`crate::PairReads`].
+#[derive(
+	Clone,
+	Copy,
+	Default,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct ReadCounter {
+	/// Index of the period `count` is for: the block number divided by
+	/// [`Config::ReadPeriod`](crate::Config::ReadPeriod).
+	pub period: u32,
+	/// Number of reads in `period`.
+	pub count: u32,
+	/// Number of reads in the period before `period`.
+	pub previous: u32,
+}
+
+/// A deviation alert on a pair, see [`crate::Alerts`].
+#[derive(
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct PriceAlert<AccountId> {
+	/// Account that registered the alert.
+	pub who: AccountId,
+	/// Smallest move of the aggregated median away from `target` that triggers the alert, in
+	/// basis points of `target`.
+	pub threshold_bps: u32,
+	/// Reference price, with [`crate::fixed::PRICE_DECIMALS`] decimals.
+	pub target: Price,
+}
+
+/// A consumer on another consensus system pushed the price of a pair, see
+/// [`crate::PriceSubscriptions`].
+#[derive(
+	Clone,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct PriceSubscription<Subscriber> {
+	/// Location of the consumer, e.g. a sibling parachain.
+	pub subscriber: Subscriber,
+	/// Query id the pushed prices respond to, chosen by the consumer.
+	pub query_id: u64,
+	/// Smallest move of the aggregated median away from the last pushed price that pushes it
+	/// again, in basis points of the last pushed price.
+	pub threshold_bps: u32,
+	/// Last price pushed, with [`crate::fixed::PRICE_DECIMALS`] decimals. `None` until the first
+	/// push, which follows the next aggregation.
+	pub last: Option<Price>,
+}
+
+/// Token layout of a pool, needed to turn its raw state into a price.
+#[derive(
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	Debug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+	serde::Serialize,
+	serde::Deserialize,
+)]
+pub struct PoolMetadata {
+	/// Decimals of the pool's token0.
+	pub token0_decimals: u8,
+	/// Decimals of the pool's token1.
+	pub token1_decimals: u8,
+	/// Whether the priced (base) token is token0. The other token is the quote token.
+	pub base_is_token0: bool,
+}
+
+impl PoolMetadata {
+	/// Power of ten converting a raw quote/base amount ratio into a whole token price.
+	pub fn decimals_delta(&self) -> i32 {
+		let (base, quote) = if self.base_is_token0 {
+			(self.token0_decimals, self.token1_decimals)
+		} else {
+			(self.token1_decimals, self.token0_decimals)
+		};
+		i32::from(base) - i32::from(quote)
+	}
+}
+
+/// Price history of a pair, oldest entry first, see [`crate::PriceHistory`].
+///
+/// Only the oldest entry is stored in full. Every later entry is stored as its difference to the
+/// entry before it, SCALE compact encoded, so the small moves between consecutive aggregations
+/// take a few bytes instead of a full record.
+#[derive(
+	CloneNoBound,
+	DefaultNoBound,
+	PartialEqNoBound,
+	EqNoBound,
+	RuntimeDebugNoBound,
+	Encode,
+	Decode,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(S))]
+#[codec(mel_bound(BlockNumber: MaxEncodedLen))]
+pub struct DeltaHistory<BlockNumber: Clone + PartialEq + Eq + core::fmt::Debug, S: Get<u32>> {
+	/// The oldest entry.
+	first: Option<(Price, BlockNumber)>,
+	/// Zigzag encoded price difference and block difference of every later entry to the entry
+	/// before it.
+	deltas: BoundedVec<(Compact<u128>, Compact<u64>), S>,
+}
+
+impl<BlockNumber, S> DeltaHistory<BlockNumber, S>
+where
+	BlockNumber: AtLeast32BitUnsigned + Copy + core::fmt::Debug,
+	S: Get<u32>,
+{
+	/// Number of entries.
+	pub fn len(&self) -> usize {
+		self.first.map_or(0, |_| 1 + self.deltas.len())
+	}
+
+	/// Whether there are no entries.
+	pub fn is_empty(&self) -> bool {
+		self.first.is_none()
+	}
+
+	/// The entries, oldest first.
+	pub fn iter(&self) -> impl Iterator<Item = (Price, BlockNumber)> + '_ {
+		let mut entry = self.first;
+		self.first.into_iter().chain(self.deltas.iter().filter_map(move |delta| {
+			entry = Some(apply(entry?, *delta));
+			entry
+		}))
+	}
+
+	/// The newest entry.
+	pub fn last(&self) -> Option<(Price, BlockNumber)> {
+		self.iter().last()
+	}
+
+	/// Append the price aggregated in `block`, replacing the entry of `block` if there is one and
+	/// dropping the oldest entry if the history holds `S` entries.
+	pub fn record(&mut self, price: Price, block: BlockNumber) {
+		if self.last().is_some_and(|(_, last)| last == block) {
+			self.pop();
+		} else if self.len() >= S::get() as usize {
+			self.remove_first();
+		}
+		match self.last() {
+			// At most `S - 1` deltas follow the first entry, so there is room for one more.
+			Some(last) => {
+				let _ = self.deltas.try_push(delta(last, (price, block)));
+			},
+			None if S::get() > 0 => self.first = Some((price, block)),
+			None => {},
+		}
+	}
+
+	fn pop(&mut self) {
+		if self.deltas.pop().is_none() {
+			self.first = None;
+		}
+	}
+
+	fn remove_first(&mut self) {
+		let Some(first) = self.first else { return };
+		self.first = match self.deltas.is_empty() {
+			true => None,
+			false => Some(apply(first, self.deltas.remove(0))),
+		};
+	}
+}
+
+impl<BlockNumber, S> FromIterator<(Price, BlockNumber)> for DeltaHistory<BlockNumber, S>
+where
+	BlockNumber: AtLeast32BitUnsigned + Copy + core::fmt::Debug,
+	S: Get<u32>,
+{
+	/// Record every entry in turn, keeping the last `S`.
+	fn from_iter<I: IntoIterator<Item = (Price, BlockNumber)>>(entries: I) -> Self {
+		let mut history = Self::default();
+		for (price, block) in entries {
+			history.record(price, block);
+		}
+		history
+	}
+}
+
+/// Difference of `next` to `previous`, with the price difference zigzag encoded so small moves in
+/// either direction encode to small numbers.
+fn delta<BlockNumber: AtLeast32BitUnsigned + Copy>(
+	previous: (Price, BlockNumber),
+	next: (Price, BlockNumber),
+) -> (Compact<u128>, Compact<u64>) {
+	let price = next.0.wrapping_sub(previous.0) as i128;
+	let blocks = next.1.saturating_sub(previous.1).unique_saturated_into();
+	(Compact(((price << 1) ^ (price >> 127)) as u128), Compact(blocks))
+}
+
+/// Entry `delta` after `previous`, the inverse of [`delta`].
+fn apply<BlockNumber: AtLeast32BitUnsigned + Copy>(
+	previous: (Price, BlockNumber),
+	(Compact(price), Compact(blocks)): (Compact<u128>, Compact<u64>),
+) -> (Price, BlockNumber) {
+	let price = ((price >> 1) as i128) ^ -((price & 1) as i128);
+	(
+		previous.0.wrapping_add(price as u128),
+		previous.1.saturating_add(BlockNumber::unique_saturated_from(blocks)),
+	)
+}
+
+/// How [`crate::PriceProvider`] serves a pair without a fresh price, see
+/// [`crate::FailureModes`].
+#[derive(
+	Clone,
+	Copy,
+	Default,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub enum FailureMode {
+	/// Fail, for consumers that must not act on an old price, e.g. liquidations.
+	#[default]
+	FailClosed,
+	/// Serve the last aggregated price, flagged as stale.
+	FailOpen,
+}
+
+/// A price served by [`crate::PriceProvider`].
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct ProvidedPrice {
+	/// Aggregated median price.
+	pub price: Price,
+	/// Observation time of the newest exchange price of the aggregate, in unix milliseconds.
+	pub timestamp: u64,
+	/// Whether the price is older than [`Config::MaxPriceAge`](crate::Config::MaxPriceAge) or
+	/// the pair is paused. Only served under [`FailureMode::FailOpen`].
+	pub stale: bool,
+	/// Whether the pair is a long-tail pair whose price combined a pool short of its minimum
+	/// notional, see [`crate::LowQualityPairs`].
+	pub low_quality: bool,
+}
+
+/// Fees of the next block of an EIP-1559 chain, see [`crate::GasData`].
+#[derive(
+	Clone,
+	Copy,
+	Default,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct GasFees {
+	/// Base fee per gas of the block, in wei.
+	pub base_fee: u128,
+	/// Suggested priority fee per gas, in wei.
+	pub priority_fee: u128,
+	/// Number of the block on its chain.
+	pub block: u64,
+	/// Time the fees were read, in unix milliseconds.
+	pub timestamp: u64,
+	/// Typical priority fee per gas of the transactions competing for the top of a block, in
+	/// wei, for the chains in [`crate::BuilderTipChains`].
+	pub builder_tip: Option<u128>,
+}
+
+impl GasFees {
+	/// Priority fee per gas to get a transaction into a competitive block: the builder tip where
+	/// it is tracked, otherwise the suggested priority fee.
+	pub fn competitive_tip(&self) -> u128 {
+		self.builder_tip.unwrap_or(self.priority_fee)
+	}
+}
+
+/// A price proven against the storage of its pool, see [`crate::VerifiedPrices`].
+#[derive(
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct VerifiedPrice {
+	/// The price with [`crate::fixed::PRICE_DECIMALS`] decimals.
+	pub price: Price,
+	/// Number of the block of the pool's chain the price was proven at.
+	pub block: u64,
+	/// On-chain time the proof was verified, in unix milliseconds.
+	pub timestamp: u64,
+}
+
+/// Health of the whole oracle at a glance, for block explorers and dashboards, see
+/// [`crate::Pallet::oracle_status`].
+#[derive(
+	Clone,
+	Copy,
+	Default,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct OracleStatus {
+	/// Number of tracked pairs.
+	pub pairs_total: u32,
+	/// Tracked pairs whose aggregated price is younger than the maximum price age.
+	pub pairs_fresh: u32,
+	/// Tracked pairs without an aggregated price, or with an older one, unless paused.
+	pub pairs_stale: u32,
+	/// Tracked pairs paused by the registry origin.
+	pub pairs_paused: u32,
+	/// Enabled exchanges with a fresh price of at least one pair, out of quarantine.
+	pub exchanges_healthy: u32,
+	/// Enabled exchanges without a fresh price of any pair, or in quarantine.
+	pub exchanges_unhealthy: u32,
+	/// Observation time of the newest aggregated price, in unix milliseconds: the last time
+	/// the prices of the offchain workers made it into an aggregate.
+	pub last_update: Option<u64>,
+	/// Pairs whose exchanges offer a fresh arbitrage opportunity.
+	pub open_opportunities: u32,
+}
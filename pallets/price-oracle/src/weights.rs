@@ -0,0 +1,1213 @@
+//! Placeholder weights for `pallet_price_oracle`.
+//!
+//! These are estimated from the storage accesses of the benchmarks in `benchmarking.rs`, not
+//! measured, so the runtime doesn't use them yet. Replace them with benchmarked weights,
+//! generated on reference hardware with
+//! `frame-omni-bencher v1 benchmark pallet --pallet pallet_price_oracle --extrinsic "*"
+//! --output pallets/price-oracle/src/weights.rs`.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use core::marker::PhantomData;
+use frame_support::{
+	traits::Get,
+	weights::{constants::RocksDbWeight, Weight},
+};
+
+/// Weight functions needed for `pallet_price_oracle`.
+pub trait WeightInfo {
+	fn submit_price_unsigned() -> Weight;
+	fn submit_price_signed() -> Weight;
+	fn commit_price() -> Weight;
+	fn reveal_price() -> Weight;
+	fn report_misbehavior() -> Weight;
+	fn add_pair() -> Weight;
+	fn update_pair() -> Weight;
+	fn remove_pair() -> Weight;
+	fn add_exchange() -> Weight;
+	fn set_exchange_enabled() -> Weight;
+	fn set_rpc_endpoints() -> Weight;
+	fn report_fetch_failure() -> Weight;
+	fn register_operator() -> Weight;
+	fn deregister_operator() -> Weight;
+	fn set_alert() -> Weight;
+	fn request_price_xcm() -> Weight;
+	fn subscribe_price() -> Weight;
+	fn remap_exchange(p: u32, a: u32) -> Weight;
+	fn set_twap_check() -> Weight;
+	fn set_fast_path() -> Weight;
+	fn aggregate_prices(e: u32) -> Weight;
+	fn prune_stale_prices(p: u32) -> Weight;
+	fn latest_price() -> Weight;
+	fn get_prices(e: u32) -> Weight;
+	fn twap() -> Weight;
+	fn latest_aggregates() -> Weight;
+	fn set_failure_mode() -> Weight;
+	fn set_pair_paused() -> Weight;
+	fn provided_price() -> Weight;
+	fn set_router() -> Weight;
+	fn set_pool_hop() -> Weight;
+	fn submit_gas_fees() -> Weight;
+	fn set_gas_tracking() -> Weight;
+	fn set_builder_tips() -> Weight;
+	fn set_native_token() -> Weight;
+	fn import_registry(e: u32, p: u32) -> Weight;
+	fn set_cross_chain_check() -> Weight;
+	fn set_call_options() -> Weight;
+	fn set_sla() -> Weight;
+	fn check_slas(s: u32) -> Weight;
+	fn advance_bootstraps(b: u32) -> Weight;
+	fn set_long_tail() -> Weight;
+	fn deprecate_pair() -> Weight;
+	fn advance_deprecations(d: u32) -> Weight;
+	fn set_multicall() -> Weight;
+	fn set_confirmation() -> Weight;
+	fn submit_verified_price(n: u32) -> Weight;
+	fn guarded_price() -> Weight;
+	fn release_exchange() -> Weight;
+}
+
+/// Weights for `pallet_price_oracle` using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `PriceOracle::Exchanges` (r:1 w:0)
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Storage: `PriceOracle::PriceData` (r:1 w:1)
+	/// Storage: `PriceOracle::PriceHistory` (r:1 w:0)
+	/// Storage: `PriceOracle::PendingAggregation` (r:0 w:1)
+	/// Storage: `PriceOracle::RepeatedPrices` (r:1 w:1)
+	/// Storage: `PriceOracle::SourceBlocks` (r:0 w:1)
+	/// Storage: `PriceOracle::LongTailPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::PoolNotionals` (r:0 w:1)
+	/// Storage: `PriceOracle::SubmissionSummaries` (r:1 w:1)
+	/// Storage: `PriceOracle::QuarantinedExchanges` (r:1 w:1)
+	/// Storage: `PriceOracle::ExchangeStats` (r:1 w:1)
+	fn submit_price_unsigned() -> Weight {
+		Weight::from_parts(43_000_000, 4_986)
+			.saturating_add(T::DbWeight::get().reads(10_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+	}
+	/// Storage: `PriceOracle::Operators` (r:1 w:0)
+	/// Storage: `PriceOracle::Exchanges` (r:1 w:0)
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Storage: `PriceOracle::PriceData` (r:1 w:1)
+	/// Storage: `PriceOracle::PriceHistory` (r:1 w:0)
+	/// Storage: `PriceOracle::PendingAggregation` (r:0 w:1)
+	/// Storage: `PriceOracle::RepeatedPrices` (r:1 w:1)
+	/// Storage: `PriceOracle::SourceBlocks` (r:0 w:1)
+	/// Storage: `PriceOracle::LongTailPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::PoolNotionals` (r:0 w:1)
+	/// Storage: `PriceOracle::SubmissionSummaries` (r:1 w:1)
+	/// Storage: `PriceOracle::QuarantinedExchanges` (r:1 w:1)
+	/// Storage: `PriceOracle::ExchangeStats` (r:1 w:1)
+	/// Storage: `PriceOracle::SignedSubmissions` (r:0 w:1)
+	fn submit_price_signed() -> Weight {
+		Weight::from_parts(51_000_000, 4_986)
+			.saturating_add(T::DbWeight::get().reads(11_u64))
+			.saturating_add(T::DbWeight::get().writes(9_u64))
+	}
+	/// Storage: `PriceOracle::Operators` (r:1 w:0)
+	/// Storage: `PriceOracle::Exchanges` (r:1 w:0)
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::PriceCommitments` (r:0 w:1)
+	fn commit_price() -> Weight {
+		Weight::from_parts(19_000_000, 3_593)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::Operators` (r:1 w:0)
+	/// Storage: `PriceOracle::PriceCommitments` (r:1 w:1)
+	/// Storage: `PriceOracle::Exchanges` (r:1 w:0)
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Storage: `PriceOracle::PriceData` (r:1 w:1)
+	/// Storage: `PriceOracle::PriceHistory` (r:1 w:0)
+	/// Storage: `PriceOracle::PendingAggregation` (r:0 w:1)
+	/// Storage: `PriceOracle::RepeatedPrices` (r:1 w:1)
+	/// Storage: `PriceOracle::SourceBlocks` (r:0 w:1)
+	/// Storage: `PriceOracle::LongTailPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::PoolNotionals` (r:0 w:1)
+	/// Storage: `PriceOracle::SubmissionSummaries` (r:1 w:1)
+	/// Storage: `PriceOracle::QuarantinedExchanges` (r:1 w:1)
+	/// Storage: `PriceOracle::ExchangeStats` (r:1 w:1)
+	/// Storage: `PriceOracle::SignedSubmissions` (r:0 w:1)
+	fn reveal_price() -> Weight {
+		Weight::from_parts(58_000_000, 4_986)
+			.saturating_add(T::DbWeight::get().reads(12_u64))
+			.saturating_add(T::DbWeight::get().writes(10_u64))
+	}
+	/// Storage: `PriceOracle::SignedSubmissions` (r:1 w:1)
+	/// Storage: `PriceOracle::PriceHistory` (r:1 w:0)
+	/// Storage: `PriceOracle::OperatorBonds` (r:1 w:1)
+	/// Storage: `System::Account` (r:2 w:2)
+	fn report_misbehavior() -> Weight {
+		Weight::from_parts(47_000_000, 5_312)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: `PriceOracle::Exchanges` (r:16 w:0)
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:1)
+	/// Storage: `PriceOracle::CounterForTrackedPairs` (r:1 w:1)
+	/// Storage: `PriceOracle::Bootstrapping` (r:0 w:1)
+	fn add_pair() -> Weight {
+		Weight::from_parts(41_000_000, 44_928)
+			.saturating_add(T::DbWeight::get().reads(18_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: `PriceOracle::Exchanges` (r:16 w:0)
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:1)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:1 w:0)
+	fn update_pair() -> Weight {
+		Weight::from_parts(38_000_000, 44_928)
+			.saturating_add(T::DbWeight::get().reads(18_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:1)
+	/// Storage: `PriceOracle::CounterForTrackedPairs` (r:1 w:1)
+	/// Storage: `PriceOracle::PendingAggregation` (r:0 w:1)
+	/// Storage: `PriceOracle::PriceGuards` (r:0 w:1)
+	/// Storage: `PriceOracle::AggregationDeferrals` (r:0 w:1)
+	/// Storage: `PriceOracle::PairReads` (r:0 w:1)
+	/// Storage: `PriceOracle::Alerts` (r:1 w:1)
+	/// Storage: `PriceOracle::AlertDeposits` (r:16 w:16)
+	/// Storage: `System::Account` (r:16 w:16)
+	/// Storage: `PriceOracle::PriceSubscriptions` (r:0 w:1)
+	/// Storage: `PriceOracle::TwapChecks` (r:0 w:1)
+	/// Storage: `PriceOracle::FastPaths` (r:0 w:1)
+	/// Storage: `PriceOracle::FastPathUntil` (r:0 w:1)
+	/// Storage: `PriceOracle::FailureModes` (r:0 w:1)
+	/// Storage: `PriceOracle::PausedPairs` (r:0 w:1)
+	/// Storage: `PriceOracle::LatestAggregates` (r:1 w:1)
+	/// Storage: `PriceOracle::ChainAggregates` (r:16 w:16)
+	/// Storage: `PriceOracle::CrossChainChecks` (r:0 w:1)
+	/// Storage: `PriceOracle::SourceBlocks` (r:16 w:16)
+	/// Storage: `PriceOracle::PairVenues` (r:0 w:1)
+	/// Storage: `PriceOracle::PairSlas` (r:0 w:1)
+	/// Storage: `PriceOracle::BreachedSlas` (r:0 w:1)
+	/// Storage: `PriceOracle::Bootstrapping` (r:0 w:1)
+	/// Storage: `PriceOracle::ArbitrageOpportunities` (r:0 w:1)
+	/// Storage: `PriceOracle::LongTailPairs` (r:0 w:1)
+	/// Storage: `PriceOracle::LowQualityPairs` (r:0 w:1)
+	/// Storage: `PriceOracle::PoolNotionals` (r:16 w:16)
+	/// Storage: `PriceOracle::PoolHops` (r:16 w:16)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:0 w:1)
+	fn remove_pair() -> Weight {
+		Weight::from_parts(160_000_000, 42_060)
+			.saturating_add(T::DbWeight::get().reads(100_u64))
+			.saturating_add(T::DbWeight::get().writes(119_u64))
+	}
+	/// Storage: `PriceOracle::Exchanges` (r:1 w:1)
+	fn add_exchange() -> Weight {
+		Weight::from_parts(15_000_000, 3_569)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::Exchanges` (r:1 w:1)
+	fn set_exchange_enabled() -> Weight {
+		Weight::from_parts(16_000_000, 3_569)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::RpcEndpoints` (r:0 w:1)
+	fn set_rpc_endpoints() -> Weight {
+		Weight::from_parts(20_000_000, 0).saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::Operators` (r:1 w:0)
+	/// Storage: `PriceOracle::Exchanges` (r:1 w:0)
+	/// Storage: `PriceOracle::QuarantinedExchanges` (r:1 w:1)
+	/// Storage: `PriceOracle::ExchangeStats` (r:1 w:1)
+	fn report_fetch_failure() -> Weight {
+		Weight::from_parts(22_000_000, 3_569)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `PriceOracle::Operators` (r:1 w:1)
+	/// Storage: `PriceOracle::CounterForOperators` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Storage: `PriceOracle::OperatorBonds` (r:0 w:1)
+	fn register_operator() -> Weight {
+		Weight::from_parts(31_000_000, 3_593)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: `PriceOracle::Operators` (r:1 w:1)
+	/// Storage: `PriceOracle::CounterForOperators` (r:1 w:1)
+	/// Storage: `PriceOracle::OperatorBonds` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	fn deregister_operator() -> Weight {
+		Weight::from_parts(32_000_000, 3_593)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::Alerts` (r:1 w:1)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::AlertDeposits` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	fn set_alert() -> Weight {
+		Weight::from_parts(41_000_000, 7_830)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: `PriceOracle::PairReads` (r:1 w:1)
+	/// Storage: `PriceOracle::Bootstrapping` (r:1 w:0)
+	/// Storage: `PriceOracle::AggregatedPrice` (r:1 w:0)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Storage: `PriceOracle::PausedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::FailureModes` (r:1 w:0)
+	/// Storage: `PriceOracle::LowQualityPairs` (r:1 w:0)
+	fn request_price_xcm() -> Weight {
+		Weight::from_parts(19_000_000, 3_650)
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::PriceSubscriptions` (r:1 w:1)
+	fn subscribe_price() -> Weight {
+		Weight::from_parts(24_000_000, 7_830)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::TwapChecks` (r:0 w:1)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:1 w:0)
+	fn set_twap_check() -> Weight {
+		Weight::from_parts(15_000_000, 3_840)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::FastPaths` (r:0 w:1)
+	/// Storage: `PriceOracle::FastPathUntil` (r:0 w:1)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:1 w:0)
+	fn set_fast_path() -> Weight {
+		Weight::from_parts(16_000_000, 3_840)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `PriceOracle::ExchangeKeys` (r:1 w:1)
+	/// Storage: `PriceOracle::Exchanges` (r:2 w:2)
+	/// Storage: `PriceOracle::TrackedPairs` (r:p w:p)
+	/// Storage: `PriceOracle::PriceData` (r:p w:2p)
+	/// Storage: `PriceOracle::RepeatedPrices` (r:p w:2p)
+	/// Storage: `PriceOracle::SourceBlocks` (r:p w:2p)
+	/// Storage: `PriceOracle::PoolNotionals` (r:p w:2p)
+	/// Storage: `PriceOracle::PoolHops` (r:p w:2p)
+	/// Storage: `PriceOracle::Operators` (r:a w:0)
+	/// Storage: `PriceOracle::QuarantinedExchanges` (r:1 w:2)
+	/// Storage: `PriceOracle::ExchangeStats` (r:1 w:2)
+	/// Storage: `PriceOracle::OutlierCounts` (r:1 w:2)
+	/// Storage: `PriceOracle::VerifiedPrices` (r:p w:2p)
+	/// Storage: `PriceOracle::PriceCommitments` (r:p*a w:2p*a)
+	/// Storage: `PriceOracle::SignedSubmissions` (r:p*a w:2p*a)
+	/// The range of component `p` is `[0, 100]`.
+	/// The range of component `a` is `[0, 32]`.
+	fn remap_exchange(p: u32, a: u32) -> Weight {
+		Weight::from_parts(31_000_000, 3_569)
+			.saturating_add(Weight::from_parts(33_000_000, 6_384).saturating_mul(p.into()))
+			.saturating_add(Weight::from_parts(2_600_000, 0).saturating_mul(a.into()))
+			.saturating_add(
+				Weight::from_parts(9_000_000, 5_270).saturating_mul(u64::from(p) * u64::from(a)),
+			)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().reads((7_u64).saturating_mul(p.into())))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(a.into())))
+			.saturating_add(
+				T::DbWeight::get().reads((2_u64).saturating_mul(u64::from(p) * u64::from(a))),
+			)
+			.saturating_add(T::DbWeight::get().writes(9_u64))
+			.saturating_add(T::DbWeight::get().writes((13_u64).saturating_mul(p.into())))
+			.saturating_add(
+				T::DbWeight::get().writes((4_u64).saturating_mul(u64::from(p) * u64::from(a))),
+			)
+	}
+	/// Storage: `PriceOracle::PendingAggregation` (r:1 w:1)
+	/// Storage: `PriceOracle::AggregationDeferrals` (r:1 w:1)
+	/// Storage: `PriceOracle::PriceData` (r:e w:0)
+	/// Storage: `PriceOracle::Exchanges` (r:e w:0)
+	/// Storage: `PriceOracle::RepeatedPrices` (r:e w:0)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Storage: `PriceOracle::PriceHistory` (r:1 w:1)
+	/// Storage: `PriceOracle::AggregatedPrice` (r:0 w:1)
+	/// Storage: `PriceOracle::Alerts` (r:1 w:1)
+	/// Storage: `PriceOracle::AlertDeposits` (r:16 w:16)
+	/// Storage: `System::Account` (r:16 w:16)
+	/// Storage: `PriceOracle::PriceSubscriptions` (r:1 w:0)
+	/// Storage: `PriceOracle::AggregatedPrice` (r:1 w:0)
+	/// Storage: `PriceOracle::FastPaths` (r:1 w:0)
+	/// Storage: `PriceOracle::FastPathUntil` (r:1 w:1)
+	/// Storage: `PriceOracle::Bootstrapping` (r:1 w:0)
+	/// Storage: `PriceOracle::LatestAggregates` (r:1 w:1)
+	/// Storage: `PriceOracle::ChainAggregates` (r:e w:2e)
+	/// Storage: `PriceOracle::CrossChainChecks` (r:1 w:0)
+	/// Storage: `PriceOracle::PairVenues` (r:0 w:1)
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::GasData` (r:2 w:0)
+	/// Storage: `PriceOracle::NativeTokens` (r:2 w:0)
+	/// Storage: `PriceOracle::AggregatedPrice` (r:2 w:0)
+	/// Storage: `PriceOracle::ArbitrageOpportunities` (r:0 w:1)
+	/// Storage: `PriceOracle::LongTailPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::PoolNotionals` (r:e w:0)
+	/// Storage: `PriceOracle::LowQualityPairs` (r:1 w:1)
+	/// Storage: `PriceOracle::OutlierCounts` (r:e w:e)
+	/// Storage: `PriceOracle::QuarantinedExchanges` (r:2e w:e)
+	/// Storage: `PriceOracle::ExchangeStats` (r:e w:e)
+	/// The range of component `e` is `[1, 256]`.
+	fn aggregate_prices(e: u32) -> Weight {
+		Weight::from_parts(186_000_000, 49_880)
+			.saturating_add(Weight::from_parts(25_000_000, 12_645).saturating_mul(e.into()))
+			.saturating_add(T::DbWeight::get().reads(53_u64))
+			.saturating_add(T::DbWeight::get().reads((9_u64).saturating_mul(e.into())))
+			.saturating_add(T::DbWeight::get().writes(42_u64))
+			.saturating_add(T::DbWeight::get().writes((5_u64).saturating_mul(e.into())))
+	}
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Storage: `PriceOracle::PriceData` (r:p w:p)
+	/// Storage: `PriceOracle::RepeatedPrices` (r:0 w:p)
+	/// Storage: `PriceOracle::SourceBlocks` (r:0 w:p)
+	/// Storage: `PriceOracle::PoolNotionals` (r:0 w:p)
+	/// Storage: `PriceOracle::Exchanges` (r:p w:0)
+	/// The range of component `p` is `[0, 1000]`.
+	fn prune_stale_prices(p: u32) -> Weight {
+		Weight::from_parts(6_000_000, 1_489)
+			.saturating_add(Weight::from_parts(12_500_000, 3_569).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(p.into())))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(p.into())))
+	}
+	/// Storage: `PriceOracle::Bootstrapping` (r:1 w:0)
+	/// Storage: `PriceOracle::AggregatedPrice` (r:1 w:0)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Storage: `PriceOracle::PairReads` (r:1 w:1)
+	fn latest_price() -> Weight {
+		Weight::from_parts(13_000_000, 3_650)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::PairReads` (r:1 w:1)
+	/// Storage: `PriceOracle::PriceData` (r:e w:0)
+	/// Storage: `PriceOracle::Exchanges` (r:e w:0)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// The range of component `e` is `[1, 256]`.
+	fn get_prices(e: u32) -> Weight {
+		Weight::from_parts(10_000_000, 3_620)
+			.saturating_add(Weight::from_parts(6_200_000, 3_569).saturating_mul(e.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(e.into())))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::PriceHistory` (r:1 w:0)
+	fn twap() -> Weight {
+		Weight::from_parts(24_000_000, 9_230).saturating_add(T::DbWeight::get().reads(1_u64))
+	}
+	/// Storage: `PriceOracle::LatestAggregates` (r:1 w:0)
+	fn latest_aggregates() -> Weight {
+		Weight::from_parts(18_000_000, 3_570).saturating_add(T::DbWeight::get().reads(1_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::FailureModes` (r:0 w:1)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:1 w:0)
+	fn set_failure_mode() -> Weight {
+		Weight::from_parts(15_000_000, 3_840)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::PausedPairs` (r:0 w:1)
+	fn set_pair_paused() -> Weight {
+		Weight::from_parts(15_000_000, 3_840)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::PairReads` (r:1 w:1)
+	/// Storage: `PriceOracle::Bootstrapping` (r:1 w:0)
+	/// Storage: `PriceOracle::AggregatedPrice` (r:1 w:0)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Storage: `PriceOracle::PausedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::FailureModes` (r:1 w:0)
+	/// Storage: `PriceOracle::LowQualityPairs` (r:1 w:0)
+	fn provided_price() -> Weight {
+		Weight::from_parts(16_000_000, 3_650)
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::Exchanges` (r:1 w:0)
+	/// Storage: `PriceOracle::Routers` (r:0 w:1)
+	fn set_router() -> Weight {
+		Weight::from_parts(15_000_000, 3_569)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::Exchanges` (r:1 w:0)
+	/// Storage: `PriceOracle::PoolHops` (r:0 w:1)
+	fn set_pool_hop() -> Weight {
+		Weight::from_parts(18_000_000, 3_840)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::GasTrackedChains` (r:1 w:0)
+	/// Storage: `PriceOracle::GasData` (r:1 w:1)
+	fn submit_gas_fees() -> Weight {
+		Weight::from_parts(18_000_000, 3_553)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::RpcEndpoints` (r:1 w:0)
+	/// Storage: `PriceOracle::GasTrackedChains` (r:0 w:1)
+	/// Storage: `PriceOracle::BuilderTipChains` (r:0 w:1)
+	/// Storage: `PriceOracle::GasData` (r:0 w:1)
+	/// Storage: `PriceOracle::NativeTokens` (r:0 w:1)
+	fn set_gas_tracking() -> Weight {
+		Weight::from_parts(16_000_000, 3_658)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: `PriceOracle::GasTrackedChains` (r:1 w:0)
+	/// Storage: `PriceOracle::BuilderTipChains` (r:0 w:1)
+	fn set_builder_tips() -> Weight {
+		Weight::from_parts(12_000_000, 3_513)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::GasTrackedChains` (r:1 w:0)
+	/// Storage: `PriceOracle::NativeTokens` (r:0 w:1)
+	fn set_native_token() -> Weight {
+		Weight::from_parts(12_000_000, 3_513)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:p w:2p)
+	/// Storage: `PriceOracle::Exchanges` (r:2e w:2e)
+	/// Storage: `PriceOracle::ExchangeKeys` (r:e w:2e)
+	/// Storage: `PriceOracle::Routers` (r:e w:2e)
+	/// Storage: `PriceOracle::PairReads` (r:0 w:p)
+	/// Storage: `PriceOracle::PendingAggregation` (r:0 w:p)
+	/// Storage: `PriceOracle::PriceGuards` (r:0 w:p)
+	/// Storage: `PriceOracle::AggregationDeferrals` (r:0 w:p)
+	/// Storage: `PriceOracle::Alerts` (r:p w:p)
+	/// Storage: `PriceOracle::AlertDeposits` (r:16p w:16p)
+	/// Storage: `System::Account` (r:16p w:16p)
+	/// Storage: `PriceOracle::TwapChecks` (r:0 w:2p)
+	/// Storage: `PriceOracle::FastPaths` (r:0 w:2p)
+	/// Storage: `PriceOracle::FastPathUntil` (r:0 w:p)
+	/// Storage: `PriceOracle::FailureModes` (r:0 w:2p)
+	/// Storage: `PriceOracle::PausedPairs` (r:0 w:p)
+	/// Storage: `PriceOracle::LatestAggregates` (r:1 w:1)
+	/// Storage: `PriceOracle::ChainAggregates` (r:16p w:16p)
+	/// Storage: `PriceOracle::CrossChainChecks` (r:0 w:2p)
+	/// Storage: `PriceOracle::SourceBlocks` (r:16p w:16p)
+	/// Storage: `PriceOracle::PairVenues` (r:0 w:p)
+	/// Storage: `PriceOracle::PairSlas` (r:0 w:2p)
+	/// Storage: `PriceOracle::BreachedSlas` (r:0 w:2p)
+	/// Storage: `PriceOracle::Bootstrapping` (r:0 w:2p)
+	/// Storage: `PriceOracle::ArbitrageOpportunities` (r:0 w:p)
+	/// Storage: `PriceOracle::LongTailPairs` (r:0 w:2p)
+	/// Storage: `PriceOracle::LowQualityPairs` (r:0 w:2p)
+	/// Storage: `PriceOracle::PoolNotionals` (r:16p w:16p)
+	/// Storage: `PriceOracle::PoolHops` (r:16p w:32p)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:0 w:p)
+	/// The range of component `e` is `[0, 256]`.
+	/// The range of component `p` is `[0, 64]`.
+	fn import_registry(e: u32, p: u32) -> Weight {
+		Weight::from_parts(20_000_000, 3_513)
+			.saturating_add(Weight::from_parts(9_000_000, 2_568).saturating_mul(e.into()))
+			.saturating_add(Weight::from_parts(151_000_000, 42_064).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().reads((4_u64).saturating_mul(e.into())))
+			.saturating_add(T::DbWeight::get().reads((98_u64).saturating_mul(p.into())))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(T::DbWeight::get().writes((6_u64).saturating_mul(e.into())))
+			.saturating_add(T::DbWeight::get().writes((143_u64).saturating_mul(p.into())))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::CrossChainChecks` (r:0 w:1)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:1 w:0)
+	fn set_cross_chain_check() -> Weight {
+		Weight::from_parts(15_000_000, 3_840)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::ProtocolCallOptions` (r:0 w:1)
+	fn set_call_options() -> Weight {
+		Weight::from_parts(12_000_000, 0).saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::PairSlas` (r:0 w:1)
+	/// Storage: `PriceOracle::BreachedSlas` (r:0 w:1)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:1 w:0)
+	fn set_sla() -> Weight {
+		Weight::from_parts(16_000_000, 3_840)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `PriceOracle::PairSlas` (r:s w:0)
+	/// Storage: `PriceOracle::AggregatedPrice` (r:s w:0)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Storage: `PriceOracle::PairVenues` (r:s w:0)
+	/// Storage: `PriceOracle::BreachedSlas` (r:s w:s)
+	/// The range of component `s` is `[0, 64]`.
+	fn check_slas(s: u32) -> Weight {
+		Weight::from_parts(4_000_000, 1_489)
+			.saturating_add(Weight::from_parts(14_000_000, 4_000).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().reads((4_u64).saturating_mul(s.into())))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(s.into())))
+	}
+	/// Storage: `PriceOracle::Bootstrapping` (r:b w:b)
+	/// Storage: `PriceOracle::AggregatedPrice` (r:b w:0)
+	/// Storage: `PriceOracle::LatestAggregates` (r:b w:b)
+	/// The range of component `b` is `[0, 64]`.
+	fn advance_bootstraps(b: u32) -> Weight {
+		Weight::from_parts(3_000_000, 1_489)
+			.saturating_add(Weight::from_parts(16_000_000, 6_380).saturating_mul(b.into()))
+			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(b.into())))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(b.into())))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::LongTailPairs` (r:0 w:1)
+	/// Storage: `PriceOracle::LowQualityPairs` (r:0 w:1)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:1 w:0)
+	fn set_long_tail() -> Weight {
+		Weight::from_parts(15_000_000, 3_840)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:1 w:1)
+	/// Storage: `System::Number` (r:1 w:0)
+	fn deprecate_pair() -> Weight {
+		Weight::from_parts(16_000_000, 3_840)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:d w:0)
+	/// The range of component `d` is `[0, 64]`.
+	fn advance_deprecations(d: u32) -> Weight {
+		Weight::from_parts(3_000_000, 1_489)
+			.saturating_add(Weight::from_parts(6_000_000, 2_541).saturating_mul(d.into()))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(d.into())))
+	}
+	/// Storage: `PriceOracle::RpcEndpoints` (r:1 w:0)
+	/// Storage: `PriceOracle::MulticallChains` (r:0 w:1)
+	fn set_multicall() -> Weight {
+		Weight::from_parts(12_000_000, 3_658)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::RpcEndpoints` (r:1 w:0)
+	/// Storage: `PriceOracle::ChainConfirmations` (r:0 w:1)
+	fn set_confirmation() -> Weight {
+		Weight::from_parts(12_000_000, 3_658)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::Exchanges` (r:1 w:0)
+	/// Storage: `PriceOracle::VerifiedPrices` (r:1 w:1)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// The range of component `n` is `[2, 32]`.
+	fn submit_verified_price(n: u32) -> Weight {
+		Weight::from_parts(21_000_000, 6_218)
+			.saturating_add(Weight::from_parts(1_900_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::PairReads` (r:1 w:1)
+	/// Storage: `PriceOracle::Bootstrapping` (r:1 w:0)
+	/// Storage: `PriceOracle::AggregatedPrice` (r:1 w:0)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Storage: `PriceOracle::PausedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::FailureModes` (r:1 w:0)
+	/// Storage: `PriceOracle::LowQualityPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::PriceGuards` (r:0 w:1)
+	fn guarded_price() -> Weight {
+		Weight::from_parts(18_000_000, 3_650)
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `PriceOracle::QuarantinedExchanges` (r:1 w:1)
+	/// Storage: `PriceOracle::ExchangeStats` (r:0 w:1)
+	fn release_exchange() -> Weight {
+		Weight::from_parts(15_000_000, 3_493)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+	/// Storage: `PriceOracle::Exchanges` (r:1 w:0)
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Storage: `PriceOracle::PriceData` (r:1 w:1)
+	/// Storage: `PriceOracle::PriceHistory` (r:1 w:0)
+	/// Storage: `PriceOracle::PendingAggregation` (r:0 w:1)
+	/// Storage: `PriceOracle::RepeatedPrices` (r:1 w:1)
+	/// Storage: `PriceOracle::SourceBlocks` (r:0 w:1)
+	/// Storage: `PriceOracle::LongTailPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::PoolNotionals` (r:0 w:1)
+	/// Storage: `PriceOracle::SubmissionSummaries` (r:1 w:1)
+	/// Storage: `PriceOracle::QuarantinedExchanges` (r:1 w:1)
+	/// Storage: `PriceOracle::ExchangeStats` (r:1 w:1)
+	fn submit_price_unsigned() -> Weight {
+		Weight::from_parts(43_000_000, 4_986)
+			.saturating_add(RocksDbWeight::get().reads(10_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+	}
+	/// Storage: `PriceOracle::Operators` (r:1 w:0)
+	/// Storage: `PriceOracle::Exchanges` (r:1 w:0)
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Storage: `PriceOracle::PriceData` (r:1 w:1)
+	/// Storage: `PriceOracle::PriceHistory` (r:1 w:0)
+	/// Storage: `PriceOracle::PendingAggregation` (r:0 w:1)
+	/// Storage: `PriceOracle::RepeatedPrices` (r:1 w:1)
+	/// Storage: `PriceOracle::SourceBlocks` (r:0 w:1)
+	/// Storage: `PriceOracle::LongTailPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::PoolNotionals` (r:0 w:1)
+	/// Storage: `PriceOracle::SubmissionSummaries` (r:1 w:1)
+	/// Storage: `PriceOracle::QuarantinedExchanges` (r:1 w:1)
+	/// Storage: `PriceOracle::ExchangeStats` (r:1 w:1)
+	/// Storage: `PriceOracle::SignedSubmissions` (r:0 w:1)
+	fn submit_price_signed() -> Weight {
+		Weight::from_parts(51_000_000, 4_986)
+			.saturating_add(RocksDbWeight::get().reads(11_u64))
+			.saturating_add(RocksDbWeight::get().writes(9_u64))
+	}
+	/// Storage: `PriceOracle::Operators` (r:1 w:0)
+	/// Storage: `PriceOracle::Exchanges` (r:1 w:0)
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::PriceCommitments` (r:0 w:1)
+	fn commit_price() -> Weight {
+		Weight::from_parts(19_000_000, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::Operators` (r:1 w:0)
+	/// Storage: `PriceOracle::PriceCommitments` (r:1 w:1)
+	/// Storage: `PriceOracle::Exchanges` (r:1 w:0)
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Storage: `PriceOracle::PriceData` (r:1 w:1)
+	/// Storage: `PriceOracle::PriceHistory` (r:1 w:0)
+	/// Storage: `PriceOracle::PendingAggregation` (r:0 w:1)
+	/// Storage: `PriceOracle::RepeatedPrices` (r:1 w:1)
+	/// Storage: `PriceOracle::SourceBlocks` (r:0 w:1)
+	/// Storage: `PriceOracle::LongTailPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::PoolNotionals` (r:0 w:1)
+	/// Storage: `PriceOracle::SubmissionSummaries` (r:1 w:1)
+	/// Storage: `PriceOracle::QuarantinedExchanges` (r:1 w:1)
+	/// Storage: `PriceOracle::ExchangeStats` (r:1 w:1)
+	/// Storage: `PriceOracle::SignedSubmissions` (r:0 w:1)
+	fn reveal_price() -> Weight {
+		Weight::from_parts(58_000_000, 4_986)
+			.saturating_add(RocksDbWeight::get().reads(12_u64))
+			.saturating_add(RocksDbWeight::get().writes(10_u64))
+	}
+	/// Storage: `PriceOracle::SignedSubmissions` (r:1 w:1)
+	/// Storage: `PriceOracle::PriceHistory` (r:1 w:0)
+	/// Storage: `PriceOracle::OperatorBonds` (r:1 w:1)
+	/// Storage: `System::Account` (r:2 w:2)
+	fn report_misbehavior() -> Weight {
+		Weight::from_parts(47_000_000, 5_312)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: `PriceOracle::Exchanges` (r:16 w:0)
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:1)
+	/// Storage: `PriceOracle::CounterForTrackedPairs` (r:1 w:1)
+	/// Storage: `PriceOracle::Bootstrapping` (r:0 w:1)
+	fn add_pair() -> Weight {
+		Weight::from_parts(41_000_000, 44_928)
+			.saturating_add(RocksDbWeight::get().reads(18_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: `PriceOracle::Exchanges` (r:16 w:0)
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:1)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:1 w:0)
+	fn update_pair() -> Weight {
+		Weight::from_parts(38_000_000, 44_928)
+			.saturating_add(RocksDbWeight::get().reads(18_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:1)
+	/// Storage: `PriceOracle::CounterForTrackedPairs` (r:1 w:1)
+	/// Storage: `PriceOracle::PendingAggregation` (r:0 w:1)
+	/// Storage: `PriceOracle::PriceGuards` (r:0 w:1)
+	/// Storage: `PriceOracle::AggregationDeferrals` (r:0 w:1)
+	/// Storage: `PriceOracle::PairReads` (r:0 w:1)
+	/// Storage: `PriceOracle::Alerts` (r:1 w:1)
+	/// Storage: `PriceOracle::AlertDeposits` (r:16 w:16)
+	/// Storage: `System::Account` (r:16 w:16)
+	/// Storage: `PriceOracle::PriceSubscriptions` (r:0 w:1)
+	/// Storage: `PriceOracle::TwapChecks` (r:0 w:1)
+	/// Storage: `PriceOracle::FastPaths` (r:0 w:1)
+	/// Storage: `PriceOracle::FastPathUntil` (r:0 w:1)
+	/// Storage: `PriceOracle::FailureModes` (r:0 w:1)
+	/// Storage: `PriceOracle::PausedPairs` (r:0 w:1)
+	/// Storage: `PriceOracle::LatestAggregates` (r:1 w:1)
+	/// Storage: `PriceOracle::ChainAggregates` (r:16 w:16)
+	/// Storage: `PriceOracle::CrossChainChecks` (r:0 w:1)
+	/// Storage: `PriceOracle::SourceBlocks` (r:16 w:16)
+	/// Storage: `PriceOracle::PairVenues` (r:0 w:1)
+	/// Storage: `PriceOracle::PairSlas` (r:0 w:1)
+	/// Storage: `PriceOracle::BreachedSlas` (r:0 w:1)
+	/// Storage: `PriceOracle::Bootstrapping` (r:0 w:1)
+	/// Storage: `PriceOracle::ArbitrageOpportunities` (r:0 w:1)
+	/// Storage: `PriceOracle::LongTailPairs` (r:0 w:1)
+	/// Storage: `PriceOracle::LowQualityPairs` (r:0 w:1)
+	/// Storage: `PriceOracle::PoolNotionals` (r:16 w:16)
+	/// Storage: `PriceOracle::PoolHops` (r:16 w:16)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:0 w:1)
+	fn remove_pair() -> Weight {
+		Weight::from_parts(160_000_000, 42_060)
+			.saturating_add(RocksDbWeight::get().reads(100_u64))
+			.saturating_add(RocksDbWeight::get().writes(119_u64))
+	}
+	/// Storage: `PriceOracle::Exchanges` (r:1 w:1)
+	fn add_exchange() -> Weight {
+		Weight::from_parts(15_000_000, 3_569)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::Exchanges` (r:1 w:1)
+	fn set_exchange_enabled() -> Weight {
+		Weight::from_parts(16_000_000, 3_569)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::RpcEndpoints` (r:0 w:1)
+	fn set_rpc_endpoints() -> Weight {
+		Weight::from_parts(20_000_000, 0).saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::Operators` (r:1 w:0)
+	/// Storage: `PriceOracle::Exchanges` (r:1 w:0)
+	/// Storage: `PriceOracle::QuarantinedExchanges` (r:1 w:1)
+	/// Storage: `PriceOracle::ExchangeStats` (r:1 w:1)
+	fn report_fetch_failure() -> Weight {
+		Weight::from_parts(22_000_000, 3_569)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `PriceOracle::Operators` (r:1 w:1)
+	/// Storage: `PriceOracle::CounterForOperators` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Storage: `PriceOracle::OperatorBonds` (r:0 w:1)
+	fn register_operator() -> Weight {
+		Weight::from_parts(31_000_000, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: `PriceOracle::Operators` (r:1 w:1)
+	/// Storage: `PriceOracle::CounterForOperators` (r:1 w:1)
+	/// Storage: `PriceOracle::OperatorBonds` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	fn deregister_operator() -> Weight {
+		Weight::from_parts(32_000_000, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::Alerts` (r:1 w:1)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::AlertDeposits` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	fn set_alert() -> Weight {
+		Weight::from_parts(41_000_000, 7_830)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: `PriceOracle::PairReads` (r:1 w:1)
+	/// Storage: `PriceOracle::Bootstrapping` (r:1 w:0)
+	/// Storage: `PriceOracle::AggregatedPrice` (r:1 w:0)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Storage: `PriceOracle::PausedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::FailureModes` (r:1 w:0)
+	/// Storage: `PriceOracle::LowQualityPairs` (r:1 w:0)
+	fn request_price_xcm() -> Weight {
+		Weight::from_parts(19_000_000, 3_650)
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::PriceSubscriptions` (r:1 w:1)
+	fn subscribe_price() -> Weight {
+		Weight::from_parts(24_000_000, 7_830)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::TwapChecks` (r:0 w:1)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:1 w:0)
+	fn set_twap_check() -> Weight {
+		Weight::from_parts(15_000_000, 3_840)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::FastPaths` (r:0 w:1)
+	/// Storage: `PriceOracle::FastPathUntil` (r:0 w:1)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:1 w:0)
+	fn set_fast_path() -> Weight {
+		Weight::from_parts(16_000_000, 3_840)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `PriceOracle::ExchangeKeys` (r:1 w:1)
+	/// Storage: `PriceOracle::Exchanges` (r:2 w:2)
+	/// Storage: `PriceOracle::TrackedPairs` (r:p w:p)
+	/// Storage: `PriceOracle::PriceData` (r:p w:2p)
+	/// Storage: `PriceOracle::RepeatedPrices` (r:p w:2p)
+	/// Storage: `PriceOracle::SourceBlocks` (r:p w:2p)
+	/// Storage: `PriceOracle::PoolNotionals` (r:p w:2p)
+	/// Storage: `PriceOracle::PoolHops` (r:p w:2p)
+	/// Storage: `PriceOracle::Operators` (r:a w:0)
+	/// Storage: `PriceOracle::QuarantinedExchanges` (r:1 w:2)
+	/// Storage: `PriceOracle::ExchangeStats` (r:1 w:2)
+	/// Storage: `PriceOracle::OutlierCounts` (r:1 w:2)
+	/// Storage: `PriceOracle::VerifiedPrices` (r:p w:2p)
+	/// Storage: `PriceOracle::PriceCommitments` (r:p*a w:2p*a)
+	/// Storage: `PriceOracle::SignedSubmissions` (r:p*a w:2p*a)
+	/// The range of component `p` is `[0, 100]`.
+	/// The range of component `a` is `[0, 32]`.
+	fn remap_exchange(p: u32, a: u32) -> Weight {
+		Weight::from_parts(31_000_000, 3_569)
+			.saturating_add(Weight::from_parts(33_000_000, 6_384).saturating_mul(p.into()))
+			.saturating_add(Weight::from_parts(2_600_000, 0).saturating_mul(a.into()))
+			.saturating_add(
+				Weight::from_parts(9_000_000, 5_270).saturating_mul(u64::from(p) * u64::from(a)),
+			)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().reads((7_u64).saturating_mul(p.into())))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(a.into())))
+			.saturating_add(
+				RocksDbWeight::get().reads((2_u64).saturating_mul(u64::from(p) * u64::from(a))),
+			)
+			.saturating_add(RocksDbWeight::get().writes(9_u64))
+			.saturating_add(RocksDbWeight::get().writes((13_u64).saturating_mul(p.into())))
+			.saturating_add(
+				RocksDbWeight::get().writes((4_u64).saturating_mul(u64::from(p) * u64::from(a))),
+			)
+	}
+	/// Storage: `PriceOracle::PendingAggregation` (r:1 w:1)
+	/// Storage: `PriceOracle::AggregationDeferrals` (r:1 w:1)
+	/// Storage: `PriceOracle::PriceData` (r:e w:0)
+	/// Storage: `PriceOracle::Exchanges` (r:e w:0)
+	/// Storage: `PriceOracle::RepeatedPrices` (r:e w:0)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Storage: `PriceOracle::PriceHistory` (r:1 w:1)
+	/// Storage: `PriceOracle::AggregatedPrice` (r:0 w:1)
+	/// Storage: `PriceOracle::Alerts` (r:1 w:1)
+	/// Storage: `PriceOracle::AlertDeposits` (r:16 w:16)
+	/// Storage: `System::Account` (r:16 w:16)
+	/// Storage: `PriceOracle::PriceSubscriptions` (r:1 w:0)
+	/// Storage: `PriceOracle::AggregatedPrice` (r:1 w:0)
+	/// Storage: `PriceOracle::FastPaths` (r:1 w:0)
+	/// Storage: `PriceOracle::FastPathUntil` (r:1 w:1)
+	/// Storage: `PriceOracle::Bootstrapping` (r:1 w:0)
+	/// Storage: `PriceOracle::LatestAggregates` (r:1 w:1)
+	/// Storage: `PriceOracle::ChainAggregates` (r:e w:2e)
+	/// Storage: `PriceOracle::CrossChainChecks` (r:1 w:0)
+	/// Storage: `PriceOracle::PairVenues` (r:0 w:1)
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::GasData` (r:2 w:0)
+	/// Storage: `PriceOracle::NativeTokens` (r:2 w:0)
+	/// Storage: `PriceOracle::AggregatedPrice` (r:2 w:0)
+	/// Storage: `PriceOracle::ArbitrageOpportunities` (r:0 w:1)
+	/// Storage: `PriceOracle::LongTailPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::PoolNotionals` (r:e w:0)
+	/// Storage: `PriceOracle::LowQualityPairs` (r:1 w:1)
+	/// Storage: `PriceOracle::OutlierCounts` (r:e w:e)
+	/// Storage: `PriceOracle::QuarantinedExchanges` (r:2e w:e)
+	/// Storage: `PriceOracle::ExchangeStats` (r:e w:e)
+	/// The range of component `e` is `[1, 256]`.
+	fn aggregate_prices(e: u32) -> Weight {
+		Weight::from_parts(186_000_000, 49_880)
+			.saturating_add(Weight::from_parts(25_000_000, 12_645).saturating_mul(e.into()))
+			.saturating_add(RocksDbWeight::get().reads(53_u64))
+			.saturating_add(RocksDbWeight::get().reads((9_u64).saturating_mul(e.into())))
+			.saturating_add(RocksDbWeight::get().writes(42_u64))
+			.saturating_add(RocksDbWeight::get().writes((5_u64).saturating_mul(e.into())))
+	}
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Storage: `PriceOracle::PriceData` (r:p w:p)
+	/// Storage: `PriceOracle::RepeatedPrices` (r:0 w:p)
+	/// Storage: `PriceOracle::SourceBlocks` (r:0 w:p)
+	/// Storage: `PriceOracle::PoolNotionals` (r:0 w:p)
+	/// Storage: `PriceOracle::Exchanges` (r:p w:0)
+	/// The range of component `p` is `[0, 1000]`.
+	fn prune_stale_prices(p: u32) -> Weight {
+		Weight::from_parts(6_000_000, 1_489)
+			.saturating_add(Weight::from_parts(12_500_000, 3_569).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(p.into())))
+			.saturating_add(RocksDbWeight::get().writes((4_u64).saturating_mul(p.into())))
+	}
+	/// Storage: `PriceOracle::Bootstrapping` (r:1 w:0)
+	/// Storage: `PriceOracle::AggregatedPrice` (r:1 w:0)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Storage: `PriceOracle::PairReads` (r:1 w:1)
+	fn latest_price() -> Weight {
+		Weight::from_parts(13_000_000, 3_650)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::PairReads` (r:1 w:1)
+	/// Storage: `PriceOracle::PriceData` (r:e w:0)
+	/// Storage: `PriceOracle::Exchanges` (r:e w:0)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// The range of component `e` is `[1, 256]`.
+	fn get_prices(e: u32) -> Weight {
+		Weight::from_parts(10_000_000, 3_620)
+			.saturating_add(Weight::from_parts(6_200_000, 3_569).saturating_mul(e.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(e.into())))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::PriceHistory` (r:1 w:0)
+	fn twap() -> Weight {
+		Weight::from_parts(24_000_000, 9_230).saturating_add(RocksDbWeight::get().reads(1_u64))
+	}
+	/// Storage: `PriceOracle::LatestAggregates` (r:1 w:0)
+	fn latest_aggregates() -> Weight {
+		Weight::from_parts(18_000_000, 3_570).saturating_add(RocksDbWeight::get().reads(1_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::FailureModes` (r:0 w:1)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:1 w:0)
+	fn set_failure_mode() -> Weight {
+		Weight::from_parts(15_000_000, 3_840)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::PausedPairs` (r:0 w:1)
+	fn set_pair_paused() -> Weight {
+		Weight::from_parts(15_000_000, 3_840)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::PairReads` (r:1 w:1)
+	/// Storage: `PriceOracle::Bootstrapping` (r:1 w:0)
+	/// Storage: `PriceOracle::AggregatedPrice` (r:1 w:0)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Storage: `PriceOracle::PausedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::FailureModes` (r:1 w:0)
+	/// Storage: `PriceOracle::LowQualityPairs` (r:1 w:0)
+	fn provided_price() -> Weight {
+		Weight::from_parts(16_000_000, 3_650)
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::Exchanges` (r:1 w:0)
+	/// Storage: `PriceOracle::Routers` (r:0 w:1)
+	fn set_router() -> Weight {
+		Weight::from_parts(15_000_000, 3_569)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::Exchanges` (r:1 w:0)
+	/// Storage: `PriceOracle::PoolHops` (r:0 w:1)
+	fn set_pool_hop() -> Weight {
+		Weight::from_parts(18_000_000, 3_840)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::GasTrackedChains` (r:1 w:0)
+	/// Storage: `PriceOracle::GasData` (r:1 w:1)
+	fn submit_gas_fees() -> Weight {
+		Weight::from_parts(18_000_000, 3_553)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::RpcEndpoints` (r:1 w:0)
+	/// Storage: `PriceOracle::GasTrackedChains` (r:0 w:1)
+	/// Storage: `PriceOracle::BuilderTipChains` (r:0 w:1)
+	/// Storage: `PriceOracle::GasData` (r:0 w:1)
+	/// Storage: `PriceOracle::NativeTokens` (r:0 w:1)
+	fn set_gas_tracking() -> Weight {
+		Weight::from_parts(16_000_000, 3_658)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: `PriceOracle::GasTrackedChains` (r:1 w:0)
+	/// Storage: `PriceOracle::BuilderTipChains` (r:0 w:1)
+	fn set_builder_tips() -> Weight {
+		Weight::from_parts(12_000_000, 3_513)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::GasTrackedChains` (r:1 w:0)
+	/// Storage: `PriceOracle::NativeTokens` (r:0 w:1)
+	fn set_native_token() -> Weight {
+		Weight::from_parts(12_000_000, 3_513)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:p w:2p)
+	/// Storage: `PriceOracle::Exchanges` (r:2e w:2e)
+	/// Storage: `PriceOracle::ExchangeKeys` (r:e w:2e)
+	/// Storage: `PriceOracle::Routers` (r:e w:2e)
+	/// Storage: `PriceOracle::PairReads` (r:0 w:p)
+	/// Storage: `PriceOracle::PendingAggregation` (r:0 w:p)
+	/// Storage: `PriceOracle::PriceGuards` (r:0 w:p)
+	/// Storage: `PriceOracle::AggregationDeferrals` (r:0 w:p)
+	/// Storage: `PriceOracle::Alerts` (r:p w:p)
+	/// Storage: `PriceOracle::AlertDeposits` (r:16p w:16p)
+	/// Storage: `System::Account` (r:16p w:16p)
+	/// Storage: `PriceOracle::TwapChecks` (r:0 w:2p)
+	/// Storage: `PriceOracle::FastPaths` (r:0 w:2p)
+	/// Storage: `PriceOracle::FastPathUntil` (r:0 w:p)
+	/// Storage: `PriceOracle::FailureModes` (r:0 w:2p)
+	/// Storage: `PriceOracle::PausedPairs` (r:0 w:p)
+	/// Storage: `PriceOracle::LatestAggregates` (r:1 w:1)
+	/// Storage: `PriceOracle::ChainAggregates` (r:16p w:16p)
+	/// Storage: `PriceOracle::CrossChainChecks` (r:0 w:2p)
+	/// Storage: `PriceOracle::SourceBlocks` (r:16p w:16p)
+	/// Storage: `PriceOracle::PairVenues` (r:0 w:p)
+	/// Storage: `PriceOracle::PairSlas` (r:0 w:2p)
+	/// Storage: `PriceOracle::BreachedSlas` (r:0 w:2p)
+	/// Storage: `PriceOracle::Bootstrapping` (r:0 w:2p)
+	/// Storage: `PriceOracle::ArbitrageOpportunities` (r:0 w:p)
+	/// Storage: `PriceOracle::LongTailPairs` (r:0 w:2p)
+	/// Storage: `PriceOracle::LowQualityPairs` (r:0 w:2p)
+	/// Storage: `PriceOracle::PoolNotionals` (r:16p w:16p)
+	/// Storage: `PriceOracle::PoolHops` (r:16p w:32p)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:0 w:p)
+	/// The range of component `e` is `[0, 256]`.
+	/// The range of component `p` is `[0, 64]`.
+	fn import_registry(e: u32, p: u32) -> Weight {
+		Weight::from_parts(20_000_000, 3_513)
+			.saturating_add(Weight::from_parts(9_000_000, 2_568).saturating_mul(e.into()))
+			.saturating_add(Weight::from_parts(151_000_000, 42_064).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().reads((4_u64).saturating_mul(e.into())))
+			.saturating_add(RocksDbWeight::get().reads((98_u64).saturating_mul(p.into())))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((6_u64).saturating_mul(e.into())))
+			.saturating_add(RocksDbWeight::get().writes((143_u64).saturating_mul(p.into())))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::CrossChainChecks` (r:0 w:1)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:1 w:0)
+	fn set_cross_chain_check() -> Weight {
+		Weight::from_parts(15_000_000, 3_840)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::ProtocolCallOptions` (r:0 w:1)
+	fn set_call_options() -> Weight {
+		Weight::from_parts(12_000_000, 0).saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::PairSlas` (r:0 w:1)
+	/// Storage: `PriceOracle::BreachedSlas` (r:0 w:1)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:1 w:0)
+	fn set_sla() -> Weight {
+		Weight::from_parts(16_000_000, 3_840)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `PriceOracle::PairSlas` (r:s w:0)
+	/// Storage: `PriceOracle::AggregatedPrice` (r:s w:0)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Storage: `PriceOracle::PairVenues` (r:s w:0)
+	/// Storage: `PriceOracle::BreachedSlas` (r:s w:s)
+	/// The range of component `s` is `[0, 64]`.
+	fn check_slas(s: u32) -> Weight {
+		Weight::from_parts(4_000_000, 1_489)
+			.saturating_add(Weight::from_parts(14_000_000, 4_000).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().reads((4_u64).saturating_mul(s.into())))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(s.into())))
+	}
+	/// Storage: `PriceOracle::Bootstrapping` (r:b w:b)
+	/// Storage: `PriceOracle::AggregatedPrice` (r:b w:0)
+	/// Storage: `PriceOracle::LatestAggregates` (r:b w:b)
+	/// The range of component `b` is `[0, 64]`.
+	fn advance_bootstraps(b: u32) -> Weight {
+		Weight::from_parts(3_000_000, 1_489)
+			.saturating_add(Weight::from_parts(16_000_000, 6_380).saturating_mul(b.into()))
+			.saturating_add(RocksDbWeight::get().reads((3_u64).saturating_mul(b.into())))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(b.into())))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::LongTailPairs` (r:0 w:1)
+	/// Storage: `PriceOracle::LowQualityPairs` (r:0 w:1)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:1 w:0)
+	fn set_long_tail() -> Weight {
+		Weight::from_parts(15_000_000, 3_840)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:1 w:1)
+	/// Storage: `System::Number` (r:1 w:0)
+	fn deprecate_pair() -> Weight {
+		Weight::from_parts(16_000_000, 3_840)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::DeprecatedPairs` (r:d w:0)
+	/// The range of component `d` is `[0, 64]`.
+	fn advance_deprecations(d: u32) -> Weight {
+		Weight::from_parts(3_000_000, 1_489)
+			.saturating_add(Weight::from_parts(6_000_000, 2_541).saturating_mul(d.into()))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(d.into())))
+	}
+	/// Storage: `PriceOracle::RpcEndpoints` (r:1 w:0)
+	/// Storage: `PriceOracle::MulticallChains` (r:0 w:1)
+	fn set_multicall() -> Weight {
+		Weight::from_parts(12_000_000, 3_658)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::RpcEndpoints` (r:1 w:0)
+	/// Storage: `PriceOracle::ChainConfirmations` (r:0 w:1)
+	fn set_confirmation() -> Weight {
+		Weight::from_parts(12_000_000, 3_658)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::TrackedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::Exchanges` (r:1 w:0)
+	/// Storage: `PriceOracle::VerifiedPrices` (r:1 w:1)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// The range of component `n` is `[2, 32]`.
+	fn submit_verified_price(n: u32) -> Weight {
+		Weight::from_parts(21_000_000, 6_218)
+			.saturating_add(Weight::from_parts(1_900_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PriceOracle::PairReads` (r:1 w:1)
+	/// Storage: `PriceOracle::Bootstrapping` (r:1 w:0)
+	/// Storage: `PriceOracle::AggregatedPrice` (r:1 w:0)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Storage: `PriceOracle::PausedPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::FailureModes` (r:1 w:0)
+	/// Storage: `PriceOracle::LowQualityPairs` (r:1 w:0)
+	/// Storage: `PriceOracle::PriceGuards` (r:0 w:1)
+	fn guarded_price() -> Weight {
+		Weight::from_parts(18_000_000, 3_650)
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `PriceOracle::QuarantinedExchanges` (r:1 w:1)
+	/// Storage: `PriceOracle::ExchangeStats` (r:0 w:1)
+	fn release_exchange() -> Weight {
+		Weight::from_parts(15_000_000, 3_493)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+}
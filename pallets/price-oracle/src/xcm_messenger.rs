@@ -0,0 +1,81 @@
+//! [`PriceMessenger`] sending prices over XCM, behind the `xcm` feature.
+//!
+//! [`XcmPriceMessenger`] serves consumers identified by their XCM [`Location`], e.g. sibling
+//! parachains, with `Subscriber = Location` in the [`Config`](crate::Config) of the pallet. Each
+//! price is sent as an unpaid `Transact` of a call of the consumer, with an `Xcm` origin so the
+//! consumer can check the message comes from the oracle chain. The call takes the
+//! `(query_id, pair_id, price)` of [`PriceMessenger::send_price`], SCALE encoded after the call
+//! index, so a consumer declares it as e.g.
+//!
+//! ```ignore
+//! pub fn receive_price(
+//!     origin: OriginFor<T>,
+//!     query_id: u64,
+//!     pair_id: PairId,
+//!     price: ProvidedPrice,
+//! ) -> DispatchResult
+//! ```
+//!
+//! and lets the oracle chain execute it unpaid through its barrier. Delivery fees aren't charged
+//! to anyone, so the router should only reach consumers that accept the messages for free.
+
+use crate::{PairId, PriceMessenger, ProvidedPrice, LOG_TARGET};
+use alloc::vec;
+use codec::Encode;
+use core::marker::PhantomData;
+use frame_support::{traits::Get, weights::Weight};
+use xcm::latest::prelude::*;
+
+/// [`PriceMessenger`] sending prices through `Router` to the call at the `CallIndex` of each
+/// consumer, a `(pallet index, call index)` pair, whose weight on the consumer is at most
+/// `CallWeight`. `SendWeight` is the weight of routing one message on this chain.
+pub struct XcmPriceMessenger<Router, CallIndex, CallWeight, SendWeight>(
+	PhantomData<(Router, CallIndex, CallWeight, SendWeight)>,
+);
+
+impl<Router, CallIndex, CallWeight, SendWeight>
+	XcmPriceMessenger<Router, CallIndex, CallWeight, SendWeight>
+where
+	CallIndex: Get<[u8; 2]>,
+	CallWeight: Get<Weight>,
+{
+	/// The message delivering `price` of `pair_id` in response to the query `query_id`.
+	pub fn message(query_id: u64, pair_id: PairId, price: ProvidedPrice) -> Xcm<()> {
+		let call = (CallIndex::get(), query_id, pair_id, price).encode();
+		Xcm(vec![
+			UnpaidExecution { weight_limit: Unlimited, check_origin: None },
+			Transact {
+				origin_kind: OriginKind::Xcm,
+				fallback_max_weight: Some(CallWeight::get()),
+				call: call.into(),
+			},
+		])
+	}
+}
+
+impl<Router, CallIndex, CallWeight, SendWeight> PriceMessenger<Location>
+	for XcmPriceMessenger<Router, CallIndex, CallWeight, SendWeight>
+where
+	Router: SendXcm,
+	CallIndex: Get<[u8; 2]>,
+	CallWeight: Get<Weight>,
+	SendWeight: Get<Weight>,
+{
+	fn send_price(
+		dest: &Location,
+		query_id: u64,
+		pair_id: PairId,
+		price: ProvidedPrice,
+	) -> sp_runtime::DispatchResult {
+		let message = Self::message(query_id, pair_id, price);
+		send_xcm::<Router>(dest.clone(), message).map_err(|e| {
+			log::warn!(target: LOG_TARGET, "Failed to send a price over XCM: {e:?}");
+			sp_runtime::DispatchError::Unavailable
+		})?;
+		Ok(())
+	}
+
+	fn send_price_weight() -> Weight {
+		SendWeight::get()
+	}
+}
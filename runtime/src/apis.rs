@@ -27,6 +27,7 @@
 use alloc::vec::Vec;
 use frame_support::{
 	genesis_builder_helper::{build_state, get_preset},
+	traits::Get,
 	weights::Weight,
 };
 use pallet_grandpa::AuthorityId as GrandpaId;
@@ -42,8 +43,9 @@ use sp_version::RuntimeVersion;
 
 // Local module imports
 use super::{
-	AccountId, Aura, Balance, Block, Executive, Grandpa, InherentDataExt, Nonce, Runtime,
-	RuntimeCall, RuntimeGenesisConfig, SessionKeys, System, TransactionPayment, VERSION,
+	AccountId, Aura, Balance, Block, BlockNumber, Executive, Grandpa, InherentDataExt, Nonce,
+	PriceOracle, Runtime, RuntimeCall, RuntimeGenesisConfig, SessionKeys, System,
+	TransactionPayment, VERSION,
 };
 
 impl_runtime_apis! {
@@ -220,6 +222,129 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_price_oracle_runtime_api::PriceOracleApi<Block> for Runtime {
+		fn get_oracle_config() -> pallet_price_oracle_runtime_api::OracleConfig {
+			use pallet_price_oracle::Config;
+
+			let pair_bounds = pallet_price_oracle::TrackedPairs::<Runtime>::iter()
+				.map(|(pair_id, pair)| {
+					let bounds = PriceOracle::price_bounds(&pair);
+					pallet_price_oracle_runtime_api::PairBounds {
+						pair_id,
+						min_price: *bounds.start(),
+						max_price: *bounds.end(),
+					}
+				})
+				.collect();
+			pallet_price_oracle_runtime_api::OracleConfig {
+				update_interval: <Runtime as Config>::UpdateInterval::get(),
+				http_timeout_ms: <Runtime as Config>::HttpTimeout::get(),
+				max_exchanges_per_block: <Runtime as Config>::MaxExchangesPerBlock::get(),
+				max_price_age_ms: <Runtime as Config>::MaxPriceAge::get(),
+				max_submission_delay_ms: <Runtime as Config>::MaxSubmissionDelay::get(),
+				pair_bounds,
+			}
+		}
+
+		fn latest_price(pair_id: pallet_price_oracle::PairId) -> Option<(pallet_price_oracle::Price, u64)> {
+			PriceOracle::latest_price(pair_id)
+		}
+
+		fn aggregated_price(
+			pair_id: pallet_price_oracle::PairId,
+		) -> Option<pallet_price_oracle::PriceAggregate> {
+			PriceOracle::get_aggregated_price(pair_id)
+		}
+
+		fn all_prices(
+			pair_id: pallet_price_oracle::PairId,
+		) -> Vec<(pallet_price_oracle::exchanges::ExchangeId, pallet_price_oracle::Price, u64)> {
+			PriceOracle::get_all_prices(pair_id)
+		}
+
+		fn tracked_pairs() -> Vec<(pallet_price_oracle::PairId, pallet_price_oracle::PairInfo)> {
+			PriceOracle::tracked_pairs()
+		}
+
+		fn pair_reads() -> Vec<(pallet_price_oracle::PairId, pallet_price_oracle::ReadCounter)> {
+			pallet_price_oracle::TrackedPairs::<Runtime>::iter_keys()
+				.map(|pair_id| (pair_id, PriceOracle::pair_reads(pair_id)))
+				.collect()
+		}
+
+		fn get_history(
+			pair_id: pallet_price_oracle::PairId,
+			from_block: BlockNumber,
+			to_block: BlockNumber,
+			step: BlockNumber,
+		) -> Vec<(pallet_price_oracle::Price, BlockNumber)> {
+			PriceOracle::history(pair_id, from_block, to_block, step)
+		}
+
+		fn export_registry() -> Vec<u8> {
+			codec::Encode::encode(&PriceOracle::export_registry())
+		}
+
+		fn chain_prices(
+			pair_id: pallet_price_oracle::PairId,
+		) -> Vec<(pallet_price_oracle::ChainId, pallet_price_oracle::PriceAggregate)> {
+			PriceOracle::chain_prices(pair_id)
+		}
+
+		fn sample_skew(pair_id: pallet_price_oracle::PairId) -> Option<u64> {
+			PriceOracle::sample_skew(pair_id)
+		}
+
+		fn best_venues(pair_id: pallet_price_oracle::PairId) -> Option<pallet_price_oracle::BestVenues> {
+			PriceOracle::best_venues(pair_id)
+		}
+
+		fn read_many(pair_ids: Vec<pallet_price_oracle::PairId>) -> Vec<Option<pallet_price_oracle::PriceAggregate>> {
+			PriceOracle::read_many(&pair_ids)
+		}
+
+		fn meets_sla(pair_id: pallet_price_oracle::PairId) -> bool {
+			PriceOracle::meets_sla(pair_id)
+		}
+
+		fn is_bootstrapping(pair_id: pallet_price_oracle::PairId) -> bool {
+			pallet_price_oracle::Bootstrapping::<Runtime>::contains_key(pair_id)
+		}
+
+		fn arbitrage_opportunity(
+			pair_id: pallet_price_oracle::PairId,
+		) -> Option<pallet_price_oracle::ArbitrageOpportunity> {
+			PriceOracle::arbitrage_opportunity(pair_id)
+		}
+
+		fn liquid_prices(
+			pair_id: pallet_price_oracle::PairId,
+			min_liquidity: u128,
+		) -> Vec<(pallet_price_oracle::exchanges::ExchangeId, pallet_price_oracle::LiquidPrice)> {
+			let exchanges = u32::from(pallet_price_oracle::exchanges::ExchangeId::MAX) + 1;
+			PriceOracle::liquid_prices(pair_id, min_liquidity, exchanges)
+		}
+
+		fn oracle_status() -> pallet_price_oracle::OracleStatus {
+			PriceOracle::oracle_status()
+		}
+
+		fn derived_price(
+			base: Vec<u8>,
+			quote: Vec<u8>,
+		) -> Option<(pallet_price_oracle::Price, u64)> {
+			PriceOracle::get_derived_price(&base, &quote)
+		}
+
+		fn indexed_price_keys(
+			pair_id: pallet_price_oracle::PairId,
+			from_block: BlockNumber,
+			to_block: BlockNumber,
+		) -> Vec<(BlockNumber, Vec<u8>)> {
+			PriceOracle::indexed_price_keys(pair_id, from_block, to_block)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
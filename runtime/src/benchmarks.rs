@@ -30,5 +30,7 @@ frame_benchmarking::define_benchmarks!(
 	[pallet_balances, Balances]
 	[pallet_timestamp, Timestamp]
 	[pallet_sudo, Sudo]
-	[pallet_template, Template]
+	[pallet_membership, OracleCommittee]
+	[pallet_oracle_rewards, OracleRewards]
+	[pallet_price_oracle, PriceOracle]
 );
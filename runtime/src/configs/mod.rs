@@ -31,18 +31,28 @@ use frame_support::{
 		constants::{RocksDbWeight, WEIGHT_REF_TIME_PER_SECOND},
 		IdentityFee, Weight,
 	},
+	PalletId,
+};
+use frame_system::{
+	limits::{BlockLength, BlockWeights},
+	EnsureNever, EnsureRoot,
 };
-use frame_system::limits::{BlockLength, BlockWeights};
 use pallet_transaction_payment::{ConstFeeMultiplier, FungibleAdapter, Multiplier};
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-use sp_runtime::{traits::One, Perbill};
+use sp_runtime::{
+	generic::Era,
+	traits::{One, SaturatedConversion, Verify},
+	MultiAddress, Perbill, Permill,
+};
 use sp_version::RuntimeVersion;
 
 // Local module imports
 use super::{
-	AccountId, Aura, Balance, Balances, Block, BlockNumber, Hash, Nonce, PalletInfo, Runtime,
-	RuntimeCall, RuntimeEvent, RuntimeFreezeReason, RuntimeHoldReason, RuntimeOrigin, RuntimeTask,
-	System, EXISTENTIAL_DEPOSIT, SLOT_DURATION, VERSION,
+	AccountId, Aura, Balance, Balances, Block, BlockNumber, Hash, Nonce, OracleCommittee,
+	OracleRewards, PalletInfo, Runtime, RuntimeCall, RuntimeEvent, RuntimeFreezeReason,
+	RuntimeHoldReason, RuntimeOrigin, RuntimeTask, Signature, SignedPayload, System, Timestamp,
+	TxExtension, UncheckedExtrinsic, DAYS, EXISTENTIAL_DEPOSIT, HOURS, MILLI_UNIT, SLOT_DURATION,
+	UNIT, VERSION,
 };
 
 const NORMAL_DISPATCH_RATIO: Perbill = Perbill::from_percent(75);
@@ -175,8 +185,169 @@ where
 	}
 }
 
+impl frame_system::offchain::SigningTypes for Runtime {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
+}
+
+// Implement CreateSignedTransaction for transactions signed by offchain workers
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Runtime
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_signed_transaction<
+		C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>,
+	>(
+		call: RuntimeCall,
+		public: <Signature as Verify>::Signer,
+		account: AccountId,
+		nonce: Nonce,
+	) -> Option<UncheckedExtrinsic> {
+		// Mortal for half of the kept block hashes, like a wallet would do.
+		let period =
+			BlockHashCount::get().checked_next_power_of_two().map(|c| c / 2).unwrap_or(2) as u64;
+		let current_block = System::block_number().saturated_into::<u64>().saturating_sub(1);
+		let tx_ext: TxExtension = (
+			frame_system::CheckNonZeroSender::<Runtime>::new(),
+			frame_system::CheckSpecVersion::<Runtime>::new(),
+			frame_system::CheckTxVersion::<Runtime>::new(),
+			frame_system::CheckGenesis::<Runtime>::new(),
+			frame_system::CheckEra::<Runtime>::from(Era::mortal(period, current_block)),
+			frame_system::CheckNonce::<Runtime>::from(nonce),
+			frame_system::CheckWeight::<Runtime>::new(),
+			pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(0),
+			frame_metadata_hash_extension::CheckMetadataHash::<Runtime>::new(false),
+			frame_system::WeightReclaim::<Runtime>::new(),
+		);
+		let payload = SignedPayload::new(call, tx_ext).ok()?;
+		let signature = codec::Encode::using_encoded(&payload, |bytes| C::sign(bytes, public))?;
+		let (call, tx_ext, _) = payload.deconstruct();
+		Some(UncheckedExtrinsic::new_signed(call, MultiAddress::Id(account), signature, tx_ext))
+	}
+}
+
+/// Blocks between two price fetches of the oracle: 18 seconds on 6 second blocks.
+const ORACLE_UPDATE_INTERVAL: BlockNumber = 3;
+
+/// Blocks between two oracle reward payouts: a day.
+const ORACLE_REWARDS_ERA: BlockNumber = DAYS;
+
+parameter_types! {
+	/// Truncate oracle prices, as they were before the rounding became configurable.
+	pub const OracleRounding: pallet_price_oracle::RoundingPolicy =
+		pallet_price_oracle::RoundingPolicy::FLOOR;
+	/// Let the dynamic bounds of long-tail pairs reach 25% off their TWAP.
+	pub const OracleLongTailDeviation: Permill = Permill::from_percent(25);
+	/// Weigh arbitrage spreads against the gas of a 10,000 USD trade.
+	pub const OracleArbitrageTradeSize: pallet_price_oracle::Price =
+		10_000 * pallet_price_oracle::fixed::PRICE_UNIT;
+	/// Slash a tenth of the bond of an operator per deviant price.
+	pub const OracleMisbehaviorSlash: Permill = Permill::from_percent(10);
+	/// Pay a tenth of a slash to the reporter, burn the rest.
+	pub const OracleReporterReward: Permill = Permill::from_percent(10);
+}
+
 impl pallet_price_oracle::Config for Runtime {
-	type UpdateInterval = ConstU32<3>; // Update every 3 blocks (18 seconds on 6s blocks)
+	type RuntimeEvent = RuntimeEvent;
+	type RegistryOrigin = EnsureRoot<AccountId>;
+	type AuthorityId = pallet_price_oracle::crypto::OracleAuthId;
+	type AcceptUnsigned = ConstBool<false>; // Only registered operators submit prices
+	type MaxOperators = ConstU32<32>;
+	type MaxPairs = ConstU32<64>;
+	type Committee = OracleCommittee;
+	type ContributionReporter = OracleRewards;
+	type Currency = Balances;
+	type OperatorBond = ConstU128<{ 1_000 * UNIT }>;
+	type SlashDeviationBps = ConstU32<1_000>; // Slash prices 10% off the median
+	type MisbehaviorSlash = OracleMisbehaviorSlash;
+	type ReporterReward = OracleReporterReward;
+	type UpdateInterval = ConstU32<ORACLE_UPDATE_INTERVAL>;
 	type HttpTimeout = ConstU64<10000>; // 10 second timeout
 	type MaxExchangesPerBlock = ConstU8<5>; // Query all exchanges per block
+	type MaxOcwMillis = ConstU64<4_000>; // Start no fetches past two thirds of a slot
+	type ChainTimeTolerance = ConstU64<60>; // Five Ethereum slots behind the freshest endpoint
+	type MaxPriceAge = ConstU64<{ 10 * 60_000 }>; // Ignore prices older than ten minutes
+	type MaxHistory = ConstU32<100>; // Half an hour of aggregates at one per UpdateInterval
+	type UnixTime = Timestamp;
+	type MaxSubmissionDelay = ConstU64<60_000>; // Accept observations up to a minute off
+	type FrozenPriceThreshold = ConstU32<20>; // Six minutes of reads of the same price from one pool
+	type PairHasher = pallet_price_oracle::Blake2PairHasher;
+	type ReadPeriod = ConstU32<DAYS>; // Count pair reads per day
+	type MaxAlertsPerPair = ConstU32<16>; // Alerts any account can register on a pair
+	type AlertDeposit = ConstU128<UNIT>; // Reserved per alert until it is removed or triggered
+	type Subscriber = AccountId;
+	type SubscriberOrigin = EnsureNever<AccountId>; // No sibling parachains on a solochain
+	type PriceMessenger = ();
+	type MaxSubscriptionsPerPair = ConstU32<16>;
+	type FastPathBudget = ConstU32<4>; // Extra pools queried per block for volatile pairs
+	type Rounding = OracleRounding;
+	type BootstrapIntervals = ConstU32<10>; // Serve new pairs after three minutes of agreement
+	type BootstrapMaxMoveBps = ConstU32<500>; // Within 5% from one interval to the next
+	type SummarizeSourceEvents = ConstBool<false>; // An event per submission, for indexers
+	type MinSpreadBps = ConstU32<100>; // Report spreads of 1% and more
+	type ArbitrageGasUnits = ConstU64<150_000>; // A Uniswap V3 swap
+	type ArbitrageTradeSize = OracleArbitrageTradeSize;
+	type MaxReferenceDeviationBps = ConstU32<300>; // Flag prices 3% off Chainlink
+	type SamplingJitter = ConstU32<2>; // Sample each exchange anywhere in the interval
+	type LongTailDeviation = OracleLongTailDeviation;
+	type ReportLiquidity = ConstBool<true>; // Pool depth next to every price, for MEV searchers
+	type BatchRpcCalls = ConstBool<true>; // One request per chain for the pools of a run
+	type MaxReadDelay = ConstU32<ORACLE_UPDATE_INTERVAL>; // Pools left out read within an interval
+	type SubmissionDeviationBps = ConstU32<10>; // Submit moves of 0.1% and more right away
+	type SubmissionHeartbeat = ConstU64<{ 5 * 60_000 }>; // Flat prices every five minutes
+	type ConsensusBps = ConstU32<100>; // Favour exchanges within 1% of the median
+	type OutlierMads = ConstU32<5>; // Leave out prices five MADs and 1% off the median
+	type QuarantineBps = ConstU32<2_500>; // Quarantine exchanges failing a quarter of their reads
+	type QuarantineMinSamples = ConstU32<40>; // Judge exchanges on forty reads at the least
+	type QuarantinePeriod = ConstU32<{ HOURS }>; // Retry quarantined exchanges after an hour
+	type RevealWindow = ConstU32<0>; // Prices are submitted in the clear, without commit-reveal
+	type LightClient = (); // No light client of the source chains yet, so no verified prices
+	type MaxAggregationDeferrals = ConstU32<2>; // Guarded pairs are aggregated every third block
+	type WeightInfo = (); // Not benchmarked yet
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = OracleBenchmarkHelper;
+}
+
+/// Sets the on-chain time for the price oracle benchmarks. The runtime has no light client to
+/// track state roots in.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct OracleBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_price_oracle::BenchmarkHelper for OracleBenchmarkHelper {
+	fn set_now(millis: u64) {
+		pallet_timestamp::Now::<Runtime>::put(millis);
+	}
+
+	fn set_state_root(_chain_id: u64, _block_number: u64, _state_root: sp_core::H256) {}
+}
+
+impl pallet_membership::Config<pallet_membership::Instance1> for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type AddOrigin = EnsureRoot<AccountId>;
+	type RemoveOrigin = EnsureRoot<AccountId>;
+	type SwapOrigin = EnsureRoot<AccountId>;
+	type ResetOrigin = EnsureRoot<AccountId>;
+	type PrimeOrigin = EnsureRoot<AccountId>;
+	type MembershipInitialized = ();
+	type MembershipChanged = ();
+	type MaxMembers = ConstU32<32>;
+	type WeightInfo = pallet_membership::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const OracleRewardsPalletId: PalletId = PalletId(*b"py/orrwd");
+}
+
+impl pallet_oracle_rewards::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type PalletId = OracleRewardsPalletId;
+	type EraLength = ConstU32<ORACLE_REWARDS_ERA>;
+	type EraPayout = ConstU128<{ 100 * UNIT }>;
+	type MaxOperators = ConstU32<64>;
+	type RewardPerSubmission = ConstU128<MILLI_UNIT>;
+	// One submission per oracle update
+	type MaxRewardedSubmissions = ConstU32<{ ORACLE_REWARDS_ERA / ORACLE_UPDATE_INTERVAL }>;
+	type WeightInfo = (); // Not benchmarked yet
 }
@@ -15,20 +15,29 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{AccountId, BalancesConfig, RuntimeGenesisConfig, SudoConfig};
+use crate::{
+	AccountId, Balance, BalancesConfig, OracleCommitteeConfig, OracleRewards, PriceOracleConfig,
+	RuntimeGenesisConfig, SudoConfig, UNIT,
+};
 use alloc::{vec, vec::Vec};
 use frame_support::build_struct_json_patch;
+use pallet_price_oracle::registry;
 use serde_json::Value;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
 use sp_genesis_builder::{self, PresetId};
 use sp_keyring::Sr25519Keyring;
 
+/// Initial balance of the oracle rewards pot: a year of era payouts.
+const ORACLE_REWARDS_POT: Balance = 365 * 100 * UNIT;
+
 // Returns the genesis config presets populated with given parameters.
 fn testnet_genesis(
 	initial_authorities: Vec<(AuraId, GrandpaId)>,
 	endowed_accounts: Vec<AccountId>,
 	root: AccountId,
+	oracle_operators: Vec<AccountId>,
+	oracle_committee: Vec<AccountId>,
 ) -> Value {
 	build_struct_json_patch!(RuntimeGenesisConfig {
 		balances: BalancesConfig {
@@ -36,15 +45,26 @@ fn testnet_genesis(
 				.iter()
 				.cloned()
 				.map(|k| (k, 1u128 << 60))
+				.chain([(OracleRewards::account_id(), ORACLE_REWARDS_POT)])
 				.collect::<Vec<_>>(),
 		},
 		aura: pallet_aura::GenesisConfig {
-			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect::<Vec<_>>(),
+			authorities: initial_authorities.iter().map(|x| x.0.clone()).collect::<Vec<_>>(),
 		},
 		grandpa: pallet_grandpa::GenesisConfig {
 			authorities: initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect::<Vec<_>>(),
 		},
 		sudo: SudoConfig { key: Some(root) },
+		price_oracle: PriceOracleConfig {
+			exchanges: registry::default_exchanges(),
+			pairs: registry::default_pairs(),
+			pool_hops: registry::default_pool_hops(),
+			rpc_endpoints: registry::default_rpc_endpoints(),
+			operators: oracle_operators,
+		},
+		oracle_committee: OracleCommitteeConfig {
+			members: oracle_committee.try_into().expect("committee fits MaxMembers"),
+		},
 	})
 }
 
@@ -62,6 +82,9 @@ pub fn development_config_genesis() -> Value {
 			Sr25519Keyring::BobStash.to_account_id(),
 		],
 		sp_keyring::Sr25519Keyring::Alice.to_account_id(),
+		// The `orcl` key the node derives from `--dev`, see the service.
+		vec![Sr25519Keyring::Alice.to_account_id()],
+		vec![Sr25519Keyring::Alice.to_account_id()],
 	)
 }
 
@@ -83,6 +106,8 @@ pub fn local_config_genesis() -> Value {
 			.map(|v| v.to_account_id())
 			.collect::<Vec<_>>(),
 		Sr25519Keyring::Alice.to_account_id(),
+		vec![Sr25519Keyring::Alice.to_account_id(), Sr25519Keyring::Bob.to_account_id()],
+		vec![Sr25519Keyring::Alice.to_account_id(), Sr25519Keyring::Bob.to_account_id()],
 	)
 }
 
@@ -224,4 +224,10 @@ mod runtime {
 
 	#[runtime::pallet_index(7)]
 	pub type PriceOracle = pallet_price_oracle;
+
+	#[runtime::pallet_index(8)]
+	pub type OracleRewards = pallet_oracle_rewards;
+
+	#[runtime::pallet_index(9)]
+	pub type OracleCommittee = pallet_membership<Instance1>;
 }